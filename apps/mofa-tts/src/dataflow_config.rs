@@ -0,0 +1,110 @@
+//! TTS dataflow generation
+//!
+//! `dataflow/tts.yml` is compiled into the app as a template and rendered with
+//! the user's profile and overrides from ~/.dora/primespeech/dataflow.json:
+//!
+//! ```json
+//! {
+//!   "profile": { "device": "cuda", "text_language": "en", "log_level": "DEBUG" },
+//!   "overrides": { "primespeech-tts": { "SPEED_FACTOR": "1.0" } }
+//! }
+//! ```
+//!
+//! The result is written to ~/.dora/primespeech/run/tts.yml, which is what the
+//! dataflow is started from, so the app works from any working directory.
+
+use mofa_dora_bridge::{DataflowBuilder, DataflowProfile, EnvOverrides};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+/// Dataflow template bundled with the app
+const TEMPLATE: &str = include_str!("../dataflow/tts.yml");
+
+/// TTS node in the template
+const TTS_NODE: &str = "primespeech-tts";
+
+/// TTS env var that sends audio fragments as they are synthesized instead of
+/// one clip per request
+const RETURN_FRAGMENT: &str = "RETURN_FRAGMENT";
+
+/// User configuration file format
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct DataflowConfig {
+    /// Device, engines, languages and log level
+    #[serde(default)]
+    pub profile: DataflowProfile,
+    /// Per-node env vars applied after the profile
+    #[serde(default)]
+    pub overrides: EnvOverrides,
+}
+
+/// Get the dataflow config file path
+pub fn get_config_path() -> PathBuf {
+    let home = dirs::home_dir().unwrap_or_else(|| PathBuf::from("."));
+    home.join(".dora").join("primespeech").join("dataflow.json")
+}
+
+/// Get the directory generated dataflows (and their node logs) live in
+pub fn get_run_dir() -> PathBuf {
+    let home = dirs::home_dir().unwrap_or_else(|| PathBuf::from("."));
+    home.join(".dora").join("primespeech").join("run")
+}
+
+/// Load the config file, falling back to defaults
+pub fn load_config() -> DataflowConfig {
+    let path = get_config_path();
+    let Ok(content) = fs::read_to_string(&path) else {
+        return DataflowConfig::default();
+    };
+    serde_json::from_str(&content).unwrap_or_else(|e| {
+        log::error!("Failed to parse {}: {}", path.display(), e);
+        DataflowConfig::default()
+    })
+}
+
+/// Save the config file
+pub fn save_config(config: &DataflowConfig) -> Result<(), String> {
+    let path = get_config_path();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create config dir: {}", e))?;
+    }
+    let json = serde_json::to_string_pretty(config)
+        .map_err(|e| format!("Failed to serialize config: {}", e))?;
+    fs::write(&path, json).map_err(|e| format!("Failed to write config: {}", e))
+}
+
+/// Render the TTS dataflow with the saved config and return its path. With
+/// `streaming` TTS sends audio fragments as they are synthesized, unless the
+/// config overrides `RETURN_FRAGMENT` itself.
+pub fn render_dataflow(streaming: bool) -> Result<PathBuf, String> {
+    let mut config = load_config();
+    if streaming {
+        config
+            .overrides
+            .entry(TTS_NODE.to_string())
+            .or_default()
+            .entry(RETURN_FRAGMENT.to_string())
+            .or_insert_with(|| "true".to_string());
+    }
+    render_dataflow_with(&config)
+}
+
+/// Render the TTS dataflow with an explicit config and return its path
+pub fn render_dataflow_with(config: &DataflowConfig) -> Result<PathBuf, String> {
+    let path = DataflowBuilder::from_yaml(TEMPLATE)
+        .map_err(|e| format!("Invalid dataflow template: {}", e))?
+        .with_name("tts")
+        .with_profile(config.profile.clone())
+        .with_overrides(&config.overrides)
+        .write_to(get_run_dir())
+        .map_err(|e| format!("Failed to generate dataflow: {}", e))?;
+    log::info!(
+        "Generated dataflow {} (device: {}, language: {}, log level: {})",
+        path.display(),
+        config.profile.device.as_str(),
+        config.profile.text_language.code(),
+        config.profile.log_level
+    );
+    Ok(path)
+}
@@ -0,0 +1,445 @@
+//! Dataset builder for few-shot training from multiple recordings
+//!
+//! Datasets are stored in:
+//! - Clips: ~/.dora/primespeech/datasets/{dataset_id}/clips/clip_{n}.wav (32kHz mono)
+//! - Manifest: ~/.dora/primespeech/datasets/{dataset_id}/manifest.json
+//!
+//! Each imported recording is sliced into utterances with an energy-based VAD
+//! (same start/end frame hysteresis as `VadState` in the AEC input bridge), then each
+//! clip is transcribed via the ASR bridge and can be edited or excluded before training.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+/// Sample rate of stored clips (required by GPT-SoVITS)
+pub const DATASET_SAMPLE_RATE: u32 = 32000;
+
+/// Energy-based VAD parameters for slicing
+#[derive(Clone, Debug)]
+pub struct VadConfig {
+    /// Analysis frame length in milliseconds
+    pub frame_ms: u32,
+    /// RMS threshold above which a frame counts as speech
+    pub energy_threshold: f32,
+    /// Consecutive speech frames needed to start an utterance
+    pub speech_start_frames: usize,
+    /// Consecutive silent frames needed to end an utterance
+    pub speech_end_frames: usize,
+    /// Clips shorter than this are dropped
+    pub min_clip_secs: f32,
+    /// Clips are force-split at this length (GPT-SoVITS prefers < 15s)
+    pub max_clip_secs: f32,
+    /// Silence kept before and after each clip
+    pub padding_ms: u32,
+}
+
+impl Default for VadConfig {
+    fn default() -> Self {
+        Self {
+            frame_ms: 10,
+            energy_threshold: 0.01,
+            speech_start_frames: 3,
+            speech_end_frames: 40, // ~400ms pause between sentences
+            min_clip_secs: 1.0,
+            max_clip_secs: 12.0,
+            padding_ms: 100,
+        }
+    }
+}
+
+/// Slice a mono recording into utterance sample ranges
+pub fn slice_utterances(samples: &[f32], sample_rate: u32, config: &VadConfig) -> Vec<Range<usize>> {
+    let frame_len = (sample_rate as usize * config.frame_ms as usize / 1000).max(1);
+    let min_len = (config.min_clip_secs * sample_rate as f32) as usize;
+    let max_len = (config.max_clip_secs * sample_rate as f32) as usize;
+    let padding = sample_rate as usize * config.padding_ms as usize / 1000;
+
+    let mut ranges = Vec::new();
+    let mut is_speaking = false;
+    let mut speech_frames = 0;
+    let mut silence_frames = 0;
+    let mut start = 0;
+    let mut last_speech_end = 0;
+
+    let close = |start: usize, end: usize, ranges: &mut Vec<Range<usize>>| {
+        if end - start >= min_len {
+            let padded = start.saturating_sub(padding)..(end + padding).min(samples.len());
+            ranges.push(padded);
+        }
+    };
+
+    for (i, frame) in samples.chunks(frame_len).enumerate() {
+        let frame_start = i * frame_len;
+        let frame_end = frame_start + frame.len();
+        let rms = (frame.iter().map(|s| s * s).sum::<f32>() / frame.len() as f32).sqrt();
+        let voiced = rms > config.energy_threshold;
+
+        if !is_speaking {
+            if voiced {
+                speech_frames += 1;
+                if speech_frames >= config.speech_start_frames {
+                    is_speaking = true;
+                    silence_frames = 0;
+                    start = (i + 1 - speech_frames) * frame_len;
+                    last_speech_end = frame_end;
+                }
+            } else {
+                speech_frames = 0;
+            }
+            continue;
+        }
+
+        if voiced {
+            silence_frames = 0;
+            last_speech_end = frame_end;
+        } else {
+            silence_frames += 1;
+        }
+
+        if silence_frames >= config.speech_end_frames {
+            close(start, last_speech_end, &mut ranges);
+            is_speaking = false;
+            speech_frames = 0;
+        } else if frame_end - start >= max_len {
+            close(start, last_speech_end.max(start + min_len), &mut ranges);
+            start = frame_end;
+            last_speech_end = frame_end;
+        }
+    }
+
+    if is_speaking {
+        close(start, last_speech_end, &mut ranges);
+    }
+    ranges
+}
+
+/// One utterance in the dataset
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct DatasetClip {
+    pub id: String,
+    /// Source recording file name
+    pub source: String,
+    /// Position within the source recording
+    pub start_secs: f32,
+    pub end_secs: f32,
+    /// Sliced clip WAV (32kHz mono)
+    pub audio_path: PathBuf,
+    /// Transcript (ASR output, possibly edited by the user)
+    #[serde(default)]
+    pub transcript: String,
+    /// Whether ASR has been run (or skipped) for this clip
+    #[serde(default)]
+    pub transcribed: bool,
+    /// Whether the clip is used for training
+    pub included: bool,
+}
+
+impl DatasetClip {
+    pub fn duration_secs(&self) -> f32 {
+        self.end_secs - self.start_secs
+    }
+}
+
+/// Dataset manifest passed to the training service
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DatasetManifest {
+    /// Manifest version for future compatibility
+    pub version: String,
+    pub language: String,
+    pub sample_rate: u32,
+    /// Included clips only
+    pub clips: Vec<DatasetClip>,
+}
+
+impl DatasetManifest {
+    /// Total duration of all clips in seconds
+    pub fn total_duration_secs(&self) -> f32 {
+        self.clips.iter().map(DatasetClip::duration_secs).sum()
+    }
+
+    /// Longest clip, used as the reference audio for the trained voice
+    pub fn reference_clip(&self) -> Option<&DatasetClip> {
+        self.clips
+            .iter()
+            .max_by(|a, b| a.duration_secs().total_cmp(&b.duration_secs()))
+    }
+
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let content =
+            fs::read_to_string(path).map_err(|e| format!("Failed to read manifest: {}", e))?;
+        serde_json::from_str(&content).map_err(|e| format!("Failed to parse manifest: {}", e))
+    }
+}
+
+/// Get the datasets base directory
+pub fn get_datasets_dir() -> PathBuf {
+    crate::voice_persistence::get_primespeech_dir().join("datasets")
+}
+
+/// Load a WAV file as mono f32 samples
+///
+/// Returns (samples, sample_rate).
+pub fn load_wav_mono(path: &Path) -> Result<(Vec<f32>, u32), String> {
+    let reader =
+        hound::WavReader::open(path).map_err(|e| format!("Failed to read audio file: {}", e))?;
+    let spec = reader.spec();
+    let channels = spec.channels.max(1) as usize;
+
+    let interleaved: Vec<f32> = match spec.sample_format {
+        hound::SampleFormat::Int => {
+            let max_val = (1u32 << (spec.bits_per_sample - 1)) as f32;
+            reader
+                .into_samples::<i32>()
+                .filter_map(|s| s.ok())
+                .map(|s| s as f32 / max_val)
+                .collect()
+        }
+        hound::SampleFormat::Float => reader.into_samples::<f32>().filter_map(|s| s.ok()).collect(),
+    };
+
+    let mono = interleaved
+        .chunks(channels)
+        .map(|chunk| chunk.iter().sum::<f32>() / channels as f32)
+        .collect();
+    Ok((mono, spec.sample_rate))
+}
+
+fn save_wav_mono(path: &Path, samples: &[f32], sample_rate: u32) -> Result<(), String> {
+    let spec = hound::WavSpec {
+        channels: 1,
+        sample_rate,
+        bits_per_sample: 16,
+        sample_format: hound::SampleFormat::Int,
+    };
+    let mut writer =
+        hound::WavWriter::create(path, spec).map_err(|e| format!("Failed to create WAV: {}", e))?;
+    for &sample in samples {
+        writer
+            .write_sample((sample * 32767.0).clamp(-32768.0, 32767.0) as i16)
+            .map_err(|e| format!("Failed to write sample: {}", e))?;
+    }
+    writer
+        .finalize()
+        .map_err(|e| format!("Failed to finalize WAV: {}", e))
+}
+
+/// Builds a dataset from imported recordings
+pub struct DatasetBuilder {
+    dir: PathBuf,
+    language: String,
+    clips: Vec<DatasetClip>,
+    vad: VadConfig,
+}
+
+impl DatasetBuilder {
+    /// Create a builder that stores clips under the datasets directory
+    pub fn new(dataset_id: &str, language: &str) -> Self {
+        Self::with_dir(get_datasets_dir().join(dataset_id), language)
+    }
+
+    /// Create a builder rooted at an explicit directory
+    pub fn with_dir(dir: PathBuf, language: &str) -> Self {
+        Self {
+            dir,
+            language: language.to_string(),
+            clips: Vec::new(),
+            vad: VadConfig::default(),
+        }
+    }
+
+    pub fn set_language(&mut self, language: &str) {
+        self.language = language.to_string();
+    }
+
+    pub fn clips(&self) -> &[DatasetClip] {
+        &self.clips
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.clips.is_empty()
+    }
+
+    /// Slice a 32kHz mono recording and store its clips
+    ///
+    /// Returns the number of clips added.
+    pub fn add_recording(&mut self, source: &str, samples: &[f32]) -> Result<usize, String> {
+        let clips_dir = self.dir.join("clips");
+        fs::create_dir_all(&clips_dir)
+            .map_err(|e| format!("Failed to create {}: {}", clips_dir.display(), e))?;
+
+        let ranges = slice_utterances(samples, DATASET_SAMPLE_RATE, &self.vad);
+        let rate = DATASET_SAMPLE_RATE as f32;
+        for range in &ranges {
+            let id = format!("clip_{:04}", self.clips.len() + 1);
+            let audio_path = clips_dir.join(format!("{}.wav", id));
+            save_wav_mono(&audio_path, &samples[range.clone()], DATASET_SAMPLE_RATE)?;
+            self.clips.push(DatasetClip {
+                id,
+                source: source.to_string(),
+                start_secs: range.start as f32 / rate,
+                end_secs: range.end as f32 / rate,
+                audio_path,
+                transcript: String::new(),
+                transcribed: false,
+                included: true,
+            });
+        }
+        Ok(ranges.len())
+    }
+
+    pub fn set_included(&mut self, index: usize, included: bool) {
+        if let Some(clip) = self.clips.get_mut(index) {
+            clip.included = included;
+        }
+    }
+
+    pub fn set_transcript(&mut self, index: usize, transcript: &str) {
+        if let Some(clip) = self.clips.get_mut(index) {
+            clip.transcript = transcript.trim().to_string();
+            clip.transcribed = true;
+        }
+    }
+
+    /// Mark a clip as transcribed without text (e.g. ASR timed out) and exclude it
+    pub fn skip_transcription(&mut self, index: usize) {
+        if let Some(clip) = self.clips.get_mut(index) {
+            clip.transcribed = true;
+            clip.included = false;
+        }
+    }
+
+    /// Next included clip that still needs ASR
+    pub fn next_untranscribed(&self) -> Option<usize> {
+        self.clips
+            .iter()
+            .position(|c| c.included && !c.transcribed)
+    }
+
+    /// Read a clip's samples back from disk (32kHz mono)
+    pub fn clip_samples(&self, index: usize) -> Result<Vec<f32>, String> {
+        let clip = self
+            .clips
+            .get(index)
+            .ok_or_else(|| format!("No clip at index {}", index))?;
+        load_wav_mono(&clip.audio_path).map(|(samples, _)| samples)
+    }
+
+    /// Total duration of included clips in seconds
+    pub fn included_duration_secs(&self) -> f32 {
+        self.clips
+            .iter()
+            .filter(|c| c.included)
+            .map(DatasetClip::duration_secs)
+            .sum()
+    }
+
+    /// Build the manifest from included clips
+    pub fn build_manifest(&self) -> Result<DatasetManifest, String> {
+        let clips: Vec<DatasetClip> = self.clips.iter().filter(|c| c.included).cloned().collect();
+        if clips.is_empty() {
+            return Err("No clips included in the dataset".to_string());
+        }
+        if let Some(clip) = clips.iter().find(|c| c.transcript.is_empty()) {
+            return Err(format!("Clip {} has no transcript", clip.id));
+        }
+        Ok(DatasetManifest {
+            version: "1.0".to_string(),
+            language: self.language.clone(),
+            sample_rate: DATASET_SAMPLE_RATE,
+            clips,
+        })
+    }
+
+    /// Build and write manifest.json, returning its path
+    pub fn save_manifest(&self) -> Result<PathBuf, String> {
+        let manifest = self.build_manifest()?;
+        let path = self.dir.join("manifest.json");
+        let json = serde_json::to_string_pretty(&manifest)
+            .map_err(|e| format!("Failed to serialize manifest: {}", e))?;
+        fs::write(&path, json).map_err(|e| format!("Failed to write manifest: {}", e))?;
+        Ok(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 0.5s silence, 2s tone, 1s silence, 1.5s tone, 0.5s silence at 16kHz
+    fn synthetic_recording(rate: u32) -> Vec<f32> {
+        let segment = |secs: f32, amp: f32| {
+            (0..(secs * rate as f32) as usize)
+                .map(move |i| amp * (i as f32 * 0.05).sin())
+        };
+        segment(0.5, 0.0)
+            .chain(segment(2.0, 0.3))
+            .chain(segment(1.0, 0.0))
+            .chain(segment(1.5, 0.3))
+            .chain(segment(0.5, 0.0))
+            .collect()
+    }
+
+    #[test]
+    fn test_slice_utterances() {
+        let rate = 16000;
+        let ranges = slice_utterances(&synthetic_recording(rate), rate, &VadConfig::default());
+        assert_eq!(ranges.len(), 2);
+
+        let secs = |s: usize| s as f32 / rate as f32;
+        assert!((secs(ranges[0].start) - 0.4).abs() < 0.05);
+        assert!((secs(ranges[0].end) - 2.6).abs() < 0.05);
+        assert!((secs(ranges[1].end - ranges[1].start) - 1.7).abs() < 0.05);
+    }
+
+    #[test]
+    fn test_slice_respects_max_length() {
+        let rate = 16000;
+        let tone: Vec<f32> = (0..rate as usize * 30).map(|i| 0.3 * (i as f32 * 0.05).sin()).collect();
+        let config = VadConfig::default();
+        let ranges = slice_utterances(&tone, rate, &config);
+        assert!(ranges.len() >= 3);
+        for range in ranges {
+            let secs = (range.end - range.start) as f32 / rate as f32;
+            assert!(secs <= config.max_clip_secs + 0.2);
+        }
+    }
+
+    #[test]
+    fn test_slice_short_trailing_frame() {
+        // Speech starts in a frame shorter than frame_len
+        let rate = 16000;
+        let ranges = slice_utterances(&vec![0.5; 400], rate, &VadConfig::default());
+        assert!(ranges.is_empty());
+
+        let config = VadConfig { min_clip_secs: 0.0, padding_ms: 0, ..VadConfig::default() };
+        let ranges = slice_utterances(&vec![0.5; 400], rate, &config);
+        assert_eq!(ranges, vec![0..400]);
+    }
+
+    #[test]
+    fn test_manifest_requires_transcripts() {
+        let dir = std::env::temp_dir().join("mofa_dataset_builder_test");
+        let _ = fs::remove_dir_all(&dir);
+        let mut builder = DatasetBuilder::with_dir(dir.clone(), "zh");
+
+        let added = builder
+            .add_recording("test.wav", &synthetic_recording(DATASET_SAMPLE_RATE))
+            .unwrap();
+        assert_eq!(added, 2);
+        assert!(builder.build_manifest().is_err());
+
+        builder.set_transcript(0, " 你好 ");
+        builder.set_included(1, false);
+        assert_eq!(builder.next_untranscribed(), None);
+
+        let manifest = DatasetManifest::load(&builder.save_manifest().unwrap()).unwrap();
+        assert_eq!(manifest.clips.len(), 1);
+        assert_eq!(manifest.clips[0].transcript, "你好");
+        let clip_secs = builder.clip_samples(0).unwrap().len() as f32 / DATASET_SAMPLE_RATE as f32;
+        assert!((clip_secs - manifest.clips[0].duration_secs()).abs() < 0.01);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,454 @@
+//! Dataset editor component - table of sliced clips for few-shot training
+//!
+//! Shows one row per clip (include toggle, time range, transcript). Clicking a row
+//! selects it for editing in the transcript field below the list. The owning modal
+//! handles file import and ASR round-trips through the `DatasetEditorRef` API.
+
+use crate::dataset_builder::DatasetBuilder;
+use makepad_widgets::*;
+use std::path::PathBuf;
+
+live_design! {
+    use link::theme::*;
+    use link::shaders::*;
+    use link::widgets::*;
+
+    use mofa_widgets::theme::*;
+
+    DatasetButton = <Button> {
+        width: Fit, height: 32
+        padding: {left: 12, right: 12, top: 6, bottom: 6}
+
+        draw_bg: {
+            instance dark_mode: 0.0
+            fn pixel(self) -> vec4 {
+                let sdf = Sdf2d::viewport(self.pos * self.rect_size);
+                sdf.box(1., 1., self.rect_size.x - 2., self.rect_size.y - 2., 6.0);
+                let bg = mix((SLATE_100), (SLATE_700), self.dark_mode);
+                let border = mix((SLATE_300), (SLATE_500), self.dark_mode);
+                sdf.fill(bg);
+                sdf.stroke(border, 1.0);
+                return sdf.result;
+            }
+        }
+
+        draw_text: {
+            instance dark_mode: 0.0
+            text_style: <FONT_SEMIBOLD>{ font_size: 10.0 }
+            fn get_color(self) -> vec4 {
+                return mix((TEXT_PRIMARY), (TEXT_PRIMARY_DARK), self.dark_mode);
+            }
+        }
+    }
+
+    DatasetClipItem = <View> {
+        width: Fill, height: Fit
+        padding: {left: 8, right: 8, top: 6, bottom: 6}
+        flow: Right
+        align: {y: 0.5}
+        spacing: 10
+        cursor: Hand
+
+        show_bg: true
+        draw_bg: {
+            instance dark_mode: 0.0
+            instance selected: 0.0
+            fn pixel(self) -> vec4 {
+                let base = mix((SURFACE), (SURFACE_DARK), self.dark_mode);
+                let selected_color = mix((PRIMARY_50), (PRIMARY_900), self.dark_mode);
+                return mix(base, selected_color, self.selected);
+            }
+        }
+
+        include_toggle = <View> {
+            width: 16, height: 16
+            cursor: Hand
+            show_bg: true
+            draw_bg: {
+                instance dark_mode: 0.0
+                instance included: 1.0
+                fn pixel(self) -> vec4 {
+                    let sdf = Sdf2d::viewport(self.pos * self.rect_size);
+                    sdf.box(1., 1., self.rect_size.x - 2., self.rect_size.y - 2., 3.0);
+                    let off = mix((WHITE), (SLATE_700), self.dark_mode);
+                    sdf.fill(mix(off, (PRIMARY_500), self.included));
+                    sdf.stroke(mix(mix((SLATE_300), (SLATE_500), self.dark_mode), (PRIMARY_500), self.included), 1.0);
+                    // Check mark
+                    sdf.move_to(4.0, 8.0);
+                    sdf.line_to(7.0, 11.0);
+                    sdf.line_to(12.0, 5.0);
+                    sdf.stroke(vec4(1.0, 1.0, 1.0, self.included), 1.5);
+                    return sdf.result;
+                }
+            }
+        }
+
+        time_label = <Label> {
+            width: 120, height: Fit
+            draw_text: {
+                instance dark_mode: 0.0
+                text_style: { font_size: 10.0 }
+                fn get_color(self) -> vec4 {
+                    return mix((TEXT_TERTIARY), (TEXT_TERTIARY_DARK), self.dark_mode);
+                }
+            }
+            text: ""
+        }
+
+        transcript_label = <Label> {
+            width: Fill, height: Fit
+            draw_text: {
+                instance dark_mode: 0.0
+                text_style: { font_size: 11.0 }
+                wrap: Ellipsis
+                fn get_color(self) -> vec4 {
+                    return mix((TEXT_PRIMARY), (TEXT_PRIMARY_DARK), self.dark_mode);
+                }
+            }
+            text: ""
+        }
+    }
+
+    pub DatasetEditor = {{DatasetEditor}} {
+        width: Fill, height: Fit
+        flow: Down
+        spacing: 8
+
+        header = <View> {
+            width: Fill, height: Fit
+            flow: Right
+            spacing: 8
+            align: {y: 0.5}
+
+            title = <Label> {
+                width: Fit, height: Fit
+                draw_text: {
+                    instance dark_mode: 0.0
+                    text_style: <FONT_SEMIBOLD>{ font_size: 12.0 }
+                    fn get_color(self) -> vec4 {
+                        return mix((TEXT_PRIMARY), (TEXT_PRIMARY_DARK), self.dark_mode);
+                    }
+                }
+                text: "Dataset"
+            }
+
+            summary_label = <Label> {
+                width: Fill, height: Fit
+                draw_text: {
+                    instance dark_mode: 0.0
+                    text_style: { font_size: 11.0 }
+                    fn get_color(self) -> vec4 {
+                        return mix((TEXT_SECONDARY), (TEXT_SECONDARY_DARK), self.dark_mode);
+                    }
+                }
+                text: "Import several recordings to build a multi-clip dataset"
+            }
+
+            add_files_btn = <DatasetButton> { text: "Add Files" }
+            transcribe_btn = <DatasetButton> { text: "Transcribe All" }
+        }
+
+        clip_list = <PortalList> {
+            width: Fill, height: 200
+            flow: Down
+            visible: false
+
+            DatasetClipItem = <DatasetClipItem> {}
+        }
+
+        transcript_editor = <TextInput> {
+            width: Fill, height: Fit
+            padding: {left: 12, right: 12, top: 8, bottom: 8}
+            visible: false
+            empty_text: "Select a clip to edit its transcript..."
+
+            draw_bg: {
+                instance dark_mode: 0.0
+                fn pixel(self) -> vec4 {
+                    let sdf = Sdf2d::viewport(self.pos * self.rect_size);
+                    sdf.box(0., 0., self.rect_size.x, self.rect_size.y, 6.0);
+                    sdf.fill(mix((WHITE), (SLATE_700), self.dark_mode));
+                    sdf.stroke(mix((SLATE_200), (SLATE_600), self.dark_mode), 1.0);
+                    return sdf.result;
+                }
+            }
+
+            draw_text: {
+                instance dark_mode: 0.0
+                text_style: { font_size: 12.0 }
+                fn get_color(self) -> vec4 {
+                    return mix((TEXT_PRIMARY), (TEXT_PRIMARY_DARK), self.dark_mode);
+                }
+            }
+        }
+    }
+}
+
+/// Actions emitted by the dataset editor
+#[derive(Clone, Debug, DefaultNone)]
+pub enum DatasetEditorAction {
+    None,
+    /// User wants to import recordings (modal opens the file dialog)
+    AddFilesClicked,
+    /// User wants to run ASR on all untranscribed clips
+    TranscribeClicked,
+    /// Clip inclusion or transcript changed
+    DatasetChanged,
+}
+
+#[derive(Live, LiveHook, Widget)]
+pub struct DatasetEditor {
+    #[deref]
+    view: View,
+
+    #[rust]
+    builder: Option<DatasetBuilder>,
+
+    #[rust]
+    selected_clip: Option<usize>,
+
+    #[rust]
+    dark_mode: f64,
+
+    /// Drawn item areas for hit testing: (clip_index, item_area, toggle_area)
+    #[rust]
+    item_areas: Vec<(usize, Area, Area)>,
+}
+
+impl Widget for DatasetEditor {
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
+        self.view.handle_event(cx, event, scope);
+
+        let add_btn = self.view.button(ids!(header.add_files_btn));
+        if let Hit::FingerUp(fe) = event.hits(cx, add_btn.area()) {
+            if fe.was_tap() {
+                cx.widget_action(self.widget_uid(), &scope.path, DatasetEditorAction::AddFilesClicked);
+            }
+        }
+
+        let transcribe_btn = self.view.button(ids!(header.transcribe_btn));
+        if let Hit::FingerUp(fe) = event.hits(cx, transcribe_btn.area()) {
+            if fe.was_tap() {
+                cx.widget_action(self.widget_uid(), &scope.path, DatasetEditorAction::TranscribeClicked);
+            }
+        }
+
+        // Portal list rows use stored areas (see VoiceSelector for the same pattern)
+        for (index, item_area, toggle_area) in self.item_areas.clone() {
+            if let Hit::FingerUp(fe) = event.hits(cx, toggle_area) {
+                if fe.was_tap() {
+                    if let Some(builder) = &mut self.builder {
+                        let included = builder.clips()[index].included;
+                        builder.set_included(index, !included);
+                    }
+                    self.update_summary(cx);
+                    cx.widget_action(self.widget_uid(), &scope.path, DatasetEditorAction::DatasetChanged);
+                    continue;
+                }
+            }
+            if let Hit::FingerUp(fe) = event.hits(cx, item_area) {
+                if fe.was_tap() {
+                    self.select_clip(cx, index);
+                }
+            }
+        }
+
+        let Event::Actions(actions) = event else {
+            return;
+        };
+
+        if let Some(text) = self.view.text_input(ids!(transcript_editor)).changed(actions) {
+            if let (Some(builder), Some(index)) = (&mut self.builder, self.selected_clip) {
+                builder.set_transcript(index, &text);
+                self.view.redraw(cx);
+                cx.widget_action(self.widget_uid(), &scope.path, DatasetEditorAction::DatasetChanged);
+            }
+        }
+    }
+
+    fn draw_walk(&mut self, cx: &mut Cx2d, scope: &mut Scope, walk: Walk) -> DrawStep {
+        self.item_areas.clear();
+        let clips = self
+            .builder
+            .as_ref()
+            .map(|b| b.clips().to_vec())
+            .unwrap_or_default();
+
+        while let Some(item) = self.view.draw_walk(cx, scope, walk).step() {
+            if let Some(mut list) = item.as_portal_list().borrow_mut() {
+                list.set_item_range(cx, 0, clips.len());
+
+                while let Some(index) = list.next_visible_item(cx) {
+                    let Some(clip) = clips.get(index) else {
+                        continue;
+                    };
+                    let item = list.item(cx, index, live_id!(DatasetClipItem));
+
+                    item.label(ids!(time_label)).set_text(
+                        cx,
+                        &format!("{} {:.1}-{:.1}s", clip.id, clip.start_secs, clip.end_secs),
+                    );
+                    let transcript = if !clip.transcript.is_empty() {
+                        clip.transcript.as_str()
+                    } else if clip.transcribed {
+                        "(no transcript)"
+                    } else {
+                        "(not transcribed)"
+                    };
+                    item.label(ids!(transcript_label)).set_text(cx, transcript);
+
+                    let selected = if self.selected_clip == Some(index) { 1.0 } else { 0.0 };
+                    let included = if clip.included { 1.0 } else { 0.0 };
+                    item.apply_over(cx, live! {
+                        draw_bg: { selected: (selected), dark_mode: (self.dark_mode) }
+                    });
+                    item.view(ids!(include_toggle)).apply_over(cx, live! {
+                        draw_bg: { included: (included), dark_mode: (self.dark_mode) }
+                    });
+
+                    item.draw_all(cx, scope);
+                    self.item_areas
+                        .push((index, item.area(), item.view(ids!(include_toggle)).area()));
+                }
+            }
+        }
+        DrawStep::done()
+    }
+}
+
+impl DatasetEditor {
+    fn select_clip(&mut self, cx: &mut Cx, index: usize) {
+        let Some(clip) = self.builder.as_ref().and_then(|b| b.clips().get(index)) else {
+            return;
+        };
+        let transcript = clip.transcript.clone();
+        self.selected_clip = Some(index);
+        let editor = self.view.text_input(ids!(transcript_editor));
+        editor.set_text(cx, &transcript);
+        editor.set_visible(cx, true);
+        self.view.redraw(cx);
+    }
+
+    fn update_summary(&mut self, cx: &mut Cx) {
+        let text = match &self.builder {
+            Some(builder) if !builder.is_empty() => {
+                let clips = builder.clips();
+                let included = clips.iter().filter(|c| c.included).count();
+                let transcribed = clips.iter().filter(|c| c.transcribed).count();
+                format!(
+                    "{} clips · {} included ({:.1} min) · {} transcribed",
+                    clips.len(),
+                    included,
+                    builder.included_duration_secs() / 60.0,
+                    transcribed
+                )
+            }
+            _ => "Import several recordings to build a multi-clip dataset".to_string(),
+        };
+        self.view.label(ids!(header.summary_label)).set_text(cx, &text);
+        let has_clips = self.builder.as_ref().is_some_and(|b| !b.is_empty());
+        self.view.portal_list(ids!(clip_list)).set_visible(cx, has_clips);
+        self.view.redraw(cx);
+    }
+}
+
+impl DatasetEditorRef {
+    /// Slice a 32kHz mono recording into clips and add them to the dataset
+    ///
+    /// A new dataset is started on first import. Returns the number of clips added.
+    pub fn add_recording(
+        &self,
+        cx: &mut Cx,
+        dataset_id: &str,
+        language: &str,
+        source: &str,
+        samples: &[f32],
+    ) -> Result<usize, String> {
+        let Some(mut inner) = self.borrow_mut() else {
+            return Err("Dataset editor not available".to_string());
+        };
+        let builder = inner
+            .builder
+            .get_or_insert_with(|| DatasetBuilder::new(dataset_id, language));
+        builder.set_language(language);
+        let added = builder.add_recording(source, samples)?;
+        inner.update_summary(cx);
+        Ok(added)
+    }
+
+    /// Whether any clips have been imported
+    pub fn has_clips(&self) -> bool {
+        self.borrow()
+            .is_some_and(|inner| inner.builder.as_ref().is_some_and(|b| !b.is_empty()))
+    }
+
+    /// Next clip that needs ASR, with its 32kHz samples
+    pub fn next_untranscribed(&self) -> Option<(usize, Vec<f32>)> {
+        let inner = self.borrow()?;
+        let builder = inner.builder.as_ref()?;
+        let index = builder.next_untranscribed()?;
+        match builder.clip_samples(index) {
+            Ok(samples) => Some((index, samples)),
+            Err(e) => {
+                log::error!("Failed to load clip {}: {}", index, e);
+                None
+            }
+        }
+    }
+
+    /// Store an ASR transcript (or `None` to skip and exclude the clip)
+    pub fn set_transcript(&self, cx: &mut Cx, index: usize, transcript: Option<&str>) {
+        if let Some(mut inner) = self.borrow_mut() {
+            if let Some(builder) = &mut inner.builder {
+                match transcript {
+                    Some(text) => builder.set_transcript(index, text),
+                    None => builder.skip_transcription(index),
+                }
+            }
+            if inner.selected_clip == Some(index) {
+                inner.select_clip(cx, index);
+            }
+            inner.update_summary(cx);
+        }
+    }
+
+    /// Write manifest.json for the included clips
+    ///
+    /// Returns (manifest_path, reference_clip_path).
+    pub fn save_manifest(&self) -> Result<(PathBuf, PathBuf), String> {
+        let inner = self.borrow().ok_or("Dataset editor not available")?;
+        let builder = inner.builder.as_ref().ok_or("No clips imported")?;
+        let manifest = builder.build_manifest()?;
+        let reference = manifest
+            .reference_clip()
+            .map(|c| c.audio_path.clone())
+            .ok_or("No clips included in the dataset")?;
+        Ok((builder.save_manifest()?, reference))
+    }
+
+    /// Drop all clips (files on disk are kept)
+    pub fn clear(&self, cx: &mut Cx) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.builder = None;
+            inner.selected_clip = None;
+            inner.view.text_input(ids!(transcript_editor)).set_visible(cx, false);
+            inner.update_summary(cx);
+        }
+    }
+
+    pub fn update_dark_mode(&self, cx: &mut Cx, dark_mode: f64) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.dark_mode = dark_mode;
+            inner.view.apply_over(cx, live! {
+                header = {
+                    title = { draw_text: { dark_mode: (dark_mode) } }
+                    summary_label = { draw_text: { dark_mode: (dark_mode) } }
+                    add_files_btn = { draw_bg: { dark_mode: (dark_mode) } draw_text: { dark_mode: (dark_mode) } }
+                    transcribe_btn = { draw_bg: { dark_mode: (dark_mode) } draw_text: { dark_mode: (dark_mode) } }
+                }
+                transcript_editor = { draw_bg: { dark_mode: (dark_mode) } draw_text: { dark_mode: (dark_mode) } }
+            });
+            inner.view.redraw(cx);
+        }
+    }
+}
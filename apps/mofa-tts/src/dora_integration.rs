@@ -1,19 +1,33 @@
 //! Dora Integration for MoFA TTS
 //!
 //! Manages the lifecycle of dora bridges and routes data between
-//! the dora dataflow and MoFA widgets.
+//! the dora dataflows and MoFA widgets.
+//!
+//! Several dataflows can run side by side (TTS next to an ASR or training
+//! dataflow, or a warm standby TTS for a second voice model). Each is
+//! addressed by name through a [`DataflowHandle`] with its own dispatcher,
+//! [`SharedDoraState`], status flags and event queue; one worker thread
+//! supervises all of them. The methods on [`DoraIntegration`] itself drive
+//! the [`DEFAULT_DATAFLOW`].
 
 use crossbeam_channel::{bounded, Receiver, Sender};
 use mofa_dora_bridge::{
-    controller::DataflowController, dispatcher::DynamicNodeDispatcher, SharedDoraState,
+    controller::DataflowController, dispatcher::DynamicNodeDispatcher, BridgeResult, ModelStatus,
+    NodeLogCollector, NodeState, NodeStatus, SendHandle, SendOutcome, SendRequest, Severity,
+    SharedDoraState,
 };
-use std::path::PathBuf;
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
+
+/// Dataflow driven by the single-dataflow methods of [`DoraIntegration`]
+pub const DEFAULT_DATAFLOW: &str = "tts";
 
-/// Commands sent from UI to dora integration
+/// Commands sent from UI to one dataflow of the dora integration
 #[derive(Debug, Clone)]
 pub enum DoraCommand {
     /// Start the dataflow with optional environment variables
@@ -31,6 +45,8 @@ pub enum DoraCommand {
         sample_rate: u32,
         language: String,
     },
+    /// Mark the in-flight request as answered so it is not replayed after recovery
+    CompleteRequest,
 }
 
 /// Events sent from dora integration to UI
@@ -44,110 +60,217 @@ pub enum DoraEvent {
     Error { message: String },
     /// ASR transcription result
     AsrTranscription { text: String, language: String },
+    /// Dataflow died unexpectedly and a restart is scheduled (1-based attempt)
+    Recovering { attempt: u32 },
+    /// TTS model is loading (progress 0.0–1.0 when the node reports it)
+    ModelLoading {
+        progress: Option<f32>,
+        message: String,
+    },
+    /// All bridges connected and the TTS model reported ready
+    Ready,
+    /// A prompt or audio request failed or got no reply in time
+    RequestFailed { message: String },
+    /// The in-flight prompt or audio request was answered (for a prompt,
+    /// TTS has sent all of its audio)
+    RequestCompleted,
 }
 
-/// Helper function to send data with retry logic
-///
-/// Retries up to 10 times with 150ms delay between attempts (1.5s total).
-/// This is reduced from 20 retries to minimize thread blocking in the worker.
-fn send_with_retry(
-    bridge: &dyn mofa_dora_bridge::DoraBridge,
-    output: &str,
-    data: mofa_dora_bridge::DoraData,
-) -> Result<(), String> {
-    const MAX_RETRIES: u32 = 10;
-    const RETRY_DELAY_MS: u64 = 150;
-
-    for attempt in 1..=MAX_RETRIES {
-        match bridge.send(output, data.clone()) {
-            Ok(_) => {
-                if attempt > 1 {
-                    log::info!("Successfully sent data on attempt {}", attempt);
-                }
-                return Ok(());
-            }
-            Err(e) => {
-                if attempt == MAX_RETRIES {
-                    return Err(format!("Failed after {} retries: {}", MAX_RETRIES, e));
-                }
-                std::thread::sleep(Duration::from_millis(RETRY_DELAY_MS));
-            }
+/// How long to wait for bridges and model after the dataflow started
+const READY_TIMEOUT: Duration = Duration::from_secs(180);
+
+/// Input on a MoFA node that carries the TTS model status
+const MODEL_STATUS_INPUT: &str = "tts_status";
+
+/// How often node log files are tailed into the shared log
+const NODE_LOG_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Input on which TTS reports a prompt as synthesized
+const TTS_REPLY_INPUT: &str = "segment_complete";
+
+/// Input on which ASR delivers the transcription of submitted audio
+const ASR_REPLY_INPUT: &str = "transcription";
+
+/// How long a prompt may take to synthesize (long texts on CPU are slow)
+const TTS_REPLY_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// How long a recording may take to transcribe
+const ASR_REPLY_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Supervision policy for restarting a dataflow that stopped unexpectedly
+#[derive(Debug, Clone)]
+pub struct RestartPolicy {
+    /// Delay before the first restart attempt
+    pub initial_backoff: Duration,
+    /// Upper bound for the exponential backoff
+    pub max_backoff: Duration,
+    /// Number of restart attempts before giving up (0 disables recovery)
+    pub max_retries: u32,
+}
+
+impl Default for RestartPolicy {
+    fn default() -> Self {
+        Self {
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(30),
+            max_retries: 5,
         }
     }
-    Err("Retry exhausted".into())
 }
 
-/// Dora integration manager
-pub struct DoraIntegration {
+impl RestartPolicy {
+    /// Delay before the given restart attempt (1-based), doubling each time
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+        self.initial_backoff
+            .saturating_mul(factor)
+            .min(self.max_backoff)
+    }
+}
+
+/// Readiness handshake in progress after a (re)start
+#[derive(Clone, Copy)]
+struct ReadyWait {
+    deadline: Instant,
+    /// Whether the dataflow routes a model status to a MoFA node
+    needs_model: bool,
+}
+
+impl ReadyWait {
+    fn for_dispatcher(disp: &DynamicNodeDispatcher) -> Self {
+        let needs_model = disp
+            .discover_mofa_nodes()
+            .iter()
+            .any(|node| node.inputs.iter().any(|input| input.id == MODEL_STATUS_INPUT));
+        Self {
+            deadline: Instant::now() + READY_TIMEOUT,
+            needs_model,
+        }
+    }
+}
+
+/// Restart in progress after an unexpected stop
+struct Recovery {
+    attempt: u32,
+    next_attempt_at: Instant,
+}
+
+/// Last request sent to the dataflow, replayed if the dataflow is restarted
+/// before it was answered
+#[derive(Debug, Clone)]
+enum PendingRequest {
+    Prompt {
+        message: String,
+    },
+    Audio {
+        audio_samples: Vec<f32>,
+        sample_rate: u32,
+        language: String,
+    },
+}
+
+impl PendingRequest {
+    fn describe(&self) -> &'static str {
+        match self {
+            PendingRequest::Prompt { .. } => "TTS request",
+            PendingRequest::Audio { .. } => "ASR request",
+        }
+    }
+}
+
+/// Lifecycle flags written by the worker and read by the UI
+#[derive(Clone, Default)]
+struct WorkerFlags {
     /// Whether dataflow is currently running
     running: Arc<AtomicBool>,
+    /// Whether the worker is restarting a dataflow that stopped unexpectedly
+    recovering: Arc<AtomicBool>,
+    /// Whether bridges are connected and the TTS model is loaded
+    ready: Arc<AtomicBool>,
+}
+
+/// UI side of one named dataflow, shared with the worker
+#[derive(Clone)]
+struct DataflowChannels {
+    /// Lifecycle flags written by the worker
+    flags: WorkerFlags,
     /// Shared state for direct Dora↔UI communication
     shared_dora_state: Arc<SharedDoraState>,
-    /// Command sender (UI -> dora thread)
-    command_tx: Sender<DoraCommand>,
+    /// Event sender (dora thread -> UI)
+    event_tx: Sender<DoraEvent>,
     /// Event receiver (dora thread -> UI)
     event_rx: Receiver<DoraEvent>,
-    /// Worker thread handle
-    worker_handle: Option<thread::JoinHandle<()>>,
-    /// Stop signal
-    stop_tx: Option<Sender<()>>,
 }
 
-impl DoraIntegration {
-    /// Create a new dora integration (not started)
-    pub fn new() -> Self {
-        let (command_tx, command_rx) = bounded(100);
+impl DataflowChannels {
+    fn new() -> Self {
         let (event_tx, event_rx) = bounded(100);
-        let (stop_tx, stop_rx) = bounded(1);
+        Self {
+            flags: WorkerFlags::default(),
+            shared_dora_state: SharedDoraState::new(),
+            event_tx,
+            event_rx,
+        }
+    }
+}
 
-        let running = Arc::new(AtomicBool::new(false));
-        let running_clone = Arc::clone(&running);
+/// Channels of every dataflow, by name
+type DataflowTable = Arc<Mutex<HashMap<String, DataflowChannels>>>;
 
-        // Create shared state for direct Dora↔UI communication
-        let shared_dora_state = SharedDoraState::new();
-        let shared_dora_state_clone = Arc::clone(&shared_dora_state);
+/// Channels for `name`, created on first use
+fn channels_for(table: &DataflowTable, name: &str) -> DataflowChannels {
+    table
+        .lock()
+        .entry(name.to_string())
+        .or_insert_with(DataflowChannels::new)
+        .clone()
+}
 
-        // Spawn worker thread
-        let handle = thread::spawn(move || {
-            Self::run_worker(
-                running_clone,
-                shared_dora_state_clone,
-                command_rx,
-                event_tx,
-                stop_rx,
-            );
-        });
+/// Handle to one named dataflow of a [`DoraIntegration`]
+///
+/// Cheap to clone. Commands go through the integration's worker; status,
+/// events and shared state belong to this dataflow only.
+#[derive(Clone)]
+pub struct DataflowHandle {
+    name: String,
+    channels: DataflowChannels,
+    command_tx: Sender<(String, DoraCommand)>,
+}
 
-        Self {
-            running,
-            shared_dora_state,
-            command_tx,
-            event_rx,
-            worker_handle: Some(handle),
-            stop_tx: Some(stop_tx),
-        }
+impl DataflowHandle {
+    /// Name the dataflow was opened with
+    pub fn name(&self) -> &str {
+        &self.name
     }
 
     /// Get shared Dora state for direct UI polling
     pub fn shared_dora_state(&self) -> &Arc<SharedDoraState> {
-        &self.shared_dora_state
+        &self.channels.shared_dora_state
     }
 
-    /// Send a command to the dora integration (non-blocking)
+    /// Send a command to this dataflow (non-blocking)
     pub fn send_command(&self, cmd: DoraCommand) -> bool {
-        self.command_tx.try_send(cmd).is_ok()
+        self.command_tx.try_send((self.name.clone(), cmd)).is_ok()
     }
 
-    /// Start a dataflow with optional environment variables
+    /// Start the dataflow, replacing what this handle was running before
     pub fn start_dataflow(&self, dataflow_path: impl Into<PathBuf>) -> bool {
-        let path = dataflow_path.into();
+        self.start_dataflow_with_env(dataflow_path, HashMap::new())
+    }
+
+    /// Start the dataflow with environment variables for its nodes
+    pub fn start_dataflow_with_env(
+        &self,
+        dataflow_path: impl Into<PathBuf>,
+        env_vars: HashMap<String, String>,
+    ) -> bool {
         self.send_command(DoraCommand::StartDataflow {
-            dataflow_path: path,
-            env_vars: std::collections::HashMap::new(),
+            dataflow_path: dataflow_path.into(),
+            env_vars,
         })
     }
 
-    /// Stop the current dataflow gracefully
+    /// Stop the dataflow gracefully
     pub fn stop_dataflow(&self) -> bool {
         self.send_command(DoraCommand::StopDataflow)
     }
@@ -168,36 +291,401 @@ impl DoraIntegration {
         })
     }
 
-    /// Poll for events (non-blocking)
+    /// Tell the worker the last prompt has been answered (audio received)
+    pub fn complete_request(&self) -> bool {
+        self.send_command(DoraCommand::CompleteRequest)
+    }
+
+    /// Poll for this dataflow's events (non-blocking)
     pub fn poll_events(&self) -> Vec<DoraEvent> {
         let mut events = Vec::new();
-        while let Ok(event) = self.event_rx.try_recv() {
+        while let Ok(event) = self.channels.event_rx.try_recv() {
             events.push(event);
         }
         events
     }
 
-    /// Check if dataflow is running
+    /// Check if the dataflow is running
+    pub fn is_running(&self) -> bool {
+        self.channels.flags.running.load(Ordering::Acquire)
+    }
+
+    /// Check if bridges are connected and the TTS model is ready for requests
+    pub fn is_ready(&self) -> bool {
+        self.channels.flags.ready.load(Ordering::Acquire)
+    }
+
+    /// Check if the dataflow is being restarted after an unexpected stop
+    pub fn is_recovering(&self) -> bool {
+        self.channels.flags.recovering.load(Ordering::Acquire)
+    }
+}
+
+/// Dora integration manager
+pub struct DoraIntegration {
+    /// Channels of every dataflow opened so far
+    dataflows: DataflowTable,
+    /// Handle for [`DEFAULT_DATAFLOW`]
+    default: DataflowHandle,
+    /// Command sender (UI -> dora thread)
+    command_tx: Sender<(String, DoraCommand)>,
+    /// Worker thread handle
+    worker_handle: Option<thread::JoinHandle<()>>,
+    /// Stop signal
+    stop_tx: Option<Sender<()>>,
+}
+
+impl DoraIntegration {
+    /// Create a new dora integration (not started) with the default restart policy
+    pub fn new() -> Self {
+        Self::with_restart_policy(RestartPolicy::default())
+    }
+
+    /// Create a new dora integration (not started) with a custom restart policy
+    ///
+    /// The policy applies to every dataflow.
+    pub fn with_restart_policy(policy: RestartPolicy) -> Self {
+        let (command_tx, command_rx) = bounded(100);
+        let (stop_tx, stop_rx) = bounded(1);
+
+        let dataflows: DataflowTable = Arc::default();
+        let default = DataflowHandle {
+            name: DEFAULT_DATAFLOW.to_string(),
+            channels: channels_for(&dataflows, DEFAULT_DATAFLOW),
+            command_tx: command_tx.clone(),
+        };
+
+        // Spawn worker thread
+        let table = Arc::clone(&dataflows);
+        let handle = thread::spawn(move || {
+            Self::run_worker(policy, table, command_rx, stop_rx);
+        });
+
+        Self {
+            dataflows,
+            default,
+            command_tx,
+            worker_handle: Some(handle),
+            stop_tx: Some(stop_tx),
+        }
+    }
+
+    /// Handle to the dataflow called `name`, opened on first use
+    pub fn dataflow(&self, name: &str) -> DataflowHandle {
+        DataflowHandle {
+            name: name.to_string(),
+            channels: channels_for(&self.dataflows, name),
+            command_tx: self.command_tx.clone(),
+        }
+    }
+
+    /// Names of the dataflows opened so far, sorted
+    pub fn dataflow_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.dataflows.lock().keys().cloned().collect();
+        names.sort();
+        names
+    }
+
+    /// Handle to the [`DEFAULT_DATAFLOW`]
+    pub fn default_dataflow(&self) -> &DataflowHandle {
+        &self.default
+    }
+
+    /// Get shared Dora state of the default dataflow for direct UI polling
+    pub fn shared_dora_state(&self) -> &Arc<SharedDoraState> {
+        self.default.shared_dora_state()
+    }
+
+    /// Send a command to the default dataflow (non-blocking)
+    pub fn send_command(&self, cmd: DoraCommand) -> bool {
+        self.default.send_command(cmd)
+    }
+
+    /// Start the default dataflow
+    pub fn start_dataflow(&self, dataflow_path: impl Into<PathBuf>) -> bool {
+        self.default.start_dataflow(dataflow_path)
+    }
+
+    /// Stop the default dataflow gracefully
+    pub fn stop_dataflow(&self) -> bool {
+        self.default.stop_dataflow()
+    }
+
+    /// Send text to TTS
+    pub fn send_prompt(&self, message: impl Into<String>) -> bool {
+        self.default.send_prompt(message)
+    }
+
+    /// Send audio to ASR for transcription
+    pub fn send_audio(&self, audio_samples: Vec<f32>, sample_rate: u32, language: String) -> bool {
+        self.default
+            .send_audio(audio_samples, sample_rate, language)
+    }
+
+    /// Tell the worker the last prompt has been answered (audio received)
+    pub fn complete_request(&self) -> bool {
+        self.default.complete_request()
+    }
+
+    /// Poll for events of the default dataflow (non-blocking)
+    pub fn poll_events(&self) -> Vec<DoraEvent> {
+        self.default.poll_events()
+    }
+
+    /// Check if the default dataflow is running
     pub fn is_running(&self) -> bool {
-        self.running.load(Ordering::Acquire)
+        self.default.is_running()
+    }
+
+    /// Check if bridges are connected and the TTS model is ready for requests
+    pub fn is_ready(&self) -> bool {
+        self.default.is_ready()
+    }
+
+    /// Check if the default dataflow is being restarted after an unexpected stop
+    pub fn is_recovering(&self) -> bool {
+        self.default.is_recovering()
+    }
+
+    /// Create a dispatcher for the dataflow and start it
+    ///
+    /// `nodes_in_use` maps the dynamic node ids of the other running
+    /// dataflows to their names; dora can't attach one node id to two
+    /// dataflows, so a clash fails before anything is spawned. On success
+    /// the shared status lists the connected bridges; on failure it carries
+    /// the error. Returns the dispatcher and the dataflow id.
+    ///
+    /// `env_vars` go into every node's env in the descriptor the controller
+    /// hands to dora (see [`Self::dataflow_controller`]); the app's own
+    /// environment is left alone so other dataflows don't inherit them.
+    fn start_dispatcher(
+        dataflow_path: &Path,
+        env_vars: &HashMap<String, String>,
+        shared_dora_state: &Arc<SharedDoraState>,
+        nodes_in_use: &HashMap<String, String>,
+    ) -> Result<(DynamicNodeDispatcher, String), String> {
+        // Forget the previous run's model status so readiness starts fresh
+        shared_dora_state.model_status.set(ModelStatus::Unknown);
+
+        let fail = |message: String| {
+            shared_dora_state.status.set(mofa_dora_bridge::DoraStatus {
+                active_bridges: Vec::new(),
+                last_error: Some(message.clone()),
+            });
+            Err(message)
+        };
+
+        let controller = match Self::dataflow_controller(dataflow_path, env_vars) {
+            Ok(controller) => controller,
+            Err(e) => {
+                log::error!("Failed to create controller: {}", e);
+                // Clear bridges on failure
+                return fail(format!("Failed to create controller: {}", e));
+            }
+        };
+
+        let mut disp =
+            DynamicNodeDispatcher::with_shared_state(controller, Arc::clone(shared_dora_state));
+
+        let clashes: Vec<String> = disp
+            .discover_mofa_nodes()
+            .iter()
+            .filter_map(|node| {
+                nodes_in_use
+                    .get(&node.id)
+                    .map(|owner| format!("{} (used by '{}')", node.id, owner))
+            })
+            .collect();
+        if !clashes.is_empty() {
+            let message = format!("Dynamic nodes already running: {}", clashes.join(", "));
+            log::error!("{}", message);
+            return fail(message);
+        }
+
+        // Surface wiring mistakes before anything is spawned
+        match disp.validate() {
+            Ok(report) => {
+                for diagnostic in &report.diagnostics {
+                    match diagnostic.severity {
+                        Severity::Error => log::error!("Dataflow {}", diagnostic),
+                        Severity::Warning => log::warn!("Dataflow {}", diagnostic),
+                        Severity::Info => log::debug!("Dataflow {}", diagnostic),
+                    }
+                }
+                if report.has_errors() {
+                    let errors: Vec<String> = report
+                        .at_least(Severity::Error)
+                        .map(|d| d.to_string())
+                        .collect();
+                    return fail(format!("Invalid dataflow: {}", errors.join("; ")));
+                }
+            }
+            Err(e) => log::warn!("Dataflow validation skipped: {}", e),
+        }
+
+        match disp.start() {
+            Ok(dataflow_id) => {
+                log::info!("Dataflow started: {}", dataflow_id);
+
+                // Log discovered MoFA nodes for debugging
+                let mofa_nodes = disp.discover_mofa_nodes();
+                log::info!("Discovered {} MoFA nodes:", mofa_nodes.len());
+                for node in &mofa_nodes {
+                    log::info!("  - {} (type: {:?})", node.id, node.node_type);
+                }
+
+                // Check which bridges are actually connected
+                log::info!("Checking bridge connection status...");
+                let mut connected_bridges = Vec::new();
+                for binding in disp.bindings() {
+                    log::info!("  Bridge {}: state={:?}", binding.node_id, binding.state);
+                    if binding.state == mofa_dora_bridge::BridgeState::Connected {
+                        connected_bridges.push(binding.node_id.clone());
+                    }
+                }
+
+                // Update shared state with connected bridges
+                shared_dora_state.status.set(mofa_dora_bridge::DoraStatus {
+                    active_bridges: connected_bridges,
+                    last_error: None,
+                });
+
+                Ok((disp, dataflow_id))
+            }
+            Err(e) => {
+                log::error!("Failed to start dataflow: {}", e);
+                // Clear bridges on failure
+                fail(format!("Failed to start dataflow: {}", e))
+            }
+        }
+    }
+    /// Controller for the dataflow at `dataflow_path` whose nodes get `env_vars`
+    fn dataflow_controller(
+        dataflow_path: &Path,
+        env_vars: &HashMap<String, String>,
+    ) -> BridgeResult<DataflowController> {
+        let mut controller = DataflowController::new(dataflow_path)?;
+        controller.set_envs(env_vars.clone());
+        Ok(controller)
     }
 
-    /// Worker thread main loop
+    /// Queue a request on the matching bridge
+    ///
+    /// Returns the handle that completes when TTS or ASR answers. Bridges
+    /// queue requests while they are still connecting, so nothing here
+    /// blocks the worker.
+    fn send_request(
+        disp: &DynamicNodeDispatcher,
+        request: &PendingRequest,
+        event_tx: &Sender<DoraEvent>,
+    ) -> Option<SendHandle> {
+        let (bridge, send_request) = match request {
+            PendingRequest::Prompt { message } => {
+                // Try generic prompt input bridge or TTS-specific one if we define it in dataflow
+                let Some(bridge) = disp
+                    .get_bridge("mofa-prompt-input-tts")
+                    .or_else(|| disp.get_bridge("mofa-prompt-input"))
+                else {
+                    log::warn!("mofa-prompt-input bridge not found");
+                    let _ = event_tx.send(DoraEvent::RequestFailed {
+                        message: "TTS not available (mofa-prompt-input bridge not found)"
+                            .to_string(),
+                    });
+                    return None;
+                };
+                log::info!("Sending text to TTS via bridge: {}", message);
+                let send_request =
+                    SendRequest::new("control", mofa_dora_bridge::DoraData::Text(message.clone()))
+                        .expect_reply(TTS_REPLY_INPUT)
+                        .with_reply_timeout(TTS_REPLY_TIMEOUT);
+                (bridge, send_request)
+            }
+            PendingRequest::Audio {
+                audio_samples,
+                sample_rate,
+                language,
+            } => {
+                let Some(bridge) = disp.get_bridge("mofa-audio-input") else {
+                    log::warn!("mofa-audio-input bridge not found");
+                    let _ = event_tx.send(DoraEvent::Error {
+                        message: "ASR not available (mofa-audio-input bridge not found)"
+                            .to_string(),
+                    });
+                    return None;
+                };
+                log::info!(
+                    "Sending audio to ASR: {} samples at {}Hz, language: {}",
+                    audio_samples.len(),
+                    sample_rate,
+                    language
+                );
+
+                // Create audio data with metadata
+                let audio_data = mofa_dora_bridge::DoraData::Audio(mofa_dora_bridge::AudioData {
+                    samples: audio_samples.clone(),
+                    sample_rate: *sample_rate,
+                    channels: 1,
+                    participant_id: None,
+                    question_id: None,
+                });
+                let send_request = SendRequest::new("audio", audio_data)
+                    .expect_reply(ASR_REPLY_INPUT)
+                    .with_reply_timeout(ASR_REPLY_TIMEOUT);
+                (bridge, send_request)
+            }
+        };
+
+        match bridge.submit(send_request) {
+            Ok(handle) => {
+                log::debug!("{} queued as request {}", request.describe(), handle.id());
+                Some(handle)
+            }
+            Err(e) => {
+                log::error!("Failed to queue {}: {}", request.describe(), e);
+                let _ = event_tx.send(DoraEvent::RequestFailed {
+                    message: format!("Failed to send {}: {}", request.describe(), e),
+                });
+                None
+            }
+        }
+    }
+
+    /// Publish node health, skipping the update when nothing changed
+    fn publish_node_statuses(
+        shared_dora_state: &SharedDoraState,
+        mut nodes: Vec<NodeStatus>,
+        node_logs: Option<&NodeLogCollector>,
+    ) {
+        if let Some(logs) = node_logs {
+            logs.apply_tails(&mut nodes);
+        }
+        if shared_dora_state.nodes.read() != nodes {
+            shared_dora_state.nodes.set(nodes);
+        }
+    }
+
+    /// Move newly written node log lines into the shared log
+    fn drain_node_logs(
+        shared_dora_state: &SharedDoraState,
+        node_logs: Option<&mut NodeLogCollector>,
+    ) {
+        if let Some(logs) = node_logs {
+            for entry in logs.poll() {
+                shared_dora_state.logs.push(entry);
+            }
+        }
+    }
+
+    /// Worker thread main loop: supervises every dataflow that received a command
     fn run_worker(
-        running: Arc<AtomicBool>,
-        shared_dora_state: Arc<SharedDoraState>,
-        command_rx: Receiver<DoraCommand>,
-        event_tx: Sender<DoraEvent>,
+        policy: RestartPolicy,
+        dataflows: DataflowTable,
+        command_rx: Receiver<(String, DoraCommand)>,
         stop_rx: Receiver<()>,
     ) {
         log::info!("Dora integration worker started");
 
-        let mut dispatcher: Option<DynamicNodeDispatcher> = None;
-        let shared_state_for_dispatcher = shared_dora_state;
-        let mut last_status_check = std::time::Instant::now();
-        let status_check_interval = std::time::Duration::from_secs(2);
-        let mut dataflow_start_time: Option<std::time::Instant> = None;
-        let startup_grace_period = std::time::Duration::from_secs(10);
+        let mut workers: HashMap<String, DataflowWorker> = HashMap::new();
 
         loop {
             // Check for stop signal
@@ -206,237 +694,516 @@ impl DoraIntegration {
             }
 
             // Process commands
-            while let Ok(cmd) = command_rx.try_recv() {
-                match cmd {
-                    DoraCommand::StartDataflow {
-                        dataflow_path,
-                        env_vars,
-                    } => {
-                        log::info!("Starting dataflow: {:?}", dataflow_path);
-
-                        // IMPORTANT: Stop any existing dispatcher first to avoid "Bridge already connected" errors
-                        if let Some(mut old_disp) = dispatcher.take() {
-                            log::warn!("Stopping existing dataflow before starting new one");
-                            if let Err(e) = old_disp.stop() {
-                                log::error!("Failed to stop existing dataflow: {}", e);
-                            }
-                            // Give bridges time to fully disconnect
-                            std::thread::sleep(Duration::from_millis(500));
-                        }
+            while let Ok((name, cmd)) = command_rx.try_recv() {
+                let nodes_in_use = Self::nodes_in_use(&workers, &name);
+                workers
+                    .entry(name.clone())
+                    .or_insert_with(|| DataflowWorker::new(&name, channels_for(&dataflows, &name)))
+                    .handle_command(cmd, &nodes_in_use);
+            }
 
-                        for (key, value) in &env_vars {
-                            std::env::set_var(key, value);
-                        }
+            let names: Vec<String> = workers.keys().cloned().collect();
+            for name in names {
+                let nodes_in_use = Self::nodes_in_use(&workers, &name);
+                if let Some(worker) = workers.get_mut(&name) {
+                    worker.tick(&policy, &nodes_in_use);
+                }
+            }
 
-                        match DataflowController::new(&dataflow_path) {
-                            Ok(mut controller) => {
-                                controller.set_envs(env_vars.clone());
-
-                                let mut disp = DynamicNodeDispatcher::with_shared_state(
-                                    controller,
-                                    Arc::clone(&shared_state_for_dispatcher),
-                                );
-
-                                match disp.start() {
-                                    Ok(dataflow_id) => {
-                                        log::info!("Dataflow started: {}", dataflow_id);
-
-                                        // Log discovered MoFA nodes for debugging
-                                        let mofa_nodes = disp.discover_mofa_nodes();
-                                        log::info!("Discovered {} MoFA nodes:", mofa_nodes.len());
-                                        for node in &mofa_nodes {
-                                            log::info!(
-                                                "  - {} (type: {:?})",
-                                                node.id,
-                                                node.node_type
-                                            );
-                                        }
-
-                                        // Check which bridges are actually connected
-                                        log::info!("Checking bridge connection status...");
-                                        let bindings = disp.bindings();
-                                        let mut connected_bridges = Vec::new();
-                                        for binding in bindings {
-                                            log::info!(
-                                                "  Bridge {}: state={:?}",
-                                                binding.node_id,
-                                                binding.state
-                                            );
-                                            if binding.state == mofa_dora_bridge::BridgeState::Connected {
-                                                connected_bridges.push(binding.node_id.clone());
-                                            }
-                                        }
-
-                                        // Update shared state with connected bridges
-                                        shared_state_for_dispatcher.status.set(
-                                            mofa_dora_bridge::DoraStatus {
-                                                active_bridges: connected_bridges,
-                                                last_error: None,
-                                            }
-                                        );
-
-                                        running.store(true, Ordering::Release);
-                                        dataflow_start_time = Some(std::time::Instant::now());
-                                        let _ = event_tx
-                                            .send(DoraEvent::DataflowStarted { dataflow_id });
-                                        dispatcher = Some(disp);
-                                    }
-                                    Err(e) => {
-                                        log::error!("Failed to start dataflow: {}", e);
-                                        // Clear bridges on failure
-                                        shared_state_for_dispatcher.status.set(
-                                            mofa_dora_bridge::DoraStatus {
-                                                active_bridges: Vec::new(),
-                                                last_error: Some(e.to_string()),
-                                            }
-                                        );
-                                        let _ = event_tx.send(DoraEvent::Error {
-                                            message: format!("Failed to start dataflow: {}", e),
-                                        });
-                                    }
-                                }
-                            }
-                            Err(e) => {
-                                log::error!("Failed to create controller: {}", e);
-                                // Clear bridges on failure
-                                shared_state_for_dispatcher.status.set(
-                                    mofa_dora_bridge::DoraStatus {
-                                        active_bridges: Vec::new(),
-                                        last_error: Some(e.to_string()),
-                                    }
-                                );
-                                let _ = event_tx.send(DoraEvent::Error {
-                                    message: format!("Failed to create controller: {}", e),
-                                });
-                            }
-                        }
-                    }
+            std::thread::sleep(Duration::from_millis(10));
+        }
 
-                    DoraCommand::StopDataflow => {
-                        log::info!("Stopping dataflow");
-                        if let Some(mut disp) = dispatcher.take() {
-                            if let Err(e) = disp.stop() {
-                                log::error!("Failed to stop dataflow: {}", e);
-                            }
-                            // Give bridges time to fully disconnect before allowing restart
-                            log::debug!("Waiting for bridges to fully disconnect...");
-                            std::thread::sleep(Duration::from_millis(300));
-                        }
+        for worker in workers.values_mut() {
+            worker.shutdown();
+        }
 
-                        // Clear shared state
-                        shared_state_for_dispatcher.status.set(
-                            mofa_dora_bridge::DoraStatus {
-                                active_bridges: Vec::new(),
-                                last_error: None,
-                            }
-                        );
+        log::info!("Dora integration worker stopped");
+    }
 
-                        running.store(false, Ordering::Release);
-                        dataflow_start_time = None;
-                        let _ = event_tx.send(DoraEvent::DataflowStopped);
-                    }
+    /// Dynamic node ids of every running dataflow except `except`, mapped to its name
+    fn nodes_in_use(
+        workers: &HashMap<String, DataflowWorker>,
+        except: &str,
+    ) -> HashMap<String, String> {
+        workers
+            .iter()
+            .filter(|(name, _)| name.as_str() != except)
+            .filter_map(|(name, worker)| worker.dispatcher.as_ref().map(|disp| (name, disp)))
+            .flat_map(|(name, disp)| {
+                disp.bindings()
+                    .iter()
+                    .map(move |binding| (binding.node_id.clone(), name.clone()))
+            })
+            .collect()
+    }
+}
 
-                    DoraCommand::SendPrompt { message } => {
-                        if let Some(ref disp) = dispatcher {
-                            // Try generic prompt input bridge or TTS-specific one if we define it in dataflow
-                            if let Some(bridge) = disp
-                                .get_bridge("mofa-prompt-input-tts")
-                                .or_else(|| disp.get_bridge("mofa-prompt-input"))
-                            {
-                                log::info!("Sending text to TTS via bridge: {}", message);
-                                if let Err(e) = send_with_retry(
-                                    bridge,
-                                    "prompt",
-                                    mofa_dora_bridge::DoraData::Text(message.clone()),
-                                ) {
-                                    log::error!("Failed to send text: {}", e);
-                                }
-                            } else {
-                                log::warn!("mofa-prompt-input bridge not found");
-                            }
-                        }
-                    }
+/// Supervision state of one named dataflow, owned by the worker thread
+struct DataflowWorker {
+    name: String,
+    flags: WorkerFlags,
+    shared_dora_state: Arc<SharedDoraState>,
+    event_tx: Sender<DoraEvent>,
+    dispatcher: Option<DynamicNodeDispatcher>,
+    last_status_check: Instant,
+    dataflow_start_time: Option<Instant>,
+    /// What to restart
+    last_start: Option<(PathBuf, HashMap<String, String>)>,
+    /// What to replay after a restart
+    pending_request: Option<PendingRequest>,
+    in_flight: Option<SendHandle>,
+    recovery: Option<Recovery>,
+    ready_wait: Option<ReadyWait>,
+    /// Log tailing for the running dataflow
+    node_logs: Option<NodeLogCollector>,
+    last_log_poll: Instant,
+}
 
-                    DoraCommand::SendAudio {
-                        audio_samples,
-                        sample_rate,
-                        language,
-                    } => {
-                        if let Some(ref disp) = dispatcher {
-                            if let Some(bridge) = disp.get_bridge("mofa-audio-input") {
-                                log::info!(
-                                    "Sending audio to ASR: {} samples at {}Hz, language: {}",
-                                    audio_samples.len(),
-                                    sample_rate,
-                                    language
-                                );
-
-                                // Create audio data with metadata
-                                let audio_data = mofa_dora_bridge::DoraData::Audio(
-                                    mofa_dora_bridge::AudioData {
-                                        samples: audio_samples,
-                                        sample_rate,
-                                        channels: 1,
-                                        participant_id: None,
-                                        question_id: None,
-                                    },
-                                );
-
-                                if let Err(e) = send_with_retry(bridge, "audio", audio_data) {
-                                    log::error!("Failed to send audio: {}", e);
-                                    let _ = event_tx.send(DoraEvent::Error {
-                                        message: format!("Failed to send audio to ASR: {}", e),
-                                    });
-                                }
-                            } else {
-                                log::warn!("mofa-audio-input bridge not found");
-                                let _ = event_tx.send(DoraEvent::Error {
-                                    message:
-                                        "ASR not available (mofa-audio-input bridge not found)"
-                                            .to_string(),
-                                });
-                            }
-                        }
+/// How often a running dataflow's status is checked
+const STATUS_CHECK_INTERVAL: Duration = Duration::from_secs(2);
+
+/// How long after a start status checks are skipped
+const STARTUP_GRACE_PERIOD: Duration = Duration::from_secs(10);
+
+impl DataflowWorker {
+    fn new(name: &str, channels: DataflowChannels) -> Self {
+        Self {
+            name: name.to_string(),
+            flags: channels.flags,
+            shared_dora_state: channels.shared_dora_state,
+            event_tx: channels.event_tx,
+            dispatcher: None,
+            last_status_check: Instant::now(),
+            dataflow_start_time: None,
+            last_start: None,
+            pending_request: None,
+            in_flight: None,
+            recovery: None,
+            ready_wait: None,
+            node_logs: None,
+            last_log_poll: Instant::now(),
+        }
+    }
+
+    fn send_event(&self, event: DoraEvent) {
+        let _ = self.event_tx.send(event);
+    }
+
+    /// Take over a freshly started dispatcher
+    fn started(&mut self, disp: DynamicNodeDispatcher, dataflow_id: String) {
+        self.flags.running.store(true, Ordering::Release);
+        self.dataflow_start_time = Some(Instant::now());
+        self.node_logs = disp
+            .controller()
+            .read()
+            .log_dir()
+            .map(NodeLogCollector::new);
+        self.ready_wait = Some(ReadyWait::for_dispatcher(&disp));
+        self.send_event(DoraEvent::DataflowStarted { dataflow_id });
+        self.dispatcher = Some(disp);
+    }
+
+    fn handle_command(&mut self, cmd: DoraCommand, nodes_in_use: &HashMap<String, String>) {
+        match cmd {
+            DoraCommand::StartDataflow {
+                dataflow_path,
+                env_vars,
+            } => {
+                log::info!("Starting dataflow '{}': {:?}", self.name, dataflow_path);
+
+                // An explicit start supersedes any restart in progress
+                self.recovery = None;
+                self.flags.recovering.store(false, Ordering::Release);
+                self.flags.ready.store(false, Ordering::Release);
+                self.ready_wait = None;
+
+                // IMPORTANT: Stop this handle's dispatcher first to avoid "Bridge already connected" errors
+                if let Some(mut old_disp) = self.dispatcher.take() {
+                    log::warn!(
+                        "Stopping existing dataflow '{}' before starting new one",
+                        self.name
+                    );
+                    if let Err(e) = old_disp.stop() {
+                        log::error!("Failed to stop existing dataflow: {}", e);
                     }
+                    // Give bridges time to fully disconnect
+                    std::thread::sleep(Duration::from_millis(500));
+                }
+
+                match DoraIntegration::start_dispatcher(
+                    &dataflow_path,
+                    &env_vars,
+                    &self.shared_dora_state,
+                    nodes_in_use,
+                ) {
+                    Ok((disp, dataflow_id)) => self.started(disp, dataflow_id),
+                    Err(message) => self.send_event(DoraEvent::Error { message }),
                 }
+                self.last_start = Some((dataflow_path, env_vars));
             }
 
-            // Periodic status check
-            let in_grace_period = dataflow_start_time
-                .map(|t| t.elapsed() < startup_grace_period)
-                .unwrap_or(false);
-
-            if !in_grace_period && last_status_check.elapsed() >= status_check_interval {
-                last_status_check = std::time::Instant::now();
-
-                if let Some(ref disp) = dispatcher {
-                    match disp.controller().read().get_status() {
-                        Ok(status) => {
-                            let was_running = running.load(Ordering::Acquire);
-                            let is_running = status.state.is_running();
-
-                            if was_running && !is_running {
-                                log::warn!("Dataflow stopped unexpectedly");
-                                running.store(false, Ordering::Release);
-                                dataflow_start_time = None;
-                                let _ = event_tx.send(DoraEvent::DataflowStopped);
-                            }
-                        }
-                        Err(e) => {
-                            log::debug!("Status check failed: {}", e);
-                        }
+            DoraCommand::StopDataflow => {
+                log::info!("Stopping dataflow '{}'", self.name);
+                self.stop();
+                self.send_event(DoraEvent::DataflowStopped);
+            }
+
+            DoraCommand::SendPrompt { message } => {
+                self.submit(PendingRequest::Prompt { message });
+            }
+
+            DoraCommand::SendAudio {
+                audio_samples,
+                sample_rate,
+                language,
+            } => {
+                self.submit(PendingRequest::Audio {
+                    audio_samples,
+                    sample_rate,
+                    language,
+                });
+            }
+
+            DoraCommand::CompleteRequest => {
+                self.pending_request = None;
+            }
+        }
+    }
+
+    /// Send a request now, or hold it for replay while the dataflow recovers
+    fn submit(&mut self, request: PendingRequest) {
+        if let Some(ref disp) = self.dispatcher {
+            self.in_flight = DoraIntegration::send_request(disp, &request, &self.event_tx);
+            self.pending_request = self.in_flight.as_ref().map(|_| request);
+        } else if self.recovery.is_some() {
+            log::info!(
+                "Dataflow '{}' recovering, {} will be sent after restart",
+                self.name,
+                request.describe()
+            );
+            self.pending_request = Some(request);
+        }
+    }
+
+    /// Stop the dataflow and forget everything about the run
+    fn stop(&mut self) {
+        DoraIntegration::drain_node_logs(&self.shared_dora_state, self.node_logs.as_mut());
+        self.node_logs = None;
+        if let Some(mut disp) = self.dispatcher.take() {
+            if let Err(e) = disp.stop() {
+                log::error!("Failed to stop dataflow: {}", e);
+            }
+            // Give bridges time to fully disconnect before allowing restart
+            log::debug!("Waiting for bridges to fully disconnect...");
+            std::thread::sleep(Duration::from_millis(300));
+        }
+
+        // Clear shared state
+        self.shared_dora_state
+            .status
+            .set(mofa_dora_bridge::DoraStatus {
+                active_bridges: Vec::new(),
+                last_error: None,
+            });
+
+        self.shared_dora_state.nodes.set(Vec::new());
+        self.shared_dora_state.replies.cancel_all();
+
+        self.flags.running.store(false, Ordering::Release);
+        self.flags.recovering.store(false, Ordering::Release);
+        self.flags.ready.store(false, Ordering::Release);
+        self.ready_wait = None;
+        self.dataflow_start_time = None;
+        self.last_start = None;
+        self.pending_request = None;
+        self.in_flight = None;
+        self.recovery = None;
+    }
+
+    /// Stop the dispatcher when the integration shuts down
+    fn shutdown(&mut self) {
+        if let Some(mut disp) = self.dispatcher.take() {
+            let _ = disp.stop();
+        }
+    }
+
+    /// One pass of supervision: replies, readiness, restarts, logs, health
+    fn tick(&mut self, policy: &RestartPolicy, nodes_in_use: &HashMap<String, String>) {
+        self.settle_in_flight();
+        self.check_ready();
+        self.restart_if_due(policy, nodes_in_use);
+
+        // Tail node logs, including during startup where crashes on model load happen
+        if self.last_log_poll.elapsed() >= NODE_LOG_POLL_INTERVAL {
+            self.last_log_poll = Instant::now();
+            DoraIntegration::drain_node_logs(&self.shared_dora_state, self.node_logs.as_mut());
+        }
+
+        self.check_status(policy);
+    }
+
+    /// Settle the in-flight request once TTS or ASR answered it
+    fn settle_in_flight(&mut self) {
+        self.shared_dora_state.replies.expire_overdue();
+        let Some(outcome) = self.in_flight.as_ref().and_then(|h| h.outcome()) else {
+            return;
+        };
+        let what = self
+            .pending_request
+            .as_ref()
+            .map(|r| r.describe())
+            .unwrap_or("Request");
+        match outcome {
+            SendOutcome::Delivered | SendOutcome::Replied(_) => {
+                log::debug!("{} completed", what);
+                self.pending_request = None;
+                self.send_event(DoraEvent::RequestCompleted);
+            }
+            SendOutcome::Failed(message) => {
+                log::error!("{} failed: {}", what, message);
+                self.pending_request = None;
+                self.send_event(DoraEvent::RequestFailed {
+                    message: format!("{} failed: {}", what, message),
+                });
+            }
+            SendOutcome::TimedOut => {
+                log::warn!("{} got no reply in time", what);
+                self.pending_request = None;
+                self.send_event(DoraEvent::RequestFailed {
+                    message: format!("{} timed out", what),
+                });
+            }
+            SendOutcome::Cancelled => {
+                // Dataflow went away; pending_request is replayed after a restart
+                log::info!("{} interrupted", what);
+            }
+        }
+        self.in_flight = None;
+    }
+
+    /// Readiness handshake: all bridges connected and, if the dataflow
+    /// reports it, the TTS model loaded
+    fn check_ready(&mut self) {
+        let (Some(wait), Some(disp)) = (self.ready_wait, self.dispatcher.as_mut()) else {
+            return;
+        };
+        disp.refresh_binding_states();
+
+        if let Some(ModelStatus::Loading { progress, message }) =
+            self.shared_dora_state.model_status.read_if_dirty()
+        {
+            let _ = self
+                .event_tx
+                .send(DoraEvent::ModelLoading { progress, message });
+        }
+
+        let model_status = self.shared_dora_state.model_status.read();
+        let bridges_ready = disp.all_bridges_connected();
+
+        if let ModelStatus::Failed { message } = model_status {
+            log::error!("TTS model failed to load: {}", message);
+            self.ready_wait = None;
+            self.send_event(DoraEvent::Error {
+                message: format!("TTS model failed to load: {}", message),
+            });
+        } else if bridges_ready && (!wait.needs_model || model_status.is_ready()) {
+            log::info!("Dataflow '{}' ready", self.name);
+            self.ready_wait = None;
+            self.flags.ready.store(true, Ordering::Release);
+            self.send_event(DoraEvent::Ready);
+        } else if Instant::now() >= wait.deadline {
+            let reason = if !bridges_ready {
+                let pending: Vec<&str> = disp
+                    .bindings()
+                    .iter()
+                    .filter(|b| b.state != mofa_dora_bridge::BridgeState::Connected)
+                    .map(|b| b.node_id.as_str())
+                    .collect();
+                format!("bridges not connected: {}", pending.join(", "))
+            } else {
+                "TTS model is still loading".to_string()
+            };
+            log::error!("Dataflow not ready after {:?}: {}", READY_TIMEOUT, reason);
+            self.ready_wait = None;
+            self.send_event(DoraEvent::Error {
+                message: format!(
+                    "Dataflow not ready after {}s ({})",
+                    READY_TIMEOUT.as_secs(),
+                    reason
+                ),
+            });
+        }
+    }
+
+    /// Restart a dataflow that stopped unexpectedly once its backoff has elapsed
+    fn restart_if_due(&mut self, policy: &RestartPolicy, nodes_in_use: &HashMap<String, String>) {
+        let restart_due = self
+            .recovery
+            .as_ref()
+            .map(|r| Instant::now() >= r.next_attempt_at)
+            .unwrap_or(false);
+        if !restart_due {
+            return;
+        }
+        let (Some(r), Some((dataflow_path, env_vars))) = (self.recovery.take(), &self.last_start)
+        else {
+            return;
+        };
+        log::info!(
+            "Restarting dataflow '{}' (attempt {}/{})",
+            self.name,
+            r.attempt,
+            policy.max_retries
+        );
+        match DoraIntegration::start_dispatcher(
+            dataflow_path,
+            env_vars,
+            &self.shared_dora_state,
+            nodes_in_use,
+        ) {
+            Ok((disp, dataflow_id)) => {
+                log::info!("Dataflow recovered after {} attempt(s)", r.attempt);
+                self.flags.recovering.store(false, Ordering::Release);
+                if let Some(ref request) = self.pending_request {
+                    log::info!("Re-sending request interrupted by the restart");
+                    self.in_flight = DoraIntegration::send_request(&disp, request, &self.event_tx);
+                    if self.in_flight.is_none() {
+                        self.pending_request = None;
                     }
                 }
+                self.started(disp, dataflow_id);
+            }
+            Err(e) if r.attempt >= policy.max_retries => {
+                log::error!("Giving up on dataflow recovery: {}", e);
+                let nodes = self
+                    .shared_dora_state
+                    .nodes
+                    .read()
+                    .into_iter()
+                    .map(|mut node| {
+                        if node.state == NodeState::Restarting {
+                            node.state = if node.error.is_some() {
+                                NodeState::Failed
+                            } else {
+                                NodeState::Exited
+                            };
+                        }
+                        node
+                    })
+                    .collect();
+                DoraIntegration::publish_node_statuses(&self.shared_dora_state, nodes, None);
+                self.flags.recovering.store(false, Ordering::Release);
+                self.pending_request = None;
+                self.in_flight = None;
+                self.send_event(DoraEvent::Error {
+                    message: format!(
+                        "Dataflow could not be restarted after {} attempts: {}",
+                        r.attempt, e
+                    ),
+                });
+                self.send_event(DoraEvent::DataflowStopped);
             }
+            Err(e) => {
+                log::warn!("Restart attempt {} failed: {}", r.attempt, e);
+                let attempt = r.attempt + 1;
+                self.recovery = Some(Recovery {
+                    attempt,
+                    next_attempt_at: Instant::now() + policy.backoff(attempt),
+                });
+                self.send_event(DoraEvent::Recovering { attempt });
+            }
+        }
+    }
 
-            std::thread::sleep(std::time::Duration::from_millis(10));
+    /// Periodic status check; detects a dataflow that stopped on its own
+    fn check_status(&mut self, policy: &RestartPolicy) {
+        let in_grace_period = self
+            .dataflow_start_time
+            .map(|t| t.elapsed() < STARTUP_GRACE_PERIOD)
+            .unwrap_or(false);
+        if in_grace_period || self.last_status_check.elapsed() < STATUS_CHECK_INTERVAL {
+            return;
         }
+        self.last_status_check = Instant::now();
 
-        if let Some(mut disp) = dispatcher {
-            let _ = disp.stop();
+        let stopped_status = match self.dispatcher {
+            Some(ref disp) => match disp.controller().read().get_status() {
+                Ok(status) => {
+                    if self.flags.running.load(Ordering::Acquire) && !status.state.is_running() {
+                        for node in status.failed_nodes() {
+                            log::error!(
+                                "Node {} failed: {}",
+                                node.node_id,
+                                node.error.as_deref().unwrap_or("unknown error")
+                            );
+                        }
+                        Some(status)
+                    } else {
+                        DoraIntegration::publish_node_statuses(
+                            &self.shared_dora_state,
+                            status.nodes,
+                            self.node_logs.as_ref(),
+                        );
+                        None
+                    }
+                }
+                Err(e) => {
+                    log::debug!("Status check failed: {}", e);
+                    None
+                }
+            },
+            None => None,
+        };
+
+        let Some(status) = stopped_status else {
+            return;
+        };
+        log::warn!("Dataflow '{}' stopped unexpectedly", self.name);
+        self.flags.running.store(false, Ordering::Release);
+        self.flags.ready.store(false, Ordering::Release);
+        self.ready_wait = None;
+        self.dataflow_start_time = None;
+
+        // Tear down the dead dispatcher so its bridges release their node ids
+        if let Some(mut disp) = self.dispatcher.take() {
+            if let Err(e) = disp.force_stop() {
+                log::debug!("Cleanup after unexpected stop failed: {}", e);
+            }
         }
+        // Replies can't arrive anymore; the request is replayed after restart
+        self.shared_dora_state.replies.cancel_all();
 
-        log::info!("Dora integration worker stopped");
+        // Pick up the last lines the nodes wrote before exiting
+        DoraIntegration::drain_node_logs(&self.shared_dora_state, self.node_logs.as_mut());
+        let will_restart = policy.max_retries > 0 && self.last_start.is_some();
+        let nodes = status
+            .nodes
+            .into_iter()
+            .map(|mut node| {
+                if will_restart {
+                    node.state = NodeState::Restarting;
+                }
+                node
+            })
+            .collect();
+        DoraIntegration::publish_node_statuses(
+            &self.shared_dora_state,
+            nodes,
+            self.node_logs.as_ref(),
+        );
+        self.node_logs = None;
+
+        if will_restart {
+            self.flags.recovering.store(true, Ordering::Release);
+            self.recovery = Some(Recovery {
+                attempt: 1,
+                next_attempt_at: Instant::now() + policy.backoff(1),
+            });
+            self.send_event(DoraEvent::Recovering { attempt: 1 });
+        } else {
+            self.pending_request = None;
+            self.send_event(DoraEvent::DataflowStopped);
+        }
     }
 }
 
@@ -456,3 +1223,160 @@ impl Default for DoraIntegration {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_named_dataflows_have_separate_state_and_events() {
+        let dora = DoraIntegration::new();
+        let asr = dora.dataflow("asr");
+        assert_eq!(asr.name(), "asr");
+        assert_eq!(
+            dora.dataflow_names(),
+            vec!["asr".to_string(), "tts".to_string()]
+        );
+        assert!(!Arc::ptr_eq(
+            asr.shared_dora_state(),
+            dora.shared_dora_state()
+        ));
+        assert!(Arc::ptr_eq(
+            dora.dataflow(DEFAULT_DATAFLOW).shared_dora_state(),
+            dora.shared_dora_state()
+        ));
+
+        assert!(asr.start_dataflow("/nonexistent/asr.yml"));
+        let deadline = Instant::now() + Duration::from_secs(5);
+        let mut events = Vec::new();
+        while events.is_empty() && Instant::now() < deadline {
+            events = asr.poll_events();
+            thread::sleep(Duration::from_millis(10));
+        }
+        assert!(matches!(events.as_slice(), [DoraEvent::Error { .. }]));
+        assert!(!asr.is_running());
+        assert!(asr.shared_dora_state().status.read().last_error.is_some());
+
+        // The default dataflow saw none of it
+        assert!(dora.poll_events().is_empty());
+        assert!(dora.shared_dora_state().status.read().last_error.is_none());
+    }
+
+    /// A worker that started `name.yml` with `MOFA_TEST_SPEAKER` set
+    ///
+    /// The dataflow fails validation, so nothing is spawned.
+    fn started_with_env(name: &str) -> (DataflowWorker, Receiver<DoraEvent>, PathBuf) {
+        let dir =
+            std::env::temp_dir().join(format!("mofa-tts-env-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(format!("{}.yml", name));
+        std::fs::write(
+            &path,
+            "nodes:
+  - id: tts
+    path: dora-tts
+    inputs:
+      text: missing/text
+    env:
+      SPEED: \"1.0\"
+  - id: sink
+    path: dora-sink
+",
+        )
+        .unwrap();
+
+        let channels = DataflowChannels::new();
+        let events = channels.event_rx.clone();
+        let mut worker = DataflowWorker::new(name, channels);
+        let env_vars = HashMap::from([("MOFA_TEST_SPEAKER".to_string(), "Doubao".to_string())]);
+        worker.handle_command(
+            DoraCommand::StartDataflow {
+                dataflow_path: path.clone(),
+                env_vars,
+            },
+            &HashMap::new(),
+        );
+        (worker, events, dir)
+    }
+
+    /// Env every node of the worker's last start receives, by node id
+    fn node_env(worker: &DataflowWorker, key: &str) -> Vec<(String, Option<String>)> {
+        let (path, env_vars) = worker.last_start.as_ref().unwrap();
+        let descriptor = DoraIntegration::dataflow_controller(path, env_vars)
+            .unwrap()
+            .descriptor()
+            .unwrap();
+        descriptor["nodes"]
+            .as_sequence()
+            .unwrap()
+            .iter()
+            .map(|node| {
+                (
+                    node["id"].as_str().unwrap().to_string(),
+                    node["env"][key].as_str().map(str::to_string),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_start_passes_env_to_every_node() {
+        let (worker, events, dir) = started_with_env("start");
+        assert!(matches!(
+            events.try_recv(),
+            Ok(DoraEvent::Error { message }) if message.starts_with("Invalid dataflow")
+        ));
+
+        let speaker = Some("Doubao".to_string());
+        assert_eq!(
+            node_env(&worker, "MOFA_TEST_SPEAKER"),
+            vec![
+                ("tts".to_string(), speaker.clone()),
+                ("sink".to_string(), speaker)
+            ]
+        );
+        // Node env of its own is kept, and the app's environment untouched
+        assert_eq!(node_env(&worker, "SPEED")[0].1.as_deref(), Some("1.0"));
+        assert!(std::env::var("MOFA_TEST_SPEAKER").is_err());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_restart_passes_env_to_every_node() {
+        let (mut worker, events, dir) = started_with_env("restart");
+        let _ = events.try_recv();
+
+        worker.recovery = Some(Recovery {
+            attempt: 1,
+            next_attempt_at: Instant::now(),
+        });
+        worker.restart_if_due(&RestartPolicy::default(), &HashMap::new());
+        // The restart ran (and failed the same way), so it used the replayed start
+        assert!(matches!(
+            events.try_recv(),
+            Ok(DoraEvent::Recovering { attempt: 2 })
+        ));
+        let speaker = Some("Doubao".to_string());
+        assert_eq!(
+            node_env(&worker, "MOFA_TEST_SPEAKER"),
+            vec![
+                ("tts".to_string(), speaker.clone()),
+                ("sink".to_string(), speaker)
+            ]
+        );
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_restart_backoff_doubles_and_caps() {
+        let policy = RestartPolicy::default();
+        assert_eq!(policy.backoff(1), Duration::from_secs(1));
+        assert_eq!(policy.backoff(2), Duration::from_secs(2));
+        assert_eq!(policy.backoff(4), Duration::from_secs(8));
+        assert_eq!(policy.backoff(6), Duration::from_secs(30));
+        assert_eq!(policy.backoff(100), Duration::from_secs(30));
+    }
+}
@@ -1,8 +1,14 @@
 //! MoFA TTS App - Text to Speech using GPT-SoVITS with voice cloning
 
 // Local modules
-pub mod audio_player; // Keep local: simplified TTS-specific version
+pub mod dataset_builder;
+pub mod dataset_editor;
+pub mod dataflow_config;
 pub mod dora_integration;
+pub mod music_bed;
+pub mod output_render;
+pub mod playback_settings;
+pub mod post_process;
 
 // Screen modules - conditionally compiled based on features
 #[cfg(not(feature = "moyoyo-ui"))]
@@ -12,9 +18,11 @@ pub mod screen;
 #[path = "screen_moyoyo.rs"]
 pub mod screen;
 
+pub mod python_env;
 pub mod training_manager;
 pub mod voice_clone_modal;
 pub mod voice_data;
+pub mod voice_evaluation;
 pub mod voice_persistence;
 pub mod voice_selector;
 pub mod task_persistence;
@@ -49,6 +57,7 @@ impl MofaApp for MoFaTTSApp {
         // app-specific components here.
 
         voice_selector::live_design(cx);
+        dataset_editor::live_design(cx);
         voice_clone_modal::live_design(cx);
         screen::live_design(cx);
     }
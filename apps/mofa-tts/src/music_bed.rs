@@ -0,0 +1,415 @@
+//! Background music under narration
+//!
+//! Mixes a music track under the output clip: the track loops to cover the
+//! narration plus an intro and outro, sits at a set gain, ducks while speech
+//! plays (sidechained on the speech itself) and fades in and out.
+//!
+//! Mix settings are stored in:
+//! - Config: ~/.dora/primespeech/music_bed.json
+
+use crate::post_process::PEAK_CEILING;
+use crate::voice_persistence::{ensure_directories, get_primespeech_dir};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Peak follower release of the speech detector, bridging zero crossings
+const DETECTOR_RELEASE_SECONDS: f32 = 0.02;
+
+/// Release of the limiter holding the track under the peak ceiling
+const LIMITER_RELEASE_SECONDS: f32 = 0.1;
+
+/// Music bed settings file format
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MusicBedSettings {
+    /// Config version for future compatibility
+    pub version: String,
+    /// Mix the track under previews and exports
+    pub enabled: bool,
+    /// WAV file to play under the narration
+    pub track_path: Option<PathBuf>,
+    /// Track level before ducking (dB)
+    pub gain_db: f32,
+    /// Lower the track while speech plays
+    pub ducking: bool,
+    /// Gain reduction while ducked (dB)
+    pub duck_db: f32,
+    /// Speech level that triggers ducking (dBFS)
+    pub threshold_db: f32,
+    /// How quickly the track ducks ahead of speech and recovers after it
+    pub attack_ms: u32,
+    pub release_ms: u32,
+    /// Music before and after the narration
+    pub intro_ms: u32,
+    pub outro_ms: u32,
+    /// Track fades at the start and end of the mix
+    pub fade_in_ms: u32,
+    pub fade_out_ms: u32,
+}
+
+impl Default for MusicBedSettings {
+    fn default() -> Self {
+        Self {
+            version: "1.0".to_string(),
+            enabled: false,
+            track_path: None,
+            gain_db: -18.0,
+            ducking: true,
+            duck_db: -12.0,
+            threshold_db: -40.0,
+            attack_ms: 80,
+            release_ms: 500,
+            intro_ms: 1500,
+            outro_ms: 2000,
+            fade_in_ms: 1000,
+            fade_out_ms: 2000,
+        }
+    }
+}
+
+impl MusicBedSettings {
+    /// Mix `track` under `speech`, both interleaved with `channels` channels
+    /// at `sample_rate`. The result runs from the intro through the outro.
+    /// Where the sum would peak above −1 dBFS only the track is pulled down,
+    /// so the speech keeps the loudness it was normalized to.
+    pub fn mix(&self, speech: &[f32], track: &[f32], sample_rate: u32, channels: u16) -> Vec<f32> {
+        let channels = channels.max(1) as usize;
+        let track_frames = track.len() / channels;
+        if track_frames == 0 {
+            return speech.to_vec();
+        }
+        let frames_per_ms = sample_rate as f32 / 1000.0;
+        let ms = |ms: u32| (ms as f32 * frames_per_ms).round() as usize;
+        let per_second = |seconds: f32| (-1.0 / (seconds * sample_rate as f32).max(1.0)).exp();
+
+        let intro = ms(self.intro_ms);
+        let speech_frames = speech.len() / channels;
+        let total = intro + speech_frames + ms(self.outro_ms);
+        let fade_in = ms(self.fade_in_ms).min(total);
+        let fade_out = ms(self.fade_out_ms).min(total);
+
+        let gain = db_to_gain(self.gain_db);
+        let ducked = db_to_gain(self.duck_db);
+        let threshold = db_to_gain(self.threshold_db);
+        let attack = per_second(self.attack_ms as f32 / 1000.0);
+        let release = per_second(self.release_ms as f32 / 1000.0);
+        let detector_release = per_second(DETECTOR_RELEASE_SECONDS);
+        let limiter_release = per_second(LIMITER_RELEASE_SECONDS);
+        // The detector runs one attack time ahead so the track is already
+        // down when a phrase starts
+        let lookahead = ms(self.attack_ms);
+        let speech_peak = |frame: usize| {
+            frame
+                .checked_sub(intro)
+                .filter(|&f| f < speech_frames)
+                .map(|f| {
+                    speech[f * channels..(f + 1) * channels]
+                        .iter()
+                        .fold(0.0f32, |peak, s| peak.max(s.abs()))
+                })
+                .unwrap_or(0.0)
+        };
+
+        let mut out = vec![0.0f32; total * channels];
+        out[intro * channels..(intro + speech_frames) * channels]
+            .copy_from_slice(&speech[..speech_frames * channels]);
+
+        let mut level = 0.0f32;
+        let mut duck = 1.0f32;
+        let mut limit = 1.0f32;
+        for i in 0..total {
+            if self.ducking {
+                level = speech_peak(i + lookahead).max(level * detector_release);
+                let target = if level > threshold { ducked } else { 1.0 };
+                let coef = if target < duck { attack } else { release };
+                duck = target + (duck - target) * coef;
+            }
+
+            let mut fade = 1.0;
+            if i < fade_in {
+                fade *= i as f32 / fade_in as f32;
+            }
+            if i >= total - fade_out {
+                fade *= (total - i - 1) as f32 / fade_out as f32;
+            }
+
+            let music = &track[(i % track_frames) * channels..][..channels];
+            let frame = &mut out[i * channels..(i + 1) * channels];
+            let level = gain * duck * fade;
+            // Music over loud speech can push the sum past full scale; the
+            // limiter clamps the track at once and lets it back up slowly
+            let room = frame
+                .iter()
+                .zip(music)
+                .map(|(s, m)| headroom(*s, m * level))
+                .fold(1.0f32, f32::min);
+            limit = room.min(1.0 + (limit - 1.0) * limiter_release);
+            for (sample, m) in frame.iter_mut().zip(music) {
+                *sample += m * level * limit;
+            }
+        }
+        out
+    }
+}
+
+/// A music track decoded for mixing at a clip's format
+pub struct MusicTrack {
+    pub path: PathBuf,
+    pub sample_rate: u32,
+    pub channels: u16,
+    /// Interleaved samples at `sample_rate` with `channels` channels
+    pub samples: Vec<f32>,
+}
+
+impl MusicTrack {
+    /// Decode the WAV file at `path`, converted to `channels` channels at
+    /// `sample_rate`
+    pub fn load(path: &Path, sample_rate: u32, channels: u16) -> Result<Self, String> {
+        let reader =
+            hound::WavReader::open(path).map_err(|e| format!("Failed to open WAV: {}", e))?;
+        let spec = reader.spec();
+        let source_channels = spec.channels.max(1) as usize;
+        let samples: Vec<f32> = match spec.sample_format {
+            hound::SampleFormat::Int => {
+                let max_val = (1i64 << (spec.bits_per_sample - 1)) as f32;
+                reader
+                    .into_samples::<i32>()
+                    .filter_map(Result::ok)
+                    .map(|s| s as f32 / max_val)
+                    .collect()
+            }
+            hound::SampleFormat::Float => reader
+                .into_samples::<f32>()
+                .filter_map(Result::ok)
+                .collect(),
+        };
+        if samples.len() < source_channels {
+            return Err("Track has no audio".to_string());
+        }
+
+        let channels = channels.max(1);
+        let planar = convert_channels(&samples, source_channels, channels as usize);
+        let planar = resample(planar, spec.sample_rate, sample_rate)?;
+        let frames = planar[0].len();
+        let samples = (0..frames)
+            .flat_map(|i| planar.iter().map(move |channel| channel[i]))
+            .collect();
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            sample_rate,
+            channels,
+            samples,
+        })
+    }
+
+    /// Whether this is `path` decoded for a clip at `sample_rate` with
+    /// `channels` channels
+    pub fn matches(&self, path: &Path, sample_rate: u32, channels: u16) -> bool {
+        self.path == path && self.sample_rate == sample_rate && self.channels == channels.max(1)
+    }
+}
+
+/// Split interleaved `samples` into `target` channels: mono is copied to
+/// every channel, otherwise channels are kept or averaged down to mono
+fn convert_channels(samples: &[f32], source: usize, target: usize) -> Vec<Vec<f32>> {
+    let frames = samples.chunks_exact(source);
+    if source == target {
+        (0..target)
+            .map(|ch| frames.clone().map(|f| f[ch]).collect())
+            .collect()
+    } else if source == 1 {
+        vec![samples.to_vec(); target]
+    } else {
+        let mono: Vec<f32> = frames
+            .map(|f| f.iter().sum::<f32>() / source as f32)
+            .collect();
+        vec![mono; target]
+    }
+}
+
+/// Resample each channel with a sinc interpolator (anti-aliased)
+fn resample(
+    planar: Vec<Vec<f32>>,
+    source_rate: u32,
+    target_rate: u32,
+) -> Result<Vec<Vec<f32>>, String> {
+    use rubato::{
+        Resampler, SincFixedIn, SincInterpolationParameters, SincInterpolationType, WindowFunction,
+    };
+
+    if source_rate == target_rate {
+        return Ok(planar);
+    }
+    let params = SincInterpolationParameters {
+        sinc_len: 256,
+        f_cutoff: 0.95,
+        oversampling_factor: 256,
+        interpolation: SincInterpolationType::Linear,
+        window: WindowFunction::BlackmanHarris2,
+    };
+    let mut resampler = SincFixedIn::<f32>::new(
+        target_rate as f64 / source_rate as f64,
+        1.0,
+        params,
+        planar[0].len(),
+        planar.len(),
+    )
+    .map_err(|e| format!("Failed to create resampler: {}", e))?;
+    resampler
+        .process(&planar, None)
+        .map_err(|e| format!("Failed to resample track: {}", e))
+}
+
+/// Largest share of `music` that fits over `speech` under the peak ceiling
+fn headroom(speech: f32, music: f32) -> f32 {
+    if (speech + music).abs() <= PEAK_CEILING {
+        1.0
+    } else if speech.abs() >= PEAK_CEILING {
+        0.0
+    } else {
+        (PEAK_CEILING.copysign(music) - speech) / music
+    }
+}
+
+fn db_to_gain(db: f32) -> f32 {
+    10f32.powf(db / 20.0)
+}
+
+/// Get the music bed settings file path
+pub fn get_config_path() -> PathBuf {
+    get_primespeech_dir().join("music_bed.json")
+}
+
+/// Load music bed settings, falling back to defaults
+pub fn load_settings() -> MusicBedSettings {
+    let config_path = get_config_path();
+
+    if !config_path.exists() {
+        return MusicBedSettings::default();
+    }
+
+    match fs::read_to_string(&config_path) {
+        Ok(content) => match serde_json::from_str::<MusicBedSettings>(&content) {
+            Ok(settings) => settings,
+            Err(e) => {
+                log::error!("Failed to parse music bed settings: {}", e);
+                MusicBedSettings::default()
+            }
+        },
+        Err(e) => {
+            log::error!("Failed to read music bed settings: {}", e);
+            MusicBedSettings::default()
+        }
+    }
+}
+
+/// Save music bed settings to the config file
+pub fn save_settings(settings: &MusicBedSettings) -> Result<(), String> {
+    ensure_directories().map_err(|e| format!("Failed to create directories: {}", e))?;
+
+    let json = serde_json::to_string_pretty(settings)
+        .map_err(|e| format!("Failed to serialize settings: {}", e))?;
+
+    fs::write(get_config_path(), json).map_err(|e| format!("Failed to write settings: {}", e))?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plain_bed() -> MusicBedSettings {
+        MusicBedSettings {
+            gain_db: 0.0,
+            intro_ms: 0,
+            outro_ms: 0,
+            fade_in_ms: 0,
+            fade_out_ms: 0,
+            ..MusicBedSettings::default()
+        }
+    }
+
+    #[test]
+    fn test_music_ducks_under_speech_and_recovers() {
+        let sample_rate = 1000;
+        // 1 s of silence, 1 s of speech, 2 s of silence over a constant track
+        let mut speech = vec![0.0; 1000];
+        speech.extend((0..1000).map(|i| if i % 2 == 0 { 0.3 } else { -0.3 }));
+        speech.extend(vec![0.0; 2000]);
+        let track = vec![0.5; 100];
+
+        let mix = plain_bed().mix(&speech, &track, sample_rate, 1);
+        assert_eq!(mix.len(), speech.len());
+        let music = |i: usize| mix[i] - speech[i];
+
+        assert!((music(500) - 0.5).abs() < 1e-3);
+        // Already ducked as the phrase starts, fully ducked within it
+        assert!(music(1000) < 0.5 * 0.6);
+        assert!((music(1500) - 0.5 * db_to_gain(-12.0)).abs() < 1e-3);
+        // Back up after the release
+        assert!((music(3900) - 0.5).abs() < 0.01);
+        assert!(music(2100) < music(2500) && music(2500) < music(3900));
+    }
+
+    #[test]
+    fn test_track_loops_and_fades_around_padded_speech() {
+        let bed = MusicBedSettings {
+            intro_ms: 500,
+            outro_ms: 500,
+            fade_in_ms: 200,
+            fade_out_ms: 200,
+            ducking: false,
+            ..plain_bed()
+        };
+        // Stereo: a 3-frame track under 1 s of silent speech
+        let speech = vec![0.0; 2 * 1000];
+        let track = [0.1, -0.1, 0.2, -0.2, 0.3, -0.3];
+
+        let mix = bed.mix(&speech, &track, 1000, 2);
+        assert_eq!(mix.len(), 2 * 2000);
+        assert_eq!(&mix[..2], &[0.0, 0.0]);
+        assert!((mix[2 * 100] - 0.5 * 0.2).abs() < 1e-6);
+        // Frame 1000 is frame 1 of the looping track
+        assert_eq!(&mix[2 * 1000..2 * 1001], &[0.2, -0.2]);
+        assert_eq!(&mix[2 * 1999..], &[0.0, 0.0]);
+    }
+
+    #[test]
+    fn test_music_is_limited_under_loud_speech_instead_of_clipping() {
+        let bed = MusicBedSettings {
+            ducking: false,
+            ..plain_bed()
+        };
+        // Loud speech, then silence, over a track in phase with it
+        let mut speech: Vec<f32> = (0..500)
+            .map(|i| if i % 2 == 0 { 0.8 } else { -0.8 })
+            .collect();
+        speech.extend(vec![0.0; 500]);
+        let track = [0.5, -0.5];
+
+        let mix = bed.mix(&speech, &track, 1000, 1);
+        let peak = mix.iter().fold(0.0f32, |peak, s| peak.max(s.abs()));
+        assert!((peak - PEAK_CEILING).abs() < 1e-6);
+        // The speech keeps its level, only the music makes room
+        let music = |i: usize| mix[i] - speech[i];
+        assert!((music(0) - (PEAK_CEILING - 0.8)).abs() < 1e-6);
+        assert!((music(499) + (PEAK_CEILING - 0.8)).abs() < 1e-6);
+        // and comes back up once the speech is gone
+        assert!(music(600).abs() < music(998).abs());
+        assert!((music(998) - 0.5).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_channels_are_converted_for_the_clip() {
+        let stereo = [0.2, 0.4, -0.2, 0.0];
+        assert_eq!(convert_channels(&stereo, 2, 1), vec![vec![0.3f32, -0.1]]);
+        assert_eq!(
+            convert_channels(&stereo, 2, 2),
+            vec![vec![0.2, -0.2], vec![0.4, 0.0]]
+        );
+        assert_eq!(convert_channels(&[0.5, 0.1], 1, 2), vec![vec![0.5, 0.1]; 2]);
+    }
+}
@@ -0,0 +1,220 @@
+//! Output clip rendering off the UI thread
+//!
+//! The effects chain, decoding the music bed track and the mix take a
+//! noticeable time on a long clip, so the screen hands the stored clip to a
+//! worker and swaps the result in once it is done.
+
+use crate::music_bed::{MusicBedSettings, MusicTrack};
+use crate::post_process::PostProcessChain;
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::sync::Arc;
+
+/// What the output clip is rendered from
+pub struct OutputJob {
+    /// Interleaved stored clip at `sample_rate` with `channels` channels
+    pub samples: Vec<f32>,
+    pub sample_rate: u32,
+    pub channels: u16,
+    /// Effects chain, `None` when effects are off
+    pub chain: Option<PostProcessChain>,
+    /// Music bed to mix under the clip, `None` when off
+    pub music: Option<MusicBedSettings>,
+    /// Track decoded by an earlier render, reused while it still fits
+    pub track: Option<Arc<MusicTrack>>,
+}
+
+/// A finished render
+pub struct RenderedOutput {
+    /// The clip with its effects and music bed, `None` when neither applied
+    pub samples: Option<Vec<f32>>,
+    /// Track the music bed was mixed from, or why it could not be loaded
+    pub track: Option<Result<Arc<MusicTrack>, String>>,
+}
+
+impl OutputJob {
+    /// Decode the music bed track if needed, then run the effects and mix
+    pub fn render(self) -> RenderedOutput {
+        let (sample_rate, channels) = (self.sample_rate, self.channels);
+        let track = self.music.as_ref().and_then(|settings| {
+            let path = settings.track_path.as_ref()?;
+            Some(match self.track {
+                Some(track) if track.matches(path, sample_rate, channels) => Ok(track),
+                _ => MusicTrack::load(path, sample_rate, channels)
+                    .map(Arc::new)
+                    .map_err(|e| format!("{}: {}", path.display(), e)),
+            })
+        });
+        let music = self.music.zip(track.clone().and_then(Result::ok));
+        if self.chain.is_none() && music.is_none() {
+            return RenderedOutput {
+                samples: None,
+                track,
+            };
+        }
+        let speech = match &self.chain {
+            Some(chain) => chain.process(&self.samples, sample_rate, channels),
+            None => self.samples,
+        };
+        let samples = match music {
+            Some((settings, track)) => settings.mix(&speech, &track.samples, sample_rate, channels),
+            None => speech,
+        };
+        RenderedOutput {
+            samples: Some(samples),
+            track,
+        }
+    }
+}
+
+/// An output clip rendering on a worker thread
+pub struct OutputRender {
+    source_len: usize,
+    receiver: Receiver<RenderedOutput>,
+    output: Option<RenderedOutput>,
+}
+
+impl OutputRender {
+    /// Start rendering `job` on a worker thread
+    pub fn spawn(job: OutputJob) -> Self {
+        let source_len = job.samples.len();
+        let (sender, receiver) = mpsc::channel();
+        std::thread::spawn(move || {
+            // The screen may have moved on and dropped the receiver
+            let _ = sender.send(job.render());
+        });
+        Self {
+            source_len,
+            receiver,
+            output: None,
+        }
+    }
+
+    /// Length of the stored clip this renders
+    pub fn source_len(&self) -> usize {
+        self.source_len
+    }
+
+    /// Whether the render has finished; its output is kept until taken
+    pub fn poll(&mut self) -> bool {
+        if self.output.is_none() {
+            match self.receiver.try_recv() {
+                Ok(output) => self.output = Some(output),
+                Err(TryRecvError::Disconnected) => {
+                    log::error!("Output clip render stopped without a result");
+                    self.output = Some(RenderedOutput {
+                        samples: None,
+                        track: None,
+                    });
+                }
+                Err(TryRecvError::Empty) => {}
+            }
+        }
+        self.output.is_some()
+    }
+
+    /// The finished output, `None` while rendering or when nothing applied
+    pub fn output(&self) -> Option<&[f32]> {
+        self.output
+            .as_ref()
+            .and_then(|output| output.samples.as_deref())
+    }
+
+    /// The music bed track the finished render loaded or reused, handed out
+    /// once
+    pub fn take_track(&mut self) -> Option<Result<Arc<MusicTrack>, String>> {
+        self.output.as_mut().and_then(|output| output.track.take())
+    }
+
+    /// Take the finished output, `None` when nothing applied
+    pub fn into_output(self) -> Option<Vec<f32>> {
+        self.output.and_then(|output| output.samples)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, Instant};
+
+    fn finish(render: &mut OutputRender) {
+        let started = Instant::now();
+        while !render.poll() {
+            assert!(
+                started.elapsed() < Duration::from_secs(10),
+                "render did not finish"
+            );
+            std::thread::sleep(Duration::from_millis(1));
+        }
+    }
+
+    #[test]
+    fn test_render_without_effects_or_music_is_none() {
+        let mut render = OutputRender::spawn(OutputJob {
+            samples: vec![0.1; 480],
+            sample_rate: 48000,
+            channels: 1,
+            chain: None,
+            music: None,
+            track: None,
+        });
+        assert_eq!(render.source_len(), 480);
+        finish(&mut render);
+        assert!(render.output().is_none());
+        assert!(render.take_track().is_none());
+        assert!(render.into_output().is_none());
+    }
+
+    #[test]
+    fn test_render_reuses_a_matching_track() {
+        let speech: Vec<f32> = (0..4800).map(|i| (i as f32 * 0.05).sin() * 0.5).collect();
+        let track = Arc::new(MusicTrack {
+            path: "bed.wav".into(),
+            sample_rate: 48000,
+            channels: 1,
+            samples: (0..960).map(|i| (i as f32 * 0.01).sin() * 0.3).collect(),
+        });
+        let settings = MusicBedSettings {
+            enabled: true,
+            track_path: Some("bed.wav".into()),
+            ..Default::default()
+        };
+        let expected = settings.mix(&speech, &track.samples, 48000, 1);
+
+        let mut render = OutputRender::spawn(OutputJob {
+            samples: speech,
+            sample_rate: 48000,
+            channels: 1,
+            chain: None,
+            music: Some(settings),
+            track: Some(Arc::clone(&track)),
+        });
+        finish(&mut render);
+        assert_eq!(render.output(), Some(&expected[..]));
+        let reused = render.take_track().unwrap().unwrap();
+        assert!(Arc::ptr_eq(&reused, &track));
+        assert!(render.take_track().is_none());
+        assert_eq!(render.into_output(), Some(expected));
+    }
+
+    #[test]
+    fn test_render_without_its_track_reports_why() {
+        let mut render = OutputRender::spawn(OutputJob {
+            samples: vec![0.1; 480],
+            sample_rate: 48000,
+            channels: 1,
+            chain: None,
+            music: Some(MusicBedSettings {
+                enabled: true,
+                track_path: Some("/nonexistent/bed.wav".into()),
+                ..Default::default()
+            }),
+            track: None,
+        });
+        finish(&mut render);
+        let Some(Err(error)) = render.take_track() else {
+            panic!("missing track was not reported");
+        };
+        assert!(error.starts_with("/nonexistent/bed.wav: "), "{}", error);
+        assert!(render.into_output().is_none());
+    }
+}
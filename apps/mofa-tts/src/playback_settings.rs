@@ -0,0 +1,77 @@
+//! Playback settings persistence
+//!
+//! Per-user playback preferences are stored in:
+//! - Config: ~/.dora/primespeech/playback_settings.json
+
+use crate::post_process::PostProcessPreset;
+use crate::voice_persistence::{ensure_directories, get_primespeech_dir};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+/// Playback settings file format
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PlaybackSettings {
+    /// Config version for future compatibility
+    pub version: String,
+    /// Output device name; `None` follows the system default device
+    #[serde(default)]
+    pub output_device: Option<String>,
+    /// Play generated audio as it arrives instead of after the full render
+    #[serde(default)]
+    pub streaming: bool,
+    /// Post-processing applied to previews and exports
+    #[serde(default)]
+    pub post_fx: PostProcessPreset,
+}
+
+impl Default for PlaybackSettings {
+    fn default() -> Self {
+        Self {
+            version: "1.0".to_string(),
+            output_device: None,
+            streaming: false,
+            post_fx: PostProcessPreset::default(),
+        }
+    }
+}
+
+/// Get the playback settings file path
+pub fn get_config_path() -> PathBuf {
+    get_primespeech_dir().join("playback_settings.json")
+}
+
+/// Load playback settings, falling back to defaults
+pub fn load_settings() -> PlaybackSettings {
+    let config_path = get_config_path();
+
+    if !config_path.exists() {
+        return PlaybackSettings::default();
+    }
+
+    match fs::read_to_string(&config_path) {
+        Ok(content) => match serde_json::from_str::<PlaybackSettings>(&content) {
+            Ok(settings) => settings,
+            Err(e) => {
+                log::error!("Failed to parse playback settings: {}", e);
+                PlaybackSettings::default()
+            }
+        },
+        Err(e) => {
+            log::error!("Failed to read playback settings: {}", e);
+            PlaybackSettings::default()
+        }
+    }
+}
+
+/// Save playback settings to the config file
+pub fn save_settings(settings: &PlaybackSettings) -> Result<(), String> {
+    ensure_directories().map_err(|e| format!("Failed to create directories: {}", e))?;
+
+    let json = serde_json::to_string_pretty(settings)
+        .map_err(|e| format!("Failed to serialize settings: {}", e))?;
+
+    fs::write(get_config_path(), json).map_err(|e| format!("Failed to write settings: {}", e))?;
+
+    Ok(())
+}
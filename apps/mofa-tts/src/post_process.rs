@@ -0,0 +1,593 @@
+//! Post-processing of generated speech
+//!
+//! A fixed-order DSP chain run on the stored clip before it is previewed or
+//! exported, leaving the stored clip itself untouched:
+//! trim silence → speed (pitch preserved) → loudness normalization → gain →
+//! fades → padding.
+//!
+//! Chains are configured per voice, with a default for the rest, in:
+//! - Config: ~/.dora/primespeech/post_process.json
+//!
+//! Each preview or export picks a [`PostProcessPreset`]: no processing, the
+//! voice's chain as configured, or the chain normalized for podcast (−16 LUFS)
+//! or broadcast (−23 LUFS) delivery.
+
+use crate::voice_persistence::{ensure_directories, get_primespeech_dir};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+/// Loudness targets of the delivery presets (LUFS)
+pub const PODCAST_LUFS: f32 = -16.0;
+pub const BROADCAST_LUFS: f32 = -23.0;
+
+/// Audio kept around the loud part when trimming silence
+const TRIM_MARGIN_SECONDS: f32 = 0.01;
+
+/// Normalization and the music bed never push peaks above −1 dBFS
+pub const PEAK_CEILING: f32 = 0.891;
+
+/// Time-stretch analysis window and how far a segment may shift to line up
+/// with the previous one
+const STRETCH_WINDOW_SECONDS: f32 = 0.03;
+const STRETCH_TOLERANCE_SECONDS: f32 = 0.008;
+
+/// Every n-th sample is compared when lining up stretch segments
+const STRETCH_CORRELATION_STEP: usize = 2;
+
+/// Supported speed range
+const MIN_SPEED: f32 = 0.5;
+const MAX_SPEED: f32 = 2.0;
+
+/// Loudness measurement blocks (BS.1770: 400 ms, 75% overlap) and gates
+const LOUDNESS_BLOCK_SECONDS: f64 = 0.4;
+const LOUDNESS_STEP_SECONDS: f64 = 0.1;
+const ABSOLUTE_GATE_LUFS: f64 = -70.0;
+const RELATIVE_GATE_LU: f64 = -10.0;
+
+/// Processing steps for a clip
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PostProcessChain {
+    /// Cut leading and trailing audio quieter than `silence_threshold_db`
+    pub trim_silence: bool,
+    pub silence_threshold_db: f32,
+    /// Speaking rate (1.0 = unchanged, 0.5-2.0); pitch is preserved
+    pub speed: f32,
+    /// Integrated loudness to normalize to (LUFS), `None` to keep the level
+    pub loudness_target: Option<f32>,
+    /// Gain applied after normalization (dB)
+    pub gain_db: f32,
+    pub fade_in_ms: u32,
+    pub fade_out_ms: u32,
+    /// Silence added before and after the clip
+    pub pad_start_ms: u32,
+    pub pad_end_ms: u32,
+}
+
+impl Default for PostProcessChain {
+    fn default() -> Self {
+        Self {
+            trim_silence: true,
+            silence_threshold_db: -50.0,
+            speed: 1.0,
+            loudness_target: None,
+            gain_db: 0.0,
+            fade_in_ms: 10,
+            fade_out_ms: 20,
+            pad_start_ms: 0,
+            pad_end_ms: 0,
+        }
+    }
+}
+
+impl PostProcessChain {
+    /// Run the chain on interleaved `samples`, returning the processed copy
+    pub fn process(&self, samples: &[f32], sample_rate: u32, channels: u16) -> Vec<f32> {
+        let channels = channels.max(1) as usize;
+        let frames_per_ms = sample_rate as f32 / 1000.0;
+        let ms = |ms: u32| (ms as f32 * frames_per_ms).round() as usize;
+
+        let mut audio = samples[..samples.len() / channels * channels].to_vec();
+        if self.trim_silence {
+            audio = trim_silence(&audio, channels, sample_rate, self.silence_threshold_db);
+        }
+        if (self.speed - 1.0).abs() > 1e-3 {
+            let speed = self.speed.clamp(MIN_SPEED, MAX_SPEED);
+            audio = time_stretch(&audio, channels, sample_rate, speed);
+        }
+        if let Some(target) = self.loudness_target {
+            normalize_loudness(&mut audio, channels, sample_rate, target);
+        }
+        if self.gain_db != 0.0 {
+            let gain = db_to_gain(self.gain_db);
+            audio.iter_mut().for_each(|s| *s *= gain);
+        }
+        apply_fades(
+            &mut audio,
+            channels,
+            ms(self.fade_in_ms),
+            ms(self.fade_out_ms),
+        );
+
+        let mut padded = vec![0.0; ms(self.pad_start_ms) * channels];
+        padded.extend_from_slice(&audio);
+        padded.resize(padded.len() + ms(self.pad_end_ms) * channels, 0.0);
+        padded
+    }
+}
+
+/// Which chain a preview or export runs
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PostProcessPreset {
+    /// Leave the clip untouched
+    #[default]
+    Off,
+    /// The voice's chain as configured
+    Voice,
+    /// The voice's chain, normalized to −16 LUFS
+    Podcast,
+    /// The voice's chain, normalized to −23 LUFS
+    Broadcast,
+}
+
+impl PostProcessPreset {
+    /// Presets in picker order
+    pub const ALL: [Self; 4] = [Self::Off, Self::Voice, Self::Podcast, Self::Broadcast];
+
+    pub fn from_index(index: usize) -> Self {
+        Self::ALL.get(index).copied().unwrap_or_default()
+    }
+
+    pub fn index(self) -> usize {
+        Self::ALL.iter().position(|&p| p == self).unwrap_or(0)
+    }
+
+    /// Short description for logs
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Off => "off",
+            Self::Voice => "voice chain",
+            Self::Podcast => "podcast (-16 LUFS)",
+            Self::Broadcast => "broadcast (-23 LUFS)",
+        }
+    }
+
+    /// The chain to run with `voice_chain` as the base, `None` for none
+    pub fn chain(self, voice_chain: &PostProcessChain) -> Option<PostProcessChain> {
+        let loudness_target = match self {
+            Self::Off => return None,
+            Self::Voice => voice_chain.loudness_target,
+            Self::Podcast => Some(PODCAST_LUFS),
+            Self::Broadcast => Some(BROADCAST_LUFS),
+        };
+        Some(PostProcessChain {
+            loudness_target,
+            ..voice_chain.clone()
+        })
+    }
+}
+
+/// Post-processing config file format
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PostProcessConfig {
+    /// Config version for future compatibility
+    pub version: String,
+    /// Chain for voices without their own
+    #[serde(default)]
+    pub default: PostProcessChain,
+    /// Chains by voice id
+    #[serde(default)]
+    pub voices: HashMap<String, PostProcessChain>,
+}
+
+impl Default for PostProcessConfig {
+    fn default() -> Self {
+        Self {
+            version: "1.0".to_string(),
+            default: PostProcessChain::default(),
+            voices: HashMap::new(),
+        }
+    }
+}
+
+impl PostProcessConfig {
+    /// Chain configured for `voice_id`, or the default one
+    pub fn chain_for(&self, voice_id: &str) -> &PostProcessChain {
+        self.voices.get(voice_id).unwrap_or(&self.default)
+    }
+}
+
+/// Get the post-processing config file path
+pub fn get_config_path() -> PathBuf {
+    get_primespeech_dir().join("post_process.json")
+}
+
+/// Load the post-processing config. A missing file is created with the
+/// defaults so there is something to edit.
+pub fn load_config() -> PostProcessConfig {
+    let config_path = get_config_path();
+
+    if !config_path.exists() {
+        let config = PostProcessConfig::default();
+        if let Err(e) = save_config(&config) {
+            log::warn!("Failed to write default post-processing config: {}", e);
+        }
+        return config;
+    }
+
+    match fs::read_to_string(&config_path) {
+        Ok(content) => match serde_json::from_str::<PostProcessConfig>(&content) {
+            Ok(config) => config,
+            Err(e) => {
+                log::error!("Failed to parse post-processing config: {}", e);
+                PostProcessConfig::default()
+            }
+        },
+        Err(e) => {
+            log::error!("Failed to read post-processing config: {}", e);
+            PostProcessConfig::default()
+        }
+    }
+}
+
+/// Save the post-processing config
+pub fn save_config(config: &PostProcessConfig) -> Result<(), String> {
+    ensure_directories().map_err(|e| format!("Failed to create directories: {}", e))?;
+
+    let json = serde_json::to_string_pretty(config)
+        .map_err(|e| format!("Failed to serialize config: {}", e))?;
+
+    fs::write(get_config_path(), json).map_err(|e| format!("Failed to write config: {}", e))?;
+
+    Ok(())
+}
+
+fn db_to_gain(db: f32) -> f32 {
+    10f32.powf(db / 20.0)
+}
+
+/// Cut frames quieter than `threshold_db` from both ends, keeping a short
+/// margin. A clip that is silent throughout is left as is.
+fn trim_silence(samples: &[f32], channels: usize, sample_rate: u32, threshold_db: f32) -> Vec<f32> {
+    let threshold = db_to_gain(threshold_db);
+    let loud = |frame: &[f32]| frame.iter().any(|s| s.abs() > threshold);
+    let frames: Vec<&[f32]> = samples.chunks_exact(channels).collect();
+    let (Some(first), Some(last)) = (
+        frames.iter().position(|f| loud(f)),
+        frames.iter().rposition(|f| loud(f)),
+    ) else {
+        return samples.to_vec();
+    };
+
+    let margin = (TRIM_MARGIN_SECONDS * sample_rate as f32) as usize;
+    let start = first.saturating_sub(margin);
+    let end = (last + 1 + margin).min(frames.len());
+    samples[start * channels..end * channels].to_vec()
+}
+
+/// Change the duration by `1 / speed` without changing pitch (WSOLA): Hann
+/// windowed segments are taken from the input at `speed` times the output
+/// hop, each shifted within a small tolerance to line up with how the
+/// previous segment continues, and overlap-added.
+fn time_stretch(samples: &[f32], channels: usize, sample_rate: u32, speed: f32) -> Vec<f32> {
+    let frames = samples.len() / channels;
+    let window = ((sample_rate as f32 * STRETCH_WINDOW_SECONDS) as usize / 2 * 2).max(64);
+    if frames < window {
+        return samples.to_vec();
+    }
+    let hop_out = window / 2;
+    let hop_in = hop_out as f32 * speed;
+    let tolerance = ((sample_rate as f32 * STRETCH_TOLERANCE_SECONDS) as usize).max(1);
+    let out_frames = (frames as f32 / speed).round() as usize;
+
+    let mono: Vec<f32> = samples
+        .chunks_exact(channels)
+        .map(|f| f.iter().sum::<f32>() / channels as f32)
+        .collect();
+    // Periodic Hann: windows at half-window hops sum to one
+    let hann: Vec<f32> = (0..window)
+        .map(|i| 0.5 - 0.5 * (2.0 * std::f32::consts::PI * i as f32 / window as f32).cos())
+        .collect();
+
+    let mut out = vec![0.0f32; (out_frames + window) * channels];
+    let mut weight = vec![0.0f32; out_frames + window];
+    let mut previous: Option<usize> = None;
+    let mut segment = 0;
+    loop {
+        let out_pos = segment * hop_out;
+        if out_pos >= out_frames {
+            break;
+        }
+        let nominal = (segment as f32 * hop_in).round() as usize;
+        let start = match previous {
+            None => 0,
+            Some(previous) => {
+                best_alignment(&mono, previous + hop_out, nominal, tolerance, hop_out)
+            }
+        };
+        for (i, &w) in hann.iter().enumerate() {
+            let src = start + i;
+            if src >= frames {
+                break;
+            }
+            for ch in 0..channels {
+                out[(out_pos + i) * channels + ch] += samples[src * channels + ch] * w;
+            }
+            weight[out_pos + i] += w;
+        }
+        previous = Some(start);
+        segment += 1;
+    }
+
+    // Undo the window where segments do not fully overlap (clip edges)
+    for (frame, &w) in out.chunks_exact_mut(channels).zip(&weight) {
+        if w > 1e-3 {
+            frame.iter_mut().for_each(|s| *s /= w);
+        }
+    }
+    out.truncate(out_frames * channels);
+    out
+}
+
+/// Start within `tolerance` of `nominal` whose next `len` samples best match
+/// the `len` samples at `natural` (cross-correlation)
+fn best_alignment(
+    mono: &[f32],
+    natural: usize,
+    nominal: usize,
+    tolerance: usize,
+    len: usize,
+) -> usize {
+    if natural + len > mono.len() || nominal + len > mono.len() {
+        return nominal;
+    }
+    let target = &mono[natural..natural + len];
+    let first = nominal.saturating_sub(tolerance);
+    let last = (nominal + tolerance).min(mono.len() - len);
+    let mut best = (f32::MIN, nominal);
+    for candidate in first..=last {
+        let correlation: f32 = (0..len)
+            .step_by(STRETCH_CORRELATION_STEP)
+            .map(|i| target[i] * mono[candidate + i])
+            .sum();
+        if correlation > best.0 {
+            best = (correlation, candidate);
+        }
+    }
+    best.1
+}
+
+/// Second-order IIR section (direct form I)
+struct Biquad {
+    b: [f64; 3],
+    a: [f64; 2],
+    x: [f64; 2],
+    y: [f64; 2],
+}
+
+impl Biquad {
+    fn process(&mut self, x: f64) -> f64 {
+        let y = self.b[0] * x + self.b[1] * self.x[0] + self.b[2] * self.x[1]
+            - self.a[0] * self.y[0]
+            - self.a[1] * self.y[1];
+        self.x = [x, self.x[0]];
+        self.y = [y, self.y[0]];
+        y
+    }
+}
+
+/// BS.1770 K-weighting for `sample_rate`: a high shelf modelling the head,
+/// then a high pass
+fn k_weighting(sample_rate: u32) -> [Biquad; 2] {
+    let fs = sample_rate as f64;
+
+    let (f0, gain_db, q) = (1681.974450955533, 3.999843853973347, 0.7071752369554196);
+    let k = (std::f64::consts::PI * f0 / fs).tan();
+    let vh = 10f64.powf(gain_db / 20.0);
+    let vb = vh.powf(0.4996667741545416);
+    let a0 = 1.0 + k / q + k * k;
+    let shelf = Biquad {
+        b: [
+            (vh + vb * k / q + k * k) / a0,
+            2.0 * (k * k - vh) / a0,
+            (vh - vb * k / q + k * k) / a0,
+        ],
+        a: [2.0 * (k * k - 1.0) / a0, (1.0 - k / q + k * k) / a0],
+        x: [0.0; 2],
+        y: [0.0; 2],
+    };
+
+    let (f0, q) = (38.13547087602444, 0.5003270373238773);
+    let k = (std::f64::consts::PI * f0 / fs).tan();
+    let a0 = 1.0 + k / q + k * k;
+    let high_pass = Biquad {
+        b: [1.0, -2.0, 1.0],
+        a: [2.0 * (k * k - 1.0) / a0, (1.0 - k / q + k * k) / a0],
+        x: [0.0; 2],
+        y: [0.0; 2],
+    };
+
+    [shelf, high_pass]
+}
+
+/// Integrated loudness (LUFS) as per ITU-R BS.1770, `None` for clips that
+/// are silent after gating
+fn integrated_loudness(samples: &[f32], channels: usize, sample_rate: u32) -> Option<f64> {
+    let frames = samples.len() / channels;
+    if frames == 0 {
+        return None;
+    }
+
+    // K-weighted energy per frame, summed over channels (all weighted 1.0)
+    let mut energy = vec![0.0f64; frames];
+    for ch in 0..channels {
+        let [mut shelf, mut high_pass] = k_weighting(sample_rate);
+        for (frame, e) in energy.iter_mut().enumerate() {
+            let y = high_pass.process(shelf.process(samples[frame * channels + ch] as f64));
+            *e += y * y;
+        }
+    }
+
+    let block = ((LOUDNESS_BLOCK_SECONDS * sample_rate as f64) as usize).clamp(1, frames);
+    let step = ((LOUDNESS_STEP_SECONDS * sample_rate as f64) as usize).max(1);
+    let mut blocks = Vec::new();
+    let mut start = 0;
+    while start + block <= frames {
+        blocks.push(energy[start..start + block].iter().sum::<f64>() / block as f64);
+        start += step;
+    }
+
+    let lufs = |z: f64| -0.691 + 10.0 * z.log10();
+    let mean = |blocks: &[f64]| blocks.iter().sum::<f64>() / blocks.len() as f64;
+    let audible: Vec<f64> = blocks
+        .into_iter()
+        .filter(|&z| z > 0.0 && lufs(z) > ABSOLUTE_GATE_LUFS)
+        .collect();
+    if audible.is_empty() {
+        return None;
+    }
+    let relative_gate = lufs(mean(&audible)) + RELATIVE_GATE_LU;
+    let gated: Vec<f64> = audible
+        .into_iter()
+        .filter(|&z| lufs(z) > relative_gate)
+        .collect();
+    Some(lufs(mean(&gated)))
+}
+
+/// Scale the clip to `target` LUFS, holding peaks under the ceiling
+fn normalize_loudness(samples: &mut [f32], channels: usize, sample_rate: u32, target: f32) {
+    let Some(loudness) = integrated_loudness(samples, channels, sample_rate) else {
+        return;
+    };
+    let mut gain = db_to_gain(target - loudness as f32);
+    let peak = samples.iter().fold(0.0f32, |peak, s| peak.max(s.abs()));
+    if peak * gain > PEAK_CEILING {
+        gain = PEAK_CEILING / peak;
+        log::warn!(
+            "Loudness normalization held back by peaks: {:.1} LUFS instead of {:.1}",
+            loudness as f32 + 20.0 * gain.log10(),
+            target
+        );
+    }
+    samples.iter_mut().for_each(|s| *s *= gain);
+}
+
+/// Linear fade in over the first `fade_in` frames and out over the last
+/// `fade_out`
+fn apply_fades(samples: &mut [f32], channels: usize, fade_in: usize, fade_out: usize) {
+    let frames = samples.len() / channels;
+    let fade_in = fade_in.min(frames);
+    let fade_out = fade_out.min(frames);
+    for (i, frame) in samples.chunks_exact_mut(channels).enumerate() {
+        let mut gain = 1.0;
+        if i < fade_in {
+            gain *= i as f32 / fade_in as f32;
+        }
+        if i >= frames - fade_out {
+            gain *= (frames - i - 1) as f32 / fade_out as f32;
+        }
+        frame.iter_mut().for_each(|s| *s *= gain);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sine(hz: f32, amplitude: f32, sample_rate: u32, seconds: f32) -> Vec<f32> {
+        let frames = (sample_rate as f32 * seconds) as usize;
+        (0..frames)
+            .map(|i| {
+                amplitude * (2.0 * std::f32::consts::PI * hz * i as f32 / sample_rate as f32).sin()
+            })
+            .collect()
+    }
+
+    /// Upward zero crossings per second in the middle half of a clip
+    fn frequency(samples: &[f32], sample_rate: u32) -> f32 {
+        let middle = &samples[samples.len() / 4..samples.len() * 3 / 4];
+        let crossings = middle
+            .windows(2)
+            .filter(|w| w[0] < 0.0 && w[1] >= 0.0)
+            .count();
+        crossings as f32 * sample_rate as f32 / middle.len() as f32
+    }
+
+    #[test]
+    fn test_loudness_matches_bs1770_reference_tone() {
+        // A 997 Hz sine at -20 dBFS on one channel reads -23 LUFS
+        let tone = sine(997.0, 0.1, 48000, 3.0);
+        let loudness = integrated_loudness(&tone, 1, 48000).unwrap();
+        assert!((loudness + 23.0).abs() < 0.1, "loudness {}", loudness);
+        assert_eq!(integrated_loudness(&vec![0.0; 48000], 1, 48000), None);
+
+        let mut normalized = tone.clone();
+        normalize_loudness(&mut normalized, 1, 48000, PODCAST_LUFS);
+        let loudness = integrated_loudness(&normalized, 1, 48000).unwrap();
+        assert!(
+            (loudness - PODCAST_LUFS as f64).abs() < 0.1,
+            "loudness {}",
+            loudness
+        );
+    }
+
+    #[test]
+    fn test_time_stretch_keeps_pitch() {
+        let tone = sine(440.0, 0.5, 16000, 1.0);
+        for speed in [0.75, 1.5] {
+            let stretched = time_stretch(&tone, 1, 16000, speed);
+            let expected = (16000.0 / speed).round() as usize;
+            assert_eq!(stretched.len(), expected);
+            let hz = frequency(&stretched, 16000);
+            assert!((hz - 440.0).abs() < 10.0, "speed {} gave {} Hz", speed, hz);
+        }
+    }
+
+    #[test]
+    fn test_chain_trims_fades_and_pads() {
+        let sample_rate = 1000;
+        let mut clip = vec![0.0; 200];
+        clip.extend(vec![0.5; 100]);
+        clip.extend(vec![0.0; 300]);
+        let chain = PostProcessChain {
+            fade_in_ms: 30,
+            fade_out_ms: 30,
+            pad_start_ms: 50,
+            pad_end_ms: 20,
+            gain_db: 6.0,
+            ..PostProcessChain::default()
+        };
+
+        let out = chain.process(&clip, sample_rate, 1);
+        // 10 ms margin either side of the loud part, plus the padding
+        assert_eq!(out.len(), 50 + 10 + 100 + 10 + 20);
+        assert!(out[..60].iter().all(|&s| s == 0.0));
+        assert!(out[50 + 120..].iter().all(|&s| s == 0.0));
+        // Faded in over the first 30 ms of the trimmed clip, then at +6 dB
+        assert!(out[60] > 0.0 && out[60] < out[79]);
+        assert!(out[159] < out[130]);
+        assert!((out[100] - 0.5 * db_to_gain(6.0)).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_presets_override_only_the_loudness_target() {
+        let voice = PostProcessChain {
+            speed: 1.2,
+            loudness_target: Some(-20.0),
+            ..PostProcessChain::default()
+        };
+        assert_eq!(PostProcessPreset::Off.chain(&voice), None);
+        assert_eq!(PostProcessPreset::Voice.chain(&voice), Some(voice.clone()));
+        let podcast = PostProcessPreset::Podcast.chain(&voice).unwrap();
+        assert_eq!(podcast.loudness_target, Some(PODCAST_LUFS));
+        assert_eq!(podcast.speed, 1.2);
+        assert_eq!(
+            PostProcessPreset::from_index(3),
+            PostProcessPreset::Broadcast
+        );
+        assert_eq!(PostProcessPreset::from_index(9), PostProcessPreset::Off);
+    }
+}
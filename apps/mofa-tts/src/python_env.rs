@@ -0,0 +1,451 @@
+//! Python environment discovery for subprocess launches
+//!
+//! Training, GPU detection and evaluation all shell out to Python. Running a bare
+//! `python` only works when the `mofa-studio` conda env happens to be activated, so
+//! every launch goes through [`resolve`] instead.
+//!
+//! Resolution order:
+//! 1. `MOFA_PYTHON` environment variable (path to an interpreter)
+//! 2. `interpreter` in ~/.dora/primespeech/python_env.json
+//! 3. Active environment: `CONDA_PREFIX`, `VIRTUAL_ENV`, `PIXI_PROJECT_ROOT`
+//! 4. Well-known locations: `<conda>/envs/mofa-studio`, `.pixi/envs/default`, `.venv`
+//! 5. `python3` / `python` on PATH
+//!
+//! The first candidate that runs and imports all required packages wins. A found
+//! environment is cached for the lifetime of the process; call [`refresh`] after the
+//! user changes the config. Failures are not cached, so the next lookup probes again
+//! once the user has installed the env. UI code uses [`resolve_in_background`] since
+//! probing runs the interpreters.
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Conda environment name created by the setup scripts
+pub const DEFAULT_CONDA_ENV: &str = "mofa-studio";
+
+/// Python modules every subprocess launch needs
+pub const REQUIRED_PACKAGES: &[&str] = &["torch", "dora_primespeech"];
+
+/// Kind of environment an interpreter was found in
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum PythonEnvKind {
+    /// Set explicitly via `MOFA_PYTHON` or the config file
+    Configured,
+    Conda,
+    Pixi,
+    Venv,
+    /// Interpreter found on PATH
+    System,
+}
+
+impl PythonEnvKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PythonEnvKind::Configured => "configured",
+            PythonEnvKind::Conda => "conda",
+            PythonEnvKind::Pixi => "pixi",
+            PythonEnvKind::Venv => "venv",
+            PythonEnvKind::System => "system",
+        }
+    }
+}
+
+/// User configuration file format
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct PythonEnvConfig {
+    /// Explicit interpreter path (highest priority after `MOFA_PYTHON`)
+    #[serde(default)]
+    pub interpreter: Option<PathBuf>,
+    /// Conda environment name to look for (defaults to `mofa-studio`)
+    #[serde(default)]
+    pub conda_env: Option<String>,
+    /// Extra packages to require on top of [`REQUIRED_PACKAGES`]
+    #[serde(default)]
+    pub extra_packages: Vec<String>,
+}
+
+/// A resolved Python interpreter
+#[derive(Clone, Debug)]
+pub struct PythonEnv {
+    /// Absolute path to the interpreter
+    pub interpreter: PathBuf,
+    /// Where the interpreter was found
+    pub kind: PythonEnvKind,
+    /// Interpreter version string (e.g. "3.12.4")
+    pub version: String,
+    /// Whether `torch.cuda.is_available()` returned true
+    pub has_cuda: bool,
+}
+
+impl PythonEnv {
+    /// Create a command that runs this interpreter
+    pub fn command(&self) -> Command {
+        Command::new(&self.interpreter)
+    }
+
+    /// One-line summary for logs and the UI
+    pub fn describe(&self) -> String {
+        format!(
+            "Python {} ({}) at {}",
+            self.version,
+            self.kind.as_str(),
+            self.interpreter.display()
+        )
+    }
+}
+
+/// A candidate that was probed and rejected
+#[derive(Clone, Debug)]
+pub struct RejectedCandidate {
+    pub interpreter: PathBuf,
+    pub kind: PythonEnvKind,
+    pub reason: String,
+}
+
+/// Resolution failure with per-candidate diagnostics
+#[derive(Clone, Debug)]
+pub struct PythonEnvError {
+    pub rejected: Vec<RejectedCandidate>,
+}
+
+impl PythonEnvError {
+    /// Human-readable lines describing why each candidate was rejected
+    pub fn diagnostics(&self) -> Vec<String> {
+        if self.rejected.is_empty() {
+            return vec![
+                "No Python interpreter found. Set MOFA_PYTHON or activate the mofa-studio conda env."
+                    .to_string(),
+            ];
+        }
+        self.rejected
+            .iter()
+            .map(|c| {
+                format!(
+                    "{} ({}): {}",
+                    c.interpreter.display(),
+                    c.kind.as_str(),
+                    c.reason
+                )
+            })
+            .collect()
+    }
+}
+
+impl std::fmt::Display for PythonEnvError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "No usable Python environment (need {}). Set MOFA_PYTHON or edit {}",
+            REQUIRED_PACKAGES.join(", "),
+            get_config_path().display()
+        )
+    }
+}
+
+impl std::error::Error for PythonEnvError {}
+
+static CACHE: Mutex<Option<PythonEnv>> = Mutex::new(None);
+
+/// Lookup started by [`resolve_in_background`]
+enum BackgroundProbe {
+    Idle,
+    Running,
+    /// Failure not yet picked up by the caller
+    Failed(PythonEnvError),
+}
+
+static BACKGROUND: Mutex<BackgroundProbe> = Mutex::new(BackgroundProbe::Idle);
+
+/// Get the python_env config file path
+pub fn get_config_path() -> PathBuf {
+    let home = dirs::home_dir().unwrap_or_else(|| PathBuf::from("."));
+    home.join(".dora").join("primespeech").join("python_env.json")
+}
+
+/// Load the config file, falling back to defaults
+pub fn load_config() -> PythonEnvConfig {
+    let path = get_config_path();
+    let Ok(content) = fs::read_to_string(&path) else {
+        return PythonEnvConfig::default();
+    };
+    serde_json::from_str(&content).unwrap_or_else(|e| {
+        log::error!("Failed to parse {}: {}", path.display(), e);
+        PythonEnvConfig::default()
+    })
+}
+
+/// Save the config file and invalidate the cached resolution
+pub fn save_config(config: &PythonEnvConfig) -> Result<(), String> {
+    let path = get_config_path();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create config dir: {}", e))?;
+    }
+    let json = serde_json::to_string_pretty(config)
+        .map_err(|e| format!("Failed to serialize config: {}", e))?;
+    fs::write(&path, json).map_err(|e| format!("Failed to write config: {}", e))?;
+    refresh();
+    Ok(())
+}
+
+/// Resolve the Python environment, probing candidates until one is found
+///
+/// Probing runs the interpreters, so an uncached call may take a second or two;
+/// don't call it from the UI thread.
+pub fn resolve() -> Result<PythonEnv, PythonEnvError> {
+    if let Some(env) = CACHE.lock().as_ref() {
+        return Ok(env.clone());
+    }
+    // Probe without the lock so cached lookups elsewhere never wait on it
+    let result = discover(&load_config());
+    match &result {
+        Ok(env) => {
+            log::info!("Using {}", env.describe());
+            *CACHE.lock() = Some(env.clone());
+        }
+        Err(e) => {
+            log::warn!("{}", e);
+            for line in e.diagnostics() {
+                log::warn!("  {}", line);
+            }
+        }
+    }
+    result
+}
+
+/// Non-blocking [`resolve`] for the UI thread
+///
+/// Returns the cached environment, or starts a probe on a background thread and
+/// returns `None` until it finishes. A failed probe is returned once; the call
+/// after that probes again.
+pub fn resolve_in_background() -> Option<Result<PythonEnv, PythonEnvError>> {
+    if let Some(env) = CACHE.lock().as_ref() {
+        return Some(Ok(env.clone()));
+    }
+    let mut background = BACKGROUND.lock();
+    match std::mem::replace(&mut *background, BackgroundProbe::Running) {
+        BackgroundProbe::Running => None,
+        BackgroundProbe::Failed(e) => {
+            *background = BackgroundProbe::Idle;
+            Some(Err(e))
+        }
+        BackgroundProbe::Idle => {
+            std::thread::spawn(|| {
+                let state = match resolve() {
+                    Ok(_) => BackgroundProbe::Idle,
+                    Err(e) => BackgroundProbe::Failed(e),
+                };
+                *BACKGROUND.lock() = state;
+            });
+            None
+        }
+    }
+}
+
+/// Drop the cached resolution so the next [`resolve`] probes again
+pub fn refresh() {
+    *CACHE.lock() = None;
+}
+
+/// Probe all candidates in order and return the first usable one
+pub fn discover(config: &PythonEnvConfig) -> Result<PythonEnv, PythonEnvError> {
+    let mut required: Vec<&str> = REQUIRED_PACKAGES.to_vec();
+    required.extend(config.extra_packages.iter().map(String::as_str));
+
+    let mut rejected = Vec::new();
+    for (interpreter, kind) in candidates(config) {
+        match probe(&interpreter, &required) {
+            Ok((version, has_cuda)) => {
+                return Ok(PythonEnv {
+                    interpreter,
+                    kind,
+                    version,
+                    has_cuda,
+                })
+            }
+            Err(reason) => rejected.push(RejectedCandidate {
+                interpreter,
+                kind,
+                reason,
+            }),
+        }
+    }
+    Err(PythonEnvError { rejected })
+}
+
+/// Ordered, de-duplicated list of interpreters to try
+fn candidates(config: &PythonEnvConfig) -> Vec<(PathBuf, PythonEnvKind)> {
+    let env = |key: &str| std::env::var_os(key).map(PathBuf::from);
+    let conda_env = config.conda_env.as_deref().unwrap_or(DEFAULT_CONDA_ENV);
+    let mut list = Vec::new();
+
+    if let Some(path) = env("MOFA_PYTHON") {
+        list.push((path, PythonEnvKind::Configured));
+    }
+    if let Some(path) = &config.interpreter {
+        list.push((path.clone(), PythonEnvKind::Configured));
+    }
+    if let Some(prefix) = env("CONDA_PREFIX") {
+        list.push((interpreter_in(&prefix), PythonEnvKind::Conda));
+    }
+    if let Some(prefix) = env("VIRTUAL_ENV") {
+        list.push((interpreter_in(&prefix), PythonEnvKind::Venv));
+    }
+    if let Some(root) = env("PIXI_PROJECT_ROOT") {
+        list.push((
+            interpreter_in(&root.join(".pixi").join("envs").join("default")),
+            PythonEnvKind::Pixi,
+        ));
+    }
+
+    for base in conda_bases() {
+        list.push((
+            interpreter_in(&base.join("envs").join(conda_env)),
+            PythonEnvKind::Conda,
+        ));
+    }
+    for root in project_roots() {
+        list.push((
+            interpreter_in(&root.join(".pixi").join("envs").join("default")),
+            PythonEnvKind::Pixi,
+        ));
+        list.push((interpreter_in(&root.join(".venv")), PythonEnvKind::Venv));
+    }
+
+    list.push((PathBuf::from("python3"), PythonEnvKind::System));
+    list.push((PathBuf::from("python"), PythonEnvKind::System));
+
+    let mut seen = std::collections::HashSet::new();
+    list.retain(|(path, kind)| {
+        // Bare names are resolved through PATH, everything else must exist
+        let usable = *kind == PythonEnvKind::System || path.exists();
+        usable && seen.insert(path.clone())
+    });
+    list
+}
+
+/// Conda installation roots, from `CONDA_EXE` and common install locations
+fn conda_bases() -> Vec<PathBuf> {
+    let mut bases = Vec::new();
+    if let Some(exe) = std::env::var_os("CONDA_EXE").map(PathBuf::from) {
+        // <base>/bin/conda or <base>\Scripts\conda.exe
+        if let Some(base) = exe.parent().and_then(Path::parent) {
+            bases.push(base.to_path_buf());
+        }
+    }
+    if let Some(home) = dirs::home_dir() {
+        for name in ["miniconda3", "anaconda3", "miniforge3", "mambaforge"] {
+            bases.push(home.join(name));
+        }
+    }
+    bases
+}
+
+/// Directories that may hold a project-local pixi or venv environment
+fn project_roots() -> Vec<PathBuf> {
+    let mut roots = Vec::new();
+    if let Ok(cwd) = std::env::current_dir() {
+        roots.extend(cwd.ancestors().take(4).map(Path::to_path_buf));
+    }
+    roots
+}
+
+/// Interpreter path inside an environment prefix
+fn interpreter_in(prefix: &Path) -> PathBuf {
+    if cfg!(windows) {
+        prefix.join("python.exe")
+    } else {
+        prefix.join("bin").join("python")
+    }
+}
+
+/// Run the interpreter and check required imports
+///
+/// Returns (version, has_cuda) on success, or a reason string on failure.
+fn probe(interpreter: &Path, required: &[&str]) -> Result<(String, bool), String> {
+    let script = format!(
+        "import importlib.util, json, platform\n\
+         missing = [m for m in {:?} if importlib.util.find_spec(m) is None]\n\
+         cuda = False\n\
+         if not missing:\n\
+         \x20   import torch\n\
+         \x20   cuda = bool(torch.cuda.is_available())\n\
+         print(json.dumps({{'version': platform.python_version(), 'missing': missing, 'cuda': cuda}}))",
+        required
+    );
+
+    let output = Command::new(interpreter)
+        .arg("-c")
+        .arg(&script)
+        .output()
+        .map_err(|e| format!("failed to run: {}", e))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let last = stderr.lines().last().unwrap_or("").trim();
+        return Err(format!("exited with {}: {}", output.status, last));
+    }
+
+    parse_probe_output(&String::from_utf8_lossy(&output.stdout))
+}
+
+#[derive(Debug, Deserialize)]
+struct ProbeResult {
+    version: String,
+    missing: Vec<String>,
+    cuda: bool,
+}
+
+fn parse_probe_output(stdout: &str) -> Result<(String, bool), String> {
+    // Packages may print banners on import, the JSON is always the last line
+    let line = stdout.lines().last().unwrap_or("").trim();
+    let result: ProbeResult =
+        serde_json::from_str(line).map_err(|e| format!("unexpected probe output: {}", e))?;
+    if !result.missing.is_empty() {
+        return Err(format!(
+            "Python {} missing packages: {}",
+            result.version,
+            result.missing.join(", ")
+        ));
+    }
+    Ok((result.version, result.cuda))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_probe_output() {
+        let out = "some banner\n{\"version\": \"3.12.4\", \"missing\": [], \"cuda\": true}\n";
+        assert_eq!(parse_probe_output(out), Ok(("3.12.4".to_string(), true)));
+
+        let out = r#"{"version": "3.11.0", "missing": ["torch"], "cuda": false}"#;
+        let err = parse_probe_output(out).unwrap_err();
+        assert!(err.contains("torch"));
+    }
+
+    #[test]
+    fn test_configured_interpreter_first() {
+        let dir = std::env::temp_dir().join("mofa_python_env_test");
+        let python = interpreter_in(&dir);
+        fs::create_dir_all(python.parent().unwrap()).unwrap();
+        fs::write(&python, "").unwrap();
+
+        let config = PythonEnvConfig {
+            interpreter: Some(python.clone()),
+            ..Default::default()
+        };
+        let list = candidates(&config);
+        let first_configured = list
+            .iter()
+            .find(|(_, kind)| *kind == PythonEnvKind::Configured)
+            .unwrap();
+        assert_eq!(first_configured.0, python);
+        assert_eq!(list.last().unwrap().1, PythonEnvKind::System);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -1,16 +1,27 @@
 //! TTS Screen - MoYoYo.tts style interface with sidebar layout
 //! This is a variant of the TTS screen with a sidebar navigation similar to MoYoYo.tts
 
-use crate::audio_player::TTSPlayer;
-use crate::dora_integration::DoraIntegration;
+use crate::dora_integration::{DoraEvent, DoraIntegration};
 use crate::log_bridge;
+use crate::music_bed::{self, MusicBedSettings, MusicTrack};
+use crate::output_render::{OutputJob, OutputRender};
+use crate::playback_settings;
+use crate::post_process::{self, PostProcessConfig, PostProcessPreset};
 use crate::voice_clone_modal::{VoiceCloneModalAction, VoiceCloneModalWidgetExt};
 use crate::voice_data::{TTSStatus, Voice};
 use crate::voice_selector::{VoiceSelectorAction, VoiceSelectorWidgetExt};
 use crate::task_persistence;
 use hound::WavReader;
 use makepad_widgets::*;
-use std::path::PathBuf;
+use mofa_dora_bridge::data::LogLevel;
+use mofa_dora_bridge::{NodeState, NodeStatus};
+use mofa_ui::AudioManager;
+use mofa_widgets::waveform_overview::WaveformOverviewWidgetExt;
+use mofa_widgets::waveform_view::WaveformViewWidgetExt;
+use mofa_widgets::AudioPlayer;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 /// Current page in the application
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
@@ -28,6 +39,8 @@ live_design! {
     use link::widgets::*;
 
     use mofa_widgets::theme::*;
+    use mofa_widgets::waveform_overview::WaveformOverview;
+    use mofa_widgets::waveform_view::WaveformView;
     use crate::voice_selector::VoiceSelector;
     use crate::voice_clone_modal::VoiceCloneModal;
 
@@ -1011,6 +1024,29 @@ live_design! {
                                 align: {y: 0.5}
                                 spacing: 8
 
+                                // Play after the full render, or stream while generating
+                                playback_mode = <DropDown> {
+                                    width: 170, height: 32
+                                    labels: ["生成后播放", "边生成边播放"]
+                                    draw_bg: {
+                                        instance dark_mode: 0.0
+                                        border_radius: 6.0
+                                        fn pixel(self) -> vec4 {
+                                            let sdf = Sdf2d::viewport(self.pos * self.rect_size);
+                                            sdf.box(0., 0., self.rect_size.x, self.rect_size.y, self.border_radius);
+                                            sdf.fill(mix((SLATE_100), (SLATE_700), self.dark_mode));
+                                            return sdf.result;
+                                        }
+                                    }
+                                    draw_text: {
+                                        instance dark_mode: 0.0
+                                        text_style: { font_size: 11.0 }
+                                        fn get_color(self) -> vec4 {
+                                            return mix((SLATE_600), (SLATE_300), self.dark_mode);
+                                        }
+                                    }
+                                }
+
                                 // Spinner on the left (hidden by default)
                                 generate_spinner = <GenerateSpinner> {}
 
@@ -1874,6 +1910,32 @@ live_design! {
 
                             <View> { width: Fill, height: 1 }
 
+                            // Per-node filter, populated from the running dataflow
+                            node_filter = <DropDown> {
+                                width: 120, height: 26
+                                margin: {right: 6}
+                                labels: ["All nodes"]
+                                values: [ALL]
+                                popup_menu_position: BelowInput
+                                draw_bg: {
+                                    instance dark_mode: 0.0
+                                    border_radius: 6.0
+                                    fn pixel(self) -> vec4 {
+                                        let sdf = Sdf2d::viewport(self.pos * self.rect_size);
+                                        sdf.box(0., 0., self.rect_size.x, self.rect_size.y, self.border_radius);
+                                        sdf.fill(mix((SLATE_100), (SLATE_700), self.dark_mode));
+                                        return sdf.result;
+                                    }
+                                }
+                                draw_text: {
+                                    instance dark_mode: 0.0
+                                    text_style: { font_size: 11.0 }
+                                    fn get_color(self) -> vec4 {
+                                        return mix((MOYOYO_TEXT_SECONDARY), (SLATE_300), self.dark_mode);
+                                    }
+                                }
+                            }
+
                             clear_log_btn = <Button> {
                                 width: Fit, height: 26
                                 padding: {left: 10, right: 10}
@@ -1969,7 +2031,7 @@ live_design! {
 
             // Left: Voice info (fixed width for balance)
             voice_info = <View> {
-                width: 220, height: Fill
+                width: 280, height: Fill
                 flow: Right
                 align: {x: 0.0, y: 0.5}
                 spacing: 12
@@ -2031,6 +2093,11 @@ live_design! {
                         text: "Ready"
                     }
                 }
+
+                // Live spectrum of the audio being played
+                output_spectrum = <WaveformView> {
+                    width: 72, height: 36
+                }
             }
 
             // Center: Playback controls (fills space)
@@ -2070,7 +2137,7 @@ live_design! {
 
                 // Progress bar row - centered with max width constraint
                 progress_row = <View> {
-                    width: 350, height: Fit
+                    width: 420, height: Fit
                     flow: Right
                     align: {y: 0.5}
                     spacing: 8
@@ -2087,32 +2154,9 @@ live_design! {
                         text: "00:00"
                     }
 
-                    // Progress bar container
-                    progress_bar_container = <View> {
-                        width: Fill, height: 4
-                        margin: {top: 2}
-
-                        // Progress bar
-                        progress_bar = <View> {
-                            width: Fill, height: Fill
-                            show_bg: true
-                            draw_bg: {
-                                instance dark_mode: 0.0
-                                instance progress: 0.0
-                                fn pixel(self) -> vec4 {
-                                    let sdf = Sdf2d::viewport(self.pos * self.rect_size);
-                                    // Background track
-                                    sdf.box(0.0, 0.0, self.rect_size.x, self.rect_size.y, 2.0);
-                                    let track_color = mix((GRAY_200), (GRAY_700), self.dark_mode);
-                                    sdf.fill(track_color);
-                                    // Progress fill
-                                    let progress_width = self.rect_size.x * self.progress;
-                                    sdf.box(0.0, 0.0, progress_width, self.rect_size.y, 2.0);
-                                    sdf.fill((PRIMARY_500));
-                                    return sdf.result;
-                                }
-                            }
-                        }
+                    // Full clip waveform - click or drag to seek, shift-drag to loop
+                    waveform_overview = <WaveformOverview> {
+                        width: Fill, height: 28
                     }
 
                     total_time = <Label> {
@@ -2131,8 +2175,82 @@ live_design! {
 
             // Right: Download button (fixed width for balance) - MoYoYo.tts style
             download_section = <View> {
-                width: 140, height: Fill
+                width: 520, height: Fill
+                flow: Right
                 align: {x: 1.0, y: 0.5}
+                spacing: 8
+
+                // Background music under previews and exports, persisted per user
+                music_bed = <DropDown> {
+                    width: 110, height: 32
+                    labels: ["无音乐", "背景音乐", "选择音乐…"]
+                    popup_menu_position: AboveInput
+                    draw_bg: {
+                        instance dark_mode: 0.0
+                        border_radius: 6.0
+                        fn pixel(self) -> vec4 {
+                            let sdf = Sdf2d::viewport(self.pos * self.rect_size);
+                            sdf.box(0., 0., self.rect_size.x, self.rect_size.y, self.border_radius);
+                            sdf.fill(mix((SLATE_100), (SLATE_700), self.dark_mode));
+                            return sdf.result;
+                        }
+                    }
+                    draw_text: {
+                        instance dark_mode: 0.0
+                        text_style: { font_size: 11.0 }
+                        fn get_color(self) -> vec4 {
+                            return mix((SLATE_600), (SLATE_300), self.dark_mode);
+                        }
+                    }
+                }
+
+                // Post-processing preset for previews and exports, persisted per user
+                post_fx = <DropDown> {
+                    width: 110, height: 32
+                    labels: ["无效果", "音色效果", "播客 −16 LUFS", "广播 −23 LUFS"]
+                    popup_menu_position: AboveInput
+                    draw_bg: {
+                        instance dark_mode: 0.0
+                        border_radius: 6.0
+                        fn pixel(self) -> vec4 {
+                            let sdf = Sdf2d::viewport(self.pos * self.rect_size);
+                            sdf.box(0., 0., self.rect_size.x, self.rect_size.y, self.border_radius);
+                            sdf.fill(mix((SLATE_100), (SLATE_700), self.dark_mode));
+                            return sdf.result;
+                        }
+                    }
+                    draw_text: {
+                        instance dark_mode: 0.0
+                        text_style: { font_size: 11.0 }
+                        fn get_color(self) -> vec4 {
+                            return mix((SLATE_600), (SLATE_300), self.dark_mode);
+                        }
+                    }
+                }
+
+                // Output device picker, persisted per user
+                output_device = <DropDown> {
+                    width: 130, height: 32
+                    labels: ["System default"]
+                    popup_menu_position: AboveInput
+                    draw_bg: {
+                        instance dark_mode: 0.0
+                        border_radius: 6.0
+                        fn pixel(self) -> vec4 {
+                            let sdf = Sdf2d::viewport(self.pos * self.rect_size);
+                            sdf.box(0., 0., self.rect_size.x, self.rect_size.y, self.border_radius);
+                            sdf.fill(mix((SLATE_100), (SLATE_700), self.dark_mode));
+                            return sdf.result;
+                        }
+                    }
+                    draw_text: {
+                        instance dark_mode: 0.0
+                        text_style: { font_size: 11.0 }
+                        fn get_color(self) -> vec4 {
+                            return mix((SLATE_600), (SLATE_300), self.dark_mode);
+                        }
+                    }
+                }
 
                 download_btn = <Button> {
                     width: Fit, height: 40
@@ -2316,7 +2434,16 @@ pub struct TTSScreen {
     tts_status: TTSStatus,
 
     #[rust]
-    audio_player: Option<TTSPlayer>,
+    audio_player: Option<AudioPlayer>,
+
+    // Output device selection; the manager holds the preferred device
+    #[rust]
+    audio_manager: Option<AudioManager>,
+    #[rust]
+    output_devices: Vec<String>,
+    // Device the player last reported playing on
+    #[rust]
+    active_output_device: Option<String>,
 
     #[rust]
     dora: Option<DoraIntegration>,
@@ -2338,14 +2465,68 @@ pub struct TTSScreen {
     log_entries: Vec<String>,
     #[rust]
     logs_initialized: bool,
+    // Node health from the dataflow and the log panel's node filter
+    #[rust]
+    node_statuses: Vec<NodeStatus>,
+    #[rust]
+    log_filter_nodes: Vec<String>,
+    #[rust]
+    log_node_filter: Option<String>,
+    // Whether the output clip is the clip loaded into the audio player
     #[rust]
-    audio_playing_time: f64,
+    audio_loaded: bool,
 
     // Stored audio for playback/download (not auto-play)
     #[rust]
     stored_audio_samples: Vec<f32>,
     #[rust]
     stored_audio_sample_rate: u32,
+    #[rust]
+    stored_audio_channels: u16,
+
+    // Opt-in streaming: generated audio plays as it arrives
+    #[rust]
+    streaming_playback: bool,
+    // The current generation is being streamed into the player
+    #[rust]
+    streaming_active: bool,
+    // TTS completed; the stream ends once its last fragments are drained
+    #[rust]
+    streaming_ending: bool,
+    // When the current prompt was sent, and how long its first audio took
+    #[rust]
+    generation_started_at: Option<Instant>,
+    #[rust]
+    first_audio_latency: Option<Duration>,
+    // Spectrum view is showing live output and must settle once it stops
+    #[rust]
+    spectrum_live: bool,
+    // Post-processing chains and the preset applied to the output clip
+    #[rust]
+    post_process: PostProcessConfig,
+    #[rust]
+    post_fx: PostProcessPreset,
+    // Voice the stored clip was generated with, for its chain
+    #[rust]
+    clip_voice_id: String,
+    // Music bed mixed under the output clip, and its track decoded for it
+    #[rust]
+    music_bed: MusicBedSettings,
+    #[rust]
+    music_track: Option<Arc<MusicTrack>>,
+    // Stored clip after post-processing and the music bed; empty when
+    // neither applies
+    #[rust]
+    processed_samples: Vec<f32>,
+    // Length of stored_audio_samples the output clip and its waveform
+    // overview were built from
+    #[rust]
+    output_built_from: Option<usize>,
+    // Output clip rendering on a worker, and a download waiting for it
+    #[rust]
+    output_render: Option<OutputRender>,
+    #[rust]
+    pending_download: Option<PathBuf>,
 
     // Current voice name for display
     #[rust]
@@ -2353,7 +2534,7 @@ pub struct TTSScreen {
 
     // Preview player for reference audio
     #[rust]
-    preview_player: Option<TTSPlayer>,
+    preview_player: Option<AudioPlayer>,
     #[rust]
     preview_playing_voice_id: Option<String>,
 
@@ -2426,7 +2607,46 @@ impl Widget for TTSScreen {
 
         // Initialize audio player
         if self.audio_player.is_none() {
-            self.audio_player = Some(TTSPlayer::new());
+            let mut manager = AudioManager::new();
+            let settings = playback_settings::load_settings();
+            if let Some(device) = &settings.output_device {
+                manager.set_output_device(device);
+            }
+            self.streaming_playback = settings.streaming;
+            self.view
+                .drop_down(ids!(
+                    content_wrapper
+                        .main_content
+                        .left_column
+                        .content_area
+                        .input_section
+                        .bottom_bar
+                        .generate_section
+                        .playback_mode
+                ))
+                .set_selected_item(cx, settings.streaming as usize);
+            self.post_process = post_process::load_config();
+            self.post_fx = settings.post_fx;
+            self.view
+                .drop_down(ids!(
+                    content_wrapper
+                        .audio_player_bar
+                        .download_section
+                        .post_fx
+                ))
+                .set_selected_item(cx, settings.post_fx.index());
+            self.music_bed = music_bed::load_settings();
+            self.view
+                .drop_down(ids!(
+                    content_wrapper
+                        .audio_player_bar
+                        .download_section
+                        .music_bed
+                ))
+                .set_selected_item(cx, self.music_bed.enabled as usize);
+            self.audio_player =
+                AudioPlayer::with_output_device(32000, settings.output_device).ok();
+            self.audio_manager = Some(manager);
         }
 
         // Initialize log bridge and timer
@@ -2437,6 +2657,7 @@ impl Widget for TTSScreen {
             self.update_timer = cx.start_interval(0.1);
             // Initialize stored audio sample rate (PrimeSpeech uses 32000)
             self.stored_audio_sample_rate = 32000;
+            self.stored_audio_channels = 1;
             // Initialize voice name
             self.current_voice_name = "Doubao".to_string();
             // Initialize current page
@@ -2518,24 +2739,58 @@ impl Widget for TTSScreen {
 
         // Poll for audio and logs
         if self.update_timer.is_event(event).is_some() {
-            // Poll Dora Audio - store audio samples instead of auto-playing
+            // Completion seen on an earlier tick, so the last fragments are in
+            let stream_ending = self.streaming_ending;
+            self.poll_dora_events(cx);
+
+            // Poll Dora Audio - store audio samples, and play them as they
+            // arrive when streaming
             if let Some(dora) = &self.dora {
                 if dora.is_running() {
                     let shared = dora.shared_dora_state();
                     let chunks = shared.audio.drain();
                     if !chunks.is_empty() {
+                        // Audio arrived, so the prompt no longer needs replaying on restart
+                        dora.complete_request();
                         for audio in chunks {
+                            if self.streaming_active {
+                                // The player's clip grows along with the stored audio
+                                if let Some(player) = &self.audio_player {
+                                    player.write_audio_with_format(
+                                        &audio.samples,
+                                        audio.sample_rate,
+                                        audio.channels.max(1),
+                                    );
+                                }
+                            } else {
+                                self.audio_loaded = false;
+                            }
                             self.stored_audio_samples.extend(&audio.samples);
                             self.stored_audio_sample_rate = audio.sample_rate;
+                            self.stored_audio_channels = audio.channels.max(1);
+                        }
+                        if let Some(started) = self.generation_started_at.take() {
+                            let latency = started.elapsed();
+                            self.first_audio_latency = Some(latency);
+                            self.add_log(
+                                cx,
+                                &format!(
+                                    "[INFO] [tts] First audio after {:.2}s",
+                                    latency.as_secs_f64()
+                                ),
+                            );
                         }
-                        // Transition to Ready state - user must click Play
-                        if self.tts_status == TTSStatus::Generating {
+                        if self.streaming_active {
+                            // Play while the rest synthesizes; the generate
+                            // button stays busy until TTS completes
+                            if self.tts_status == TTSStatus::Generating {
+                                self.tts_status = TTSStatus::Playing;
+                            }
+                            self.update_player_bar(cx);
+                        } else if self.tts_status == TTSStatus::Generating {
+                            // Transition to Ready state - user must click Play
                             let sample_count = self.stored_audio_samples.len();
-                            let duration_secs = if self.stored_audio_sample_rate > 0 {
-                                sample_count as f32 / self.stored_audio_sample_rate as f32
-                            } else {
-                                0.0
-                            };
+                            let duration_secs = self.stored_audio_duration();
                             self.add_log(
                                 cx,
                                 &format!(
@@ -2551,26 +2806,73 @@ impl Widget for TTSScreen {
                 }
             }
 
+            if stream_ending {
+                self.end_streaming(cx);
+            }
+            self.save_pending_download(cx);
+            self.refresh_output_clip(cx);
+
             // Update playback progress and check if finished
             if self.tts_status == TTSStatus::Playing {
                 if let Some(player) = &self.audio_player {
-                    // Check if playback has actually finished (buffer empty)
-                    if player.check_playback_finished() {
-                        // Audio finished - reset to Ready state
+                    // Check if playback has actually finished (buffer empty).
+                    // While streaming, running dry only means the next
+                    // fragment has not arrived yet.
+                    let finished = player.check_playback_finished() && !self.streaming_active;
+                    if finished {
+                        // Audio finished - rewind and reset to Ready state
+                        player.seek(0.0);
                         self.tts_status = TTSStatus::Ready;
-                        self.audio_playing_time = 0.0;
-                        self.update_playback_progress(cx);
+                        self.show_playback_progress(cx, 0.0);
                         self.update_player_bar(cx);
                         self.add_log(cx, "[INFO] [tts] Playback completed");
                     } else if player.is_playing() {
-                        // Still playing - update playback time and progress bar
-                        self.audio_playing_time += 0.1;
+                        // Still playing - follow the position reported by the player
                         self.update_playback_progress(cx);
                     }
                     // If paused (is_playing=false but not finished), do nothing - keep current time
                 }
             }
 
+            // Feed the spectrum view from the player's output tap
+            if let Some(player) = &self.audio_player {
+                let live = player.is_playing();
+                if live || self.spectrum_live {
+                    let bands = if live {
+                        player.get_spectrum_bands(8)
+                    } else {
+                        Vec::new()
+                    };
+                    self.view
+                        .waveform_view(ids!(
+                            content_wrapper
+                                .audio_player_bar
+                                .voice_info
+                                .output_spectrum
+                        ))
+                        .set_levels(cx, &bands);
+                }
+                self.spectrum_live = live;
+            }
+
+            // Follow device switches made by the player (unplugged or reconnected)
+            if let Some(player) = &self.audio_player {
+                let active = player.output_device();
+                if active != self.active_output_device {
+                    let was_running = self.active_output_device.is_some();
+                    self.active_output_device = active.clone();
+                    match active {
+                        Some(device) if was_running => self.add_log(
+                            cx,
+                            &format!("[INFO] [tts] Audio output switched to {}", device),
+                        ),
+                        Some(_) => {}
+                        None => self.add_log(cx, "[WARN] [tts] No audio output device available"),
+                    }
+                    self.update_output_devices(cx);
+                }
+            }
+
             // Check if preview playback has finished
             if self.preview_playing_voice_id.is_some() {
                 if let Some(player) = &self.preview_player {
@@ -2594,10 +2896,35 @@ impl Widget for TTSScreen {
 
             // Poll Logs from log_bridge
             let logs = log_bridge::poll_logs();
-            if !logs.is_empty() {
-                for log_msg in logs {
-                    self.log_entries.push(log_msg.format());
+            let mut logs_changed = !logs.is_empty();
+            for log_msg in logs {
+                self.log_entries.push(log_msg.format());
+            }
+
+            // Poll node output and node health from the dataflow
+            if let Some(dora) = &self.dora {
+                let shared = dora.shared_dora_state();
+                let node_logs = shared.logs.drain();
+                logs_changed |= !node_logs.is_empty();
+                for entry in node_logs {
+                    let level = match entry.level {
+                        LogLevel::Debug => "DEBUG",
+                        LogLevel::Info => "INFO",
+                        LogLevel::Warning => "WARN",
+                        LogLevel::Error => "ERROR",
+                    };
+                    self.log_entries
+                        .push(format!("[{}] [{}] {}", level, entry.node_id, entry.message));
+                }
+
+                if let Some(nodes) = shared.nodes.read_if_dirty() {
+                    self.node_statuses = nodes;
+                    self.update_node_filter(cx);
+                    logs_changed = true;
                 }
+            }
+
+            if logs_changed {
                 self.update_log_display(cx);
             }
 
@@ -2938,6 +3265,24 @@ impl Widget for TTSScreen {
                         &format!("Custom voice '{}' created successfully!", voice.name),
                     );
                 }
+                VoiceCloneModalAction::VoiceEvaluated(voice) => {
+                    let voice_selector = self.view.voice_selector(ids!(
+                        content_wrapper
+                            .main_content
+                            .left_column
+                            .content_area
+                            .controls_panel
+                            .voice_section
+                            .voice_selector
+                    ));
+                    voice_selector.update_custom_voice(cx, voice.clone());
+                    if let Some(ref eval) = voice.evaluation {
+                        self.add_log(
+                            cx,
+                            &format!("[INFO] [tts] Voice '{}' evaluated: {}", voice.name, eval.summary()),
+                        );
+                    }
+                }
                 VoiceCloneModalAction::SendAudioToAsr {
                     samples,
                     sample_rate,
@@ -3135,20 +3480,13 @@ impl Widget for TTSScreen {
                         cx,
                         "Please click 'Start MoFA' button first to initialize the dataflow",
                     );
+                } else if !dora.is_ready() {
+                    // Started, but bridges or TTS model not ready yet
+                    self.show_toast(cx, "TTS model is still loading, please wait...");
+                    self.add_log(cx, "[WARN] [tts] Dataflow not ready yet");
                 } else {
-                    // Check if bridges are ready (expected: 4 bridges)
-                    let bridge_count = dora.shared_dora_state().status.read().active_bridges.len();
-                    if bridge_count < 4 {
-                        // Starting but not ready
-                        self.show_toast(
-                            cx,
-                            &format!("Dataflow is starting ({}/4 bridges connected), please wait...", bridge_count),
-                        );
-                        self.add_log(cx, &format!("[WARN] [tts] Bridges not ready yet: {}/4", bridge_count));
-                    } else {
-                        // Ready to generate
-                        self.generate_speech(cx);
-                    }
+                    // Ready to generate
+                    self.generate_speech(cx);
                 }
             } else {
                 self.show_toast(cx, "Dora integration not initialized");
@@ -3170,6 +3508,21 @@ impl Widget for TTSScreen {
             self.toggle_playback(cx);
         }
 
+        // Seek and loop from the waveform overview
+        let overview = self.view.waveform_overview(ids!(
+            content_wrapper
+                .audio_player_bar
+                .playback_controls
+                .progress_row
+                .waveform_overview
+        ));
+        if let Some(at) = overview.seek_requested(&actions) {
+            self.seek_playback(cx, at);
+        }
+        if let Some(range) = overview.loop_requested(&actions) {
+            self.set_playback_loop(cx, range);
+        }
+
         // Handle stop button in audio player bar
         if self
             .view
@@ -3185,6 +3538,69 @@ impl Widget for TTSScreen {
             self.stop_playback(cx);
         }
 
+        // Handle playback mode picker next to the generate button
+        if let Some(index) = self
+            .view
+            .drop_down(ids!(
+                content_wrapper
+                    .main_content
+                    .left_column
+                    .content_area
+                    .input_section
+                    .bottom_bar
+                    .generate_section
+                    .playback_mode
+            ))
+            .changed(&actions)
+        {
+            self.set_streaming_playback(cx, index == 1);
+        }
+
+        // Handle output device picker in audio player bar
+        if let Some(index) = self
+            .view
+            .drop_down(ids!(
+                content_wrapper
+                    .audio_player_bar
+                    .download_section
+                    .output_device
+            ))
+            .changed(&actions)
+        {
+            let device = index
+                .checked_sub(1)
+                .and_then(|i| self.output_devices.get(i).cloned());
+            self.select_output_device(cx, device);
+        }
+
+        // Handle post-processing preset picker in audio player bar
+        if let Some(index) = self
+            .view
+            .drop_down(ids!(
+                content_wrapper
+                    .audio_player_bar
+                    .download_section
+                    .post_fx
+            ))
+            .changed(&actions)
+        {
+            self.set_post_fx(cx, PostProcessPreset::from_index(index));
+        }
+
+        // Handle music bed picker in audio player bar
+        if let Some(index) = self
+            .view
+            .drop_down(ids!(
+                content_wrapper
+                    .audio_player_bar
+                    .download_section
+                    .music_bed
+            ))
+            .changed(&actions)
+        {
+            self.select_music_bed(cx, index);
+        }
+
         // Handle download button in audio player bar
         if self
             .view
@@ -3216,6 +3632,25 @@ impl Widget for TTSScreen {
             self.update_log_display(cx);
         }
 
+        // Handle log node filter
+        if let Some(index) = self
+            .view
+            .drop_down(ids!(
+                main_content
+                    .log_section
+                    .log_content_column
+                    .log_header
+                    .log_title_row
+                    .node_filter
+            ))
+            .changed(&actions)
+        {
+            self.log_node_filter = index
+                .checked_sub(1)
+                .and_then(|i| self.log_filter_nodes.get(i).cloned());
+            self.update_log_display(cx);
+        }
+
         // Handle toggle log panel button
         if self
             .view
@@ -3617,6 +4052,14 @@ impl TTSScreen {
             TTSStatus::Ready => "Audio Ready",
             TTSStatus::Error(msg) => msg.as_str(),
         };
+        let status_text = match (&self.tts_status, self.first_audio_latency) {
+            (TTSStatus::Playing | TTSStatus::Ready, Some(latency)) => format!(
+                "{} · first audio {:.1}s",
+                status_text,
+                latency.as_secs_f64()
+            ),
+            _ => status_text.to_string(),
+        };
         self.view
             .label(ids!(
                 content_wrapper
@@ -3625,7 +4068,7 @@ impl TTSScreen {
                     .voice_name_container
                     .status_label
             ))
-            .set_text(cx, status_text);
+            .set_text(cx, &status_text);
 
         // Update play button state
         let is_playing = self.tts_status == TTSStatus::Playing;
@@ -3646,8 +4089,7 @@ impl TTSScreen {
 
         // Update total time
         if !self.stored_audio_samples.is_empty() && self.stored_audio_sample_rate > 0 {
-            let duration_secs =
-                self.stored_audio_samples.len() as f32 / self.stored_audio_sample_rate as f32;
+            let duration_secs = self.output_duration() as f32;
             let mins = (duration_secs / 60.0) as u32;
             let secs = (duration_secs % 60.0) as u32;
             let time_str = format!("{:02}:{:02}", mins, secs);
@@ -3665,15 +4107,57 @@ impl TTSScreen {
         self.view.redraw(cx);
     }
 
+    /// Length of the stored audio in seconds (samples are interleaved)
+    fn stored_audio_duration(&self) -> f64 {
+        if self.stored_audio_sample_rate == 0 {
+            return 0.0;
+        }
+        let frames = self.stored_audio_samples.len() / self.stored_audio_channels.max(1) as usize;
+        frames as f64 / self.stored_audio_sample_rate as f64
+    }
+
+    /// Whether `processed_samples` holds the current stored clip with effects
+    /// or music
+    fn output_processed(&self) -> bool {
+        self.output_built_from == Some(self.stored_audio_samples.len())
+            && !self.processed_samples.is_empty()
+    }
+
+    /// The clip that plays and exports: the stored clip with effects and
+    /// music when they are built, otherwise as generated
+    fn output_samples(&self) -> &[f32] {
+        if self.output_processed() {
+            &self.processed_samples
+        } else {
+            &self.stored_audio_samples
+        }
+    }
+
+    /// Length of the output clip in seconds
+    fn output_duration(&self) -> f64 {
+        if self.stored_audio_sample_rate == 0 {
+            return 0.0;
+        }
+        let frames = self.output_samples().len() / self.stored_audio_channels.max(1) as usize;
+        frames as f64 / self.stored_audio_sample_rate as f64
+    }
+
     fn update_playback_progress(&mut self, cx: &mut Cx) {
+        let current_time = match &self.audio_player {
+            Some(player) if self.audio_loaded => player.position(),
+            _ => 0.0,
+        };
+        self.show_playback_progress(cx, current_time);
+    }
+
+    fn show_playback_progress(&mut self, cx: &mut Cx, current_time: f64) {
         // Calculate total duration and current position
         if self.stored_audio_samples.is_empty() || self.stored_audio_sample_rate == 0 {
             return;
         }
 
-        let total_duration =
-            self.stored_audio_samples.len() as f32 / self.stored_audio_sample_rate as f32;
-        let current_time = self.audio_playing_time as f32;
+        let total_duration = self.output_duration() as f32;
+        let current_time = current_time as f32;
         let progress = (current_time / total_duration).min(1.0).max(0.0);
 
         // Update current time label
@@ -3690,22 +4174,16 @@ impl TTSScreen {
             ))
             .set_text(cx, &time_str);
 
-        // Update progress bar
+        // Update the played region of the waveform
         self.view
-            .view(ids!(
+            .waveform_overview(ids!(
                 content_wrapper
                     .audio_player_bar
                     .playback_controls
                     .progress_row
-                    .progress_bar_container
-                    .progress_bar
+                    .waveform_overview
             ))
-            .apply_over(
-                cx,
-                live! {
-                    draw_bg: { progress: (progress as f64) }
-                },
-            );
+            .set_progress(cx, progress as f64);
 
         self.view.redraw(cx);
     }
@@ -3729,7 +4207,7 @@ impl TTSScreen {
         if self.preview_playing_voice_id.as_ref() == Some(&voice_id.to_string()) {
             // Stop preview
             if let Some(player) = &self.preview_player {
-                player.stop();
+                player.reset();
             }
             self.preview_playing_voice_id = None;
             voice_selector.set_preview_playing(cx, None);
@@ -3739,7 +4217,7 @@ impl TTSScreen {
 
         // Stop any currently playing preview
         if let Some(player) = &self.preview_player {
-            player.stop();
+            player.reset();
         }
 
         // Get voice info
@@ -3802,12 +4280,12 @@ impl TTSScreen {
             Ok(samples) => {
                 // Initialize preview player if needed
                 if self.preview_player.is_none() {
-                    self.preview_player = Some(TTSPlayer::new());
+                    self.preview_player = AudioPlayer::new(32000).ok();
                 }
 
                 // Play the audio
                 if let Some(player) = &self.preview_player {
-                    player.write_audio(&samples);
+                    player.write_audio(&samples, None, None);
                 }
 
                 self.preview_playing_voice_id = Some(voice_id.to_string());
@@ -3860,7 +4338,7 @@ impl TTSScreen {
             samples
         };
 
-        // Resample to 32000 Hz if needed (TTSPlayer expects 32000 Hz)
+        // Resample to 32000 Hz if needed (the preview player expects 32000 Hz)
         let target_rate = 32000;
         let resampled = if sample_rate != target_rate {
             let ratio = target_rate as f32 / sample_rate as f32;
@@ -3910,16 +4388,101 @@ impl TTSScreen {
         self.view.redraw(cx);
     }
 
+    /// Refresh the node filter options from the known node statuses
+    ///
+    /// Keeps the current selection if that node is still listed.
+    fn update_node_filter(&mut self, cx: &mut Cx) {
+        let mut nodes: Vec<String> = self
+            .node_statuses
+            .iter()
+            .map(|n| n.node_id.clone())
+            .collect();
+        nodes.sort();
+        if nodes == self.log_filter_nodes {
+            return;
+        }
+        self.log_filter_nodes = nodes;
+
+        let mut labels = vec!["All nodes".to_string()];
+        labels.extend(self.log_filter_nodes.iter().cloned());
+        let selected = self
+            .log_node_filter
+            .as_ref()
+            .and_then(|id| self.log_filter_nodes.iter().position(|n| n == id))
+            .map(|i| i + 1);
+        if selected.is_none() {
+            self.log_node_filter = None;
+        }
+
+        let dropdown = self.view.drop_down(ids!(
+            main_content
+                .log_section
+                .log_content_column
+                .log_header
+                .log_title_row
+                .node_filter
+        ));
+        dropdown.set_labels(cx, labels);
+        dropdown.set_selected_item(cx, selected.unwrap_or(0));
+    }
+
+    /// Markdown summary of node health, with recent output for the filtered node
+    fn node_health_text(&self) -> Option<String> {
+        let shown: Vec<&NodeStatus> = self
+            .node_statuses
+            .iter()
+            .filter(|n| match &self.log_node_filter {
+                Some(id) => &n.node_id == id,
+                None => true,
+            })
+            .collect();
+        if shown.is_empty() {
+            return None;
+        }
+
+        let mut lines = Vec::new();
+        for node in &shown {
+            let mut line = format!("**{}** · {}", node.node_id, node.state.label());
+            if let Some(code) = node.exit_code.filter(|c| *c != 0) {
+                line.push_str(&format!(" (exit {})", code));
+            }
+            if let Some(error) = &node.error {
+                line.push_str(&format!(" - {}", error));
+            }
+            lines.push(line);
+
+            // Show what a failed node printed last; for the filtered node always
+            let show_tail = self.log_node_filter.is_some()
+                || matches!(node.state, NodeState::Failed | NodeState::Restarting);
+            if show_tail {
+                for tail_line in &node.stderr_tail {
+                    lines.push(format!("`{}`", tail_line.replace('`', "'")));
+                }
+            }
+        }
+        Some(lines.join("\n\n"))
+    }
+
     fn update_log_display(&mut self, cx: &mut Cx) {
-        let log_text = if self.log_entries.is_empty() {
+        let tag = self.log_node_filter.as_ref().map(|id| format!("[{}]", id));
+        let entries: Vec<&str> = self
+            .log_entries
+            .iter()
+            .filter(|entry| match &tag {
+                Some(tag) => entry.contains(tag.as_str()),
+                None => true,
+            })
+            .map(|s| s.as_str())
+            .collect();
+
+        let mut log_text = if entries.is_empty() {
             "*No log entries*".to_string()
         } else {
-            self.log_entries
-                .iter()
-                .map(|s| s.as_str())
-                .collect::<Vec<_>>()
-                .join("\n\n")
+            entries.join("\n\n")
         };
+        if let Some(health) = self.node_health_text() {
+            log_text = format!("{}\n\n---\n\n{}", health, log_text);
+        }
 
         self.view
             .markdown(ids!(
@@ -4011,11 +4574,13 @@ impl TTSScreen {
             return;
         }
 
-        let dataflow_path = PathBuf::from("apps/mofa-tts/dataflow/tts.yml");
-        if !dataflow_path.exists() {
-            self.add_log(cx, "[ERROR] [tts] Dataflow file not found: apps/mofa-tts/dataflow/tts.yml");
-            return;
-        }
+        let dataflow_path = match crate::dataflow_config::render_dataflow(self.streaming_playback) {
+            Ok(path) => path,
+            Err(e) => {
+                self.add_log(cx, &format!("[ERROR] [tts] {}", e));
+                return;
+            }
+        };
 
         self.add_log(cx, "[INFO] [tts] Auto-starting TTS dataflow...");
 
@@ -4036,10 +4601,76 @@ impl TTSScreen {
         if let Some(dora) = &mut self.dora {
             dora.stop_dataflow();
         }
+        if self.streaming_active {
+            self.end_streaming(cx);
+        }
 
         self.add_log(cx, "[INFO] [tts] Dataflow stopped");
     }
 
+    /// Log lifecycle events from the dora worker (supervised restarts)
+    fn poll_dora_events(&mut self, cx: &mut Cx) {
+        let events = match &self.dora {
+            Some(dora) => dora.poll_events(),
+            None => return,
+        };
+
+        for event in events {
+            match event {
+                DoraEvent::Recovering { attempt } => {
+                    // Requests that already produced audio are not replayed
+                    if self.streaming_active && self.tts_status != TTSStatus::Generating {
+                        self.streaming_ending = true;
+                    }
+                    self.add_log(
+                        cx,
+                        &format!(
+                            "[WARN] [tts] Dataflow stopped unexpectedly, restarting (attempt {})",
+                            attempt
+                        ),
+                    );
+                }
+                DoraEvent::DataflowStarted { dataflow_id } => {
+                    self.add_log(
+                        cx,
+                        &format!(
+                            "[INFO] [tts] Dataflow running ({}), waiting for TTS model...",
+                            dataflow_id
+                        ),
+                    );
+                }
+                DoraEvent::ModelLoading { message, .. } => {
+                    if !message.is_empty() {
+                        self.add_log(cx, &format!("[INFO] [tts] {}", message));
+                    }
+                }
+                DoraEvent::Ready => {
+                    self.add_log(cx, "[INFO] [tts] TTS model ready");
+                }
+                DoraEvent::Error { message } => {
+                    self.add_log(cx, &format!("[ERROR] [tts] {}", message));
+                }
+                DoraEvent::RequestCompleted => {
+                    if self.streaming_active {
+                        self.streaming_ending = true;
+                    }
+                }
+                DoraEvent::RequestFailed { message } => {
+                    self.add_log(cx, &format!("[ERROR] [tts] {}", message));
+                    if self.streaming_active {
+                        self.streaming_ending = true;
+                    }
+                    if self.tts_status == TTSStatus::Generating {
+                        self.tts_status = TTSStatus::Error(message);
+                        self.set_generate_button_loading(cx, false);
+                        self.update_player_bar(cx);
+                    }
+                }
+                DoraEvent::DataflowStopped | DoraEvent::AsrTranscription { .. } => {}
+            }
+        }
+    }
+
     fn generate_speech(&mut self, cx: &mut Cx) {
         // Check if Dora is connected
         let is_running = self.dora.as_ref().map(|d| d.is_running()).unwrap_or(false);
@@ -4050,6 +4681,13 @@ impl TTSScreen {
             );
             return;
         }
+        // Requests sent before the bridges and model are up would be lost
+        let is_ready = self.dora.as_ref().map(|d| d.is_ready()).unwrap_or(false);
+        if !is_ready {
+            self.show_toast(cx, "TTS model is still loading, please wait...");
+            self.add_log(cx, "[WARN] [tts] Dataflow not ready yet");
+            return;
+        }
 
         let text = self
             .view
@@ -4112,6 +4750,14 @@ impl TTSScreen {
         // Clear previous audio
         self.stored_audio_samples.clear();
         self.stored_audio_sample_rate = 32000;
+        self.stored_audio_channels = 1;
+        self.clip_voice_id = voice_id.clone();
+        // When streaming, the player (reset below) holds the stored audio as it grows
+        self.audio_loaded = self.streaming_playback;
+        self.streaming_active = self.streaming_playback;
+        self.streaming_ending = false;
+        self.generation_started_at = Some(Instant::now());
+        self.first_audio_latency = None;
 
         self.tts_status = TTSStatus::Generating;
         self.set_generate_button_loading(cx, true);
@@ -4209,49 +4855,154 @@ impl TTSScreen {
             self.add_log(cx, "[INFO] [tts] Prompt sent to TTS engine");
         } else {
             self.add_log(cx, "[ERROR] [tts] Failed to send prompt to Dora");
+            self.streaming_active = false;
             self.tts_status = TTSStatus::Error("Failed to send prompt".to_string());
             self.set_generate_button_loading(cx, false);
             self.update_player_bar(cx);
         }
 
         if let Some(player) = &self.audio_player {
-            player.stop();
+            player.reset();
+        }
+    }
+
+    /// Fill the output device picker from the connected devices, keeping the
+    /// preferred device listed while it is unplugged
+    fn update_output_devices(&mut self, cx: &mut Cx) {
+        let Some(manager) = &self.audio_manager else {
+            return;
+        };
+        let preferred = manager.current_output_device().map(str::to_string);
+        let mut devices: Vec<String> = manager
+            .get_output_devices()
+            .into_iter()
+            .map(|d| d.name)
+            .collect();
+        if let Some(name) = &preferred {
+            if !devices.contains(name) {
+                devices.push(name.clone());
+            }
         }
+        let selected = preferred
+            .as_ref()
+            .and_then(|name| devices.iter().position(|d| d == name))
+            .map(|i| i + 1)
+            .unwrap_or(0);
+
+        let mut labels = vec!["System default".to_string()];
+        labels.extend(devices.iter().cloned());
+        self.output_devices = devices;
+
+        let dropdown = self.view.drop_down(ids!(
+            content_wrapper
+                .audio_player_bar
+                .download_section
+                .output_device
+        ));
+        dropdown.set_labels(cx, labels);
+        dropdown.set_selected_item(cx, selected);
+    }
+
+    /// Switch between playing after the full render and streaming while
+    /// generating, and remember the choice
+    fn set_streaming_playback(&mut self, cx: &mut Cx, streaming: bool) {
+        self.streaming_playback = streaming;
+
+        let mut settings = playback_settings::load_settings();
+        settings.streaming = streaming;
+        if let Err(e) = playback_settings::save_settings(&settings) {
+            self.add_log(cx, &format!("[WARN] [tts] Failed to save playback mode: {}", e));
+        }
+        let mode = if streaming {
+            "stream while generating"
+        } else {
+            "play when ready"
+        };
+        self.add_log(cx, &format!("[INFO] [tts] Playback mode: {}", mode));
+        if self.dora.as_ref().map(|d| d.is_running()).unwrap_or(false) {
+            self.add_log(
+                cx,
+                "[INFO] [tts] Restart the dataflow for TTS to switch fragment output",
+            );
+        }
+    }
+
+    /// End the stream of the current generation once TTS sent all of it
+    fn end_streaming(&mut self, cx: &mut Cx) {
+        self.streaming_active = false;
+        self.streaming_ending = false;
+        if let Some(player) = &self.audio_player {
+            player.end_stream();
+        }
+        if self.tts_status == TTSStatus::Generating {
+            self.tts_status = TTSStatus::Error("No audio generated".to_string());
+        } else if !self.stored_audio_samples.is_empty() {
+            let sample_count = self.stored_audio_samples.len();
+            let duration_secs = self.stored_audio_duration();
+            self.add_log(
+                cx,
+                &format!(
+                    "[INFO] [tts] Audio generated: {} samples, {:.1}s duration",
+                    sample_count, duration_secs
+                ),
+            );
+        }
+        self.set_generate_button_loading(cx, false);
+        self.update_player_bar(cx);
+    }
+
+    /// Switch playback to `device` (`None` for the system default) and remember it
+    fn select_output_device(&mut self, cx: &mut Cx, device: Option<String>) {
+        if let Some(manager) = &mut self.audio_manager {
+            match &device {
+                Some(name) => manager.set_output_device(name),
+                None => manager.use_default_output_device(),
+            }
+        }
+        if let Some(player) = &self.audio_player {
+            player.set_output_device(device.clone());
+        }
+
+        let mut settings = playback_settings::load_settings();
+        settings.output_device = device.clone();
+        if let Err(e) = playback_settings::save_settings(&settings) {
+            self.add_log(cx, &format!("[WARN] [tts] Failed to save output device: {}", e));
+        }
+        self.add_log(
+            cx,
+            &format!(
+                "[INFO] [tts] Output device: {}",
+                device.as_deref().unwrap_or("System default")
+            ),
+        );
     }
 
     fn toggle_playback(&mut self, cx: &mut Cx) {
         if self.tts_status == TTSStatus::Playing {
-            // Pause
+            // Pause - the player keeps its position
+            let mut position = 0.0;
             if let Some(player) = &self.audio_player {
                 player.pause();
+                position = player.position();
             }
             self.tts_status = TTSStatus::Ready;
-            self.add_log(cx, &format!("[INFO] [tts] Playback paused at {:.1}s", self.audio_playing_time));
+            self.add_log(cx, &format!("[INFO] [tts] Playback paused at {:.1}s", position));
         } else if !self.stored_audio_samples.is_empty() {
-            // Check if we're resuming from a paused state or starting fresh
-            let total_duration = self.stored_audio_samples.len() as f64 / self.stored_audio_sample_rate as f64;
-            let is_resuming = self.audio_playing_time > 0.1
-                && self.audio_playing_time < (total_duration - 0.1);
-
             if let Some(player) = &self.audio_player {
-                // Always stop and clear buffer first to avoid audio overlap
-                player.stop();
-
-                if is_resuming {
-                    // Resume from paused position - write remaining audio from current position
-                    let current_sample_index = (self.audio_playing_time * self.stored_audio_sample_rate as f64) as usize;
-                    if current_sample_index < self.stored_audio_samples.len() {
-                        let remaining_samples = &self.stored_audio_samples[current_sample_index..];
-                        player.write_audio(remaining_samples);
-                        self.add_log(cx, &format!("[INFO] [tts] Resuming playback from {:.1}s", self.audio_playing_time));
-                    }
-                } else {
-                    // Start from beginning
-                    player.write_audio(&self.stored_audio_samples);
-                    self.audio_playing_time = 0.0;
-                    self.update_playback_progress(cx);
+                if !self.audio_loaded {
+                    player.load(
+                        self.output_samples(),
+                        self.stored_audio_sample_rate,
+                        self.stored_audio_channels,
+                    );
+                    self.audio_loaded = true;
+                    self.show_playback_progress(cx, 0.0);
                     self.add_log(cx, "[INFO] [tts] Playing audio...");
+                } else {
+                    let position = player.position();
+                    self.add_log(cx, &format!("[INFO] [tts] Resuming playback from {:.1}s", position));
                 }
+                player.resume();
             }
             self.tts_status = TTSStatus::Playing;
         } else {
@@ -4260,24 +5011,261 @@ impl TTSScreen {
         self.update_player_bar(cx);
     }
 
-    fn stop_playback(&mut self, cx: &mut Cx) {
+    /// Seek to `fraction` of the clip
+    fn seek_playback(&mut self, cx: &mut Cx, fraction: f64) {
+        if self.stored_audio_samples.is_empty() || self.stored_audio_sample_rate == 0 {
+            return;
+        }
+        let total_duration = self.output_duration();
+        let target = fraction.clamp(0.0, 1.0) * total_duration;
         if let Some(player) = &self.audio_player {
-            player.stop();
+            if !self.audio_loaded {
+                player.load(
+                    self.output_samples(),
+                    self.stored_audio_sample_rate,
+                    self.stored_audio_channels,
+                );
+                self.audio_loaded = true;
+            }
+            player.seek(target);
         }
-        if self.tts_status == TTSStatus::Playing {
-            self.tts_status = TTSStatus::Ready;
-            self.add_log(cx, "[INFO] [tts] Playback stopped");
+        self.show_playback_progress(cx, target);
+    }
+
+    /// Loop `range` (fractions of the clip), or play through with `None`
+    fn set_playback_loop(&mut self, cx: &mut Cx, range: Option<(f64, f64)>) {
+        let overview = self.view.waveform_overview(ids!(
+            content_wrapper
+                .audio_player_bar
+                .playback_controls
+                .progress_row
+                .waveform_overview
+        ));
+        // The clip is still growing while it streams
+        if self.streaming_active
+            || self.stored_audio_samples.is_empty()
+            || self.stored_audio_sample_rate == 0
+        {
+            overview.set_loop(cx, None);
+            return;
         }
-        // Reset progress
+        let Some(player) = &self.audio_player else {
+            return;
+        };
+        if !self.audio_loaded {
+            player.load(
+                self.output_samples(),
+                self.stored_audio_sample_rate,
+                self.stored_audio_channels,
+            );
+            self.audio_loaded = true;
+        }
+        let duration = self.output_duration();
+        match range {
+            Some((start, end)) => {
+                let (start, end) = (start * duration, end * duration);
+                player.set_loop(Some((start, end)));
+                player.seek(start);
+                self.show_playback_progress(cx, start);
+                self.add_log(
+                    cx,
+                    &format!("[INFO] [tts] Looping {:.1}s - {:.1}s", start, end),
+                );
+            }
+            None => {
+                player.set_loop(None);
+                self.add_log(cx, "[INFO] [tts] Loop cleared");
+            }
+        }
+    }
+
+    /// Rebuild the output clip and its waveform overview once the stored clip,
+    /// the effects or the music changed; a clip that is still streaming is
+    /// built when the stream ends. Effects and music render on a worker, and
+    /// the clip plays as generated until they are swapped in.
+    fn refresh_output_clip(&mut self, cx: &mut Cx) {
+        let len = self.stored_audio_samples.len();
+        if self.output_built_from == Some(len) || (self.streaming_active && len > 0) {
+            return;
+        }
+        let processing = self.output_effects_on();
+        if processing && self.tts_status == TTSStatus::Playing {
+            // A streamed clip plays out unprocessed; swapping it now would cut it off
+            return;
+        }
+        self.processed_samples = Vec::new();
+        if processing && len > 0 {
+            if self
+                .output_render
+                .as_ref()
+                .is_none_or(|render| render.source_len() != len)
+            {
+                self.start_output_render();
+                self.show_output_overview(cx);
+                return;
+            }
+            if !self.poll_output_render(cx) {
+                return;
+            }
+            if let Some(output) = self.output_render.take().and_then(OutputRender::into_output) {
+                self.processed_samples = output;
+                self.audio_loaded = false;
+            }
+        }
+        self.output_built_from = Some(len);
+        self.show_output_overview(cx);
+    }
+
+    /// Show the output clip in the waveform overview
+    fn show_output_overview(&mut self, cx: &mut Cx) {
         self.view
-            .label(ids!(
+            .waveform_overview(ids!(
                 content_wrapper
                     .audio_player_bar
                     .playback_controls
                     .progress_row
-                    .current_time
+                    .waveform_overview
             ))
-            .set_text(cx, "00:00");
+            .set_clip(
+                cx,
+                self.output_samples(),
+                self.stored_audio_channels as usize,
+            );
+    }
+
+    /// Switch the post-processing preset and remember it
+    fn set_post_fx(&mut self, cx: &mut Cx, preset: PostProcessPreset) {
+        self.post_fx = preset;
+
+        let mut settings = playback_settings::load_settings();
+        settings.post_fx = preset;
+        if let Err(e) = playback_settings::save_settings(&settings) {
+            self.add_log(cx, &format!("[WARN] [tts] Failed to save effects preset: {}", e));
+        }
+        self.add_log(cx, &format!("[INFO] [tts] Effects: {}", preset.name()));
+        self.invalidate_output_clip(cx);
+    }
+
+    /// Turn the music bed off or on and remember it. A track is picked when
+    /// asked for or when none was chosen yet.
+    fn select_music_bed(&mut self, cx: &mut Cx, index: usize) {
+        let dropdown = self.view.drop_down(ids!(
+            content_wrapper
+                .audio_player_bar
+                .download_section
+                .music_bed
+        ));
+        let mut settings = self.music_bed.clone();
+        settings.enabled = index > 0;
+        if index == 2 || (settings.enabled && settings.track_path.is_none()) {
+            let dialog = rfd::FileDialog::new()
+                .add_filter("WAV Files", &["wav"])
+                .set_title("选择背景音乐");
+            match dialog.pick_file() {
+                Some(path) => settings.track_path = Some(path),
+                None => {
+                    // Cancelled - keep the previous choice
+                    dropdown.set_selected_item(cx, self.music_bed.enabled as usize);
+                    return;
+                }
+            }
+            dropdown.set_selected_item(cx, 1);
+        }
+
+        if let Err(e) = music_bed::save_settings(&settings) {
+            self.add_log(cx, &format!("[WARN] [tts] Failed to save music bed: {}", e));
+        }
+        let track = settings
+            .track_path
+            .as_ref()
+            .filter(|_| settings.enabled)
+            .and_then(|path| path.file_name())
+            .map(|name| name.to_string_lossy().into_owned());
+        self.add_log(
+            cx,
+            &format!(
+                "[INFO] [tts] Music bed: {}",
+                track.as_deref().unwrap_or("off")
+            ),
+        );
+        self.music_bed = settings;
+        self.invalidate_output_clip(cx);
+    }
+
+    /// Whether effects or the music bed apply to the output clip
+    fn output_effects_on(&self) -> bool {
+        self.post_fx != PostProcessPreset::Off || self.music_bed.enabled
+    }
+
+    /// Start rendering the stored clip with its effects and music bed on a
+    /// worker, replacing any render still running. The worker decodes the
+    /// music bed track unless the last one still fits.
+    fn start_output_render(&mut self) {
+        let chain = self
+            .post_fx
+            .chain(self.post_process.chain_for(&self.clip_voice_id));
+        self.output_render = Some(OutputRender::spawn(OutputJob {
+            samples: self.stored_audio_samples.clone(),
+            sample_rate: self.stored_audio_sample_rate,
+            channels: self.stored_audio_channels,
+            chain,
+            music: self.music_bed.enabled.then(|| self.music_bed.clone()),
+            track: self.music_track.clone(),
+        }));
+    }
+
+    /// Whether the output render is done, keeping the music bed track it
+    /// decoded for the next one
+    fn poll_output_render(&mut self, cx: &mut Cx) -> bool {
+        let Some(render) = self.output_render.as_mut() else {
+            return false;
+        };
+        if !render.poll() {
+            return false;
+        }
+        match render.take_track() {
+            Some(Ok(track)) => self.music_track = Some(track),
+            Some(Err(e)) => {
+                self.music_track = None;
+                self.add_log(cx, &format!("[ERROR] [tts] Failed to load music bed {}", e));
+            }
+            None => {}
+        }
+        true
+    }
+
+    /// Rebuild the output clip after its effects or music changed. The clip
+    /// stops so the next play starts on the new output.
+    fn invalidate_output_clip(&mut self, cx: &mut Cx) {
+        self.output_built_from = None;
+        self.output_render = None;
+        if self.streaming_active {
+            // Applied once the stream ends
+            return;
+        }
+        if self.tts_status == TTSStatus::Playing {
+            if let Some(player) = &self.audio_player {
+                player.pause();
+            }
+            self.tts_status = TTSStatus::Ready;
+        }
+        self.audio_loaded = false;
+        self.refresh_output_clip(cx);
+        self.show_playback_progress(cx, 0.0);
+        self.update_player_bar(cx);
+    }
+
+    fn stop_playback(&mut self, cx: &mut Cx) {
+        if let Some(player) = &self.audio_player {
+            player.pause();
+            player.seek(0.0);
+        }
+        if self.tts_status == TTSStatus::Playing {
+            self.tts_status = TTSStatus::Ready;
+            self.add_log(cx, "[INFO] [tts] Playback stopped");
+        }
+        // Reset progress
+        self.show_playback_progress(cx, 0.0);
         self.update_player_bar(cx);
     }
 
@@ -4306,12 +5294,65 @@ impl TTSScreen {
             PathBuf::from(&filename)
         };
 
-        // Write WAV file
-        match self.write_wav_file(&download_path) {
+        // Exports carry the effects and music even while a streamed clip still
+        // plays unprocessed; a clip still rendering is saved once it is done
+        if self.output_processed() || !self.output_effects_on() {
+            let result = self.write_wav_file(&download_path, self.output_samples());
+            self.report_download(cx, &download_path, result);
+            return;
+        }
+        let len = self.stored_audio_samples.len();
+        if self
+            .output_render
+            .as_ref()
+            .is_none_or(|render| render.source_len() != len)
+        {
+            self.start_output_render();
+        }
+        self.pending_download = Some(download_path);
+        self.add_log(cx, "[INFO] [tts] Rendering effects for download...");
+    }
+
+    /// Save the download waiting on the output clip once its render is done.
+    /// A render dropped because the effects or music changed starts over.
+    fn save_pending_download(&mut self, cx: &mut Cx) {
+        let Some(path) = self.pending_download.clone() else {
+            return;
+        };
+        if self.output_render.is_none() {
+            self.start_output_render();
+            return;
+        }
+        if !self.poll_output_render(cx) {
+            return;
+        }
+        self.pending_download = None;
+        let samples = self
+            .output_render
+            .as_ref()
+            .and_then(OutputRender::output)
+            .unwrap_or(&self.stored_audio_samples[..]);
+        let result = self.write_wav_file(&path, samples);
+        self.report_download(cx, &path, result);
+    }
+
+    /// Log whether saving a download to `path` worked
+    fn report_download(&mut self, cx: &mut Cx, path: &Path, result: std::io::Result<()>) {
+        let music = if self.music_bed.enabled && self.music_track.is_some() {
+            ", music bed"
+        } else {
+            ""
+        };
+        match result {
             Ok(_) => {
                 self.add_log(
                     cx,
-                    &format!("[INFO] [tts] Audio saved to: {}", download_path.display()),
+                    &format!(
+                        "[INFO] [tts] Audio saved to: {} (effects: {}{})",
+                        path.display(),
+                        self.post_fx.name(),
+                        music
+                    ),
                 );
                 // Show success toast
                 self.show_toast(cx, "Downloaded successfully!");
@@ -4322,15 +5363,15 @@ impl TTSScreen {
         }
     }
 
-    fn write_wav_file(&self, path: &PathBuf) -> std::io::Result<()> {
+    fn write_wav_file(&self, path: &PathBuf, samples: &[f32]) -> std::io::Result<()> {
         use std::io::Write;
 
         let sample_rate = self.stored_audio_sample_rate;
-        let num_channels: u16 = 1;
+        let num_channels: u16 = self.stored_audio_channels.max(1);
         let bits_per_sample: u16 = 16;
         let byte_rate = sample_rate * (num_channels as u32) * (bits_per_sample as u32) / 8;
         let block_align: u16 = num_channels * bits_per_sample / 8;
-        let data_size = (self.stored_audio_samples.len() * 2) as u32;
+        let data_size = (samples.len() * 2) as u32;
         let file_size = 36 + data_size;
 
         let mut file = std::fs::File::create(path)?;
@@ -4355,7 +5396,7 @@ impl TTSScreen {
         file.write_all(&data_size.to_le_bytes())?;
 
         // Convert f32 samples to i16 and write
-        for &sample in &self.stored_audio_samples {
+        for &sample in samples {
             let clamped = sample.max(-1.0).min(1.0);
             let i16_sample = (clamped * 32767.0) as i16;
             file.write_all(&i16_sample.to_le_bytes())?;
@@ -4732,6 +5773,24 @@ impl TTSScreenRef {
                     draw_bold: { dark_mode: (dark_mode) }
                 },
             );
+            inner
+                .view
+                .drop_down(ids!(
+                    content_wrapper
+                        .main_content
+                        .log_section
+                        .log_content_column
+                        .log_header
+                        .log_title_row
+                        .node_filter
+                ))
+                .apply_over(
+                    cx,
+                    live! {
+                        draw_bg: { dark_mode: (dark_mode) }
+                        draw_text: { dark_mode: (dark_mode) }
+                    },
+                );
 
             // Apply dark mode to audio player bar
             inner
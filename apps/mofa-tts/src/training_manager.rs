@@ -10,12 +10,13 @@
 /// - Parses events and updates thread-safe progress state
 /// - UI polls progress state to update display
 
+use crate::python_env;
 use crossbeam_channel::{bounded, Receiver, Sender};
 use parking_lot::Mutex;
 use serde::{Deserialize, Serialize};
 use std::io::{BufRead, BufReader, Write};
 use std::path::PathBuf;
-use std::process::{Child, Command, Stdio};
+use std::process::{Child, Stdio};
 use std::sync::Arc;
 use std::thread;
 use std::time::Instant;
@@ -29,6 +30,8 @@ pub enum TrainingCommand {
         voice_name: String,
         audio_file: PathBuf,
         language: String,
+        /// Dataset manifest from the dataset builder (multi-clip training)
+        dataset_manifest: Option<PathBuf>,
     },
     /// Cancel the current training session
     Cancel,
@@ -108,6 +111,9 @@ struct TrainingRequest {
     audio_file: String,
     language: String,
     workspace_dir: String,
+    /// Pre-sliced, transcribed clips; when set the service skips slicing and ASR
+    #[serde(skip_serializing_if = "Option::is_none")]
+    dataset_manifest: Option<String>,
     training_params: TrainingParams,
 }
 
@@ -168,6 +174,30 @@ impl TrainingManager {
                 voice_name,
                 audio_file,
                 language,
+                dataset_manifest: None,
+            })
+            .is_ok()
+    }
+
+    /// Start a new training session from a dataset manifest
+    ///
+    /// `reference_audio` is the clip used as the voice's reference audio.
+    /// Returns false if the command failed to send.
+    pub fn start_training_with_dataset(
+        &self,
+        voice_id: String,
+        voice_name: String,
+        dataset_manifest: PathBuf,
+        reference_audio: PathBuf,
+        language: String,
+    ) -> bool {
+        self.command_tx
+            .try_send(TrainingCommand::Start {
+                voice_id,
+                voice_name,
+                audio_file: reference_audio,
+                language,
+                dataset_manifest: Some(dataset_manifest),
             })
             .is_ok()
     }
@@ -209,6 +239,7 @@ impl TrainingManager {
                         voice_name,
                         audio_file,
                         language,
+                        dataset_manifest,
                     } => {
                         // Kill existing process if any
                         if let Some(mut child) = current_process.take() {
@@ -221,6 +252,7 @@ impl TrainingManager {
                             voice_name,
                             audio_file,
                             language,
+                            dataset_manifest,
                             &progress,
                         );
                     }
@@ -251,6 +283,7 @@ impl TrainingManager {
         voice_name: String,
         audio_file: PathBuf,
         language: String,
+        dataset_manifest: Option<PathBuf>,
         progress: &Arc<Mutex<TrainingProgress>>,
     ) -> Option<Child> {
         // Reset progress
@@ -280,6 +313,7 @@ impl TrainingManager {
             audio_file: audio_file.to_string_lossy().to_string(),
             language: language.clone(),
             workspace_dir: workspace_dir.to_string_lossy().to_string(),
+            dataset_manifest: dataset_manifest.map(|p| p.to_string_lossy().to_string()),
             training_params: TrainingParams {
                 gpt_epochs: 15,
                 sovits_epochs: 20,
@@ -300,11 +334,33 @@ impl TrainingManager {
             }
         };
 
+        // Resolve interpreter (conda/pixi/venv) instead of relying on PATH
+        let python = match python_env::resolve() {
+            Ok(env) => env,
+            Err(e) => {
+                let mut prog = progress.lock();
+                prog.status = TrainingStatus::Failed {
+                    error: e.to_string(),
+                };
+                prog.log_lines.push(format!("[ERROR] {}", e));
+                for line in e.diagnostics() {
+                    prog.log_lines.push(format!("[ERROR]   {}", line));
+                }
+                prog.last_updated = Instant::now();
+                return None;
+            }
+        };
+
         // Spawn Python training service
         log::info!("Spawning Python training service");
         log::info!("Workspace: {}", workspace_dir.display());
+        progress
+            .lock()
+            .log_lines
+            .push(format!("[INFO] Using {}", python.describe()));
 
-        let mut child = match Command::new("python")
+        let mut child = match python
+            .command()
             .arg("-m")
             .arg("dora_primespeech.moyoyo_tts.training_service")
             .stdin(Stdio::piped())
@@ -552,6 +608,7 @@ mod tests {
             audio_file: "/tmp/test.wav".to_string(),
             language: "zh".to_string(),
             workspace_dir: "/tmp/workspace".to_string(),
+            dataset_manifest: None,
             training_params: TrainingParams {
                 gpt_epochs: 15,
                 sovits_epochs: 20,
@@ -562,5 +619,6 @@ mod tests {
         let json = serde_json::to_string(&request).unwrap();
         assert!(json.contains("test_voice"));
         assert!(json.contains("gpt_epochs"));
+        assert!(!json.contains("dataset_manifest"));
     }
 }
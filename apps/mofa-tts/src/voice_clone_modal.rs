@@ -4,11 +4,15 @@
 //! 1. Express Mode (Zero-shot): Select existing audio file + manually enter prompt text OR record voice via microphone + auto-transcribe with ASR
 //! 2. Pro Mode (Few-shot Training): Record 3-10 minutes of audio and train custom GPT-SoVITS models
 
-use crate::audio_player::TTSPlayer;
+use crate::dataset_builder::{self, DATASET_SAMPLE_RATE};
+use crate::dataset_editor::{DatasetEditorAction, DatasetEditorWidgetExt};
+use crate::python_env;
 use crate::training_manager::{TrainingManager, TrainingProgress, TrainingStatus};
 use crate::voice_data::{CloningStatus, Voice, VoiceCategory, VoiceSource};
+use crate::voice_evaluation::{EvaluationRequest, EvaluationStatus, VoiceEvaluator};
 use crate::voice_persistence;
 use makepad_widgets::*;
+use mofa_widgets::AudioPlayer;
 use parking_lot::Mutex;
 use std::path::PathBuf;
 use std::sync::atomic::{AtomicBool, Ordering};
@@ -45,6 +49,7 @@ live_design! {
     use link::widgets::*;
 
     use mofa_widgets::theme::*;
+    use crate::dataset_editor::DatasetEditor;
 
     // Modal overlay background
     ModalOverlay = <View> {
@@ -1031,6 +1036,9 @@ live_design! {
                             }
                         }
 
+                        // Multi-recording dataset (optional, replaces the single training file)
+                        dataset_editor = <DatasetEditor> {}
+
                         // Voice name + language (reuse from express mode)
                         voice_name_input = <LabeledInput> {
                             label = { text: "Voice Name" }
@@ -1336,6 +1344,8 @@ pub enum VoiceCloneModalAction {
     None,
     Closed,
     VoiceCreated(Voice),
+    /// Post-training evaluation finished and the saved voice was updated
+    VoiceEvaluated(Voice),
     SendAudioToAsr {
         samples: Vec<f32>,
         sample_rate: u32,
@@ -1368,7 +1378,7 @@ pub struct VoiceCloneModal {
     log_messages: Vec<String>,
 
     #[rust]
-    preview_player: Option<TTSPlayer>,
+    preview_player: Option<AudioPlayer>,
 
     #[rust]
     preview_playing: bool,
@@ -1451,6 +1461,25 @@ pub struct VoiceCloneModal {
     gpu_check_done: bool,
     #[rust]
     has_gpu: bool,
+    #[rust]
+    python_env_error: Option<String>,
+
+    // Post-training evaluation
+    #[rust]
+    voice_evaluator: Option<VoiceEvaluator>,
+    #[rust]
+    evaluating_voice: Option<Voice>,
+    #[rust]
+    evaluation_log_cursor: usize,
+
+    // Dataset builder (Pro mode, multiple recordings)
+    #[rust]
+    dataset_id: Option<String>,
+    #[rust]
+    dataset_transcribing: bool,
+    /// Clip currently at the ASR bridge and when it was sent
+    #[rust]
+    dataset_asr_pending: Option<(usize, Instant)>,
 }
 
 impl LiveHook for VoiceCloneModal {
@@ -1943,15 +1972,37 @@ impl Widget for VoiceCloneModal {
                 _ => {}
             }
 
+            // Pick up the Python env lookup started by the Pro tab
+            self.poll_python_env(cx);
+
             // Poll training progress (should be called on every frame)
             self.poll_training_progress(cx, scope);
+
+            // Feed dataset clips through ASR one at a time
+            self.poll_dataset_transcription(cx, scope);
         }
 
         // Extract actions - keep for any remaining action-based handling
-        let _actions = match event {
+        let actions = match event {
             Event::Actions(actions) => actions.as_slice(),
             _ => return,
         };
+
+        for action in actions {
+            match action.as_widget_action().cast() {
+                DatasetEditorAction::AddFilesClicked => self.open_dataset_files_dialog(cx),
+                DatasetEditorAction::TranscribeClicked => {
+                    if !self.asr_bridge_ready {
+                        self.add_training_log(cx, "[ERROR] ASR bridge is not ready yet");
+                    } else if !self.dataset_transcribing {
+                        self.dataset_transcribing = true;
+                        self.add_training_log(cx, "[INFO] Transcribing dataset clips...");
+                        self.view.redraw(cx);
+                    }
+                }
+                DatasetEditorAction::DatasetChanged | DatasetEditorAction::None => {}
+            }
+        }
     }
 
     fn draw_walk(&mut self, cx: &mut Cx2d, scope: &mut Scope, walk: Walk) -> DrawStep {
@@ -2192,7 +2243,7 @@ impl VoiceCloneModal {
         if self.preview_playing {
             // Stop preview
             if let Some(player) = &self.preview_player {
-                player.stop();
+                player.reset();
             }
             self.preview_playing = false;
             self.update_preview_button(cx, false);
@@ -2203,14 +2254,15 @@ impl VoiceCloneModal {
         if let Some(path) = &self.selected_file {
             // Initialize player if needed
             if self.preview_player.is_none() {
-                self.preview_player = Some(TTSPlayer::new());
+                self.preview_player = AudioPlayer::new(32000).ok();
             }
 
             // Load and play audio
             match self.load_wav_for_preview(path) {
                 Ok(samples) => {
                     if let Some(player) = &self.preview_player {
-                        player.write_audio(&samples);
+                        player.write_audio(&samples, None, None);
+                        player.end_stream();
                     }
                     self.preview_playing = true;
                     self.update_preview_button(cx, true);
@@ -2502,7 +2554,7 @@ impl VoiceCloneModal {
 
         // Stop any preview playing
         if let Some(player) = &self.preview_player {
-            player.stop();
+            player.reset();
         }
         self.preview_playing = false;
 
@@ -3476,6 +3528,14 @@ impl VoiceCloneModal {
             return;
         }
 
+        let dataset_editor = self.view.dataset_editor(ids!(
+            modal_container.modal_wrapper.modal_content.body.pro_mode_content.dataset_editor
+        ));
+        if dataset_editor.has_clips() {
+            self.start_dataset_training(cx, voice_name);
+            return;
+        }
+
         let Some(audio_file) = &self.training_audio_file else {
             let msg = "No training audio available. Please record or upload at least 10 seconds of audio first.";
             eprintln!("[Training] ERROR: {}", msg);
@@ -3517,6 +3577,174 @@ impl VoiceCloneModal {
         self.view.redraw(cx);
     }
 
+    fn start_dataset_training(&mut self, cx: &mut Cx, voice_name: String) {
+        if self.dataset_transcribing {
+            let msg = "Dataset transcription is still running";
+            self.add_training_log(cx, &format!("[ERROR] {}", msg));
+            self.show_error(cx, msg);
+            return;
+        }
+
+        let dataset_editor = self.view.dataset_editor(ids!(
+            modal_container.modal_wrapper.modal_content.body.pro_mode_content.dataset_editor
+        ));
+        let (manifest_path, reference_audio) = match dataset_editor.save_manifest() {
+            Ok(paths) => paths,
+            Err(e) => {
+                self.add_training_log(cx, &format!("[ERROR] {}", e));
+                self.show_error(cx, &e);
+                return;
+            }
+        };
+        self.add_training_log(cx, &format!("[INFO] Dataset manifest: {}", manifest_path.display()));
+
+        let voice_id = voice_persistence::generate_voice_id(&voice_name);
+        let language = self.selected_language.clone();
+
+        self.view.view(ids!(
+            modal_container.modal_wrapper.modal_content.body.pro_mode_content.training_progress_section
+        )).set_visible(cx, true);
+        self.view.button(ids!(
+            modal_container.modal_wrapper.modal_content.footer.pro_actions.cancel_training_btn
+        )).set_visible(cx, true);
+        self.view.button(ids!(
+            modal_container.modal_wrapper.modal_content.footer.pro_actions.start_training_btn
+        )).set_visible(cx, false);
+
+        let manager = self.training_manager.as_ref().unwrap();
+        if !manager.start_training_with_dataset(
+            voice_id,
+            voice_name,
+            manifest_path,
+            reference_audio,
+            language,
+        ) {
+            let msg = "Failed to start training process";
+            self.add_training_log(cx, &format!("[ERROR] {}", msg));
+            self.show_error(cx, msg);
+            return;
+        }
+
+        self.add_training_log(cx, "[INFO] Training started from dataset...");
+        self.view.redraw(cx);
+    }
+
+    fn open_dataset_files_dialog(&mut self, cx: &mut Cx) {
+        let dialog = rfd::FileDialog::new()
+            .add_filter("WAV Files", &["wav"])
+            .set_title("Select Training Recordings");
+
+        let Some(paths) = dialog.pick_files() else {
+            return;
+        };
+
+        let dataset_id = self
+            .dataset_id
+            .get_or_insert_with(|| {
+                format!(
+                    "dataset_{}",
+                    std::time::SystemTime::now()
+                        .duration_since(std::time::UNIX_EPOCH)
+                        .map(|d| d.as_secs())
+                        .unwrap_or(0)
+                )
+            })
+            .clone();
+        let dataset_editor = self.view.dataset_editor(ids!(
+            modal_container.modal_wrapper.modal_content.body.pro_mode_content.dataset_editor
+        ));
+
+        for path in paths {
+            let file_name = path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or("Unknown")
+                .to_string();
+
+            let (samples, sample_rate) = match dataset_builder::load_wav_mono(&path) {
+                Ok(loaded) => loaded,
+                Err(e) => {
+                    self.add_training_log(cx, &format!("[ERROR] {}: {}", file_name, e));
+                    continue;
+                }
+            };
+            let samples = Self::resample(&samples, sample_rate, DATASET_SAMPLE_RATE);
+
+            match dataset_editor.add_recording(
+                cx,
+                &dataset_id,
+                &self.selected_language,
+                &file_name,
+                &samples,
+            ) {
+                Ok(count) => self.add_training_log(cx, &format!(
+                    "[INFO] {}: {:.1}s sliced into {} clips",
+                    file_name,
+                    samples.len() as f32 / DATASET_SAMPLE_RATE as f32,
+                    count
+                )),
+                Err(e) => self.add_training_log(cx, &format!("[ERROR] {}: {}", file_name, e)),
+            }
+        }
+
+        // A dataset can be trained without a single training file
+        self.view.button(ids!(
+            modal_container.modal_wrapper.modal_content.footer.pro_actions.start_training_btn
+        )).set_enabled(cx, dataset_editor.has_clips());
+    }
+
+    /// Send the next untranscribed clip to ASR and collect its result
+    fn poll_dataset_transcription(&mut self, cx: &mut Cx, scope: &mut Scope) {
+        if !self.dataset_transcribing {
+            return;
+        }
+        let dataset_editor = self.view.dataset_editor(ids!(
+            modal_container.modal_wrapper.modal_content.body.pro_mode_content.dataset_editor
+        ));
+
+        if let Some((index, sent_at)) = self.dataset_asr_pending {
+            let result = self
+                .shared_dora_state
+                .as_ref()
+                .and_then(|shared| shared.asr_transcription.read_if_dirty())
+                .flatten();
+
+            if let Some((_language, text)) = result {
+                dataset_editor.set_transcript(cx, index, Some(&text));
+                self.dataset_asr_pending = None;
+                if let Some(ref shared) = self.shared_dora_state {
+                    shared.asr_transcription.set(None);
+                }
+            } else if sent_at.elapsed() > std::time::Duration::from_secs(30) {
+                self.add_training_log(cx, &format!("[WARNING] ASR timed out for clip {}, excluding it", index + 1));
+                dataset_editor.set_transcript(cx, index, None);
+                self.dataset_asr_pending = None;
+            } else {
+                self.view.redraw(cx);
+                return;
+            }
+        }
+
+        let Some((index, samples)) = dataset_editor.next_untranscribed() else {
+            self.dataset_transcribing = false;
+            self.add_training_log(cx, "[SUCCESS] Dataset transcription finished");
+            return;
+        };
+
+        cx.widget_action(
+            self.widget_uid(),
+            &scope.path,
+            VoiceCloneModalAction::SendAudioToAsr {
+                samples: Self::resample(&samples, DATASET_SAMPLE_RATE, 16000),
+                sample_rate: 16000,
+                language: self.selected_language.clone(),
+                audio_path: PathBuf::new(),
+            },
+        );
+        self.dataset_asr_pending = Some((index, Instant::now()));
+        self.view.redraw(cx);
+    }
+
     fn cancel_training(&mut self, cx: &mut Cx) {
         if let Some(ref manager) = self.training_manager {
             manager.cancel_training();
@@ -3536,6 +3764,71 @@ impl VoiceCloneModal {
             self.training_progress = progress.clone();
             self.update_training_ui(cx, scope, &progress);
         }
+
+        self.poll_evaluation_progress(cx, scope);
+    }
+
+    fn start_evaluation(&mut self, cx: &mut Cx, voice: Voice) {
+        let (Some(gpt_weights), Some(sovits_weights), Some(reference_audio)) = (
+            voice.gpt_weights.clone(),
+            voice.sovits_weights.clone(),
+            voice.reference_audio_path.clone(),
+        ) else {
+            return;
+        };
+
+        self.add_training_log(cx, "[INFO] Evaluating trained voice (synthesis + ASR + similarity)...");
+        self.voice_evaluator = Some(VoiceEvaluator::start(EvaluationRequest {
+            voice_id: voice.id.clone(),
+            gpt_weights: PathBuf::from(gpt_weights),
+            sovits_weights: PathBuf::from(sovits_weights),
+            reference_audio: PathBuf::from(reference_audio),
+            reference_text: voice.prompt_text.clone().unwrap_or_default(),
+            language: voice.language.clone(),
+        }));
+        self.evaluating_voice = Some(voice);
+        self.evaluation_log_cursor = 0;
+    }
+
+    fn poll_evaluation_progress(&mut self, cx: &mut Cx, scope: &mut Scope) {
+        let Some(ref evaluator) = self.voice_evaluator else {
+            return;
+        };
+
+        let progress = evaluator.get_progress();
+        for line in progress.log_lines.iter().skip(self.evaluation_log_cursor) {
+            self.add_training_log(cx, line);
+        }
+        self.evaluation_log_cursor = progress.log_lines.len();
+
+        match progress.status {
+            EvaluationStatus::Completed(evaluation) => {
+                self.voice_evaluator = None;
+                let Some(mut voice) = self.evaluating_voice.take() else {
+                    return;
+                };
+                if !evaluation.is_usable() {
+                    self.add_training_log(cx, "[WARNING] Voice quality is below the recommended threshold. Consider retraining with more or cleaner audio.");
+                }
+                voice.evaluation = Some(evaluation);
+                if let Err(e) = voice_persistence::update_custom_voice(voice.clone()) {
+                    self.add_training_log(cx, &format!("[ERROR] Failed to save evaluation: {}", e));
+                    return;
+                }
+                cx.widget_action(
+                    self.widget_uid(),
+                    &scope.path,
+                    VoiceCloneModalAction::VoiceEvaluated(voice),
+                );
+            }
+            EvaluationStatus::Failed { error } => {
+                // Evaluation is advisory; the voice stays registered without metrics
+                self.voice_evaluator = None;
+                self.evaluating_voice = None;
+                self.add_training_log(cx, &format!("[WARNING] Evaluation skipped: {}", error));
+            }
+            _ => {}
+        }
     }
 
     fn update_training_ui(&mut self, cx: &mut Cx, scope: &mut Scope, progress: &TrainingProgress) {
@@ -3651,6 +3944,7 @@ impl VoiceCloneModal {
                     .as_secs(),
             ),
             preview_audio: Some(reference_audio.to_string_lossy().to_string()),
+            evaluation: None,
         };
 
         // Save to custom voices config
@@ -3665,9 +3959,12 @@ impl VoiceCloneModal {
         cx.widget_action(
             self.widget_uid(),
             &scope.path,
-            VoiceCloneModalAction::VoiceCreated(new_voice),
+            VoiceCloneModalAction::VoiceCreated(new_voice.clone()),
         );
 
+        // Score the new weights; results are attached to the saved voice when done
+        self.start_evaluation(cx, new_voice);
+
         // Show success message
         self.view.button(ids!(
             modal_container.modal_wrapper.modal_content.footer.pro_actions.start_training_btn
@@ -3678,29 +3975,54 @@ impl VoiceCloneModal {
     }
 
     fn check_gpu_availability(&mut self, cx: &mut Cx) {
-        if !self.gpu_check_done {
-            // First time: run the check and cache the result
-            let has_gpu = std::process::Command::new("python")
-                .arg("-c")
-                .arg("import torch; print(torch.cuda.is_available())")
-                .output()
-                .map(|out| String::from_utf8_lossy(&out.stdout).trim() == "True")
-                .unwrap_or(false);
-
-            self.has_gpu = has_gpu;
-            self.gpu_check_done = true;
-        }
-
-        if !self.has_gpu {
-            // Show warning
-            self.view.view(ids!(
-                modal_container.modal_wrapper.modal_content.body.pro_mode_content.gpu_warning
-            )).set_visible(cx, true);
+        // Look again after a failure, the user may have set up the env since
+        if self.python_env_error.take().is_some() {
+            self.gpu_check_done = false;
+        }
+        self.poll_python_env(cx);
+    }
+
+    /// Report the Python env (and GPU) once the background lookup finishes
+    fn poll_python_env(&mut self, cx: &mut Cx) {
+        if self.gpu_check_done {
+            return;
+        }
+        let Some(result) = python_env::resolve_in_background() else {
+            return;
+        };
+        match result {
+            Ok(env) => {
+                self.has_gpu = env.has_cuda;
+                self.python_env_error = None;
+                self.add_training_log(cx, &format!("[INFO] Using {}", env.describe()));
+            }
+            Err(e) => {
+                self.has_gpu = false;
+                self.add_training_log(cx, &format!("[ERROR] {}", e));
+                for line in e.diagnostics() {
+                    self.add_training_log(cx, &format!("[ERROR]   {}", line));
+                }
+                self.python_env_error = Some(e.to_string());
+            }
+        }
+        self.gpu_check_done = true;
+
+        let warning = if let Some(error) = &self.python_env_error {
+            Some(format!("⚠️ {}", error))
+        } else if !self.has_gpu {
+            Some("⚠️ No GPU detected. Training will be VERY slow (8-24 hours). Consider using a machine with CUDA GPU.".to_string())
         } else {
-            self.view.view(ids!(
-                modal_container.modal_wrapper.modal_content.body.pro_mode_content.gpu_warning
-            )).set_visible(cx, false);
+            None
+        };
+
+        if let Some(text) = &warning {
+            self.view.label(ids!(
+                modal_container.modal_wrapper.modal_content.body.pro_mode_content.gpu_warning.message
+            )).set_text(cx, text);
         }
+        self.view.view(ids!(
+            modal_container.modal_wrapper.modal_content.body.pro_mode_content.gpu_warning
+        )).set_visible(cx, warning.is_some());
     }
 
     fn add_training_log(&mut self, cx: &mut Cx, message: &str) {
@@ -3849,6 +4171,13 @@ impl VoiceCloneModalRef {
         if let Some(mut inner) = self.borrow_mut() {
             inner.dark_mode = dark_mode;
 
+            inner
+                .view
+                .dataset_editor(ids!(
+                    modal_container.modal_wrapper.modal_content.body.pro_mode_content.dataset_editor
+                ))
+                .update_dark_mode(cx, dark_mode);
+
             // Apply to modal content
             inner
                 .view
@@ -46,6 +46,59 @@ pub struct Voice {
     /// Creation timestamp (Unix epoch seconds)
     #[serde(default)]
     pub created_at: Option<u64>,
+    /// Post-training evaluation results (trained voices only)
+    #[serde(default)]
+    pub evaluation: Option<VoiceEvaluation>,
+}
+
+/// Quality metrics computed after few-shot training
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct VoiceEvaluation {
+    /// Mean character error rate of ASR transcripts vs. source text (0.0 = perfect)
+    pub character_error_rate: f32,
+    /// Cosine similarity between reference and synthesized speaker embeddings (-1.0..1.0)
+    pub speaker_similarity: f32,
+    /// Per-sentence results
+    #[serde(default)]
+    pub samples: Vec<EvaluationSample>,
+    /// Evaluation timestamp (Unix epoch seconds)
+    pub evaluated_at: u64,
+}
+
+/// One synthesized test sentence
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct EvaluationSample {
+    /// Source text sent to TTS
+    pub text: String,
+    /// ASR transcript of the synthesized audio
+    pub transcript: String,
+    /// Path to the synthesized WAV
+    pub audio_path: String,
+    /// Character error rate for this sentence
+    pub character_error_rate: f32,
+}
+
+impl VoiceEvaluation {
+    /// Maximum CER for a voice to be considered usable
+    pub const MAX_USABLE_CER: f32 = 0.15;
+    /// Minimum speaker similarity for a voice to be considered usable
+    pub const MIN_USABLE_SIMILARITY: f32 = 0.75;
+
+    /// Whether both metrics are within the usable thresholds
+    pub fn is_usable(&self) -> bool {
+        self.character_error_rate <= Self::MAX_USABLE_CER
+            && self.speaker_similarity >= Self::MIN_USABLE_SIMILARITY
+    }
+
+    /// Short summary for voice lists and logs, e.g. "CER 4.2% · similarity 0.86"
+    pub fn summary(&self) -> String {
+        format!(
+            "CER {:.1}% · similarity {:.2}{}",
+            self.character_error_rate * 100.0,
+            self.speaker_similarity,
+            if self.is_usable() { "" } else { " · needs review" }
+        )
+    }
 }
 
 /// Voice category
@@ -84,6 +137,7 @@ pub fn get_builtin_voices() -> Vec<Voice> {
             gpt_weights: None,
             sovits_weights: None,
             created_at: None,
+            evaluation: None,
         },
         Voice {
             id: "Luo Xiang".to_string(),
@@ -98,6 +152,7 @@ pub fn get_builtin_voices() -> Vec<Voice> {
             gpt_weights: None,
             sovits_weights: None,
             created_at: None,
+            evaluation: None,
         },
         Voice {
             id: "Yang Mi".to_string(),
@@ -112,6 +167,7 @@ pub fn get_builtin_voices() -> Vec<Voice> {
             gpt_weights: None,
             sovits_weights: None,
             created_at: None,
+            evaluation: None,
         },
         Voice {
             id: "Zhou Jielun".to_string(),
@@ -126,6 +182,7 @@ pub fn get_builtin_voices() -> Vec<Voice> {
             gpt_weights: None,
             sovits_weights: None,
             created_at: None,
+            evaluation: None,
         },
         Voice {
             id: "Ma Yun".to_string(),
@@ -140,6 +197,7 @@ pub fn get_builtin_voices() -> Vec<Voice> {
             gpt_weights: None,
             sovits_weights: None,
             created_at: None,
+            evaluation: None,
         },
         Voice {
             id: "Chen Yifan".to_string(),
@@ -154,6 +212,7 @@ pub fn get_builtin_voices() -> Vec<Voice> {
             gpt_weights: None,
             sovits_weights: None,
             created_at: None,
+            evaluation: None,
         },
         Voice {
             id: "Zhao Daniu".to_string(),
@@ -168,6 +227,7 @@ pub fn get_builtin_voices() -> Vec<Voice> {
             gpt_weights: None,
             sovits_weights: None,
             created_at: None,
+            evaluation: None,
         },
         Voice {
             id: "BYS".to_string(),
@@ -182,6 +242,7 @@ pub fn get_builtin_voices() -> Vec<Voice> {
             gpt_weights: None,
             sovits_weights: None,
             created_at: None,
+            evaluation: None,
         },
         Voice {
             id: "Ma Baoguo".to_string(),
@@ -196,6 +257,7 @@ pub fn get_builtin_voices() -> Vec<Voice> {
             gpt_weights: None,
             sovits_weights: None,
             created_at: None,
+            evaluation: None,
         },
         Voice {
             id: "Shen Yi".to_string(),
@@ -210,6 +272,7 @@ pub fn get_builtin_voices() -> Vec<Voice> {
             gpt_weights: None,
             sovits_weights: None,
             created_at: None,
+            evaluation: None,
         },
         // English voices
         Voice {
@@ -225,6 +288,7 @@ pub fn get_builtin_voices() -> Vec<Voice> {
             gpt_weights: None,
             sovits_weights: None,
             created_at: None,
+            evaluation: None,
         },
         Voice {
             id: "Cove".to_string(),
@@ -239,6 +303,7 @@ pub fn get_builtin_voices() -> Vec<Voice> {
             gpt_weights: None,
             sovits_weights: None,
             created_at: None,
+            evaluation: None,
         },
         Voice {
             id: "Ellen".to_string(),
@@ -253,6 +318,7 @@ pub fn get_builtin_voices() -> Vec<Voice> {
             gpt_weights: None,
             sovits_weights: None,
             created_at: None,
+            evaluation: None,
         },
         Voice {
             id: "Juniper".to_string(),
@@ -267,6 +333,7 @@ pub fn get_builtin_voices() -> Vec<Voice> {
             gpt_weights: None,
             sovits_weights: None,
             created_at: None,
+            evaluation: None,
         },
         Voice {
             id: "Trump".to_string(),
@@ -281,6 +348,7 @@ pub fn get_builtin_voices() -> Vec<Voice> {
             gpt_weights: None,
             sovits_weights: None,
             created_at: None,
+            evaluation: None,
         },
     ]
 }
@@ -345,6 +413,7 @@ impl Voice {
                     .map(|d| d.as_secs())
                     .unwrap_or(0),
             ),
+            evaluation: None,
         }
     }
 
@@ -0,0 +1,405 @@
+//! Post-training voice evaluation
+//!
+//! After few-shot training completes, the new weights are exercised on a fixed set of
+//! test sentences:
+//! - Python evaluation service synthesizes each sentence with the trained GPT/SoVITS weights
+//! - The same service transcribes the result with ASR and scores speaker similarity
+//!   against the reference audio
+//! - Rust computes the character error rate (CER) of each transcript vs. its source text
+//!
+//! The service speaks the same JSON-lines protocol as the training service
+//! (`{"type": ..., "message": ..., "data": ...}`), and the UI polls
+//! [`VoiceEvaluator::get_progress`] the same way it polls training progress.
+
+use crate::python_env;
+use crate::voice_data::{EvaluationSample, VoiceEvaluation};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::io::{BufRead, BufReader, Write};
+use std::path::PathBuf;
+use std::process::{Child, Stdio};
+use std::sync::Arc;
+use std::thread;
+use std::time::Instant;
+
+/// Fixed Chinese test sentences (mix of tones, numbers and punctuation)
+const TEST_SENTENCES_ZH: &[&str] = &[
+    "今天天气很好，我们一起去公园散步吧。",
+    "请在下午三点之前把报告发给我。",
+    "科技的发展正在改变我们的生活方式。",
+    "这家餐厅的菜味道不错，价格也很合理。",
+];
+
+/// Fixed English test sentences
+const TEST_SENTENCES_EN: &[&str] = &[
+    "The quick brown fox jumps over the lazy dog.",
+    "Please send me the report before three o'clock this afternoon.",
+    "Technology is changing the way we live and work.",
+    "I would like a cup of coffee with a little milk, please.",
+];
+
+/// Test sentences used for a given language code
+pub fn test_sentences(language: &str) -> &'static [&'static str] {
+    match language {
+        "en" => TEST_SENTENCES_EN,
+        _ => TEST_SENTENCES_ZH,
+    }
+}
+
+/// Everything the evaluation service needs to load the trained voice
+#[derive(Debug, Clone)]
+pub struct EvaluationRequest {
+    pub voice_id: String,
+    pub gpt_weights: PathBuf,
+    pub sovits_weights: PathBuf,
+    pub reference_audio: PathBuf,
+    pub reference_text: String,
+    pub language: String,
+}
+
+/// Evaluation status states
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum EvaluationStatus {
+    #[default]
+    Idle,
+    Running,
+    Completed(VoiceEvaluation),
+    Failed { error: String },
+}
+
+/// Evaluation progress shared between worker and UI
+#[derive(Debug, Clone)]
+pub struct EvaluationProgress {
+    pub status: EvaluationStatus,
+    /// Sentences synthesized and transcribed so far
+    pub samples: Vec<EvaluationSample>,
+    /// Number of test sentences
+    pub total_samples: usize,
+    /// Log lines from the evaluation process
+    pub log_lines: Vec<String>,
+    /// Last update timestamp
+    pub last_updated: Instant,
+}
+
+impl Default for EvaluationProgress {
+    fn default() -> Self {
+        Self {
+            status: EvaluationStatus::Idle,
+            samples: Vec::new(),
+            total_samples: 0,
+            log_lines: Vec::new(),
+            last_updated: Instant::now(),
+        }
+    }
+}
+
+/// JSON event from Python evaluation service
+#[derive(Debug, Deserialize)]
+struct EvaluationEvent {
+    #[serde(rename = "type")]
+    event_type: String,
+    #[serde(default)]
+    message: String,
+    #[serde(default)]
+    data: Option<serde_json::Value>,
+}
+
+/// Request sent to the Python evaluation service
+#[derive(Debug, Serialize)]
+struct ServiceRequest<'a> {
+    gpt_weights: String,
+    sovits_weights: String,
+    reference_audio: String,
+    reference_text: &'a str,
+    language: &'a str,
+    sentences: &'a [&'a str],
+    output_dir: String,
+}
+
+/// Runs one evaluation in the background
+///
+/// The subprocess is killed when the evaluator is dropped.
+pub struct VoiceEvaluator {
+    progress: Arc<Mutex<EvaluationProgress>>,
+    child: Arc<Mutex<Option<Child>>>,
+}
+
+impl VoiceEvaluator {
+    /// Spawn the evaluation service for a freshly trained voice
+    pub fn start(request: EvaluationRequest) -> Self {
+        let sentences = test_sentences(&request.language);
+        let progress = Arc::new(Mutex::new(EvaluationProgress {
+            status: EvaluationStatus::Running,
+            total_samples: sentences.len(),
+            log_lines: vec!["[INFO] Evaluation started".to_string()],
+            ..Default::default()
+        }));
+        let child = Arc::new(Mutex::new(None));
+
+        let progress_clone = Arc::clone(&progress);
+        let child_clone = Arc::clone(&child);
+        thread::Builder::new()
+            .name("voice-evaluation".to_string())
+            .spawn(move || {
+                if let Err(error) =
+                    Self::run(&request, sentences, &progress_clone, &child_clone)
+                {
+                    let mut prog = progress_clone.lock();
+                    prog.log_lines.push(format!("[ERROR] {}", error));
+                    prog.status = EvaluationStatus::Failed { error };
+                    prog.last_updated = Instant::now();
+                }
+            })
+            .expect("Failed to spawn evaluation thread");
+
+        Self { progress, child }
+    }
+
+    /// Get a snapshot of current evaluation progress
+    pub fn get_progress(&self) -> EvaluationProgress {
+        self.progress.lock().clone()
+    }
+
+    fn run(
+        request: &EvaluationRequest,
+        sentences: &'static [&'static str],
+        progress: &Arc<Mutex<EvaluationProgress>>,
+        child_slot: &Arc<Mutex<Option<Child>>>,
+    ) -> Result<(), String> {
+        let python = python_env::resolve().map_err(|e| e.to_string())?;
+
+        let output_dir = crate::voice_persistence::get_primespeech_dir()
+            .join("trained_models")
+            .join(&request.voice_id)
+            .join("evaluation");
+        std::fs::create_dir_all(&output_dir)
+            .map_err(|e| format!("Failed to create {}: {}", output_dir.display(), e))?;
+
+        let service_request = ServiceRequest {
+            gpt_weights: request.gpt_weights.to_string_lossy().to_string(),
+            sovits_weights: request.sovits_weights.to_string_lossy().to_string(),
+            reference_audio: request.reference_audio.to_string_lossy().to_string(),
+            reference_text: &request.reference_text,
+            language: &request.language,
+            sentences,
+            output_dir: output_dir.to_string_lossy().to_string(),
+        };
+        let request_json = serde_json::to_string(&service_request)
+            .map_err(|e| format!("Failed to serialize request: {}", e))?;
+
+        log::info!("Spawning Python evaluation service ({})", python.describe());
+        let mut child = python
+            .command()
+            .arg("-m")
+            .arg("dora_primespeech.moyoyo_tts.evaluation_service")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| format!("Failed to spawn evaluation service: {}", e))?;
+
+        if let Some(mut stdin) = child.stdin.take() {
+            writeln!(stdin, "{}", request_json)
+                .map_err(|e| format!("Failed to send request: {}", e))?;
+        }
+
+        if let Some(stderr) = child.stderr.take() {
+            thread::Builder::new()
+                .name("evaluation-stderr-reader".to_string())
+                .spawn(move || {
+                    for line in BufReader::new(stderr).lines().map_while(Result::ok) {
+                        log::info!("[Python] {}", line);
+                    }
+                })
+                .expect("Failed to spawn stderr reader thread");
+        }
+
+        let stdout = child.stdout.take();
+        *child_slot.lock() = Some(child);
+
+        if let Some(stdout) = stdout {
+            for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+                Self::handle_event(&line, progress);
+            }
+        }
+
+        // Reap the process; it may already have been killed by Drop
+        if let Some(mut child) = child_slot.lock().take() {
+            let _ = child.wait();
+        }
+
+        if progress.lock().status == EvaluationStatus::Running {
+            return Err("Evaluation process exited without completion event".to_string());
+        }
+        Ok(())
+    }
+
+    /// Parse and handle a JSON event from the evaluation service
+    fn handle_event(json_line: &str, progress: &Arc<Mutex<EvaluationProgress>>) {
+        let mut prog = progress.lock();
+        prog.last_updated = Instant::now();
+
+        let Ok(event) = serde_json::from_str::<EvaluationEvent>(json_line) else {
+            prog.log_lines.push(json_line.to_string());
+            return;
+        };
+        let data = event.data.unwrap_or_default();
+        let str_field = |key: &str| {
+            data.get(key)
+                .and_then(|v| v.as_str())
+                .unwrap_or("")
+                .to_string()
+        };
+
+        match event.event_type.as_str() {
+            "SAMPLE" => {
+                let text = str_field("text");
+                let transcript = str_field("transcript");
+                let cer = character_error_rate(&text, &transcript);
+                let line = format!(
+                    "[EVAL] {}/{} CER {:.1}%: {}",
+                    prog.samples.len() + 1,
+                    prog.total_samples,
+                    cer * 100.0,
+                    transcript
+                );
+                prog.log_lines.push(line);
+                prog.samples.push(EvaluationSample {
+                    text,
+                    transcript,
+                    audio_path: str_field("audio_path"),
+                    character_error_rate: cer,
+                });
+            }
+            "COMPLETE" => {
+                let Some(similarity) = data.get("speaker_similarity").and_then(|v| v.as_f64())
+                else {
+                    prog.status = EvaluationStatus::Failed {
+                        error: "Completion event missing speaker_similarity".to_string(),
+                    };
+                    return;
+                };
+                if prog.samples.is_empty() {
+                    prog.status = EvaluationStatus::Failed {
+                        error: "No samples were synthesized".to_string(),
+                    };
+                    return;
+                }
+                let evaluation = VoiceEvaluation {
+                    character_error_rate: mean_cer(&prog.samples),
+                    speaker_similarity: similarity as f32,
+                    samples: prog.samples.clone(),
+                    evaluated_at: std::time::SystemTime::now()
+                        .duration_since(std::time::UNIX_EPOCH)
+                        .map(|d| d.as_secs())
+                        .unwrap_or(0),
+                };
+                prog.log_lines
+                    .push(format!("[SUCCESS] Evaluation: {}", evaluation.summary()));
+                prog.status = EvaluationStatus::Completed(evaluation);
+            }
+            "ERROR" => {
+                prog.log_lines.push(format!("[ERROR] {}", event.message));
+                prog.status = EvaluationStatus::Failed {
+                    error: event.message,
+                };
+            }
+            other => {
+                prog.log_lines.push(format!("[{}] {}", other, event.message));
+            }
+        }
+    }
+}
+
+impl Drop for VoiceEvaluator {
+    fn drop(&mut self) {
+        if let Some(mut child) = self.child.lock().take() {
+            let _ = child.kill();
+        }
+    }
+}
+
+/// Characters compared for CER: alphanumerics only, lowercased
+///
+/// Punctuation and whitespace are dropped because ASR output rarely matches the
+/// source text's punctuation, and Chinese transcripts have no word spacing.
+fn normalize_for_cer(text: &str) -> Vec<char> {
+    text.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Character error rate: Levenshtein distance / reference length
+///
+/// Can exceed 1.0 when the hypothesis has many insertions.
+pub fn character_error_rate(reference: &str, hypothesis: &str) -> f32 {
+    let reference = normalize_for_cer(reference);
+    let hypothesis = normalize_for_cer(hypothesis);
+    if reference.is_empty() {
+        return if hypothesis.is_empty() { 0.0 } else { 1.0 };
+    }
+
+    // Single-row Levenshtein
+    let mut row: Vec<usize> = (0..=hypothesis.len()).collect();
+    for (i, r) in reference.iter().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, h) in hypothesis.iter().enumerate() {
+            let substitution = diagonal + usize::from(r != h);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(row[j + 1] + 1);
+        }
+    }
+
+    row[hypothesis.len()] as f32 / reference.len() as f32
+}
+
+fn mean_cer(samples: &[EvaluationSample]) -> f32 {
+    samples.iter().map(|s| s.character_error_rate).sum::<f32>() / samples.len() as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_character_error_rate() {
+        assert_eq!(character_error_rate("今天天气很好。", "今天天气很好"), 0.0);
+        assert_eq!(character_error_rate("Hello, World!", "hello world"), 0.0);
+        // One substitution out of four characters
+        assert_eq!(character_error_rate("abcd", "abxd"), 0.25);
+        // Deletion and insertion
+        assert_eq!(character_error_rate("abcd", "abc"), 0.25);
+        assert_eq!(character_error_rate("abcd", "abcde"), 0.25);
+        assert_eq!(character_error_rate("", ""), 0.0);
+    }
+
+    #[test]
+    fn test_handle_sample_and_complete_events() {
+        let progress = Arc::new(Mutex::new(EvaluationProgress {
+            status: EvaluationStatus::Running,
+            total_samples: 1,
+            ..Default::default()
+        }));
+
+        VoiceEvaluator::handle_event(
+            r#"{"type":"SAMPLE","message":"","data":{"text":"abcd","transcript":"abxd","audio_path":"/tmp/0.wav"}}"#,
+            &progress,
+        );
+        VoiceEvaluator::handle_event(
+            r#"{"type":"COMPLETE","message":"done","data":{"speaker_similarity":0.9}}"#,
+            &progress,
+        );
+
+        let status = progress.lock().status.clone();
+        match status {
+            EvaluationStatus::Completed(eval) => {
+                assert_eq!(eval.samples.len(), 1);
+                assert_eq!(eval.character_error_rate, 0.25);
+                assert!(!eval.is_usable());
+            }
+            other => panic!("unexpected status {:?}", other),
+        }
+    }
+}
@@ -583,8 +583,11 @@ impl Widget for VoiceSelector {
                         let initial = voice.name.chars().next().unwrap_or('?').to_string();
                         item.label(ids!(avatar.initial)).set_text(cx, &initial);
                         item.label(ids!(info.name)).set_text(cx, &voice.name);
-                        item.label(ids!(info.description))
-                            .set_text(cx, &voice.description);
+                        let description = match &voice.evaluation {
+                            Some(eval) => format!("{} · {}", voice.description, eval.summary()),
+                            None => voice.description.clone(),
+                        };
+                        item.label(ids!(info.description)).set_text(cx, &description);
 
                         // Set selection state
                         let is_selected = self.selected_voice_id.as_ref() == Some(&voice.id);
@@ -739,6 +742,19 @@ impl VoiceSelectorRef {
         }
     }
 
+    /// Replace a custom voice in place (e.g. after evaluation results arrive)
+    pub fn update_custom_voice(&self, cx: &mut Cx, voice: Voice) {
+        if let Some(mut inner) = self.borrow_mut() {
+            if let Some(existing) = inner.custom_voices.iter_mut().find(|v| v.id == voice.id) {
+                *existing = voice.clone();
+            }
+            if let Some(existing) = inner.voices.iter_mut().find(|v| v.id == voice.id) {
+                *existing = voice;
+            }
+            inner.view.redraw(cx);
+        }
+    }
+
     /// Delete a custom voice by ID
     pub fn delete_custom_voice(&self, cx: &mut Cx, voice_id: &str) -> Result<(), String> {
         // First remove from persistence
@@ -36,7 +36,8 @@
 //! ```
 
 use crate::data::DoraData;
-use crate::error::BridgeResult;
+use crate::error::{BridgeError, BridgeResult};
+use crate::outbox::{RequestId, SendHandle, SendOutcome, SendRequest};
 
 /// Connection state for a Dora bridge.
 ///
@@ -121,6 +122,26 @@ pub trait DoraBridge: Send + Sync {
     /// Send data to a dora output
     fn send(&self, output_id: &str, data: DoraData) -> BridgeResult<()>;
 
+    /// Queue data for a dora output and track its delivery and reply
+    ///
+    /// Bridges with an [`Outbox`](crate::outbox::Outbox) return as soon as
+    /// the request is queued and fail with [`BridgeError::OutboxFull`] under
+    /// backpressure. The default sends synchronously through [`Self::send`],
+    /// so the handle is already complete, and cannot wait for replies.
+    fn submit(&self, request: SendRequest) -> BridgeResult<SendHandle> {
+        if request.reply_on.is_some() {
+            return Err(BridgeError::NotSupported(format!(
+                "{} does not correlate replies",
+                self.node_id()
+            )));
+        }
+        let outcome = match self.send(&request.output, request.data) {
+            Ok(()) => SendOutcome::Delivered,
+            Err(e) => SendOutcome::Failed(e.to_string()),
+        };
+        Ok(SendHandle::completed(RequestId::next(), outcome))
+    }
+
     /// Get list of input IDs this bridge expects
     fn expected_inputs(&self) -> Vec<String>;
 
@@ -0,0 +1,483 @@
+//! Dataflow builder
+//!
+//! Renders a dataflow YAML from a template plus a typed [`DataflowProfile`]
+//! (device, engines, languages, log level) and per-node user overrides, and
+//! writes the result to a run directory so the app doesn't depend on its
+//! working directory:
+//!
+//! ```rust,ignore
+//! use mofa_dora_bridge::builder::{DataflowBuilder, DataflowProfile};
+//!
+//! let path = DataflowBuilder::from_yaml(include_str!("../dataflow/tts.yml"))?
+//!     .with_name("tts")
+//!     .with_profile(DataflowProfile::gpu())
+//!     .set_env("primespeech-tts", "VOICE_NAME", "Luo Xiang")
+//!     .write_to(run_dir)?;
+//! ```
+//!
+//! Profile values only replace env keys a node already declares, so one
+//! profile can be applied to any template: `LANGUAGE` reaches the ASR node,
+//! `TEXT_LANG` the TTS node. Overrides are applied last and may add keys.
+
+use crate::data::LogLevel;
+use crate::error::{BridgeError, BridgeResult};
+use serde::{Deserialize, Serialize};
+use serde_yaml::{Mapping, Value};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Compute device for model nodes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Device {
+    #[default]
+    Cpu,
+    /// NVIDIA GPU
+    Cuda,
+    /// Apple Silicon GPU
+    Mps,
+}
+
+impl Device {
+    /// Value for the `DEVICE` env var
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Device::Cpu => "cpu",
+            Device::Cuda => "cuda",
+            Device::Mps => "mps",
+        }
+    }
+
+    /// Whether this device is a GPU (`USE_GPU`)
+    pub fn is_gpu(&self) -> bool {
+        !matches!(self, Device::Cpu)
+    }
+
+    /// GPU device for the current platform
+    pub fn platform_gpu() -> Self {
+        if cfg!(target_os = "macos") {
+            Device::Mps
+        } else {
+            Device::Cuda
+        }
+    }
+}
+
+/// Speech recognition engine
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AsrEngine {
+    #[default]
+    FunAsr,
+    Whisper,
+}
+
+impl AsrEngine {
+    /// Value for the `ASR_ENGINE` env var
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AsrEngine::FunAsr => "funasr",
+            AsrEngine::Whisper => "whisper",
+        }
+    }
+}
+
+/// Language for recognition and synthesis
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Language {
+    #[default]
+    Zh,
+    En,
+    Ja,
+    Ko,
+    /// Cantonese
+    Yue,
+    /// Let the engine detect the language
+    Auto,
+}
+
+impl Language {
+    /// Language code passed to the nodes
+    pub fn code(&self) -> &'static str {
+        match self {
+            Language::Zh => "zh",
+            Language::En => "en",
+            Language::Ja => "ja",
+            Language::Ko => "ko",
+            Language::Yue => "yue",
+            Language::Auto => "auto",
+        }
+    }
+}
+
+/// Typed settings rendered into node env vars
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DataflowProfile {
+    /// `DEVICE` and `USE_GPU`
+    pub device: Device,
+    /// `ASR_ENGINE`
+    pub asr_engine: AsrEngine,
+    /// `LANGUAGE` (speech recognition)
+    pub asr_language: Language,
+    /// `TEXT_LANG` (language of the text to synthesize)
+    pub text_language: Language,
+    /// `PROMPT_LANG` (language of the voice's reference audio)
+    pub prompt_language: Language,
+    /// `LOG_LEVEL`
+    pub log_level: LogLevel,
+    /// `VECLIB_MAXIMUM_THREADS`, removed when `None`
+    ///
+    /// Only meaningful on macOS, where an unbounded Accelerate thread pool
+    /// can deadlock PyTorch.
+    pub accelerate_threads: Option<u32>,
+}
+
+impl Default for DataflowProfile {
+    fn default() -> Self {
+        Self::cpu()
+    }
+}
+
+impl DataflowProfile {
+    /// CPU inference, Chinese, funasr
+    pub fn cpu() -> Self {
+        Self {
+            device: Device::Cpu,
+            asr_engine: AsrEngine::FunAsr,
+            asr_language: Language::Zh,
+            text_language: Language::Zh,
+            prompt_language: Language::Zh,
+            log_level: LogLevel::Info,
+            accelerate_threads: cfg!(target_os = "macos").then_some(1),
+        }
+    }
+
+    /// GPU inference on the platform's GPU, otherwise like [`Self::cpu`]
+    pub fn gpu() -> Self {
+        Self {
+            device: Device::platform_gpu(),
+            ..Self::cpu()
+        }
+    }
+
+    /// Set the language for both recognition and synthesis
+    pub fn with_language(mut self, language: Language) -> Self {
+        self.asr_language = language;
+        self.text_language = language;
+        self.prompt_language = language;
+        self
+    }
+
+    /// Env values this profile controls (`None` removes the key)
+    pub fn env(&self) -> Vec<(&'static str, Option<String>)> {
+        vec![
+            ("DEVICE", Some(self.device.as_str().to_string())),
+            ("USE_GPU", Some(self.device.is_gpu().to_string())),
+            ("ASR_ENGINE", Some(self.asr_engine.as_str().to_string())),
+            ("LANGUAGE", Some(self.asr_language.code().to_string())),
+            ("TEXT_LANG", Some(self.text_language.code().to_string())),
+            ("PROMPT_LANG", Some(self.prompt_language.code().to_string())),
+            ("LOG_LEVEL", Some(self.log_level.to_string())),
+            (
+                "VECLIB_MAXIMUM_THREADS",
+                self.accelerate_threads.map(|n| n.to_string()),
+            ),
+        ]
+    }
+}
+
+/// Per-node env overrides: node id → key → value
+pub type EnvOverrides = BTreeMap<String, BTreeMap<String, String>>;
+
+/// Renders a dataflow template with a profile and overrides
+#[derive(Debug, Clone)]
+pub struct DataflowBuilder {
+    template: Value,
+    name: String,
+    profile: Option<DataflowProfile>,
+    overrides: EnvOverrides,
+}
+
+impl DataflowBuilder {
+    /// Builder from a parsed template
+    pub fn new(template: Value) -> BridgeResult<Self> {
+        if template
+            .get("nodes")
+            .and_then(|n| n.as_sequence())
+            .is_none()
+        {
+            return Err(BridgeError::ParseError(
+                "dataflow template has no nodes list".to_string(),
+            ));
+        }
+        Ok(Self {
+            template,
+            name: "dataflow".to_string(),
+            profile: None,
+            overrides: EnvOverrides::new(),
+        })
+    }
+
+    /// Builder from template YAML text (e.g. an `include_str!`)
+    pub fn from_yaml(yaml: &str) -> BridgeResult<Self> {
+        Self::new(serde_yaml::from_str(yaml)?)
+    }
+
+    /// Builder from a template file
+    pub fn from_file(path: impl AsRef<Path>) -> BridgeResult<Self> {
+        Self::from_yaml(&std::fs::read_to_string(path)?)
+    }
+
+    /// File name (without extension) used by [`Self::write_to`]
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = name.into();
+        self
+    }
+
+    /// Apply a profile; without one the template's env values are kept
+    pub fn with_profile(mut self, profile: DataflowProfile) -> Self {
+        self.profile = Some(profile);
+        self
+    }
+
+    /// Set an env var on a node, adding it if the template lacks it
+    pub fn set_env(
+        mut self,
+        node_id: impl Into<String>,
+        key: impl Into<String>,
+        value: impl Into<String>,
+    ) -> Self {
+        self.overrides
+            .entry(node_id.into())
+            .or_default()
+            .insert(key.into(), value.into());
+        self
+    }
+
+    /// Merge a set of overrides (later values win)
+    pub fn with_overrides(mut self, overrides: &EnvOverrides) -> Self {
+        for (node_id, env) in overrides {
+            let node_env = self.overrides.entry(node_id.clone()).or_default();
+            for (key, value) in env {
+                node_env.insert(key.clone(), value.clone());
+            }
+        }
+        self
+    }
+
+    /// Render the dataflow descriptor
+    ///
+    /// Fails if an override names a node that isn't in the template, so a
+    /// typo doesn't silently leave the default in place.
+    pub fn build(&self) -> BridgeResult<Value> {
+        let mut dataflow = self.template.clone();
+        let nodes = dataflow
+            .get_mut("nodes")
+            .and_then(|n| n.as_sequence_mut())
+            .ok_or_else(|| BridgeError::ParseError("dataflow has no nodes list".to_string()))?;
+
+        if let Some(unknown) = self.overrides.keys().find(|id| {
+            !nodes
+                .iter()
+                .any(|node| node.get("id").and_then(|v| v.as_str()) == Some(id.as_str()))
+        }) {
+            return Err(BridgeError::NodeNotFound(unknown.clone()));
+        }
+
+        let profile_env = self.profile.as_ref().map(|p| p.env()).unwrap_or_default();
+
+        for node in nodes.iter_mut() {
+            let Some(node_id) = node.get("id").and_then(|v| v.as_str()).map(String::from) else {
+                continue;
+            };
+            let Some(node) = node.as_mapping_mut() else {
+                continue;
+            };
+
+            if let Some(env) = node.get_mut("env").and_then(|e| e.as_mapping_mut()) {
+                for (key, value) in &profile_env {
+                    let key = Value::String(key.to_string());
+                    if !env.contains_key(&key) {
+                        continue;
+                    }
+                    match value {
+                        Some(value) => {
+                            env.insert(key, Value::String(value.clone()));
+                        }
+                        None => {
+                            env.remove(&key);
+                        }
+                    }
+                }
+            }
+
+            if let Some(overrides) = self.overrides.get(&node_id) {
+                let env = node
+                    .entry(Value::String("env".to_string()))
+                    .or_insert_with(|| Value::Mapping(Mapping::new()));
+                if let Some(env) = env.as_mapping_mut() {
+                    for (key, value) in overrides {
+                        env.insert(Value::String(key.clone()), Value::String(value.clone()));
+                    }
+                }
+            }
+        }
+
+        Ok(dataflow)
+    }
+
+    /// Render the dataflow as YAML text
+    pub fn render(&self) -> BridgeResult<String> {
+        Ok(serde_yaml::to_string(&self.build()?)?)
+    }
+
+    /// Render and write `<run_dir>/<name>.yml`, creating the directory
+    ///
+    /// dora resolves node paths and writes `out/<dataflow id>` logs relative
+    /// to this file, so the run directory also holds the node logs.
+    pub fn write_to(&self, run_dir: impl AsRef<Path>) -> BridgeResult<PathBuf> {
+        let run_dir = run_dir.as_ref();
+        std::fs::create_dir_all(run_dir)?;
+        let path = run_dir.join(format!("{}.yml", self.name));
+        let yaml = format!(
+            "# Generated by mofa-dora-bridge from the {} template, edits are overwritten\n{}",
+            self.name,
+            self.render()?
+        );
+        std::fs::write(&path, yaml)?;
+        Ok(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEMPLATE: &str = r#"
+nodes:
+  - id: asr
+    path: dora-asr
+    env:
+      USE_GPU: "false"
+      ASR_ENGINE: "funasr"
+      LANGUAGE: "zh"
+      LOG_LEVEL: "INFO"
+  - id: primespeech-tts
+    path: dora-primespeech
+    env:
+      VOICE_NAME: "Doubao"
+      TEXT_LANG: zh
+      USE_GPU: false
+      DEVICE: cpu
+      VECLIB_MAXIMUM_THREADS: "1"
+      LOG_LEVEL: DEBUG
+  - id: mofa-audio-player
+    path: dynamic
+"#;
+
+    fn env<'a>(dataflow: &'a Value, node_id: &str, key: &str) -> Option<&'a str> {
+        dataflow["nodes"]
+            .as_sequence()?
+            .iter()
+            .find(|n| n["id"].as_str() == Some(node_id))?
+            .get("env")?
+            .get(key)?
+            .as_str()
+    }
+
+    #[test]
+    fn test_profile_replaces_only_declared_keys() {
+        let profile = DataflowProfile {
+            device: Device::Cuda,
+            asr_engine: AsrEngine::Whisper,
+            log_level: LogLevel::Warning,
+            accelerate_threads: None,
+            ..DataflowProfile::cpu().with_language(Language::En)
+        };
+        let dataflow = DataflowBuilder::from_yaml(TEMPLATE)
+            .unwrap()
+            .with_profile(profile)
+            .build()
+            .unwrap();
+
+        assert_eq!(env(&dataflow, "asr", "USE_GPU"), Some("true"));
+        assert_eq!(env(&dataflow, "asr", "ASR_ENGINE"), Some("whisper"));
+        assert_eq!(env(&dataflow, "asr", "LANGUAGE"), Some("en"));
+        assert_eq!(env(&dataflow, "asr", "DEVICE"), None);
+        assert_eq!(env(&dataflow, "primespeech-tts", "DEVICE"), Some("cuda"));
+        assert_eq!(env(&dataflow, "primespeech-tts", "TEXT_LANG"), Some("en"));
+        assert_eq!(
+            env(&dataflow, "primespeech-tts", "LOG_LEVEL"),
+            Some("WARNING")
+        );
+        assert_eq!(
+            env(&dataflow, "primespeech-tts", "VECLIB_MAXIMUM_THREADS"),
+            None
+        );
+        assert_eq!(
+            env(&dataflow, "primespeech-tts", "VOICE_NAME"),
+            Some("Doubao")
+        );
+        assert!(dataflow["nodes"][2].get("env").is_none());
+    }
+
+    #[test]
+    fn test_overrides_win_and_unknown_nodes_fail() {
+        let mut overrides = EnvOverrides::new();
+        overrides
+            .entry("mofa-audio-player".to_string())
+            .or_default()
+            .insert("BUFFER_MS".to_string(), "200".to_string());
+
+        let dataflow = DataflowBuilder::from_yaml(TEMPLATE)
+            .unwrap()
+            .with_profile(DataflowProfile::cpu())
+            .set_env("primespeech-tts", "LOG_LEVEL", "DEBUG")
+            .with_overrides(&overrides)
+            .build()
+            .unwrap();
+        assert_eq!(
+            env(&dataflow, "primespeech-tts", "LOG_LEVEL"),
+            Some("DEBUG")
+        );
+        assert_eq!(env(&dataflow, "asr", "LOG_LEVEL"), Some("INFO"));
+        assert_eq!(
+            env(&dataflow, "mofa-audio-player", "BUFFER_MS"),
+            Some("200")
+        );
+
+        let err = DataflowBuilder::from_yaml(TEMPLATE)
+            .unwrap()
+            .set_env("primespeech", "VOICE_NAME", "x")
+            .build()
+            .unwrap_err();
+        assert!(matches!(err, BridgeError::NodeNotFound(id) if id == "primespeech"));
+
+        assert!(DataflowBuilder::from_yaml("foo: 1").is_err());
+    }
+
+    #[test]
+    fn test_write_to_run_dir() {
+        let run_dir = std::env::temp_dir().join(format!("mofa-builder-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&run_dir);
+
+        let path = DataflowBuilder::from_yaml(TEMPLATE)
+            .unwrap()
+            .with_name("tts")
+            .with_profile(DataflowProfile::gpu())
+            .write_to(&run_dir)
+            .unwrap();
+        assert_eq!(path, run_dir.join("tts.yml"));
+
+        let written: Value =
+            serde_yaml::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(
+            env(&written, "primespeech-tts", "DEVICE"),
+            Some(Device::platform_gpu().as_str())
+        );
+
+        let _ = std::fs::remove_dir_all(&run_dir);
+    }
+}
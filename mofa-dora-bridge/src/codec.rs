@@ -0,0 +1,393 @@
+//! Conversions between [`DoraData`] and dora's Arrow arrays plus metadata
+//!
+//! Every bridge decodes inputs and encodes outputs through this module, so
+//! the wire format lives in one place:
+//!
+//! | DoraData | Arrow array | Metadata |
+//! |----------|-------------|----------|
+//! | `Audio` | `Float32` samples (decodes `Float64`, `Int16`, and `List`/`LargeList` of floats too) | `sample_rate`, `channels`, `question_id`, `participant_id` |
+//! | `Text` | `Utf8` (decodes `LargeUtf8` and UTF-8 `UInt8` bytes too) | - |
+//! | `Json`, `Control`, `Log`, `Chat` | `Utf8` holding JSON | - |
+//! | `Binary` | `UInt8` | - |
+//! | `Empty` | empty `Null` array | - |
+//!
+//! JSON payloads carry no type tag, so [`decode`] returns them as `Text`;
+//! use [`decode_json`] when the input is known to hold JSON.
+
+use crate::data::{AudioData, DoraData, EventMetadata};
+use crate::error::{BridgeError, BridgeResult};
+use crate::outbox::CORRELATION_KEY;
+use crate::recorder::NodeOutput;
+use arrow::array::{
+    new_empty_array, Array, ArrayRef, AsArray, Float32Array, StringArray, UInt8Array,
+};
+use arrow::datatypes::{DataType, Float32Type, Float64Type, Int16Type, UInt8Type};
+use dora_node_api::{MetadataParameters, Parameter};
+use serde::de::DeserializeOwned;
+use std::sync::Arc;
+use tracing::{debug, warn};
+
+/// Metadata key for the audio sample rate in Hz
+pub const SAMPLE_RATE_KEY: &str = "sample_rate";
+/// Metadata key for the number of interleaved audio channels
+pub const CHANNELS_KEY: &str = "channels";
+/// Metadata key for the speaker of an audio chunk
+pub const PARTICIPANT_ID_KEY: &str = "participant_id";
+/// Sample rate assumed when audio arrives without `sample_rate` (primespeech output)
+pub const DEFAULT_SAMPLE_RATE: u32 = 32000;
+
+/// String form of a metadata parameter (lists use `Debug` formatting)
+pub fn parameter_to_string(value: &Parameter) -> String {
+    match value {
+        Parameter::String(s) => s.clone(),
+        Parameter::Integer(i) => i.to_string(),
+        Parameter::Float(f) => f.to_string(),
+        Parameter::Bool(b) => b.to_string(),
+        Parameter::ListInt(l) => format!("{:?}", l),
+        Parameter::ListFloat(l) => format!("{:?}", l),
+        Parameter::ListString(l) => format!("{:?}", l),
+    }
+}
+
+/// Flatten dora metadata into string key/value pairs
+pub fn event_metadata(parameters: &MetadataParameters) -> EventMetadata {
+    let mut metadata = EventMetadata::default();
+    for (key, value) in parameters {
+        metadata
+            .values
+            .insert(key.clone(), parameter_to_string(value));
+    }
+    metadata
+}
+
+/// Typed metadata for an audio chunk
+pub fn audio_parameters(audio: &AudioData) -> MetadataParameters {
+    let mut parameters = MetadataParameters::new();
+    parameters.insert(
+        SAMPLE_RATE_KEY.to_string(),
+        Parameter::Integer(audio.sample_rate as i64),
+    );
+    parameters.insert(
+        CHANNELS_KEY.to_string(),
+        Parameter::Integer(audio.channels as i64),
+    );
+    if let Some(ref qid) = audio.question_id {
+        parameters.insert(CORRELATION_KEY.to_string(), Parameter::String(qid.clone()));
+    }
+    if let Some(ref pid) = audio.participant_id {
+        parameters.insert(
+            PARTICIPANT_ID_KEY.to_string(),
+            Parameter::String(pid.clone()),
+        );
+    }
+    parameters
+}
+
+/// Encode data for a dora output
+pub fn encode(data: &DoraData) -> BridgeResult<(MetadataParameters, ArrayRef)> {
+    let array: ArrayRef = match data {
+        DoraData::Audio(audio) => {
+            return Ok((
+                audio_parameters(audio),
+                Arc::new(Float32Array::from(audio.samples.clone())),
+            ))
+        }
+        DoraData::Text(text) => text_array(text),
+        DoraData::Json(value) => text_array(&value.to_string()),
+        DoraData::Binary(bytes) => Arc::new(UInt8Array::from(bytes.clone())),
+        DoraData::Control(cmd) => text_array(&to_json(cmd)?),
+        DoraData::Log(entry) => text_array(&to_json(entry)?),
+        DoraData::Chat(msg) => text_array(&to_json(msg)?),
+        DoraData::Empty => new_empty_array(&DataType::Null),
+    };
+    Ok((MetadataParameters::new(), array))
+}
+
+/// Encode data as output `id`
+pub fn encode_output(id: &str, data: &DoraData) -> BridgeResult<NodeOutput> {
+    let (parameters, data) = encode(data)?;
+    Ok(NodeOutput {
+        id: id.to_string(),
+        parameters,
+        data,
+    })
+}
+
+/// Single-value `Utf8` array
+pub fn text_array(text: &str) -> ArrayRef {
+    Arc::new(StringArray::from(vec![text]))
+}
+
+fn to_json(value: &impl serde::Serialize) -> BridgeResult<String> {
+    serde_json::to_string(value).map_err(|e| BridgeError::InvalidData(e.to_string()))
+}
+
+/// Decode an input, inferring the variant from the Arrow type
+///
+/// Float, `Int16` and list arrays become `Audio`, strings `Text`, other
+/// `UInt8` arrays `Binary`, and empty arrays `Empty`.
+pub fn decode(data: &ArrayRef, parameters: &MetadataParameters) -> Option<DoraData> {
+    if data.is_empty() {
+        return Some(DoraData::Empty);
+    }
+    match data.data_type() {
+        DataType::Utf8 | DataType::LargeUtf8 => decode_string(data).map(DoraData::Text),
+        DataType::UInt8 => Some(DoraData::Binary(
+            data.as_primitive::<UInt8Type>().values().to_vec(),
+        )),
+        DataType::Null => Some(DoraData::Empty),
+        _ => decode_audio(data, &event_metadata(parameters)).map(DoraData::Audio),
+    }
+}
+
+/// First string of a `Utf8`/`LargeUtf8` array, or `UInt8` bytes as UTF-8
+pub fn decode_string(data: &ArrayRef) -> Option<String> {
+    match data.data_type() {
+        DataType::Utf8 => {
+            let array = data.as_string::<i32>();
+            (!array.is_empty()).then(|| array.value(0).to_string())
+        }
+        DataType::LargeUtf8 => {
+            let array = data.as_string::<i64>();
+            (!array.is_empty()).then(|| array.value(0).to_string())
+        }
+        DataType::UInt8 => {
+            let bytes = data.as_primitive::<UInt8Type>().values();
+            String::from_utf8(bytes.to_vec()).ok()
+        }
+        dt => {
+            warn!("Unsupported text data type: {:?}", dt);
+            None
+        }
+    }
+}
+
+/// Deserialize a JSON string input
+pub fn decode_json<T: DeserializeOwned>(data: &ArrayRef) -> Option<T> {
+    serde_json::from_str(&decode_string(data)?).ok()
+}
+
+/// Audio chunk from samples plus `sample_rate`, `channels`, `question_id`
+/// and `participant_id` metadata
+///
+/// `Int16` samples are scaled to -1.0..1.0. List arrays are what primespeech
+/// sends (`pa.array([audio_array])`); their first row holds the samples.
+pub fn decode_audio(data: &ArrayRef, metadata: &EventMetadata) -> Option<AudioData> {
+    let samples = samples(data.as_ref())?;
+    if samples.is_empty() {
+        return None;
+    }
+
+    let sample_rate = metadata
+        .get(SAMPLE_RATE_KEY)
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(DEFAULT_SAMPLE_RATE);
+    let channels = metadata
+        .get(CHANNELS_KEY)
+        .and_then(|s| s.parse().ok())
+        .filter(|&c| c > 0)
+        .unwrap_or(1);
+
+    Some(AudioData {
+        samples,
+        sample_rate,
+        channels,
+        participant_id: metadata.participant_id().map(|s| s.to_string()),
+        question_id: metadata.question_id().map(|s| s.to_string()),
+    })
+}
+
+/// Samples as f32, whatever numeric layout they arrived in
+fn samples(array: &dyn Array) -> Option<Vec<f32>> {
+    match array.data_type() {
+        DataType::Float32 => Some(array.as_primitive::<Float32Type>().values().to_vec()),
+        DataType::Float64 => Some(
+            array
+                .as_primitive::<Float64Type>()
+                .values()
+                .iter()
+                .map(|&x| x as f32)
+                .collect(),
+        ),
+        DataType::Int16 => Some(
+            array
+                .as_primitive::<Int16Type>()
+                .values()
+                .iter()
+                .map(|&x| x as f32 / 32768.0)
+                .collect(),
+        ),
+        DataType::List(_) => list_samples(array.as_list::<i32>().iter().next()??.as_ref()),
+        DataType::LargeList(_) => list_samples(array.as_list::<i64>().iter().next()??.as_ref()),
+        dt => {
+            warn!("Unsupported audio data type: {:?}", dt);
+            None
+        }
+    }
+}
+
+fn list_samples(inner: &dyn Array) -> Option<Vec<f32>> {
+    match inner.data_type() {
+        DataType::Float32 | DataType::Float64 => {
+            debug!("Extracted {} samples from list array", inner.len());
+            samples(inner)
+        }
+        dt => {
+            warn!("List audio inner type not Float32/Float64: {:?}", dt);
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{ControlCommand, LogEntry, LogLevel};
+    use arrow::array::{Float64Array, Int16Array, LargeListArray, ListArray};
+    use proptest::prelude::*;
+
+    fn audio(samples: Vec<f32>, sample_rate: u32, channels: u16) -> AudioData {
+        AudioData {
+            samples,
+            sample_rate,
+            channels,
+            participant_id: None,
+            question_id: None,
+        }
+    }
+
+    #[test]
+    fn test_decode_audio_layouts() {
+        let expected = [0.5f32, -0.25];
+        let meta = EventMetadata::default();
+        let list = ListArray::from_iter_primitive::<Float32Type, _, _>(vec![Some(
+            expected.iter().map(|&x| Some(x)),
+        )]);
+        let large = LargeListArray::from_iter_primitive::<Float64Type, _, _>(vec![Some(
+            expected.iter().map(|&x| Some(x as f64)),
+        )]);
+        let arrays: Vec<ArrayRef> = vec![
+            Arc::new(Float32Array::from(expected.to_vec())),
+            Arc::new(Float64Array::from(vec![0.5, -0.25])),
+            Arc::new(Int16Array::from(vec![16384, -8192])),
+            Arc::new(list),
+            Arc::new(large),
+        ];
+        for array in arrays {
+            let decoded = decode_audio(&array, &meta).unwrap();
+            assert_eq!(decoded.samples, expected, "{:?}", array.data_type());
+            assert_eq!(decoded.sample_rate, DEFAULT_SAMPLE_RATE);
+            assert_eq!(decoded.channels, 1);
+        }
+
+        let empty: ArrayRef = Arc::new(Float32Array::from(Vec::<f32>::new()));
+        assert!(decode_audio(&empty, &meta).is_none());
+        assert!(decode_audio(&text_array("x"), &meta).is_none());
+    }
+
+    #[test]
+    fn test_json_payloads_decode_as_text() {
+        let cmd = ControlCommand::reset();
+        let (_, array) = encode(&DoraData::Control(cmd.clone())).unwrap();
+        assert!(matches!(
+            decode(&array, &MetadataParameters::new()),
+            Some(DoraData::Text(_))
+        ));
+        let back: ControlCommand = decode_json(&array).unwrap();
+        assert_eq!(back.command, cmd.command);
+
+        let (_, array) = encode(&DoraData::Log(LogEntry::new(
+            LogLevel::Warning,
+            "disk",
+            "tts",
+        )))
+        .unwrap();
+        let entry: LogEntry = decode_json(&array).unwrap();
+        assert_eq!(entry.level, LogLevel::Warning);
+        assert_eq!(entry.message, "disk");
+
+        let (_, array) = encode(&DoraData::Empty).unwrap();
+        assert!(matches!(
+            decode(&array, &MetadataParameters::new()),
+            Some(DoraData::Empty)
+        ));
+    }
+
+    proptest! {
+        #[test]
+        fn prop_audio_round_trips(
+            samples in prop::collection::vec(-1.0f32..1.0, 1..512),
+            sample_rate in 8000u32..192_000,
+            channels in 1u16..8,
+            question_id in prop::option::of("[0-9]{1,8}"),
+            participant_id in prop::option::of("[a-z0-9_]{1,12}"),
+        ) {
+            let mut chunk = audio(samples, sample_rate, channels);
+            chunk.question_id = question_id;
+            chunk.participant_id = participant_id;
+
+            let (parameters, array) = encode(&DoraData::Audio(chunk.clone())).unwrap();
+            let Some(DoraData::Audio(decoded)) = decode(&array, &parameters) else {
+                panic!("expected audio");
+            };
+            prop_assert_eq!(decoded.samples, chunk.samples);
+            prop_assert_eq!(decoded.sample_rate, chunk.sample_rate);
+            prop_assert_eq!(decoded.channels, chunk.channels);
+            prop_assert_eq!(decoded.question_id, chunk.question_id);
+            prop_assert_eq!(decoded.participant_id, chunk.participant_id);
+        }
+
+        #[test]
+        fn prop_int16_audio_stays_in_range(samples in prop::collection::vec(any::<i16>(), 1..512)) {
+            let array: ArrayRef = Arc::new(Int16Array::from(samples.clone()));
+            let decoded = decode_audio(&array, &EventMetadata::default()).unwrap();
+            prop_assert_eq!(decoded.samples.len(), samples.len());
+            prop_assert!(decoded.samples.iter().all(|s| (-1.0..1.0).contains(s)));
+        }
+
+        #[test]
+        fn prop_text_round_trips(text in ".*") {
+            let (parameters, array) = encode(&DoraData::Text(text.clone())).unwrap();
+            prop_assert!(parameters.is_empty());
+            let Some(DoraData::Text(decoded)) = decode(&array, &parameters) else {
+                panic!("expected text");
+            };
+            prop_assert_eq!(decoded, text);
+        }
+
+        #[test]
+        fn prop_binary_round_trips(bytes in prop::collection::vec(any::<u8>(), 1..256)) {
+            let (parameters, array) = encode(&DoraData::Binary(bytes.clone())).unwrap();
+            let Some(DoraData::Binary(decoded)) = decode(&array, &parameters) else {
+                panic!("expected binary");
+            };
+            prop_assert_eq!(decoded, bytes);
+        }
+
+        #[test]
+        fn prop_json_round_trips(key in "[a-z]{1,8}", value in any::<i64>()) {
+            let json = serde_json::json!({ key.clone(): value });
+            let (_, array) = encode(&DoraData::Json(json.clone())).unwrap();
+            prop_assert_eq!(decode_json::<serde_json::Value>(&array), Some(json));
+        }
+
+        #[test]
+        fn prop_metadata_flattens_to_strings(
+            sample_rate in any::<i64>(),
+            flag in any::<bool>(),
+            name in "[a-z]{0,8}",
+        ) {
+            let mut parameters = MetadataParameters::new();
+            parameters.insert(SAMPLE_RATE_KEY.to_string(), Parameter::Integer(sample_rate));
+            parameters.insert("flag".to_string(), Parameter::Bool(flag));
+            parameters.insert("name".to_string(), Parameter::String(name.clone()));
+
+            let metadata = event_metadata(&parameters);
+            let expected_rate = sample_rate.to_string();
+            let expected_flag = flag.to_string();
+            prop_assert_eq!(metadata.get(SAMPLE_RATE_KEY), Some(expected_rate.as_str()));
+            prop_assert_eq!(metadata.get("flag"), Some(expected_flag.as_str()));
+            prop_assert_eq!(metadata.get("name"), Some(name.as_str()));
+        }
+    }
+}
@@ -4,7 +4,13 @@
 //! - Start dataflow with env configuration
 //! - Stop dataflow and cleanup resources
 //! - Monitor dataflow status
+//!
+//! Dataflows are controlled through the coordinator's control socket (see
+//! [`crate::coordinator`]), which reports per-node exit codes and errors.
+//! The `dora` CLI is kept as a fallback for coordinators that can't be
+//! reached or speak an incompatible protocol.
 
+use crate::coordinator::{CoordinatorClient, DataflowCheck};
 use crate::error::{BridgeError, BridgeResult};
 use crate::parser::{DataflowParser, ParsedDataflow};
 use parking_lot::RwLock;
@@ -49,6 +55,78 @@ impl DataflowState {
     }
 }
 
+/// How the controller talks to dora
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ControllerBackend {
+    /// Coordinator control socket, falling back to the CLI
+    #[default]
+    Auto,
+    /// Coordinator control socket only
+    Coordinator,
+    /// `dora` CLI only
+    Cli,
+}
+
+impl ControllerBackend {
+    /// Env var selecting the backend (`auto`, `coordinator` or `cli`)
+    pub const ENV_VAR: &'static str = "MOFA_DORA_BACKEND";
+
+    /// Backend selected by `MOFA_DORA_BACKEND`, or `Auto`
+    pub fn from_env() -> Self {
+        match std::env::var(Self::ENV_VAR)
+            .unwrap_or_default()
+            .to_ascii_lowercase()
+            .as_str()
+        {
+            "coordinator" => ControllerBackend::Coordinator,
+            "cli" => ControllerBackend::Cli,
+            _ => ControllerBackend::Auto,
+        }
+    }
+}
+
+/// Node lifecycle state
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NodeState {
+    /// Node is running
+    Running,
+    /// Node exited successfully
+    Exited,
+    /// Node failed (non-zero exit, signal, or spawn failure)
+    Failed,
+    /// Node is being restarted after a failure
+    Restarting,
+    /// The backend doesn't report per-node state (the CLI only knows
+    /// whether the dataflow is listed)
+    Unknown,
+}
+
+impl NodeState {
+    /// Short label for display
+    pub fn label(&self) -> &'static str {
+        match self {
+            NodeState::Running => "running",
+            NodeState::Exited => "exited",
+            NodeState::Failed => "failed",
+            NodeState::Restarting => "restarting",
+            NodeState::Unknown => "unknown",
+        }
+    }
+}
+
+/// Status of a single dataflow node
+#[derive(Debug, Clone, PartialEq)]
+pub struct NodeStatus {
+    pub node_id: String,
+    pub state: NodeState,
+    /// Exit code, when the node exited with one
+    pub exit_code: Option<i32>,
+    /// Failure description (exit status and stderr excerpt)
+    pub error: Option<String>,
+    /// Last lines from the node's log output
+    pub stderr_tail: Vec<String>,
+}
+
 /// Controller for managing dataflow lifecycle
 pub struct DataflowController {
     /// Path to the dataflow YAML file
@@ -61,6 +139,12 @@ pub struct DataflowController {
     env_vars: HashMap<String, String>,
     /// Dora daemon process (if we started it)
     daemon_process: Option<Child>,
+    /// Preferred control backend
+    backend: ControllerBackend,
+    /// Coordinator client, set while a dataflow started through it is running
+    coordinator: Option<CoordinatorClient>,
+    /// Node results from the last stop
+    last_node_results: Vec<NodeStatus>,
 }
 
 impl DataflowController {
@@ -83,6 +167,9 @@ impl DataflowController {
             state: Arc::new(RwLock::new(DataflowState::Stopped)),
             env_vars: HashMap::new(),
             daemon_process: None,
+            backend: ControllerBackend::from_env(),
+            coordinator: None,
+            last_node_results: Vec::new(),
         })
     }
 
@@ -96,6 +183,36 @@ impl DataflowController {
         self.state.read().clone()
     }
 
+    /// Set the control backend (defaults to `MOFA_DORA_BACKEND` or `Auto`)
+    pub fn set_backend(&mut self, backend: ControllerBackend) {
+        self.backend = backend;
+    }
+
+    /// Backend controlling the running dataflow (`Coordinator` or `Cli`)
+    pub fn active_backend(&self) -> Option<ControllerBackend> {
+        if !self.state.read().is_running() {
+            None
+        } else if self.coordinator.is_some() {
+            Some(ControllerBackend::Coordinator)
+        } else {
+            Some(ControllerBackend::Cli)
+        }
+    }
+
+    /// Directory where the daemon writes the running dataflow's node logs
+    ///
+    /// Dora writes `log_<node id>.txt` files to `out/<dataflow id>` next to
+    /// the dataflow file.
+    pub fn log_dir(&self) -> Option<PathBuf> {
+        match &*self.state.read() {
+            DataflowState::Running { dataflow_id, .. } => self
+                .dataflow_path
+                .parent()
+                .map(|dir| dir.join("out").join(dataflow_id)),
+            _ => None,
+        }
+    }
+
     /// Set environment variable for the dataflow
     pub fn set_env(&mut self, key: impl Into<String>, value: impl Into<String>) {
         self.env_vars.insert(key.into(), value.into());
@@ -106,6 +223,16 @@ impl DataflowController {
         self.env_vars.extend(vars);
     }
 
+    /// Environment variables configured for the dataflow
+    pub fn env_vars(&self) -> &HashMap<String, String> {
+        &self.env_vars
+    }
+
+    /// Path to the dataflow YAML file
+    pub fn dataflow_path(&self) -> &Path {
+        &self.dataflow_path
+    }
+
     /// Check if all required env vars are set
     pub fn check_env_requirements(&self) -> Vec<String> {
         let mut missing = Vec::new();
@@ -156,6 +283,40 @@ impl DataflowController {
         }
     }
 
+    /// Connect to the coordinator, starting the daemon if needed
+    ///
+    /// Returns `None` when the CLI should be used instead.
+    fn connect_coordinator(&mut self) -> BridgeResult<Option<CoordinatorClient>> {
+        if self.backend == ControllerBackend::Cli {
+            self.ensure_daemon()?;
+            return Ok(None);
+        }
+
+        let client = CoordinatorClient::from_env()?;
+        if !client.is_reachable() {
+            self.ensure_daemon()?;
+            let deadline = Instant::now() + Duration::from_secs(5);
+            while !client.is_reachable() && Instant::now() < deadline {
+                std::thread::sleep(Duration::from_millis(100));
+            }
+        }
+
+        if client.is_reachable() {
+            Ok(Some(client))
+        } else if self.backend == ControllerBackend::Coordinator {
+            Err(BridgeError::ConnectionFailed(format!(
+                "Dora coordinator not reachable at {}",
+                client.addr()
+            )))
+        } else {
+            warn!(
+                "Dora coordinator not reachable at {}, using CLI",
+                client.addr()
+            );
+            Ok(None)
+        }
+    }
+
     /// Start the dataflow
     pub fn start(&mut self) -> BridgeResult<String> {
         // Check current state
@@ -168,9 +329,10 @@ impl DataflowController {
 
         // Update state
         *self.state.write() = DataflowState::Starting;
+        self.last_node_results.clear();
 
-        // Ensure daemon is running
-        self.ensure_daemon()?;
+        // Ensure daemon is running and pick the backend
+        let coordinator = self.connect_coordinator()?;
 
         // Check env requirements
         let missing = self.check_env_requirements();
@@ -182,57 +344,174 @@ impl DataflowController {
             return Err(BridgeError::StartFailed(msg));
         }
 
-        // Build command - run from dataflow's directory with just the filename
+        // Nodes are resolved relative to the dataflow's directory
         let dataflow_dir = self
             .dataflow_path
             .parent()
-            .ok_or_else(|| BridgeError::StartFailed("Invalid dataflow path".to_string()))?;
+            .ok_or_else(|| BridgeError::StartFailed("Invalid dataflow path".to_string()))?
+            .to_path_buf();
+
+        info!("Starting dataflow: {:?}", self.dataflow_path);
+        let result = match coordinator {
+            Some(client) => match self.start_via_coordinator(&client, &dataflow_dir) {
+                Ok(id) => {
+                    self.coordinator = Some(client);
+                    Ok(id)
+                }
+                // The coordinator rejected the dataflow; the CLI would fail the same way
+                Err(e @ BridgeError::StartFailed(_)) => Err(e),
+                Err(e) if self.backend == ControllerBackend::Auto => {
+                    warn!("Coordinator start failed ({}), falling back to CLI", e);
+                    self.start_via_cli(&dataflow_dir)
+                }
+                Err(e) => Err(e),
+            },
+            None => self.start_via_cli(&dataflow_dir),
+        };
+
+        let dataflow_id = match result {
+            Ok(id) => id,
+            Err(e) => {
+                error!("{}", e);
+                *self.state.write() = DataflowState::Error {
+                    message: e.to_string(),
+                };
+                return Err(e);
+            }
+        };
 
+        info!("Dataflow started with ID: {}", dataflow_id);
+
+        // Update state
+        *self.state.write() = DataflowState::Running {
+            started_at: Instant::now(),
+            dataflow_id: dataflow_id.clone(),
+        };
+
+        Ok(dataflow_id)
+    }
+
+    /// Start through the coordinator control socket
+    fn start_via_coordinator(
+        &self,
+        client: &CoordinatorClient,
+        dataflow_dir: &Path,
+    ) -> BridgeResult<String> {
+        let descriptor = self.descriptor()?;
+        let name = self.dataflow_path.file_stem().and_then(|s| s.to_str());
+        client.start(&descriptor, name, dataflow_dir)
+    }
+
+    /// Dataflow YAML as handed to dora, with every configured env var set in
+    /// each node's `env`
+    ///
+    /// Nodes are spawned by the daemon rather than by this process or the
+    /// `dora` CLI, so the env vars have to travel inside the descriptor.
+    /// Keeping them out of the process environment also keeps dataflows
+    /// started from the same app apart.
+    pub fn descriptor(&self) -> BridgeResult<serde_yaml::Value> {
+        let mut descriptor = self
+            .parsed
+            .as_ref()
+            .map(|p| p.raw_yaml.clone())
+            .ok_or_else(|| BridgeError::StartFailed("Dataflow not parsed".to_string()))?;
+        if self.env_vars.is_empty() {
+            return Ok(descriptor);
+        }
+
+        let mut vars: Vec<_> = self.env_vars.iter().collect();
+        vars.sort();
+        if let Some(nodes) = descriptor
+            .get_mut("nodes")
+            .and_then(|n| n.as_sequence_mut())
+        {
+            for node in nodes.iter_mut().filter_map(|n| n.as_mapping_mut()) {
+                let env = node.entry("env".into()).or_insert(serde_yaml::Value::Null);
+                if env.is_null() {
+                    *env = serde_yaml::Mapping::new().into();
+                }
+                let Some(env) = env.as_mapping_mut() else {
+                    return Err(BridgeError::StartFailed(
+                        "Node env is not a mapping".to_string(),
+                    ));
+                };
+                for (key, value) in &vars {
+                    env.insert(key.as_str().into(), value.as_str().into());
+                }
+            }
+        }
+
+        Ok(descriptor)
+    }
+
+    /// Write the descriptor for `dora start` next to the dataflow file, so
+    /// node paths and the `out` log directory resolve as for the original
+    ///
+    /// Without env vars the original file is used as is.
+    fn write_descriptor(&self) -> BridgeResult<Option<PathBuf>> {
+        if self.env_vars.is_empty() {
+            return Ok(None);
+        }
+        let yaml = serde_yaml::to_string(&self.descriptor()?)
+            .map_err(|e| BridgeError::StartFailed(format!("Failed to render dataflow: {}", e)))?;
+        let stem = self
+            .dataflow_path
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let path =
+            self.dataflow_path
+                .with_file_name(format!(".{}.{}.env.yml", stem, std::process::id()));
+        std::fs::write(&path, yaml).map_err(|e| {
+            BridgeError::StartFailed(format!("Failed to write {}: {}", path.display(), e))
+        })?;
+        Ok(Some(path))
+    }
+
+    /// `dora start --detach` for the dataflow file at `descriptor_path`
+    fn start_command(descriptor_path: &Path, dataflow_dir: &Path) -> Command {
         let mut cmd = Command::new("dora");
         cmd.arg("start")
             // Use the absolute path so dora always resolves node paths relative to
             // the actual dataflow file location.
-            .arg(&self.dataflow_path)
+            .arg(descriptor_path)
             .arg("--detach")
             .current_dir(dataflow_dir);
+        cmd
+    }
 
-        // Add environment variables
-        for (key, value) in &self.env_vars {
-            cmd.env(key, value);
+    /// Start by running `dora start --detach`
+    fn start_via_cli(&self, dataflow_dir: &Path) -> BridgeResult<String> {
+        let rendered = self.write_descriptor()?;
+        let descriptor_path = rendered.as_deref().unwrap_or(&self.dataflow_path);
+        let output = Self::start_command(descriptor_path, dataflow_dir).output();
+        // dora has read the descriptor once `dora start` returns
+        if let Some(path) = &rendered {
+            let _ = std::fs::remove_file(path);
         }
-
-        // Execute
-        info!("Starting dataflow: {:?}", self.dataflow_path);
-        let output = cmd.output().map_err(|e| {
+        let output = output.map_err(|e| {
             BridgeError::StartFailed(format!("Failed to execute dora start: {}", e))
         })?;
 
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+
         if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            let msg = format!("Dora start failed: {}", stderr);
-            error!("{}", msg);
-            *self.state.write() = DataflowState::Error {
-                message: msg.clone(),
-            };
-            return Err(BridgeError::StartFailed(msg));
+            return Err(BridgeError::StartFailed(format!(
+                "Dora start failed: {}",
+                stderr
+            )));
         }
 
         // Parse dataflow ID from output (check both stdout and stderr - dora outputs to stderr)
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        let dataflow_id = Self::parse_dataflow_id(&stderr)
+        Self::parse_dataflow_id(&stderr)
             .or_else(|| Self::parse_dataflow_id(&stdout))
-            .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
-
-        info!("Dataflow started with ID: {}", dataflow_id);
-
-        // Update state
-        *self.state.write() = DataflowState::Running {
-            started_at: Instant::now(),
-            dataflow_id: dataflow_id.clone(),
-        };
-
-        Ok(dataflow_id)
+            .ok_or_else(|| {
+                BridgeError::StartFailed(format!(
+                    "Dora start did not report a dataflow ID: {}{}",
+                    stdout, stderr
+                ))
+            })
     }
 
     /// Stop the dataflow gracefully (default 15s grace period)
@@ -277,9 +556,34 @@ impl DataflowController {
             .unwrap_or_else(|| "default".to_string());
         info!("Stopping dataflow: {} (grace: {})", dataflow_id, grace_str);
 
-        // Build dora stop command
+        match self.coordinator.take() {
+            Some(client) => match client.stop(&dataflow_id, grace_duration) {
+                Ok(nodes) => {
+                    for node in nodes.iter().filter(|n| n.state == NodeState::Failed) {
+                        warn!(
+                            "Node {} failed: {}",
+                            node.node_id,
+                            node.error.as_deref().unwrap_or("unknown error")
+                        );
+                    }
+                    self.last_node_results = nodes;
+                }
+                // Continue anyway - the dataflow might already be stopped
+                Err(e) => warn!("Coordinator stop warning: {}", e),
+            },
+            None => Self::stop_via_cli(&dataflow_id, grace_duration)?,
+        }
+
+        *self.state.write() = DataflowState::Stopped;
+        info!("Dataflow stopped");
+
+        Ok(())
+    }
+
+    /// Stop by running `dora stop`
+    fn stop_via_cli(dataflow_id: &str, grace_duration: Option<Duration>) -> BridgeResult<()> {
         let mut cmd = Command::new("dora");
-        cmd.arg("stop").arg(&dataflow_id);
+        cmd.arg("stop").arg(dataflow_id);
 
         // Add grace duration if specified
         if let Some(duration) = grace_duration {
@@ -298,58 +602,112 @@ impl DataflowController {
             // Continue anyway - the dataflow might already be stopped
         }
 
-        *self.state.write() = DataflowState::Stopped;
-        info!("Dataflow stopped");
-
         Ok(())
     }
 
+    /// Statuses for every spawned node, all in `state`
+    fn running_nodes(&self, state: NodeState) -> Vec<NodeStatus> {
+        self.parsed
+            .as_ref()
+            .map(|p| {
+                p.nodes
+                    .iter()
+                    .filter(|n| !n.is_dynamic)
+                    .map(|n| NodeStatus {
+                        node_id: n.id.clone(),
+                        state,
+                        exit_code: None,
+                        error: None,
+                        stderr_tail: Vec::new(),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Query the running dataflow's state and node statuses
+    fn query_running(
+        &self,
+        dataflow_id: &str,
+        started_at: Instant,
+    ) -> BridgeResult<(DataflowState, Vec<NodeStatus>)> {
+        let running = DataflowState::Running {
+            dataflow_id: dataflow_id.to_string(),
+            started_at,
+        };
+
+        if let Some(client) = &self.coordinator {
+            return match client.check(dataflow_id) {
+                Ok(DataflowCheck::Running) => {
+                    Ok((running, self.running_nodes(NodeState::Running)))
+                }
+                Ok(DataflowCheck::Finished { nodes }) => {
+                    let state = match nodes.iter().find(|n| n.state == NodeState::Failed) {
+                        Some(failed) => DataflowState::Error {
+                            message: format!(
+                                "Node {} failed: {}",
+                                failed.node_id,
+                                failed.error.as_deref().unwrap_or("unknown error")
+                            ),
+                        },
+                        None => DataflowState::Stopped,
+                    };
+                    Ok((state, nodes))
+                }
+                // The coordinator no longer knows the dataflow
+                Err(BridgeError::Unknown(message)) => {
+                    Ok((DataflowState::Error { message }, Vec::new()))
+                }
+                Err(e) => Err(e),
+            };
+        }
+
+        // Query dora for node status
+        let output = Command::new("dora")
+            .arg("list")
+            .output()
+            .map_err(|e| BridgeError::Unknown(format!("Failed to query status: {}", e)))?;
+
+        // `dora list` only shows the dataflow, not how its nodes are doing
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        if stdout.contains(dataflow_id) {
+            Ok((running, self.running_nodes(NodeState::Unknown)))
+        } else {
+            Ok((DataflowState::Stopped, Vec::new()))
+        }
+    }
+
     /// Get dataflow status
     pub fn get_status(&self) -> BridgeResult<DataflowStatus> {
         let state = self.state.read().clone();
 
+        let node_count = self.parsed.as_ref().map(|p| p.nodes.len()).unwrap_or(0);
+        let mofa_node_count = self
+            .parsed
+            .as_ref()
+            .map(|p| p.mofa_nodes.len())
+            .unwrap_or(0);
+
         match state {
             DataflowState::Running {
                 ref dataflow_id,
-                ref started_at,
+                started_at,
             } => {
-                // Query dora for node status
-                let output = Command::new("dora")
-                    .arg("list")
-                    .output()
-                    .map_err(|e| BridgeError::Unknown(format!("Failed to query status: {}", e)))?;
-
-                let stdout = String::from_utf8_lossy(&output.stdout);
-                let is_running = stdout.contains(dataflow_id);
-                let uptime = started_at.elapsed();
-
+                let (state, nodes) = self.query_running(dataflow_id, started_at)?;
                 Ok(DataflowStatus {
-                    state: if is_running {
-                        DataflowState::Running {
-                            dataflow_id: dataflow_id.clone(),
-                            started_at: *started_at,
-                        }
-                    } else {
-                        DataflowState::Stopped
-                    },
-                    uptime: Some(uptime),
-                    node_count: self.parsed.as_ref().map(|p| p.nodes.len()).unwrap_or(0),
-                    mofa_node_count: self
-                        .parsed
-                        .as_ref()
-                        .map(|p| p.mofa_nodes.len())
-                        .unwrap_or(0),
+                    state,
+                    uptime: Some(started_at.elapsed()),
+                    node_count,
+                    mofa_node_count,
+                    nodes,
                 })
             }
             other => Ok(DataflowStatus {
                 state: other,
                 uptime: None,
-                node_count: self.parsed.as_ref().map(|p| p.nodes.len()).unwrap_or(0),
-                mofa_node_count: self
-                    .parsed
-                    .as_ref()
-                    .map(|p| p.mofa_nodes.len())
-                    .unwrap_or(0),
+                node_count,
+                mofa_node_count,
+                nodes: self.last_node_results.clone(),
             }),
         }
     }
@@ -392,4 +750,86 @@ pub struct DataflowStatus {
     pub uptime: Option<Duration>,
     pub node_count: usize,
     pub mofa_node_count: usize,
+    /// Per-node status (empty when unknown)
+    pub nodes: Vec<NodeStatus>,
+}
+
+impl DataflowStatus {
+    /// Nodes that failed
+    pub fn failed_nodes(&self) -> impl Iterator<Item = &NodeStatus> {
+        self.nodes.iter().filter(|n| n.state == NodeState::Failed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_named_dataflows_keep_separate_env() {
+        let dir = std::env::temp_dir().join(format!("mofa-controller-env-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let controller = |name: &str, voice: &str| {
+            let path = dir.join(format!("{}.yml", name));
+            std::fs::write(
+                &path,
+                "nodes:\n  - id: tts\n    path: dora-tts\n    env:\n      SPEED: \"1.0\"\n      MOFA_TEST_VOICE: default\n  - id: asr\n    path: dora-asr\n",
+            )
+            .unwrap();
+            let mut controller = DataflowController::new(&path).unwrap();
+            controller.set_env("MOFA_TEST_VOICE", voice);
+            controller
+        };
+        let tts = controller("tts", "Doubao");
+        let standby = controller("standby", "Luo Xiang");
+
+        // Every node gets the dataflow's env, declared or not, next to its own
+        let node_env = |descriptor: &serde_yaml::Value, node: usize, key: &str| {
+            descriptor["nodes"][node]["env"][key]
+                .as_str()
+                .map(str::to_string)
+        };
+        for (controller, voice) in [(&tts, "Doubao"), (&standby, "Luo Xiang")] {
+            let descriptor = controller.descriptor().unwrap();
+            assert_eq!(
+                node_env(&descriptor, 0, "MOFA_TEST_VOICE").as_deref(),
+                Some(voice)
+            );
+            assert_eq!(node_env(&descriptor, 0, "SPEED").as_deref(), Some("1.0"));
+            assert_eq!(
+                node_env(&descriptor, 1, "MOFA_TEST_VOICE").as_deref(),
+                Some(voice)
+            );
+
+            // The CLI starts the same descriptor, written next to the original
+            let path = controller.write_descriptor().unwrap().unwrap();
+            assert_eq!(path.parent(), Some(dir.as_path()));
+            let written: serde_yaml::Value =
+                serde_yaml::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+            assert_eq!(written, descriptor);
+            std::fs::remove_file(path).unwrap();
+        }
+        // The app's own environment is untouched
+        assert!(std::env::var("MOFA_TEST_VOICE").is_err());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_descriptor_without_env_is_the_dataflow_file() {
+        let dir = std::env::temp_dir().join(format!("mofa-controller-raw-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("tts.yml");
+        std::fs::write(&path, "nodes:\n  - id: tts\n    path: dora-tts\n").unwrap();
+
+        let controller = DataflowController::new(&path).unwrap();
+        let descriptor = controller.descriptor().unwrap();
+        assert!(descriptor["nodes"][0].get("env").is_none());
+        assert!(controller.write_descriptor().unwrap().is_none());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
 }
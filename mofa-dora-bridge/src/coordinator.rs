@@ -0,0 +1,410 @@
+//! Dora coordinator control client
+//!
+//! Talks to the dora coordinator's control socket directly instead of
+//! shelling out to the `dora` CLI. This is the same channel the CLI uses:
+//! each message is a little-endian `u64` length followed by a JSON-encoded
+//! `ControlRequest`, answered by a JSON-encoded `ControlRequestReply`.
+//!
+//! Replies are decoded from [`serde_json::Value`] rather than mirrored
+//! structs, so small differences between dora releases (e.g. `DataflowStarted`
+//! vs `DataflowSpawned`) don't break the client. When the coordinator can't
+//! be reached or answers with something unexpected, the
+//! [`DataflowController`](crate::DataflowController) falls back to the CLI.
+
+use crate::controller::{NodeState, NodeStatus};
+use crate::error::{BridgeError, BridgeResult};
+use serde_json::{json, Value};
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpStream, ToSocketAddrs};
+use std::path::Path;
+use std::time::Duration;
+use tracing::debug;
+
+/// Default coordinator control port (matches dora's `DORA_COORDINATOR_PORT_CONTROL_DEFAULT`)
+pub const DEFAULT_CONTROL_PORT: u16 = 6012;
+
+/// Env var overriding the coordinator control address (`host:port`)
+pub const COORDINATOR_ADDR_ENV: &str = "DORA_COORDINATOR_ADDR";
+
+/// Upper bound for a single control message, guards against garbage lengths
+const MAX_MESSAGE_LEN: u64 = 64 * 1024 * 1024;
+
+/// Timeout for quick queries (list, check)
+const QUERY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Timeout for start/stop, which wait for nodes to spawn or exit
+const LIFECYCLE_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Dataflow entry from the coordinator's list
+#[derive(Debug, Clone, PartialEq)]
+pub struct DataflowEntry {
+    pub uuid: String,
+    pub name: Option<String>,
+    pub running: bool,
+}
+
+/// Liveness of a dataflow as reported by the coordinator
+#[derive(Debug, Clone, PartialEq)]
+pub enum DataflowCheck {
+    /// Dataflow is running
+    Running,
+    /// Dataflow finished; per-node results with exit codes and errors
+    Finished { nodes: Vec<NodeStatus> },
+}
+
+/// Client for the coordinator control socket
+#[derive(Debug, Clone)]
+pub struct CoordinatorClient {
+    addr: SocketAddr,
+}
+
+impl CoordinatorClient {
+    /// Client for the address in `DORA_COORDINATOR_ADDR`, or localhost:6012
+    pub fn from_env() -> BridgeResult<Self> {
+        let addr = std::env::var(COORDINATOR_ADDR_ENV)
+            .unwrap_or_else(|_| format!("127.0.0.1:{}", DEFAULT_CONTROL_PORT));
+        Self::new(&addr)
+    }
+
+    /// Client for a specific `host:port`
+    pub fn new(addr: &str) -> BridgeResult<Self> {
+        let addr = addr
+            .to_socket_addrs()
+            .map_err(|e| BridgeError::ConnectionFailed(format!("{}: {}", addr, e)))?
+            .next()
+            .ok_or_else(|| BridgeError::ConnectionFailed(format!("{}: no address", addr)))?;
+        Ok(Self { addr })
+    }
+
+    /// Coordinator address
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Whether the coordinator accepts connections
+    pub fn is_reachable(&self) -> bool {
+        TcpStream::connect_timeout(&self.addr, Duration::from_millis(500)).is_ok()
+    }
+
+    /// List dataflows known to the coordinator
+    pub fn list(&self) -> BridgeResult<Vec<DataflowEntry>> {
+        let reply = self.request(&json!("List"), QUERY_TIMEOUT)?;
+        parse_list_reply(&reply)
+    }
+
+    /// Start a dataflow from its parsed YAML descriptor
+    ///
+    /// Node paths in the descriptor are resolved relative to `working_dir`.
+    pub fn start(
+        &self,
+        descriptor: &serde_yaml::Value,
+        name: Option<&str>,
+        working_dir: &Path,
+    ) -> BridgeResult<String> {
+        let descriptor = serde_json::to_value(descriptor)?;
+        let request = json!({
+            "Start": {
+                "dataflow": descriptor,
+                "name": name,
+                "local_working_dir": working_dir,
+                "uv": false,
+            }
+        });
+        let reply = self.request(&request, LIFECYCLE_TIMEOUT)?;
+        parse_started_reply(&reply).map_err(|e| match e {
+            BridgeError::Unknown(msg) => BridgeError::StartFailed(msg),
+            other => other,
+        })
+    }
+
+    /// Stop a dataflow and return the per-node results
+    pub fn stop(
+        &self,
+        uuid: &str,
+        grace_duration: Option<Duration>,
+    ) -> BridgeResult<Vec<NodeStatus>> {
+        let request = json!({
+            "Stop": {
+                "dataflow_uuid": uuid,
+                "grace_duration": grace_duration.map(|d| json!({ "secs": d.as_secs(), "nanos": d.subsec_nanos() })),
+            }
+        });
+        let reply = self.request(&request, LIFECYCLE_TIMEOUT)?;
+        match parse_check_reply(&reply) {
+            Ok(DataflowCheck::Finished { nodes }) => Ok(nodes),
+            Ok(DataflowCheck::Running) => Ok(Vec::new()),
+            Err(BridgeError::Unknown(msg)) => Err(BridgeError::StopFailed(msg)),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Check whether a dataflow is still running
+    pub fn check(&self, uuid: &str) -> BridgeResult<DataflowCheck> {
+        let request = json!({ "Check": { "dataflow_uuid": uuid } });
+        let reply = self.request(&request, QUERY_TIMEOUT)?;
+        parse_check_reply(&reply)
+    }
+
+    /// Send one request on a fresh connection and wait for the reply
+    fn request(&self, request: &Value, timeout: Duration) -> BridgeResult<Value> {
+        let mut stream =
+            TcpStream::connect_timeout(&self.addr, Duration::from_secs(1)).map_err(|e| {
+                BridgeError::ConnectionFailed(format!("coordinator {}: {}", self.addr, e))
+            })?;
+        stream.set_read_timeout(Some(timeout))?;
+        stream.set_write_timeout(Some(QUERY_TIMEOUT))?;
+
+        debug!("Coordinator request: {}", request_kind(request));
+        write_message(&mut stream, &serde_json::to_vec(request)?)?;
+        let reply = read_message(&mut stream)?;
+        Ok(serde_json::from_slice(&reply)?)
+    }
+}
+
+/// Write a length-prefixed message
+fn write_message(writer: &mut impl Write, data: &[u8]) -> BridgeResult<()> {
+    writer.write_all(&(data.len() as u64).to_le_bytes())?;
+    writer.write_all(data)?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Read a length-prefixed message
+fn read_message(reader: &mut impl Read) -> BridgeResult<Vec<u8>> {
+    let mut len_raw = [0u8; 8];
+    reader.read_exact(&mut len_raw)?;
+    let len = u64::from_le_bytes(len_raw);
+    if len > MAX_MESSAGE_LEN {
+        return Err(BridgeError::InvalidData(format!(
+            "coordinator message too large: {} bytes",
+            len
+        )));
+    }
+    let mut data = vec![0u8; len as usize];
+    reader.read_exact(&mut data)?;
+    Ok(data)
+}
+
+/// Variant name of an externally tagged request, for logging
+fn request_kind(request: &Value) -> &str {
+    match request {
+        Value::String(s) => s,
+        Value::Object(map) => map.keys().next().map(|k| k.as_str()).unwrap_or("?"),
+        _ => "?",
+    }
+}
+
+/// Split an externally tagged enum value into (variant, payload)
+fn variant(value: &Value) -> Option<(&str, &Value)> {
+    match value {
+        Value::String(s) => Some((s.as_str(), &Value::Null)),
+        Value::Object(map) if map.len() == 1 => map.iter().next().map(|(k, v)| (k.as_str(), v)),
+        _ => None,
+    }
+}
+
+/// Error for a reply we can't use, including the coordinator's own `Error` variant
+fn unexpected(reply: &Value) -> BridgeError {
+    match variant(reply) {
+        Some(("Error", Value::String(msg))) => BridgeError::Unknown(msg.clone()),
+        _ => BridgeError::InvalidData(format!("unexpected coordinator reply: {}", reply)),
+    }
+}
+
+fn parse_list_reply(reply: &Value) -> BridgeResult<Vec<DataflowEntry>> {
+    let entries = match variant(reply) {
+        Some(("DataflowList", Value::Array(entries))) => entries,
+        // Older coordinators wrap the list in a struct
+        Some(("DataflowList", Value::Object(map))) => match map.values().next() {
+            Some(Value::Array(entries)) => entries,
+            _ => return Err(unexpected(reply)),
+        },
+        _ => return Err(unexpected(reply)),
+    };
+
+    Ok(entries
+        .iter()
+        .filter_map(|entry| {
+            let id = entry.get("id").unwrap_or(entry);
+            let uuid = id.get("uuid")?.as_str()?.to_string();
+            let name = id.get("name").and_then(|n| n.as_str()).map(String::from);
+            // Entries without a status field come from coordinators that only list running dataflows
+            let running = entry
+                .get("status")
+                .map(|s| variant(s).map(|(v, _)| v == "Running").unwrap_or(false))
+                .unwrap_or(true);
+            Some(DataflowEntry {
+                uuid,
+                name,
+                running,
+            })
+        })
+        .collect())
+}
+
+fn parse_started_reply(reply: &Value) -> BridgeResult<String> {
+    match variant(reply) {
+        Some(("DataflowStarted" | "DataflowStartTriggered" | "DataflowSpawned", payload)) => {
+            payload
+                .get("uuid")
+                .and_then(|u| u.as_str())
+                .map(String::from)
+                .ok_or_else(|| unexpected(reply))
+        }
+        _ => Err(unexpected(reply)),
+    }
+}
+
+fn parse_check_reply(reply: &Value) -> BridgeResult<DataflowCheck> {
+    match variant(reply) {
+        Some(("DataflowStarted" | "DataflowStartTriggered" | "DataflowSpawned", _)) => {
+            Ok(DataflowCheck::Running)
+        }
+        Some(("DataflowStopped", payload)) => Ok(DataflowCheck::Finished {
+            nodes: parse_node_results(payload.get("result").unwrap_or(&Value::Null)),
+        }),
+        _ => Err(unexpected(reply)),
+    }
+}
+
+/// Decode `DataflowResult.node_results` (node id → `Result<(), NodeError>`)
+fn parse_node_results(result: &Value) -> Vec<NodeStatus> {
+    let Some(results) = result.get("node_results").and_then(|r| r.as_object()) else {
+        return Vec::new();
+    };
+
+    results
+        .iter()
+        .map(|(node_id, outcome)| match variant(outcome) {
+            Some(("Err", error)) => {
+                let exit_code = error.get("exit_status").and_then(variant).and_then(
+                    |(kind, value)| match kind {
+                        "ExitCode" => value.as_i64().map(|c| c as i32),
+                        "Success" => Some(0),
+                        _ => None,
+                    },
+                );
+                NodeStatus {
+                    node_id: node_id.clone(),
+                    state: NodeState::Failed,
+                    exit_code,
+                    error: Some(describe_node_error(error)),
+                    stderr_tail: Vec::new(),
+                }
+            }
+            _ => NodeStatus {
+                node_id: node_id.clone(),
+                state: NodeState::Exited,
+                exit_code: Some(0),
+                error: None,
+                stderr_tail: Vec::new(),
+            },
+        })
+        .collect()
+}
+
+/// Human-readable cause of a node failure
+fn describe_node_error(error: &Value) -> String {
+    let cause = error.get("cause").and_then(variant);
+    let exit = error.get("exit_status").and_then(variant);
+
+    let cause_text = match cause {
+        Some(("GraceDuration", _)) => Some("killed after grace duration".to_string()),
+        Some(("Cascading", payload)) => payload
+            .get("caused_by_node")
+            .and_then(|n| n.as_str())
+            .map(|n| format!("stopped because node '{}' failed", n)),
+        Some(("Other", payload)) => payload
+            .get("stderr")
+            .and_then(|s| s.as_str())
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty()),
+        Some((kind, Value::String(msg))) => Some(format!("{}: {}", kind, msg)),
+        _ => None,
+    };
+
+    let exit_text = match exit {
+        Some(("ExitCode", code)) => Some(format!("exit code {}", code)),
+        Some(("Signal", signal)) => Some(format!("killed by signal {}", signal)),
+        Some(("IoError", msg)) => msg.as_str().map(|m| format!("I/O error: {}", m)),
+        _ => None,
+    };
+
+    match (exit_text, cause_text) {
+        (Some(exit), Some(cause)) => format!("{}: {}", exit, cause),
+        (Some(exit), None) => exit,
+        (None, Some(cause)) => cause,
+        (None, None) => "node failed".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_message_framing_roundtrip() {
+        let mut buf = Vec::new();
+        write_message(&mut buf, br#""List""#).unwrap();
+        assert_eq!(&buf[..8], &6u64.to_le_bytes());
+
+        let data = read_message(&mut Cursor::new(buf)).unwrap();
+        assert_eq!(data, br#""List""#);
+    }
+
+    #[test]
+    fn test_parse_replies() {
+        let started =
+            json!({ "DataflowStarted": { "uuid": "0190c3c4-0000-7000-8000-000000000001" } });
+        assert_eq!(
+            parse_started_reply(&started).unwrap(),
+            "0190c3c4-0000-7000-8000-000000000001"
+        );
+
+        let error = json!({ "Error": "failed to spawn node" });
+        assert!(
+            matches!(parse_started_reply(&error), Err(BridgeError::Unknown(msg)) if msg == "failed to spawn node")
+        );
+
+        let list = json!({ "DataflowList": [
+            { "id": { "uuid": "a", "name": "tts" }, "status": "Running" },
+            { "id": { "uuid": "b", "name": null }, "status": "Failed" },
+        ]});
+        let entries = parse_list_reply(&list).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert!(entries[0].running);
+        assert_eq!(entries[0].name.as_deref(), Some("tts"));
+        assert!(!entries[1].running);
+    }
+
+    #[test]
+    fn test_parse_node_results() {
+        let stopped = json!({ "DataflowStopped": { "uuid": "a", "result": {
+            "uuid": "a",
+            "node_results": {
+                "asr": { "Ok": null },
+                "primespeech-tts": { "Err": {
+                    "cause": { "Other": { "stderr": "ModuleNotFoundError: torch\n" } },
+                    "exit_status": { "ExitCode": 1 },
+                }},
+            }
+        }}});
+
+        let DataflowCheck::Finished { nodes } = parse_check_reply(&stopped).unwrap() else {
+            panic!("expected finished dataflow");
+        };
+        let tts = nodes
+            .iter()
+            .find(|n| n.node_id == "primespeech-tts")
+            .unwrap();
+        assert_eq!(tts.state, NodeState::Failed);
+        assert_eq!(tts.exit_code, Some(1));
+        assert_eq!(
+            tts.error.as_deref(),
+            Some("exit code 1: ModuleNotFoundError: torch")
+        );
+        let asr = nodes.iter().find(|n| n.node_id == "asr").unwrap();
+        assert_eq!(asr.state, NodeState::Exited);
+    }
+}
@@ -372,6 +372,50 @@ impl ControlCommand {
     }
 }
 
+/// Model lifecycle reported by a synthesis node on its `status` output
+///
+/// The node sends `"loading"`, `"ready"` or `"error"` as a string, with
+/// optional `progress` (0.0–1.0) and `message` metadata.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum ModelStatus {
+    /// Nothing reported yet
+    #[default]
+    Unknown,
+    /// Model is loading
+    Loading {
+        progress: Option<f32>,
+        message: String,
+    },
+    /// Model loaded and accepting requests
+    Ready,
+    /// Model failed to load
+    Failed { message: String },
+}
+
+impl ModelStatus {
+    /// Build from a status string and its event metadata
+    pub fn from_status(status: &str, metadata: &EventMetadata) -> Self {
+        let message = metadata.get("message").unwrap_or_default().to_string();
+        match status.to_lowercase().as_str() {
+            "ready" => ModelStatus::Ready,
+            "error" | "failed" => ModelStatus::Failed { message },
+            "loading" => ModelStatus::Loading {
+                progress: metadata
+                    .get("progress")
+                    .and_then(|p| p.parse::<f32>().ok())
+                    .map(|p| p.clamp(0.0, 1.0)),
+                message,
+            },
+            _ => ModelStatus::Unknown,
+        }
+    }
+
+    /// Whether the model can serve requests
+    pub fn is_ready(&self) -> bool {
+        matches!(self, ModelStatus::Ready)
+    }
+}
+
 /// Metadata from dora events
 #[derive(Debug, Clone, Default)]
 pub struct EventMetadata {
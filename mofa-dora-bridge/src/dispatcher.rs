@@ -8,11 +8,9 @@ use crate::bridge::{BridgeState, DoraBridge};
 use crate::controller::DataflowController;
 use crate::error::{BridgeError, BridgeResult};
 use crate::parser::MofaNodeSpec;
+use crate::registry::{self, BridgeRegistry};
 use crate::shared_state::SharedDoraState;
-use crate::widgets::{
-    AecInputBridge, AsrListenerBridge, AudioInputBridge, AudioPlayerBridge, PromptInputBridge,
-    SystemLogBridge,
-};
+use crate::validator::{DataflowValidator, ValidationReport};
 use crate::MofaNodeType;
 use parking_lot::RwLock;
 use std::collections::HashMap;
@@ -24,8 +22,8 @@ use tracing::{debug, error, info, warn};
 pub struct WidgetBinding {
     /// Widget identifier in the UI
     pub widget_id: String,
-    /// MoFA node type
-    pub node_type: MofaNodeType,
+    /// Built-in MoFA node type, `None` for app-defined nodes
+    pub node_type: Option<MofaNodeType>,
     /// Node ID in the dataflow
     pub node_id: String,
     /// Connection state
@@ -45,6 +43,8 @@ pub struct DynamicNodeDispatcher {
     bridges: HashMap<String, Box<dyn DoraBridge>>,
     /// Widget bindings
     bindings: Vec<WidgetBinding>,
+    /// Bridge factories used by `create_bridges`
+    registry: BridgeRegistry,
 }
 
 impl DynamicNodeDispatcher {
@@ -60,15 +60,44 @@ impl DynamicNodeDispatcher {
     pub fn with_shared_state(
         controller: DataflowController,
         shared_state: Arc<SharedDoraState>,
+    ) -> Self {
+        let registry = registry::global().read().clone();
+        Self::with_registry(controller, shared_state, registry)
+    }
+
+    /// Create a new dispatcher that builds bridges from a specific registry
+    /// instead of the process-wide one
+    pub fn with_registry(
+        controller: DataflowController,
+        shared_state: Arc<SharedDoraState>,
+        registry: BridgeRegistry,
     ) -> Self {
         Self {
             controller: Arc::new(RwLock::new(controller)),
             shared_state,
             bridges: HashMap::new(),
             bindings: Vec::new(),
+            registry,
         }
     }
 
+    /// Get the bridge registry
+    pub fn registry(&self) -> &BridgeRegistry {
+        &self.registry
+    }
+
+    /// Validate the dataflow against this dispatcher's bridges and env
+    ///
+    /// Call before [`start`](Self::start) to surface wiring mistakes while
+    /// they can still be shown next to the dataflow file.
+    pub fn validate(&self) -> BridgeResult<ValidationReport> {
+        let controller = self.controller.read();
+        DataflowValidator::new()
+            .with_registry(self.registry.clone())
+            .with_env(controller.env_vars().clone())
+            .validate_file(controller.dataflow_path())
+    }
+
     /// Get the dataflow controller
     pub fn controller(&self) -> &Arc<RwLock<DataflowController>> {
         &self.controller
@@ -89,48 +118,25 @@ impl DynamicNodeDispatcher {
     }
 
     /// Create bridges for all discovered MoFA nodes
+    ///
+    /// Each node id is looked up in the bridge registry. Nodes without a
+    /// registered factory are logged and skipped.
     pub fn create_bridges(&mut self) -> BridgeResult<()> {
         let mofa_nodes = self.discover_mofa_nodes();
-        let shared_state = Some(self.shared_state.clone());
 
         for node_spec in mofa_nodes {
-            let bridge: Box<dyn DoraBridge> = match node_spec.node_type {
-                MofaNodeType::AudioPlayer => Box::new(AudioPlayerBridge::with_shared_state(
-                    &node_spec.id,
-                    shared_state.clone(),
-                )),
-                MofaNodeType::SystemLog => Box::new(SystemLogBridge::with_shared_state(
-                    &node_spec.id,
-                    shared_state.clone(),
-                )),
-                MofaNodeType::PromptInput => Box::new(PromptInputBridge::with_shared_state(
-                    &node_spec.id,
-                    shared_state.clone(),
-                )),
-                MofaNodeType::MicInput => Box::new(AecInputBridge::with_shared_state(
-                    &node_spec.id,
-                    shared_state.clone(),
-                )),
-                MofaNodeType::ChatViewer => {
-                    // TODO: Implement ChatViewerBridge
-                    continue;
-                }
-                MofaNodeType::ParticipantPanel => {
-                    // ParticipantPanel functionality consolidated into AudioPlayerBridge
-                    // No separate bridge needed - AudioPlayerBridge now handles LED visualization
-                    info!("Skipping ParticipantPanel bridge - consolidated into AudioPlayerBridge");
+            let bridge = match self.registry.create(&node_spec, self.shared_state.clone()) {
+                Some(result) => result?,
+                None => {
+                    match node_spec.node_type {
+                        // ParticipantPanel functionality consolidated into AudioPlayerBridge
+                        Some(MofaNodeType::ParticipantPanel) => info!(
+                            "Skipping ParticipantPanel bridge - consolidated into AudioPlayerBridge"
+                        ),
+                        _ => warn!("No bridge registered for MoFA node '{}', skipping", node_spec.id),
+                    }
                     continue;
                 }
-                MofaNodeType::AsrListener => Box::new(AsrListenerBridge::with_shared_state(
-                    &node_spec.id,
-                    shared_state.clone(),
-                )),
-                MofaNodeType::AudioInput => Box::new(AudioInputBridge::with_shared_state(
-                    &node_spec.id,
-                    shared_state
-                        .clone()
-                        .expect("AudioInputBridge requires shared state"),
-                )),
             };
 
             self.bindings.push(WidgetBinding {
@@ -225,6 +231,27 @@ impl DynamicNodeDispatcher {
         &self.bindings
     }
 
+    /// Sync binding states with the live bridge states
+    ///
+    /// Bridges can drop out on their own (dora stop event, node init error),
+    /// so bindings are only trustworthy right after calling this.
+    pub fn refresh_binding_states(&mut self) {
+        for binding in &mut self.bindings {
+            if let Some(bridge) = self.bridges.get(&binding.node_id) {
+                binding.state = bridge.state();
+            }
+        }
+    }
+
+    /// Whether every bound bridge is connected
+    pub fn all_bridges_connected(&self) -> bool {
+        !self.bindings.is_empty()
+            && self
+                .bindings
+                .iter()
+                .all(|b| b.state == BridgeState::Connected)
+    }
+
     /// Get binding for a specific node
     pub fn get_binding(&self, node_id: &str) -> Option<&WidgetBinding> {
         self.bindings.iter().find(|b| b.node_id == node_id)
@@ -351,6 +378,7 @@ impl Drop for DynamicNodeDispatcher {
 /// Builder for creating dispatchers with custom configuration
 pub struct DispatcherBuilder {
     controller: Option<DataflowController>,
+    registry: Option<BridgeRegistry>,
     auto_connect: bool,
 }
 
@@ -358,6 +386,7 @@ impl DispatcherBuilder {
     pub fn new() -> Self {
         Self {
             controller: None,
+            registry: None,
             auto_connect: false,
         }
     }
@@ -367,6 +396,12 @@ impl DispatcherBuilder {
         self
     }
 
+    /// Use this registry instead of the process-wide one
+    pub fn with_registry(mut self, registry: BridgeRegistry) -> Self {
+        self.registry = Some(registry);
+        self
+    }
+
     pub fn auto_connect(mut self, auto: bool) -> Self {
         self.auto_connect = auto;
         self
@@ -377,7 +412,12 @@ impl DispatcherBuilder {
             .controller
             .ok_or_else(|| BridgeError::Unknown("No controller provided".to_string()))?;
 
-        let mut dispatcher = DynamicNodeDispatcher::new(controller);
+        let mut dispatcher = match self.registry {
+            Some(registry) => {
+                DynamicNodeDispatcher::with_registry(controller, SharedDoraState::new(), registry)
+            }
+            None => DynamicNodeDispatcher::new(controller),
+        };
 
         if self.auto_connect {
             dispatcher.start()?;
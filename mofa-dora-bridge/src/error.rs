@@ -65,6 +65,9 @@ pub enum BridgeError {
     #[error("Channel receive error")]
     ChannelReceiveError,
 
+    #[error("Outbox full for {0}")]
+    OutboxFull(String),
+
     #[error("Timeout: {0}")]
     Timeout(String),
 
@@ -20,7 +20,7 @@
 //! │                     SharedDoraState (Arc<...>)                              │
 //! │                                                                             │
 //! │  chat: ChatState        audio: AudioState       logs: DirtyVec<LogEntry>   │
-//! │  status: DirtyValue<DoraStatus>                                            │
+//! │  status: DirtyValue<DoraStatus>   nodes: DirtyValue<Vec<NodeStatus>>       │
 //! └─────────────────────────────────────────────────────────────────────────────┘
 //!           │          Read on UI timer (single poll)         │
 //!           ▼                      ▼                          ▼
@@ -47,12 +47,17 @@
 //! - [`ChatMessage`] - Chat message with sender, role, streaming status
 //! - [`LogEntry`] - Log entry with level, node_id, timestamp
 //! - [`ControlCommand`] - Dataflow control commands (start, stop, reset)
+//! - [`codec`] - Conversions between [`DoraData`] and Arrow arrays plus metadata, used by every bridge
 //!
 //! ### Bridge Infrastructure
 //!
 //! - [`DoraBridge`] trait - Interface for widget bridges
 //! - [`BridgeState`] - Connection state (Disconnected, Connecting, Connected, Error)
-//! - [`MofaNodeType`] - Enum of known widget node types
+//! - [`MofaNodeType`] - Enum of built-in widget node types
+//! - [`BridgeRegistry`] - Bridge factories keyed by node id or prefix, for app-defined `mofa-*` nodes
+//! - [`SendRequest`] / [`SendHandle`] - Queued sends with delivery and reply tracking ([`outbox`] module)
+//! - [`Connector`] / [`Transport`] - How bridges reach the dataflow: dora, or an in-memory [`Loopback`] for tests ([`transport`], [`loopback`] modules)
+//! - [`Recorder`] / [`Recording`] - Capture bridge traffic to a file and replay it through the bridge state machines ([`recorder`] module)
 //!
 //! ## Usage Example
 //!
@@ -105,30 +110,51 @@
 //! 5. **Bounded Collections** - All collections have max sizes to prevent memory growth
 
 pub mod bridge;
+pub mod builder;
+pub mod codec;
 pub mod controller;
+pub mod coordinator;
 pub mod data;
 pub mod dispatcher;
 pub mod error;
+pub mod loopback;
+pub mod node_logs;
+pub mod outbox;
 pub mod parser;
+pub mod recorder;
+pub mod registry;
 pub mod shared_state;
+pub mod transport;
+pub mod validator;
 
 // Widget-specific bridges
 pub mod widgets;
 
 // Re-exports
 pub use bridge::{BridgeState, DoraBridge};
-pub use controller::{DataflowController, DataflowState};
-pub use data::{AudioData, ChatMessage, ControlCommand, DoraData, LogEntry};
+pub use builder::{AsrEngine, DataflowBuilder, DataflowProfile, Device, EnvOverrides, Language};
+pub use controller::{ControllerBackend, DataflowController, DataflowState, DataflowStatus, NodeState, NodeStatus};
+pub use data::{AudioData, ChatMessage, ControlCommand, DoraData, LogEntry, ModelStatus};
 pub use dispatcher::{DynamicNodeDispatcher, WidgetBinding};
 pub use error::{BridgeError, BridgeResult};
+pub use node_logs::NodeLogCollector;
+pub use outbox::{Outbox, ReplyTracker, RequestId, SendHandle, SendOutcome, SendRequest};
 pub use shared_state::{SharedDoraState, DoraStatus, ChatState, AudioState, DirtyVec, DirtyValue, MicState};
 pub use widgets::AecControlCommand;
+pub use loopback::{FakeTts, Loopback};
+pub use transport::{Connector, DoraConnector, Transport, TransportEvent};
+pub use recorder::{Direction, NodeOutput, Recorder, Record, Recording, ReplayHandler};
 pub use parser::{DataflowParser, EnvRequirement, LogSource, ParsedDataflow, ParsedNode};
+pub use registry::{BridgeFactory, BridgeRegistry};
+pub use validator::{DataflowValidator, Diagnostic, DiagnosticKind, Severity, ValidationReport};
 
-/// Prefix for MoFA built-in dynamic nodes in dataflow YAML
+/// Prefix for MoFA dynamic nodes (built-in and app-defined) in dataflow YAML
 pub const MOFA_NODE_PREFIX: &str = "mofa-";
 
-/// Known MoFA widget node types
+/// Built-in MoFA widget node types
+///
+/// App-defined `mofa-*` nodes have no variant here; their bridges come from
+/// the [`BridgeRegistry`].
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum MofaNodeType {
     /// Audio player widget - receives audio, plays through speaker
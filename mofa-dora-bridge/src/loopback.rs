@@ -0,0 +1,630 @@
+//! In-memory dataflow for tests
+//!
+//! [`Loopback`] is a [`Connector`] that wires nodes together without a dora
+//! daemon: an output sent by one node is delivered to every input routed
+//! from it, exactly like the `inputs:` section of a dataflow YAML. Tests
+//! connect real bridges to it, inject inputs, wait for outputs, and can run
+//! [`FakeTts`] in place of the Python primespeech node:
+//!
+//! ```rust,ignore
+//! let loopback = Loopback::from_dataflow(&DataflowParser::parse("tts.yml")?);
+//! let _tts = FakeTts::new().spawn(&loopback, "primespeech-tts");
+//!
+//! let mut prompt = PromptInputBridge::with_shared_state("mofa-prompt-input", Some(state.clone()))
+//!     .with_connector(Arc::new(loopback.clone()));
+//! prompt.connect()?;
+//! let handle = prompt.submit(SendRequest::new("control", DoraData::Text("hi".into()))
+//!     .expect_reply("segment_complete"))?;
+//! assert!(handle.wait(Duration::from_secs(1)).unwrap().is_success());
+//! ```
+
+use crate::codec;
+use crate::data::DoraData;
+use crate::error::{BridgeError, BridgeResult};
+use crate::parser::ParsedDataflow;
+use crate::recorder::NodeOutput;
+use crate::transport::{Connector, Transport, TransportEvent};
+use arrow::array::{Array, ArrayRef, StringArray};
+use crossbeam_channel::{unbounded, Receiver, Sender};
+use dora_node_api::{MetadataParameters, Parameter};
+use parking_lot::{Condvar, Mutex};
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+use tracing::debug;
+
+/// An output sent by a loopback node
+#[derive(Debug, Clone)]
+pub struct SentOutput {
+    pub node: String,
+    pub output: NodeOutput,
+}
+
+#[derive(Default)]
+struct Hub {
+    /// (node, output) -> [(node, input)]
+    routes: HashMap<(String, String), Vec<(String, String)>>,
+    inboxes: HashMap<String, (Sender<TransportEvent>, Receiver<TransportEvent>)>,
+    /// Outputs not yet taken by [`Loopback::wait_output`]
+    sent: VecDeque<SentOutput>,
+}
+
+impl Hub {
+    fn inbox(&mut self, node: &str) -> &(Sender<TransportEvent>, Receiver<TransportEvent>) {
+        self.inboxes
+            .entry(node.to_string())
+            .or_insert_with(unbounded)
+    }
+
+    fn deliver(&mut self, node: &str, event: TransportEvent) {
+        // Receivers are kept in the hub, so sending can't fail
+        let _ = self.inbox(node).0.send(event);
+    }
+}
+
+/// In-memory dataflow (cheap to clone; clones share the same nodes)
+#[derive(Clone, Default)]
+pub struct Loopback {
+    hub: Arc<(Mutex<Hub>, Condvar)>,
+}
+
+impl Loopback {
+    /// Empty dataflow; add connections with [`Self::route`]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Dataflow wired like a parsed YAML (timer inputs are left out)
+    pub fn from_dataflow(dataflow: &ParsedDataflow) -> Self {
+        let loopback = Self::new();
+        for node in &dataflow.nodes {
+            for input in &node.inputs {
+                if input.source.starts_with("dora/") {
+                    continue;
+                }
+                loopback.route(&input.source, &format!("{}/{}", node.id, input.id));
+            }
+        }
+        loopback
+    }
+
+    /// Deliver `source` ("node/output") to `target` ("node/input")
+    pub fn route(&self, source: &str, target: &str) -> &Self {
+        let (Some(from), Some(to)) = (split_port(source), split_port(target)) else {
+            debug!("Ignoring malformed loopback route {} -> {}", source, target);
+            return self;
+        };
+        self.hub.0.lock().routes.entry(from).or_default().push(to);
+        self
+    }
+
+    /// Transport for `node_id` (also used by fake nodes)
+    pub fn endpoint(&self, node_id: &str) -> LoopbackTransport {
+        let events = self.hub.0.lock().inbox(node_id).1.clone();
+        LoopbackTransport {
+            node_id: node_id.to_string(),
+            loopback: self.clone(),
+            events,
+        }
+    }
+
+    /// Deliver an input to `node` as if an upstream node had sent it
+    pub fn inject(
+        &self,
+        node: &str,
+        input: &str,
+        metadata: MetadataParameters,
+        data: impl Array + 'static,
+    ) {
+        self.hub.0.lock().deliver(
+            node,
+            TransportEvent::Input {
+                id: input.to_string(),
+                metadata,
+                data: Arc::new(data),
+            },
+        );
+    }
+
+    /// Ask `node` to stop
+    pub fn stop(&self, node: &str) {
+        self.hub.0.lock().deliver(node, TransportEvent::Stop);
+    }
+
+    /// Wait for the next output `node` sends on `output`, and take it
+    pub fn wait_output(&self, node: &str, output: &str, timeout: Duration) -> Option<NodeOutput> {
+        let deadline = Instant::now() + timeout;
+        let (hub, sent) = &*self.hub;
+        let mut hub = hub.lock();
+        loop {
+            let found = hub
+                .sent
+                .iter()
+                .position(|s| s.node == node && s.output.id == output);
+            if let Some(index) = found {
+                return hub.sent.remove(index).map(|s| s.output);
+            }
+            if sent.wait_until(&mut hub, deadline).timed_out() {
+                return None;
+            }
+        }
+    }
+
+    /// Outputs sent so far that haven't been taken
+    pub fn outputs(&self) -> Vec<SentOutput> {
+        self.hub.0.lock().sent.iter().cloned().collect()
+    }
+
+    fn publish(&self, node: &str, output: NodeOutput) {
+        let (hub, sent) = &*self.hub;
+        let mut hub = hub.lock();
+        let targets = hub
+            .routes
+            .get(&(node.to_string(), output.id.clone()))
+            .cloned()
+            .unwrap_or_default();
+        for (target, input) in targets {
+            hub.deliver(
+                &target,
+                TransportEvent::Input {
+                    id: input,
+                    metadata: output.parameters.clone(),
+                    data: output.data.clone(),
+                },
+            );
+        }
+        hub.sent.push_back(SentOutput {
+            node: node.to_string(),
+            output,
+        });
+        sent.notify_all();
+    }
+}
+
+impl Connector for Loopback {
+    fn connect(&self, node_id: &str) -> BridgeResult<Box<dyn Transport>> {
+        Ok(Box::new(self.endpoint(node_id)))
+    }
+}
+
+/// One node of a [`Loopback`]
+pub struct LoopbackTransport {
+    node_id: String,
+    loopback: Loopback,
+    events: Receiver<TransportEvent>,
+}
+
+impl Transport for LoopbackTransport {
+    fn node_id(&self) -> &str {
+        &self.node_id
+    }
+
+    fn send_output(&mut self, output: NodeOutput) -> BridgeResult<()> {
+        self.loopback.publish(&self.node_id, output);
+        Ok(())
+    }
+
+    fn recv_timeout(&mut self, timeout: Duration) -> Option<TransportEvent> {
+        self.events.recv_timeout(timeout).ok()
+    }
+}
+
+/// Split "node/port"
+fn split_port(port: &str) -> Option<(String, String)> {
+    let (node, id) = port.split_once('/')?;
+    Some((node.to_string(), id.to_string()))
+}
+
+/// Rust stand-in for the primespeech TTS node
+///
+/// Reports `status` "ready" on start, then answers each `text` input (the
+/// prompt-input JSON or plain text) like the Python node: `audio` chunks
+/// of silence carrying `sample_rate` and the request's `question_id`, then
+/// `segment_complete` with "completed", "empty", or "error" plus an `error`
+/// metadata entry for prompts containing [`FakeTts::fail_on`] text.
+#[derive(Debug, Clone)]
+pub struct FakeTts {
+    sample_rate: u32,
+    chunks: usize,
+    chunk_samples: usize,
+    fail_on: Option<String>,
+}
+
+impl Default for FakeTts {
+    fn default() -> Self {
+        Self {
+            sample_rate: 32000,
+            chunks: 2,
+            chunk_samples: 320,
+            fail_on: None,
+        }
+    }
+}
+
+impl FakeTts {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_sample_rate(mut self, sample_rate: u32) -> Self {
+        self.sample_rate = sample_rate;
+        self
+    }
+
+    /// Audio chunks per prompt and samples per chunk
+    pub fn with_chunks(mut self, chunks: usize, chunk_samples: usize) -> Self {
+        self.chunks = chunks;
+        self.chunk_samples = chunk_samples;
+        self
+    }
+
+    /// Fail prompts containing `text`
+    pub fn fail_on(mut self, text: impl Into<String>) -> Self {
+        self.fail_on = Some(text.into());
+        self
+    }
+
+    /// Run as `node_id` on a worker thread until the handle is dropped
+    pub fn spawn(self, loopback: &Loopback, node_id: &str) -> FakeNodeHandle {
+        let mut transport = loopback.endpoint(node_id);
+        let running = Arc::new(AtomicBool::new(true));
+        let flag = Arc::clone(&running);
+        let handle = thread::spawn(move || {
+            let _ = transport.send_output(self.status("ready"));
+            while flag.load(Ordering::Acquire) {
+                match transport.recv_timeout(Duration::from_millis(20)) {
+                    Some(TransportEvent::Input { id, metadata, data }) if id == "text" => {
+                        for output in self.synthesize(&metadata, &data) {
+                            let _ = transport.send_output(output);
+                        }
+                    }
+                    Some(TransportEvent::Stop) => break,
+                    _ => {}
+                }
+            }
+        });
+        FakeNodeHandle {
+            running,
+            handle: Some(handle),
+        }
+    }
+
+    fn status(&self, status: &str) -> NodeOutput {
+        NodeOutput::new(
+            "status",
+            MetadataParameters::new(),
+            StringArray::from(vec![status]),
+        )
+    }
+
+    /// Outputs for one `text` input
+    fn synthesize(&self, metadata: &MetadataParameters, data: &ArrayRef) -> Vec<NodeOutput> {
+        let raw = codec::decode_string(data).unwrap_or_default();
+        // Prompt input sends {"prompt": ...}; plain text is accepted too
+        let text = serde_json::from_str::<serde_json::Value>(&raw)
+            .ok()
+            .and_then(|v| v.get("prompt").and_then(|p| p.as_str()).map(str::to_string))
+            .unwrap_or(raw);
+
+        let mut passthrough = MetadataParameters::new();
+        if let Some(qid) = metadata.get("question_id") {
+            passthrough.insert("question_id".to_string(), qid.clone());
+        }
+        let complete = |status: &str, params: MetadataParameters| {
+            NodeOutput::new("segment_complete", params, StringArray::from(vec![status]))
+        };
+
+        if text.trim().is_empty() {
+            return vec![complete("empty", passthrough)];
+        }
+        if let Some(ref needle) = self.fail_on {
+            if text.contains(needle.as_str()) {
+                let mut params = passthrough;
+                params.insert(
+                    "error".to_string(),
+                    Parameter::String(format!("synthesis failed for '{}'", text)),
+                );
+                return vec![complete("error", params)];
+            }
+        }
+
+        let chunk = DoraData::audio(vec![0.0; self.chunk_samples], self.sample_rate, 1);
+        let mut outputs: Vec<NodeOutput> = (0..self.chunks)
+            .filter_map(|_| codec::encode_output("audio", &chunk).ok())
+            .map(|mut output| {
+                output.parameters.extend(passthrough.clone());
+                output
+            })
+            .collect();
+        outputs.push(complete("completed", passthrough));
+        outputs
+    }
+}
+
+/// Keeps a fake node running; stops and joins it on drop
+pub struct FakeNodeHandle {
+    running: Arc<AtomicBool>,
+    handle: Option<thread::JoinHandle<()>>,
+}
+
+impl FakeNodeHandle {
+    /// Stop the node and wait for its thread
+    pub fn stop(mut self) -> BridgeResult<()> {
+        self.shutdown()
+    }
+
+    fn shutdown(&mut self) -> BridgeResult<()> {
+        self.running.store(false, Ordering::Release);
+        match self.handle.take() {
+            Some(handle) => handle.join().map_err(|_| BridgeError::ThreadJoinFailed),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Drop for FakeNodeHandle {
+    fn drop(&mut self) {
+        let _ = self.shutdown();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bridge::DoraBridge;
+    use crate::data::LogLevel;
+    use crate::outbox::{RequestId, SendHandle, SendOutcome, SendRequest, CORRELATION_KEY};
+    use crate::parser::DataflowParser;
+    use crate::shared_state::SharedDoraState;
+    use crate::widgets::{
+        AsrListenerBridge, AudioPlayerBridge, PromptInputBridge, SystemLogBridge,
+    };
+    use arrow::array::AsArray;
+    use std::path::PathBuf;
+
+    const TTS_YAML: &str = r#"
+nodes:
+  - id: mofa-prompt-input
+    path: dynamic
+    inputs:
+      segment_complete: primespeech-tts/segment_complete
+    outputs:
+      - control
+  - id: primespeech-tts
+    path: dora-primespeech
+    inputs:
+      text: mofa-prompt-input/control
+      tick: dora/timer/secs/1
+    outputs:
+      - audio
+      - status
+      - segment_complete
+  - id: mofa-audio-player
+    path: dynamic
+    inputs:
+      audio: primespeech-tts/audio
+      tts_status: primespeech-tts/status
+    outputs:
+      - audio_complete
+"#;
+
+    fn tts_loopback() -> Loopback {
+        let dataflow = DataflowParser::parse_string(TTS_YAML, PathBuf::from("tts.yml")).unwrap();
+        Loopback::from_dataflow(&dataflow)
+    }
+
+    /// Poll `check` until it returns something or `timeout` passes
+    fn wait_for<T>(timeout: Duration, mut check: impl FnMut() -> Option<T>) -> Option<T> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            if let Some(value) = check() {
+                return Some(value);
+            }
+            if Instant::now() >= deadline {
+                return None;
+            }
+            thread::sleep(Duration::from_millis(10));
+        }
+    }
+
+    #[test]
+    fn test_routes_outputs_to_inputs() {
+        let loopback = Loopback::new();
+        loopback.route("a/out", "b/in");
+        let mut a = loopback.endpoint("a");
+        let mut b = loopback.endpoint("b");
+
+        a.send_output(NodeOutput::new(
+            "out",
+            MetadataParameters::new(),
+            StringArray::from(vec!["hi"]),
+        ))
+        .unwrap();
+
+        match b.recv_timeout(Duration::from_secs(1)) {
+            Some(TransportEvent::Input { id, data, .. }) => {
+                assert_eq!(id, "in");
+                assert_eq!(data.as_string::<i32>().value(0), "hi");
+            }
+            other => panic!("expected input, got {:?}", other),
+        }
+        assert!(loopback
+            .wait_output("a", "out", Duration::from_secs(1))
+            .is_some());
+        assert!(loopback.outputs().is_empty());
+    }
+
+    #[test]
+    fn test_tts_request_round_trip() {
+        let loopback = tts_loopback();
+        let _tts = FakeTts::new()
+            .with_chunks(3, 160)
+            .fail_on("boom")
+            .spawn(&loopback, "primespeech-tts");
+        let state = SharedDoraState::new();
+
+        let mut player =
+            AudioPlayerBridge::with_shared_state("mofa-audio-player", Some(state.clone()))
+                .with_connector(Arc::new(loopback.clone()));
+        let mut prompt =
+            PromptInputBridge::with_shared_state("mofa-prompt-input", Some(state.clone()))
+                .with_connector(Arc::new(loopback.clone()));
+        player.connect().unwrap();
+        prompt.connect().unwrap();
+
+        let handle = prompt
+            .submit(
+                SendRequest::new("control", DoraData::Text("你好".to_string()))
+                    .expect_reply("segment_complete"),
+            )
+            .unwrap();
+        match handle.wait(Duration::from_secs(5)) {
+            Some(SendOutcome::Replied(DoraData::Text(status))) => assert_eq!(status, "completed"),
+            other => panic!("expected reply, got {:?}", other),
+        }
+
+        // Each chunk reached the player tagged with the request id
+        for _ in 0..3 {
+            assert!(loopback
+                .wait_output(
+                    "mofa-audio-player",
+                    "audio_complete",
+                    Duration::from_secs(5)
+                )
+                .is_some());
+        }
+        let chunks = state.audio.drain();
+        assert_eq!(chunks.len(), 3);
+        let request_id = handle.id().to_string();
+        assert!(chunks.iter().all(|c| {
+            c.sample_rate == 32000 && c.question_id.as_deref() == Some(request_id.as_str())
+        }));
+
+        let failed = prompt
+            .submit(
+                SendRequest::new("control", DoraData::Text("boom".to_string()))
+                    .expect_reply("segment_complete"),
+            )
+            .unwrap();
+        assert!(matches!(
+            failed.wait(Duration::from_secs(5)),
+            Some(SendOutcome::Failed(_))
+        ));
+
+        prompt.disconnect().unwrap();
+        player.disconnect().unwrap();
+    }
+
+    #[test]
+    fn test_asr_listener_resolves_transcriptions() {
+        let loopback = Loopback::new();
+        loopback.route("asr/transcription", "mofa-asr-listener/transcription");
+        let mut asr = loopback.endpoint("asr");
+        let state = SharedDoraState::new();
+
+        let mut listener =
+            AsrListenerBridge::with_shared_state("mofa-asr-listener", Some(state.clone()))
+                .with_connector(Arc::new(loopback.clone()));
+        listener.connect().unwrap();
+
+        let mut transcribe = |text: &str, error: Option<&str>| {
+            let handle = SendHandle::new(RequestId::next());
+            state
+                .replies
+                .register("transcription", &handle, Duration::from_secs(5));
+            let mut metadata = MetadataParameters::new();
+            metadata.insert(
+                CORRELATION_KEY.to_string(),
+                Parameter::String(handle.id().to_string()),
+            );
+            if let Some(error) = error {
+                metadata.insert("error".to_string(), Parameter::String(error.to_string()));
+            }
+            let json = serde_json::json!({ "language": "zh", "text": text }).to_string();
+            asr.send_output(NodeOutput::new(
+                "transcription",
+                metadata,
+                StringArray::from(vec![json]),
+            ))
+            .unwrap();
+            handle.wait(Duration::from_secs(5))
+        };
+
+        match transcribe("你好", None) {
+            Some(SendOutcome::Replied(DoraData::Json(reply))) => {
+                assert_eq!(reply["language"], "zh");
+                assert_eq!(reply["text"], "你好");
+            }
+            other => panic!("expected transcription, got {:?}", other),
+        }
+        assert_eq!(
+            state.asr_transcription.read(),
+            Some(("zh".to_string(), "你好".to_string()))
+        );
+
+        assert!(matches!(
+            transcribe("", Some("no speech")),
+            Some(SendOutcome::Failed(message)) if message == "no speech"
+        ));
+
+        listener.disconnect().unwrap();
+    }
+
+    #[test]
+    fn test_system_log_collects_node_logs() {
+        let loopback = Loopback::new();
+        loopback
+            .route("tts/log", "mofa-system-log/tts_log")
+            .route("asr/status", "mofa-system-log/asr_status");
+        let mut tts = loopback.endpoint("tts");
+        let mut asr = loopback.endpoint("asr");
+        let state = SharedDoraState::new();
+
+        let mut log = SystemLogBridge::with_shared_state("mofa-system-log", Some(state.clone()))
+            .with_connector(Arc::new(loopback.clone()));
+        log.connect().unwrap();
+
+        let send = |node: &mut LoopbackTransport, output: &str, text: &str| {
+            node.send_output(NodeOutput::new(
+                output,
+                MetadataParameters::new(),
+                StringArray::from(vec![text]),
+            ))
+            .unwrap();
+        };
+        // Below the default Info level, so dropped
+        send(&mut tts, "log", r#"{"level": "debug", "message": "tick"}"#);
+        send(
+            &mut tts,
+            "log",
+            r#"{"level": "error", "message": "model missing"}"#,
+        );
+        send(&mut asr, "status", "ready");
+
+        let mut entries = Vec::new();
+        let collected = wait_for(Duration::from_secs(5), || {
+            entries.extend(state.logs.drain());
+            (entries.len() >= 2).then_some(())
+        });
+        assert!(collected.is_some());
+
+        let summary: Vec<(LogLevel, &str, &str)> = entries
+            .iter()
+            .map(|e| (e.level, e.node_id.as_str(), e.message.as_str()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (LogLevel::Error, "tts", "model missing"),
+                (LogLevel::Info, "asr", "ready"),
+            ]
+        );
+        let mut sources = log.log_sources();
+        sources.sort();
+        assert_eq!(sources, vec!["asr".to_string(), "tts".to_string()]);
+
+        log.disconnect().unwrap();
+    }
+}
@@ -0,0 +1,277 @@
+//! Node log collection
+//!
+//! The dora daemon writes each node's stdout/stderr to
+//! `<dataflow dir>/out/<dataflow id>/log_<node id>.txt`. [`NodeLogCollector`]
+//! tails those files, turning new lines into [`LogEntry`] values for the UI
+//! and keeping the last few lines per node so a crash can be explained
+//! (e.g. a Python traceback from a node that failed to load its model).
+
+use crate::controller::NodeStatus;
+use crate::data::{LogEntry, LogLevel};
+use std::collections::{HashMap, VecDeque};
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+/// Lines kept per node for [`NodeStatus::stderr_tail`]
+pub const DEFAULT_TAIL_LINES: usize = 20;
+
+/// Upper bound on bytes read per file per poll, so a chatty node can't stall the caller
+const MAX_READ_PER_POLL: u64 = 256 * 1024;
+
+/// Per-node read position and recent lines
+#[derive(Debug, Default)]
+struct NodeLog {
+    offset: u64,
+    /// Bytes after the last newline, decoded once the line is complete
+    partial: Vec<u8>,
+    tail: VecDeque<String>,
+}
+
+/// Tails dora's per-node log files
+#[derive(Debug)]
+pub struct NodeLogCollector {
+    dir: PathBuf,
+    tail_lines: usize,
+    nodes: HashMap<String, NodeLog>,
+}
+
+impl NodeLogCollector {
+    /// Collector for a dataflow's log directory (`out/<dataflow id>`)
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            tail_lines: DEFAULT_TAIL_LINES,
+            nodes: HashMap::new(),
+        }
+    }
+
+    /// Set how many lines are kept per node
+    pub fn with_tail_lines(mut self, lines: usize) -> Self {
+        self.tail_lines = lines.max(1);
+        self
+    }
+
+    /// Log directory being watched
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Read lines written since the last poll
+    ///
+    /// The directory may not exist yet while nodes are spawning; that just
+    /// yields no entries.
+    pub fn poll(&mut self) -> Vec<LogEntry> {
+        let Ok(read_dir) = std::fs::read_dir(&self.dir) else {
+            return Vec::new();
+        };
+
+        let mut files: Vec<(String, PathBuf)> = read_dir
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| {
+                let path = entry.path();
+                let node_id = node_id_from_file_name(path.file_name()?.to_str()?)?;
+                Some((node_id, path))
+            })
+            .collect();
+        files.sort();
+
+        let mut entries = Vec::new();
+        for (node_id, path) in files {
+            let log = self.nodes.entry(node_id.clone()).or_default();
+            let Some(chunk) = read_from(&path, &mut log.offset) else {
+                continue;
+            };
+
+            // A read can end inside a multibyte character, so only whole
+            // lines are decoded
+            log.partial.extend_from_slice(&chunk);
+            let Some(last_newline) = log.partial.iter().rposition(|&b| b == b'\n') else {
+                continue;
+            };
+            let complete: Vec<u8> = log.partial.drain(..=last_newline).collect();
+            let complete = String::from_utf8_lossy(&complete);
+
+            for line in complete.lines() {
+                let line = line.trim_end();
+                if line.is_empty() {
+                    continue;
+                }
+                log.tail.push_back(line.to_string());
+                if log.tail.len() > self.tail_lines {
+                    log.tail.pop_front();
+                }
+                entries.push(
+                    LogEntry::new(classify_line(line), line, node_id.clone())
+                        .with_metadata("source", "dora"),
+                );
+            }
+        }
+        entries
+    }
+
+    /// Last lines written by a node
+    pub fn tail(&self, node_id: &str) -> Vec<String> {
+        self.nodes
+            .get(node_id)
+            .map(|log| log.tail.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Node ids that have written a log file
+    pub fn node_ids(&self) -> Vec<String> {
+        let mut ids: Vec<String> = self.nodes.keys().cloned().collect();
+        ids.sort();
+        ids
+    }
+
+    /// Fill in [`NodeStatus::stderr_tail`] from the collected lines
+    pub fn apply_tails(&self, statuses: &mut [NodeStatus]) {
+        for status in statuses {
+            let tail = self.tail(&status.node_id);
+            if !tail.is_empty() {
+                status.stderr_tail = tail;
+            }
+        }
+    }
+}
+
+/// Node id from a dora log file name (`log_<node id>.txt`)
+fn node_id_from_file_name(name: &str) -> Option<String> {
+    name.strip_prefix("log_")?
+        .strip_suffix(".txt")
+        .filter(|id| !id.is_empty())
+        .map(String::from)
+}
+
+/// Read new bytes from `path` starting at `offset`, advancing it
+fn read_from(path: &Path, offset: &mut u64) -> Option<Vec<u8>> {
+    let mut file = File::open(path).ok()?;
+    let len = file.metadata().ok()?.len();
+    if len < *offset {
+        // File was truncated or replaced, start over
+        *offset = 0;
+    }
+    if len == *offset {
+        return None;
+    }
+
+    file.seek(SeekFrom::Start(*offset)).ok()?;
+    let mut buf = Vec::new();
+    file.take(MAX_READ_PER_POLL).read_to_end(&mut buf).ok()?;
+    *offset += buf.len() as u64;
+    Some(buf)
+}
+
+/// Guess a log level from a raw node output line
+fn classify_line(line: &str) -> LogLevel {
+    let upper = line.to_ascii_uppercase();
+    if upper.contains("ERROR")
+        || upper.contains("TRACEBACK")
+        || upper.contains("PANICKED")
+        || upper.contains("EXCEPTION")
+    {
+        LogLevel::Error
+    } else if upper.contains("WARN") {
+        LogLevel::Warning
+    } else if upper.contains("DEBUG") {
+        LogLevel::Debug
+    } else {
+        LogLevel::Info
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::controller::NodeState;
+    use std::io::Write;
+
+    fn temp_log_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("mofa-node-logs-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_poll_reads_only_new_complete_lines() {
+        let dir = temp_log_dir("poll");
+        let path = dir.join("log_primespeech-tts.txt");
+        std::fs::write(
+            &path,
+            "loading model\nTraceback (most recent call last):\npart",
+        )
+        .unwrap();
+
+        let mut collector = NodeLogCollector::new(&dir);
+        let entries = collector.poll();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].node_id, "primespeech-tts");
+        assert_eq!(entries[1].level, LogLevel::Error);
+
+        // The partial line is completed by the next write
+        let mut file = std::fs::OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .unwrap();
+        writeln!(file, "ial line").unwrap();
+        let entries = collector.poll();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].message, "partial line");
+        assert!(collector.poll().is_empty());
+
+        let mut statuses = vec![NodeStatus {
+            node_id: "primespeech-tts".to_string(),
+            state: NodeState::Failed,
+            exit_code: Some(1),
+            error: None,
+            stderr_tail: Vec::new(),
+        }];
+        collector.apply_tails(&mut statuses);
+        assert_eq!(statuses[0].stderr_tail.last().unwrap(), "partial line");
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_character_split_across_reads_is_kept() {
+        let dir = temp_log_dir("utf8");
+        let path = dir.join("log_asr.txt");
+        let line = "识别结果\n".as_bytes();
+        // Cut inside the second character
+        std::fs::write(&path, &line[..4]).unwrap();
+
+        let mut collector = NodeLogCollector::new(&dir);
+        assert!(collector.poll().is_empty());
+        let mut file = std::fs::OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .unwrap();
+        file.write_all(&line[4..]).unwrap();
+        let entries = collector.poll();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].message, "识别结果");
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_tail_is_bounded_and_missing_dir_is_empty() {
+        let dir = temp_log_dir("tail");
+        let lines: String = (0..10).map(|i| format!("line {}\n", i)).collect();
+        std::fs::write(dir.join("log_asr.txt"), lines).unwrap();
+        std::fs::write(dir.join("unrelated.txt"), "ignored\n").unwrap();
+
+        let mut collector = NodeLogCollector::new(&dir).with_tail_lines(3);
+        assert_eq!(collector.poll().len(), 10);
+        assert_eq!(collector.tail("asr"), vec!["line 7", "line 8", "line 9"]);
+        assert_eq!(collector.node_ids(), vec!["asr".to_string()]);
+
+        let mut missing = NodeLogCollector::new(dir.join("does-not-exist"));
+        assert!(missing.poll().is_empty());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
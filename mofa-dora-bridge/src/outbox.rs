@@ -0,0 +1,615 @@
+//! Send-side flow control
+//!
+//! [`DoraBridge::send`](crate::DoraBridge::send) is fire-and-forget. For
+//! requests the UI cares about, bridges accept a [`SendRequest`] through
+//! [`DoraBridge::submit`](crate::DoraBridge::submit) and hand back a
+//! [`SendHandle`] that completes when:
+//!
+//! - the data was written to the dora output (no reply expected), or
+//! - the reply arrived on the input named by [`SendRequest::expect_reply`]
+//!   (e.g. TTS `segment_complete`, ASR `transcription`), or
+//! - sending failed, the reply timed out, or the dataflow went away.
+//!
+//! ```text
+//! submit() ──▶ Outbox (bounded) ──▶ bridge event loop ──▶ dora output
+//!    │                                    │  question_id = request id
+//!    ▼                                    ▼
+//! SendHandle ◀── ReplyTracker ◀── reply input (any bridge)
+//! ```
+//!
+//! Each bridge owns an [`Outbox`]; a full outbox is backpressure and
+//! `submit` fails with [`BridgeError::OutboxFull`] instead of blocking.
+//! Replies often arrive on a different bridge than the one that sent the
+//! request (audio goes out through `mofa-audio-input`, the transcription
+//! comes back through `mofa-asr-listener`), so pending replies live in the
+//! shared [`ReplyTracker`] on [`SharedDoraState`](crate::SharedDoraState).
+//!
+//! Replies are matched on the `question_id` metadata, which the dora nodes
+//! pass through from input to output. Replies without it are matched to
+//! the oldest request waiting on that input.
+
+use crate::data::DoraData;
+use crate::error::{BridgeError, BridgeResult};
+use parking_lot::{Condvar, Mutex};
+use std::collections::{HashMap, VecDeque};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll, Waker};
+use std::time::{Duration, Instant};
+
+/// Metadata key carrying the request id to the dataflow and back
+pub const CORRELATION_KEY: &str = "question_id";
+
+/// Default capacity of a bridge outbox
+pub const DEFAULT_OUTBOX_CAPACITY: usize = 16;
+
+/// Default time to wait for a reply
+pub const DEFAULT_REPLY_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Process-unique id of a submitted request
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct RequestId(u64);
+
+impl RequestId {
+    /// Allocate the next id
+    pub fn next() -> Self {
+        static NEXT: AtomicU64 = AtomicU64::new(1);
+        Self(NEXT.fetch_add(1, Ordering::Relaxed))
+    }
+
+    /// Numeric value
+    pub fn value(&self) -> u64 {
+        self.0
+    }
+}
+
+impl std::fmt::Display for RequestId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Final state of a submitted request
+#[derive(Debug, Clone)]
+pub enum SendOutcome {
+    /// Written to the dora output; no reply was expected
+    Delivered,
+    /// Reply received
+    Replied(DoraData),
+    /// Sending failed or the reply reported an error
+    Failed(String),
+    /// No reply within the reply timeout
+    TimedOut,
+    /// Dropped before completion (bridge disconnected or dataflow stopped)
+    Cancelled,
+}
+
+impl SendOutcome {
+    /// Whether the request went through
+    pub fn is_success(&self) -> bool {
+        matches!(self, SendOutcome::Delivered | SendOutcome::Replied(_))
+    }
+}
+
+type Callback = Box<dyn FnOnce(&SendOutcome) + Send>;
+
+#[derive(Default)]
+struct CompletionState {
+    delivered: bool,
+    outcome: Option<SendOutcome>,
+    wakers: Vec<Waker>,
+    callbacks: Vec<Callback>,
+}
+
+#[derive(Default)]
+struct Completion {
+    state: Mutex<CompletionState>,
+    changed: Condvar,
+}
+
+/// Completion handle for a submitted request
+///
+/// Clones observe the same request. Usable as a future, by polling
+/// [`Self::outcome`], by blocking in [`Self::wait`], or with a callback.
+#[derive(Clone)]
+pub struct SendHandle {
+    id: RequestId,
+    completion: Arc<Completion>,
+}
+
+impl std::fmt::Debug for SendHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SendHandle")
+            .field("id", &self.id)
+            .field("outcome", &self.outcome())
+            .finish()
+    }
+}
+
+impl SendHandle {
+    /// Handle for a new request
+    pub fn new(id: RequestId) -> Self {
+        Self {
+            id,
+            completion: Arc::default(),
+        }
+    }
+
+    /// Handle that is already complete
+    pub fn completed(id: RequestId, outcome: SendOutcome) -> Self {
+        let handle = Self::new(id);
+        handle.complete(outcome);
+        handle
+    }
+
+    /// Request id (sent as `question_id` metadata)
+    pub fn id(&self) -> RequestId {
+        self.id
+    }
+
+    /// Whether the data reached the dora output
+    pub fn is_delivered(&self) -> bool {
+        self.completion.state.lock().delivered
+    }
+
+    /// Final outcome, if the request has completed
+    pub fn outcome(&self) -> Option<SendOutcome> {
+        self.completion.state.lock().outcome.clone()
+    }
+
+    /// Block until the request completes or `timeout` elapses
+    pub fn wait(&self, timeout: Duration) -> Option<SendOutcome> {
+        let deadline = Instant::now() + timeout;
+        let mut state = self.completion.state.lock();
+        while state.outcome.is_none() {
+            if self
+                .completion
+                .changed
+                .wait_until(&mut state, deadline)
+                .timed_out()
+            {
+                break;
+            }
+        }
+        state.outcome.clone()
+    }
+
+    /// Run `callback` on completion (immediately if already complete)
+    ///
+    /// The callback runs on the thread that completes the request, usually
+    /// a bridge event loop, so it should only hand the outcome off.
+    pub fn on_complete(&self, callback: impl FnOnce(&SendOutcome) + Send + 'static) {
+        let mut state = self.completion.state.lock();
+        match state.outcome.clone() {
+            Some(outcome) => {
+                drop(state);
+                callback(&outcome);
+            }
+            None => state.callbacks.push(Box::new(callback)),
+        }
+    }
+
+    /// Record that the data was written to the dora output
+    pub fn mark_delivered(&self) {
+        self.completion.state.lock().delivered = true;
+        self.completion.changed.notify_all();
+    }
+
+    /// Complete the request; later calls are ignored
+    pub fn complete(&self, outcome: SendOutcome) -> bool {
+        let (wakers, callbacks) = {
+            let mut state = self.completion.state.lock();
+            if state.outcome.is_some() {
+                return false;
+            }
+            if matches!(outcome, SendOutcome::Delivered) {
+                state.delivered = true;
+            }
+            state.outcome = Some(outcome.clone());
+            (
+                std::mem::take(&mut state.wakers),
+                std::mem::take(&mut state.callbacks),
+            )
+        };
+        self.completion.changed.notify_all();
+        for waker in wakers {
+            waker.wake();
+        }
+        for callback in callbacks {
+            callback(&outcome);
+        }
+        true
+    }
+}
+
+impl Future for SendHandle {
+    type Output = SendOutcome;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut state = self.completion.state.lock();
+        if let Some(outcome) = &state.outcome {
+            return Poll::Ready(outcome.clone());
+        }
+        if !state.wakers.iter().any(|w| w.will_wake(cx.waker())) {
+            state.wakers.push(cx.waker().clone());
+        }
+        Poll::Pending
+    }
+}
+
+/// Data to send on a bridge output
+#[derive(Debug, Clone)]
+pub struct SendRequest {
+    /// Output id on the bridge's node
+    pub output: String,
+    /// Payload
+    pub data: DoraData,
+    /// Input on which the reply arrives, if one is expected
+    pub reply_on: Option<String>,
+    /// How long to wait for the reply after delivery
+    pub reply_timeout: Duration,
+}
+
+impl SendRequest {
+    /// Request completing on delivery
+    pub fn new(output: impl Into<String>, data: DoraData) -> Self {
+        Self {
+            output: output.into(),
+            data,
+            reply_on: None,
+            reply_timeout: DEFAULT_REPLY_TIMEOUT,
+        }
+    }
+
+    /// Complete only when a reply arrives on `input`
+    pub fn expect_reply(mut self, input: impl Into<String>) -> Self {
+        self.reply_on = Some(input.into());
+        self
+    }
+
+    /// Set the reply timeout
+    pub fn with_reply_timeout(mut self, timeout: Duration) -> Self {
+        self.reply_timeout = timeout;
+        self
+    }
+}
+
+/// Request queued in an [`Outbox`]
+#[derive(Debug)]
+pub struct OutboxItem {
+    /// What to send
+    pub request: SendRequest,
+    /// Completion handle returned to the submitter
+    pub handle: SendHandle,
+}
+
+impl OutboxItem {
+    /// Request id
+    pub fn id(&self) -> RequestId {
+        self.handle.id()
+    }
+
+    /// Record the result of writing to the dora output
+    ///
+    /// On success the request either completes as delivered or starts
+    /// waiting for its reply in `replies`.
+    pub fn finish(self, result: BridgeResult<()>, replies: Option<&ReplyTracker>) {
+        match (result, self.request.reply_on) {
+            (Err(e), _) => {
+                self.handle.complete(SendOutcome::Failed(e.to_string()));
+            }
+            (Ok(()), None) => {
+                self.handle.complete(SendOutcome::Delivered);
+            }
+            (Ok(()), Some(input)) => {
+                self.handle.mark_delivered();
+                match replies {
+                    Some(replies) => {
+                        replies.register(input, &self.handle, self.request.reply_timeout)
+                    }
+                    None => {
+                        self.handle.complete(SendOutcome::Failed(format!(
+                            "no reply tracking for '{}'",
+                            input
+                        )));
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Bounded queue of requests waiting for a bridge's event loop
+pub struct Outbox {
+    node_id: String,
+    capacity: usize,
+    queue: Mutex<VecDeque<OutboxItem>>,
+    not_empty: Condvar,
+}
+
+impl Outbox {
+    /// Outbox for a bridge, holding at most `capacity` requests
+    pub fn new(node_id: impl Into<String>, capacity: usize) -> Self {
+        Self {
+            node_id: node_id.into(),
+            capacity: capacity.max(1),
+            queue: Mutex::new(VecDeque::new()),
+            not_empty: Condvar::new(),
+        }
+    }
+
+    /// Maximum number of queued requests
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Number of queued requests
+    pub fn len(&self) -> usize {
+        self.queue.lock().len()
+    }
+
+    /// Whether nothing is queued
+    pub fn is_empty(&self) -> bool {
+        self.queue.lock().is_empty()
+    }
+
+    /// Queue a request, failing with [`BridgeError::OutboxFull`] when full
+    pub fn submit(&self, request: SendRequest) -> BridgeResult<SendHandle> {
+        let mut queue = self.queue.lock();
+        if queue.len() >= self.capacity {
+            return Err(BridgeError::OutboxFull(self.node_id.clone()));
+        }
+        let handle = SendHandle::new(RequestId::next());
+        queue.push_back(OutboxItem {
+            request,
+            handle: handle.clone(),
+        });
+        drop(queue);
+        self.not_empty.notify_one();
+        Ok(handle)
+    }
+
+    /// Take everything queued
+    pub fn drain(&self) -> Vec<OutboxItem> {
+        self.queue.lock().drain(..).collect()
+    }
+
+    /// Take everything queued, waiting up to `timeout` for the first request
+    pub fn drain_timeout(&self, timeout: Duration) -> Vec<OutboxItem> {
+        let mut queue = self.queue.lock();
+        if queue.is_empty() {
+            self.not_empty.wait_for(&mut queue, timeout);
+        }
+        queue.drain(..).collect()
+    }
+
+    /// Cancel everything queued (bridge disconnecting)
+    pub fn cancel_all(&self) {
+        for item in self.drain() {
+            item.handle.complete(SendOutcome::Cancelled);
+        }
+    }
+}
+
+struct PendingReply {
+    handle: SendHandle,
+    deadline: Instant,
+}
+
+/// Requests waiting for a reply, keyed by the input the reply arrives on
+#[derive(Default)]
+pub struct ReplyTracker {
+    pending: Mutex<HashMap<String, VecDeque<PendingReply>>>,
+}
+
+impl ReplyTracker {
+    /// Empty tracker
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Wait for a reply on `input` for the request behind `handle`
+    pub fn register(&self, input: impl Into<String>, handle: &SendHandle, timeout: Duration) {
+        self.pending
+            .lock()
+            .entry(input.into())
+            .or_default()
+            .push_back(PendingReply {
+                handle: handle.clone(),
+                deadline: Instant::now() + timeout,
+            });
+    }
+
+    /// Complete the request a reply belongs to
+    ///
+    /// `correlation` is the reply's `question_id` metadata. A reply carrying
+    /// an id that isn't pending (e.g. a duplicate) is ignored; one without
+    /// an id goes to the oldest request waiting on `input`.
+    pub fn resolve(
+        &self,
+        input: &str,
+        correlation: Option<&str>,
+        outcome: SendOutcome,
+    ) -> Option<RequestId> {
+        let mut pending = self.pending.lock();
+        let queue = pending.get_mut(input)?;
+        let index = match correlation {
+            Some(key) => queue
+                .iter()
+                .position(|p| p.handle.id().to_string() == key)?,
+            None if !queue.is_empty() => 0,
+            None => return None,
+        };
+        let reply = queue.remove(index)?;
+        drop(pending);
+        reply.handle.complete(outcome);
+        Some(reply.handle.id())
+    }
+
+    /// Time out requests whose reply is overdue; returns how many expired
+    pub fn expire_overdue(&self) -> usize {
+        let now = Instant::now();
+        let mut expired = Vec::new();
+        {
+            let mut pending = self.pending.lock();
+            for queue in pending.values_mut() {
+                queue.retain(|p| {
+                    if p.deadline <= now {
+                        expired.push(p.handle.clone());
+                        false
+                    } else {
+                        // Completed elsewhere (e.g. cancelled by the submitter's timeout)
+                        p.handle.outcome().is_none()
+                    }
+                });
+            }
+        }
+        for handle in &expired {
+            handle.complete(SendOutcome::TimedOut);
+        }
+        expired.len()
+    }
+
+    /// Number of requests waiting for a reply
+    pub fn pending_count(&self) -> usize {
+        self.pending.lock().values().map(VecDeque::len).sum()
+    }
+
+    /// Cancel every pending reply (dataflow stopped)
+    pub fn cancel_all(&self) {
+        let pending: Vec<PendingReply> = self
+            .pending
+            .lock()
+            .drain()
+            .flat_map(|(_, queue)| queue)
+            .collect();
+        for reply in pending {
+            reply.handle.complete(SendOutcome::Cancelled);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicUsize;
+
+    fn text(s: &str) -> DoraData {
+        DoraData::Text(s.to_string())
+    }
+
+    #[test]
+    fn test_outbox_backpressure_and_delivery() {
+        let outbox = Outbox::new("mofa-prompt-input", 2);
+        let first = outbox
+            .submit(SendRequest::new("control", text("a")))
+            .unwrap();
+        let second = outbox
+            .submit(SendRequest::new("control", text("b")))
+            .unwrap();
+        assert!(matches!(
+            outbox.submit(SendRequest::new("control", text("c"))),
+            Err(BridgeError::OutboxFull(_))
+        ));
+        assert_ne!(first.id(), second.id());
+
+        let mut items = outbox.drain().into_iter();
+        items.next().unwrap().finish(Ok(()), None);
+        items
+            .next()
+            .unwrap()
+            .finish(Err(BridgeError::NotConnected), None);
+        assert!(matches!(first.outcome(), Some(SendOutcome::Delivered)));
+        assert!(matches!(second.outcome(), Some(SendOutcome::Failed(_))));
+
+        // Space is available again once the loop drained the queue
+        let third = outbox
+            .submit(SendRequest::new("control", text("c")))
+            .unwrap();
+        outbox.cancel_all();
+        assert!(matches!(
+            third.wait(Duration::ZERO),
+            Some(SendOutcome::Cancelled)
+        ));
+    }
+
+    #[test]
+    fn test_replies_correlate_by_id_then_fifo() {
+        let outbox = Outbox::new("mofa-prompt-input", 4);
+        let replies = ReplyTracker::new();
+        let a = outbox
+            .submit(SendRequest::new("control", text("a")).expect_reply("segment_complete"))
+            .unwrap();
+        let b = outbox
+            .submit(SendRequest::new("control", text("b")).expect_reply("segment_complete"))
+            .unwrap();
+        for item in outbox.drain() {
+            item.finish(Ok(()), Some(&replies));
+        }
+        assert!(a.is_delivered() && a.outcome().is_none());
+        assert_eq!(replies.pending_count(), 2);
+
+        // Out of order reply carrying b's id
+        let b_id = b.id().to_string();
+        assert_eq!(
+            replies.resolve(
+                "segment_complete",
+                Some(&b_id),
+                SendOutcome::Replied(text("completed"))
+            ),
+            Some(b.id())
+        );
+        // Duplicate for b is ignored rather than completing a
+        assert_eq!(
+            replies.resolve(
+                "segment_complete",
+                Some(&b_id),
+                SendOutcome::Replied(text("empty"))
+            ),
+            None
+        );
+        // Uncorrelated reply goes to the oldest pending request
+        assert_eq!(
+            replies.resolve("segment_complete", None, SendOutcome::Failed("boom".into())),
+            Some(a.id())
+        );
+        assert!(matches!(
+            b.outcome(),
+            Some(SendOutcome::Replied(DoraData::Text(s))) if s == "completed"
+        ));
+        assert!(matches!(a.outcome(), Some(SendOutcome::Failed(e)) if e == "boom"));
+        assert_eq!(replies.pending_count(), 0);
+    }
+
+    #[test]
+    fn test_timeouts_callbacks_and_future() {
+        let replies = ReplyTracker::new();
+        let handle = SendHandle::new(RequestId::next());
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&calls);
+        handle.on_complete(move |outcome| {
+            assert!(matches!(outcome, SendOutcome::TimedOut));
+            counter.fetch_add(1, Ordering::SeqCst);
+        });
+
+        let mut future = handle.clone();
+        let mut cx = Context::from_waker(Waker::noop());
+        assert!(Pin::new(&mut future).poll(&mut cx).is_pending());
+
+        replies.register("transcription", &handle, Duration::ZERO);
+        assert_eq!(replies.expire_overdue(), 1);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert!(matches!(
+            Pin::new(&mut future).poll(&mut cx),
+            Poll::Ready(SendOutcome::TimedOut)
+        ));
+
+        // A late reply finds nothing to complete
+        assert!(replies
+            .resolve("transcription", None, SendOutcome::Replied(text("late")))
+            .is_none());
+        assert!(!handle.complete(SendOutcome::Cancelled));
+    }
+}
@@ -34,8 +34,8 @@ pub struct ParsedDataflow {
 pub struct MofaNodeSpec {
     /// Node ID (e.g., "mofa-audio-player")
     pub id: String,
-    /// Node type
-    pub node_type: MofaNodeType,
+    /// Built-in node type, `None` for app-defined `mofa-*` nodes
+    pub node_type: Option<MofaNodeType>,
     /// Expected inputs
     pub inputs: Vec<InputDef>,
     /// Expected outputs
@@ -138,11 +138,11 @@ impl DataflowParser {
         if let Some(nodes_array) = raw_yaml.get("nodes").and_then(|n| n.as_sequence()) {
             for node_value in nodes_array {
                 if let Some(parsed) = Self::parse_node(node_value) {
-                    // Check if this is a MoFA node
-                    if let Some(mofa_type) = MofaNodeType::from_node_id(&parsed.id) {
+                    // Check if this is a MoFA node (built-in or app-defined)
+                    if MofaNodeType::is_mofa_node(&parsed.id) {
                         mofa_nodes.push(MofaNodeSpec {
                             id: parsed.id.clone(),
-                            node_type: mofa_type,
+                            node_type: MofaNodeType::from_node_id(&parsed.id),
                             inputs: parsed.inputs.clone(),
                             outputs: parsed.outputs.clone(),
                         });
@@ -216,8 +216,19 @@ impl DataflowParser {
                 .and_then(|a| a.as_str())
                 .map(|s| s.to_string());
             NodeKind::Custom { source, args }
-        } else if value.get("path").and_then(|p| p.as_str()) == Some("dynamic") {
-            NodeKind::Dynamic
+        } else if let Some(path) = value.get("path").and_then(|p| p.as_str()) {
+            if path == "dynamic" {
+                NodeKind::Dynamic
+            } else {
+                // Shorthand for a custom node: `path: dora-asr` with optional `args`
+                NodeKind::Custom {
+                    source: path.to_string(),
+                    args: value
+                        .get("args")
+                        .and_then(|a| a.as_str())
+                        .map(|s| s.to_string()),
+                }
+            }
         } else {
             return None;
         };
@@ -333,11 +344,14 @@ impl DataflowParser {
                     // ${VAR} - no default, required
                     (true, false, None)
                 }
-            } else if value.starts_with("$") {
+            } else if value.len() > 1
+                && value.starts_with('$')
+                && value[1..].chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+            {
                 // $VAR - no default, required
                 (true, false, None)
             } else {
-                // Literal value
+                // Literal value, possibly interpolating other vars (e.g. $HOME/models)
                 (false, false, Some(value.clone()))
             };
 
@@ -443,4 +457,32 @@ nodes:
         assert_eq!(parsed.log_sources[1].node_id, "mofa-audio-player");
         assert_eq!(parsed.log_sources[1].output_id, "buffer_status");
     }
+
+    #[test]
+    fn test_parse_path_nodes_and_env_placeholders() {
+        let yaml = r#"
+nodes:
+  - id: asr
+    path: dora-asr
+    env:
+      API_KEY: $ASR_API_KEY
+      MODEL_DIR: $HOME/.dora/models
+"#;
+
+        let parsed = DataflowParser::parse_string(yaml, PathBuf::from("test.yml")).unwrap();
+
+        assert_eq!(parsed.nodes.len(), 1);
+        assert!(matches!(
+            &parsed.nodes[0].kind,
+            NodeKind::Custom { source, .. } if source == "dora-asr"
+        ));
+
+        let required: Vec<&str> = parsed
+            .env_requirements
+            .iter()
+            .filter(|r| r.required)
+            .map(|r| r.key.as_str())
+            .collect();
+        assert_eq!(required, vec!["API_KEY"]);
+    }
 }
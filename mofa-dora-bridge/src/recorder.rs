@@ -0,0 +1,572 @@
+//! Bridge traffic recording and replay
+//!
+//! A [`Recorder`] appends every input a bridge receives and every output it
+//! sends to a JSON-lines file, one [`Record`] per line, with the Arrow payload
+//! and the dora metadata:
+//!
+//! ```json
+//! {"at_ms":1520,"node":"mofa-audio-player","direction":"input","port":"audio",
+//!  "metadata":{"question_id":{"string":"5"},"sample_rate":{"integer":32000}},
+//!  "payload":{"type":"float32","values":[0.0,0.01]}}
+//! ```
+//!
+//! Recording is off unless a recorder is installed on
+//! [`SharedDoraState`](crate::SharedDoraState), or the app is started with
+//! `MOFA_BRIDGE_RECORD=<path>`. Bridges record through the transport
+//! returned by [`transport::open`](crate::transport::open).
+//!
+//! A saved [`Recording`] can be replayed through any [`ReplayHandler`] (the
+//! bridge state machines, without a dora daemon); [`replay`] returns the
+//! outputs they produce as records, so they can be compared with the ones
+//! that were recorded.
+
+use crate::error::{BridgeError, BridgeResult};
+use arrow::array::{
+    Array, ArrayRef, BooleanArray, Float32Array, Float64Array, Int16Array, Int32Array, Int64Array,
+    LargeListArray, ListArray, NullArray, StringArray, UInt8Array,
+};
+use arrow::datatypes::{DataType, Field};
+use dora_node_api::{MetadataParameters, Parameter};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
+use std::time::Instant;
+use tracing::{error, info};
+
+/// Environment variable naming the file to record bridge traffic to
+pub const RECORD_ENV: &str = "MOFA_BRIDGE_RECORD";
+
+/// Whether a record was received from or sent to the dataflow
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Direction {
+    Input,
+    Output,
+}
+
+/// Serializable form of a dora metadata [`Parameter`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MetadataValue {
+    String(String),
+    Integer(i64),
+    Float(f64),
+    Bool(bool),
+    ListInt(Vec<i64>),
+    ListFloat(Vec<f64>),
+    ListString(Vec<String>),
+}
+
+impl From<&Parameter> for MetadataValue {
+    fn from(value: &Parameter) -> Self {
+        match value {
+            Parameter::String(s) => Self::String(s.clone()),
+            Parameter::Integer(i) => Self::Integer(*i),
+            Parameter::Float(f) => Self::Float(*f),
+            Parameter::Bool(b) => Self::Bool(*b),
+            Parameter::ListInt(l) => Self::ListInt(l.clone()),
+            Parameter::ListFloat(l) => Self::ListFloat(l.clone()),
+            Parameter::ListString(l) => Self::ListString(l.clone()),
+        }
+    }
+}
+
+impl From<&MetadataValue> for Parameter {
+    fn from(value: &MetadataValue) -> Self {
+        match value {
+            MetadataValue::String(s) => Parameter::String(s.clone()),
+            MetadataValue::Integer(i) => Parameter::Integer(*i),
+            MetadataValue::Float(f) => Parameter::Float(*f),
+            MetadataValue::Bool(b) => Parameter::Bool(*b),
+            MetadataValue::ListInt(l) => Parameter::ListInt(l.clone()),
+            MetadataValue::ListFloat(l) => Parameter::ListFloat(l.clone()),
+            MetadataValue::ListString(l) => Parameter::ListString(l.clone()),
+        }
+    }
+}
+
+/// Serializable form of an Arrow array
+///
+/// Covers the types the MoFA nodes exchange. Anything else is recorded as
+/// [`Payload::Unsupported`] (type name only) and replays as an empty array.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "values", rename_all = "snake_case")]
+pub enum Payload {
+    Null(usize),
+    Utf8(Vec<Option<String>>),
+    Float32(Vec<f32>),
+    Float64(Vec<f64>),
+    Int16(Vec<i16>),
+    Int32(Vec<i32>),
+    Int64(Vec<i64>),
+    UInt8(Vec<u8>),
+    Boolean(Vec<bool>),
+    /// `ListArray`, one payload per row (e.g. primespeech's `pa.array([audio])`)
+    List(Vec<Payload>),
+    /// `LargeListArray`, one payload per row
+    LargeList(Vec<Payload>),
+    Unsupported(String),
+}
+
+impl Payload {
+    /// Capture an Arrow array
+    pub fn from_array(array: &dyn Array) -> Self {
+        fn values<T: Clone>(array: &dyn Array, f: impl Fn(&dyn Array) -> Option<&[T]>) -> Vec<T> {
+            f(array).map(|v| v.to_vec()).unwrap_or_default()
+        }
+
+        match array.data_type() {
+            DataType::Null => Self::Null(array.len()),
+            DataType::Utf8 => match array.as_any().downcast_ref::<StringArray>() {
+                Some(arr) => Self::Utf8(arr.iter().map(|s| s.map(str::to_string)).collect()),
+                None => Self::Unsupported(array.data_type().to_string()),
+            },
+            DataType::Float32 => Self::Float32(values(array, |a| {
+                a.as_any()
+                    .downcast_ref::<Float32Array>()
+                    .map(|a| &a.values()[..])
+            })),
+            DataType::Float64 => Self::Float64(values(array, |a| {
+                a.as_any()
+                    .downcast_ref::<Float64Array>()
+                    .map(|a| &a.values()[..])
+            })),
+            DataType::Int16 => Self::Int16(values(array, |a| {
+                a.as_any()
+                    .downcast_ref::<Int16Array>()
+                    .map(|a| &a.values()[..])
+            })),
+            DataType::Int32 => Self::Int32(values(array, |a| {
+                a.as_any()
+                    .downcast_ref::<Int32Array>()
+                    .map(|a| &a.values()[..])
+            })),
+            DataType::Int64 => Self::Int64(values(array, |a| {
+                a.as_any()
+                    .downcast_ref::<Int64Array>()
+                    .map(|a| &a.values()[..])
+            })),
+            DataType::UInt8 => Self::UInt8(values(array, |a| {
+                a.as_any()
+                    .downcast_ref::<UInt8Array>()
+                    .map(|a| &a.values()[..])
+            })),
+            DataType::Boolean => match array.as_any().downcast_ref::<BooleanArray>() {
+                Some(arr) => Self::Boolean(arr.iter().map(|b| b.unwrap_or_default()).collect()),
+                None => Self::Unsupported(array.data_type().to_string()),
+            },
+            DataType::List(_) => match array.as_any().downcast_ref::<ListArray>() {
+                Some(arr) => Self::List(
+                    (0..arr.len())
+                        .map(|i| Self::from_array(arr.value(i).as_ref()))
+                        .collect(),
+                ),
+                None => Self::Unsupported(array.data_type().to_string()),
+            },
+            DataType::LargeList(_) => match array.as_any().downcast_ref::<LargeListArray>() {
+                Some(arr) => Self::LargeList(
+                    (0..arr.len())
+                        .map(|i| Self::from_array(arr.value(i).as_ref()))
+                        .collect(),
+                ),
+                None => Self::Unsupported(array.data_type().to_string()),
+            },
+            dt => Self::Unsupported(dt.to_string()),
+        }
+    }
+
+    /// Rebuild the Arrow array
+    pub fn to_array(&self) -> ArrayRef {
+        match self {
+            Self::Null(len) => Arc::new(NullArray::new(*len)),
+            Self::Utf8(v) => Arc::new(StringArray::from(v.clone())),
+            Self::Float32(v) => Arc::new(Float32Array::from(v.clone())),
+            Self::Float64(v) => Arc::new(Float64Array::from(v.clone())),
+            Self::Int16(v) => Arc::new(Int16Array::from(v.clone())),
+            Self::Int32(v) => Arc::new(Int32Array::from(v.clone())),
+            Self::Int64(v) => Arc::new(Int64Array::from(v.clone())),
+            Self::UInt8(v) => Arc::new(UInt8Array::from(v.clone())),
+            Self::Boolean(v) => Arc::new(BooleanArray::from(v.clone())),
+            Self::List(rows) => {
+                let (field, offsets, values) = Self::concat_rows(rows);
+                Arc::new(ListArray::new(
+                    field,
+                    arrow::buffer::OffsetBuffer::from_lengths(offsets),
+                    values,
+                    None,
+                ))
+            }
+            Self::LargeList(rows) => {
+                let (field, offsets, values) = Self::concat_rows(rows);
+                Arc::new(LargeListArray::new(
+                    field,
+                    arrow::buffer::OffsetBuffer::from_lengths(offsets),
+                    values,
+                    None,
+                ))
+            }
+            Self::Unsupported(_) => Arc::new(NullArray::new(0)),
+        }
+    }
+
+    /// Number of rows
+    pub fn len(&self) -> usize {
+        match self {
+            Self::Null(len) => *len,
+            Self::Utf8(v) => v.len(),
+            Self::Float32(v) => v.len(),
+            Self::Float64(v) => v.len(),
+            Self::Int16(v) => v.len(),
+            Self::Int32(v) => v.len(),
+            Self::Int64(v) => v.len(),
+            Self::UInt8(v) => v.len(),
+            Self::Boolean(v) => v.len(),
+            Self::List(v) | Self::LargeList(v) => v.len(),
+            Self::Unsupported(_) => 0,
+        }
+    }
+
+    /// Whether there are no rows
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Child field, row lengths and concatenated values of a list payload
+    fn concat_rows(rows: &[Payload]) -> (Arc<Field>, Vec<usize>, ArrayRef) {
+        let arrays: Vec<ArrayRef> = rows.iter().map(Payload::to_array).collect();
+        let data_type = arrays
+            .first()
+            .map(|a| a.data_type().clone())
+            .unwrap_or(DataType::Float32);
+        let refs: Vec<&dyn Array> = arrays.iter().map(|a| a.as_ref()).collect();
+        let values = if refs.is_empty() {
+            arrow::array::new_empty_array(&data_type)
+        } else {
+            arrow::compute::concat(&refs)
+                .unwrap_or_else(|_| arrow::array::new_empty_array(&data_type))
+        };
+        let lengths = arrays.iter().map(|a| a.len()).collect();
+        (
+            Arc::new(Field::new("item", data_type, true)),
+            lengths,
+            values,
+        )
+    }
+}
+
+/// One input or output of a bridge
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Record {
+    /// Milliseconds since the recording started
+    pub at_ms: u64,
+    /// Bridge node id (e.g. "mofa-audio-player")
+    pub node: String,
+    pub direction: Direction,
+    /// Input or output id
+    pub port: String,
+    #[serde(default)]
+    pub metadata: BTreeMap<String, MetadataValue>,
+    pub payload: Payload,
+}
+
+impl Record {
+    /// Capture an input or output (timestamped when written by a [`Recorder`])
+    pub fn new(
+        node: &str,
+        direction: Direction,
+        port: &str,
+        parameters: &MetadataParameters,
+        data: &dyn Array,
+    ) -> Self {
+        Self {
+            at_ms: 0,
+            node: node.to_string(),
+            direction,
+            port: port.to_string(),
+            metadata: parameters
+                .iter()
+                .map(|(k, v)| (k.clone(), MetadataValue::from(v)))
+                .collect(),
+            payload: Payload::from_array(data),
+        }
+    }
+
+    /// Metadata as dora parameters
+    pub fn parameters(&self) -> MetadataParameters {
+        self.metadata
+            .iter()
+            .map(|(k, v)| (k.clone(), Parameter::from(v)))
+            .collect()
+    }
+
+    /// Payload as an Arrow array
+    pub fn data(&self) -> ArrayRef {
+        self.payload.to_array()
+    }
+}
+
+/// An output produced by a bridge handler, to be sent to the dataflow
+#[derive(Debug, Clone)]
+pub struct NodeOutput {
+    pub id: String,
+    pub parameters: MetadataParameters,
+    pub data: ArrayRef,
+}
+
+impl NodeOutput {
+    pub fn new(id: &str, parameters: MetadataParameters, data: impl Array + 'static) -> Self {
+        Self {
+            id: id.to_string(),
+            parameters,
+            data: Arc::new(data),
+        }
+    }
+}
+
+/// Appends bridge traffic to a JSON-lines file
+///
+/// Shared by all bridges; each record is flushed as it is written so a
+/// recording survives the app being killed.
+#[derive(Debug)]
+pub struct Recorder {
+    path: PathBuf,
+    started: Instant,
+    writer: Mutex<BufWriter<File>>,
+}
+
+impl Recorder {
+    /// Create (or truncate) a recording file
+    pub fn create(path: impl Into<PathBuf>) -> BridgeResult<Self> {
+        let path = path.into();
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)?;
+        }
+        let file = File::create(&path)?;
+        info!("Recording bridge traffic to {}", path.display());
+        Ok(Self {
+            path,
+            started: Instant::now(),
+            writer: Mutex::new(BufWriter::new(file)),
+        })
+    }
+
+    /// Recorder for [`RECORD_ENV`], created once per process
+    pub fn from_env() -> Option<Arc<Self>> {
+        static RECORDER: OnceLock<Option<Arc<Recorder>>> = OnceLock::new();
+        RECORDER
+            .get_or_init(|| {
+                let path = std::env::var_os(RECORD_ENV).filter(|p| !p.is_empty())?;
+                match Self::create(PathBuf::from(path)) {
+                    Ok(recorder) => Some(Arc::new(recorder)),
+                    Err(e) => {
+                        error!("Failed to start bridge recording: {}", e);
+                        None
+                    }
+                }
+            })
+            .clone()
+    }
+
+    /// File being written
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Timestamp and append a record
+    pub fn record(&self, mut record: Record) {
+        record.at_ms = self.started.elapsed().as_millis() as u64;
+        let line = match serde_json::to_string(&record) {
+            Ok(line) => line,
+            Err(e) => {
+                error!("Failed to serialize record for {}: {}", record.node, e);
+                return;
+            }
+        };
+        let mut writer = self.writer.lock();
+        if let Err(e) = writeln!(writer, "{}", line).and_then(|_| writer.flush()) {
+            error!("Failed to write {}: {}", self.path.display(), e);
+        }
+    }
+
+    /// Record an input received by `node`
+    pub fn input(&self, node: &str, port: &str, parameters: &MetadataParameters, data: &dyn Array) {
+        self.record(Record::new(node, Direction::Input, port, parameters, data));
+    }
+
+    /// Record an output sent by `node`
+    pub fn output(
+        &self,
+        node: &str,
+        port: &str,
+        parameters: &MetadataParameters,
+        data: &dyn Array,
+    ) {
+        self.record(Record::new(node, Direction::Output, port, parameters, data));
+    }
+}
+
+/// A loaded recording
+#[derive(Debug, Clone, Default)]
+pub struct Recording {
+    records: Vec<Record>,
+}
+
+impl Recording {
+    pub fn new(records: Vec<Record>) -> Self {
+        Self { records }
+    }
+
+    /// Load a recording file
+    pub fn load(path: impl AsRef<Path>) -> BridgeResult<Self> {
+        Self::from_jsonl(&std::fs::read_to_string(path)?)
+    }
+
+    /// Parse JSON lines (blank lines are skipped)
+    pub fn from_jsonl(content: &str) -> BridgeResult<Self> {
+        let records = content
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(n, line)| {
+                serde_json::from_str(line)
+                    .map_err(|e| BridgeError::ParseError(format!("line {}: {}", n + 1, e)))
+            })
+            .collect::<BridgeResult<_>>()?;
+        Ok(Self { records })
+    }
+
+    pub fn records(&self) -> &[Record] {
+        &self.records
+    }
+
+    /// Records of one node in one direction, in order
+    pub fn filter<'a>(
+        &'a self,
+        node: &'a str,
+        direction: Direction,
+    ) -> impl Iterator<Item = &'a Record> + 'a {
+        self.records
+            .iter()
+            .filter(move |r| r.node == node && r.direction == direction)
+    }
+}
+
+/// A bridge state machine that can be driven by recorded inputs
+pub trait ReplayHandler {
+    /// Node id whose inputs this handler consumes
+    fn node_id(&self) -> &str;
+
+    /// Handle one input, returning the outputs it produces
+    fn handle_input(
+        &mut self,
+        input_id: &str,
+        parameters: &MetadataParameters,
+        data: &ArrayRef,
+    ) -> Vec<NodeOutput>;
+}
+
+/// Feed a recording's inputs for `handler`'s node through it
+///
+/// Returns the outputs as records (timestamped with the input that caused
+/// them), comparable with `recording.filter(node, Direction::Output)`.
+pub fn replay(recording: &Recording, handler: &mut dyn ReplayHandler) -> Vec<Record> {
+    let node = handler.node_id().to_string();
+    let mut outputs = Vec::new();
+    for input in recording.filter(&node, Direction::Input) {
+        for output in handler.handle_input(&input.port, &input.parameters(), &input.data()) {
+            let mut record = Record::new(
+                &node,
+                Direction::Output,
+                &output.id,
+                &output.parameters,
+                output.data.as_ref(),
+            );
+            record.at_ms = input.at_ms;
+            outputs.push(record);
+        }
+    }
+    outputs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_payload_round_trip() {
+        let samples = Float32Array::from(vec![0.0f32, 0.5, -0.25]);
+        let list = ListArray::from_iter_primitive::<arrow::datatypes::Float32Type, _, _>(vec![
+            Some(vec![Some(0.1f32), Some(0.2)]),
+            Some(vec![Some(0.3f32)]),
+        ]);
+        let arrays: Vec<ArrayRef> = vec![
+            Arc::new(samples),
+            Arc::new(list),
+            Arc::new(StringArray::from(vec![Some("completed"), None])),
+            Arc::new(Int16Array::from(vec![i16::MIN, 0, i16::MAX])),
+            Arc::new(UInt8Array::from(vec![1u8, 2, 3])),
+            Arc::new(Float64Array::from(vec![42.5])),
+        ];
+
+        for array in arrays {
+            let payload = Payload::from_array(array.as_ref());
+            let json = serde_json::to_string(&payload).unwrap();
+            let parsed: Payload = serde_json::from_str(&json).unwrap();
+            assert_eq!(parsed, payload);
+            assert_eq!(parsed.to_array().as_ref(), array.as_ref());
+        }
+    }
+
+    #[test]
+    fn test_record_file_round_trip() {
+        let path = std::env::temp_dir().join(format!("mofa-record-{}.jsonl", uuid::Uuid::new_v4()));
+        let recorder = Recorder::create(&path).unwrap();
+
+        let mut params = MetadataParameters::new();
+        params.insert("question_id".into(), Parameter::String("7".into()));
+        params.insert("sample_rate".into(), Parameter::Integer(32000));
+        recorder.input(
+            "mofa-audio-player",
+            "audio",
+            &params,
+            &Float32Array::from(vec![0.1f32]),
+        );
+        recorder.output(
+            "mofa-audio-player",
+            "audio_complete",
+            &params,
+            &StringArray::from(vec!["received"]),
+        );
+        drop(recorder);
+
+        let recording = Recording::load(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(recording.records().len(), 2);
+        let input = recording
+            .filter("mofa-audio-player", Direction::Input)
+            .next()
+            .unwrap();
+        assert_eq!(input.port, "audio");
+        assert_eq!(
+            input.metadata.get("sample_rate"),
+            Some(&MetadataValue::Integer(32000))
+        );
+        assert!(matches!(
+            input.parameters().get("question_id"),
+            Some(Parameter::String(id)) if id == "7"
+        ));
+        assert_eq!(input.payload, Payload::Float32(vec![0.1]));
+        assert_eq!(
+            recording
+                .filter("mofa-audio-player", Direction::Output)
+                .count(),
+            1
+        );
+        assert!(Recording::from_jsonl("{not json").is_err());
+    }
+}
@@ -0,0 +1,264 @@
+//! Bridge registry
+//!
+//! Maps `mofa-*` node ids to [`DoraBridge`] factories so the dispatcher can
+//! create bridges for node types it does not know about at compile time.
+//!
+//! Factories are keyed either by exact node id or by prefix. Exact matches
+//! win over prefixes, and among prefixes the longest one wins:
+//!
+//! ```text
+//! mofa-audio-player      → exact "mofa-audio-player"
+//! mofa-metrics-latency   → prefix "mofa-metrics"
+//! mofa-unknown           → no bridge (dispatcher logs and skips it)
+//! ```
+//!
+//! The built-in bridges are registered through the same API by
+//! [`BridgeRegistry::with_builtins`]. Apps add their own at startup, either
+//! on the process-wide registry or on a registry passed to the dispatcher:
+//!
+//! ```rust,ignore
+//! use mofa_dora_bridge::registry::{self, BridgeRegistry};
+//!
+//! registry::global().write().register_prefix("mofa-metrics", |spec, shared| {
+//!     Ok(Box::new(MetricsBridge::new(&spec.id, shared)))
+//! });
+//! ```
+
+use crate::bridge::DoraBridge;
+use crate::error::BridgeResult;
+use crate::parser::MofaNodeSpec;
+use crate::shared_state::SharedDoraState;
+use crate::widgets::{
+    AecInputBridge, AsrListenerBridge, AudioInputBridge, AudioPlayerBridge, PromptInputBridge,
+    SystemLogBridge,
+};
+use crate::MofaNodeType;
+use parking_lot::RwLock;
+use std::collections::HashMap;
+use std::sync::{Arc, OnceLock};
+
+/// Factory creating a bridge for a discovered MoFA node
+pub type BridgeFactory = Arc<
+    dyn Fn(&MofaNodeSpec, Arc<SharedDoraState>) -> BridgeResult<Box<dyn DoraBridge>> + Send + Sync,
+>;
+
+/// Registry of bridge factories keyed by node id or node id prefix
+#[derive(Clone, Default)]
+pub struct BridgeRegistry {
+    /// Factories for exact node ids
+    exact: HashMap<String, BridgeFactory>,
+    /// Factories for node id prefixes
+    prefixes: Vec<(String, BridgeFactory)>,
+}
+
+impl BridgeRegistry {
+    /// Create an empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a registry with the built-in MoFA bridges
+    ///
+    /// `mofa-chat-viewer` and `mofa-participant-panel` have no bridge:
+    /// chat has no viewer bridge yet and participant levels are computed by
+    /// the audio player bridge.
+    pub fn with_builtins() -> Self {
+        let mut registry = Self::new();
+        registry
+            .register(MofaNodeType::AudioPlayer.node_id(), |spec, shared| {
+                Ok(Box::new(AudioPlayerBridge::with_shared_state(
+                    &spec.id,
+                    Some(shared),
+                )))
+            })
+            .register(MofaNodeType::SystemLog.node_id(), |spec, shared| {
+                Ok(Box::new(SystemLogBridge::with_shared_state(
+                    &spec.id,
+                    Some(shared),
+                )))
+            })
+            .register(MofaNodeType::PromptInput.node_id(), |spec, shared| {
+                Ok(Box::new(PromptInputBridge::with_shared_state(
+                    &spec.id,
+                    Some(shared),
+                )))
+            })
+            .register(MofaNodeType::MicInput.node_id(), |spec, shared| {
+                Ok(Box::new(AecInputBridge::with_shared_state(
+                    &spec.id,
+                    Some(shared),
+                )))
+            })
+            .register(MofaNodeType::AsrListener.node_id(), |spec, shared| {
+                Ok(Box::new(AsrListenerBridge::with_shared_state(
+                    &spec.id,
+                    Some(shared),
+                )))
+            })
+            .register(MofaNodeType::AudioInput.node_id(), |spec, shared| {
+                Ok(Box::new(AudioInputBridge::with_shared_state(
+                    &spec.id, shared,
+                )))
+            });
+        registry
+    }
+
+    /// Register a factory for an exact node id, replacing any previous one
+    pub fn register<F>(&mut self, node_id: impl Into<String>, factory: F) -> &mut Self
+    where
+        F: Fn(&MofaNodeSpec, Arc<SharedDoraState>) -> BridgeResult<Box<dyn DoraBridge>>
+            + Send
+            + Sync
+            + 'static,
+    {
+        self.exact.insert(node_id.into(), Arc::new(factory));
+        self
+    }
+
+    /// Register a factory for every node id starting with `prefix`
+    pub fn register_prefix<F>(&mut self, prefix: impl Into<String>, factory: F) -> &mut Self
+    where
+        F: Fn(&MofaNodeSpec, Arc<SharedDoraState>) -> BridgeResult<Box<dyn DoraBridge>>
+            + Send
+            + Sync
+            + 'static,
+    {
+        let prefix = prefix.into();
+        self.prefixes.retain(|(p, _)| *p != prefix);
+        self.prefixes.push((prefix, Arc::new(factory)));
+        self
+    }
+
+    /// Remove the factory for an exact node id or prefix
+    pub fn unregister(&mut self, key: &str) -> bool {
+        let removed_exact = self.exact.remove(key).is_some();
+        let before = self.prefixes.len();
+        self.prefixes.retain(|(p, _)| p != key);
+        removed_exact || self.prefixes.len() != before
+    }
+
+    /// Find the factory for a node id (exact match first, then longest prefix)
+    pub fn resolve(&self, node_id: &str) -> Option<&BridgeFactory> {
+        self.exact.get(node_id).or_else(|| {
+            self.prefixes
+                .iter()
+                .filter(|(prefix, _)| node_id.starts_with(prefix.as_str()))
+                .max_by_key(|(prefix, _)| prefix.len())
+                .map(|(_, factory)| factory)
+        })
+    }
+
+    /// Whether a bridge can be created for this node id
+    pub fn contains(&self, node_id: &str) -> bool {
+        self.resolve(node_id).is_some()
+    }
+
+    /// Create a bridge for a node, or `None` if nothing is registered for it
+    pub fn create(
+        &self,
+        spec: &MofaNodeSpec,
+        shared_state: Arc<SharedDoraState>,
+    ) -> Option<BridgeResult<Box<dyn DoraBridge>>> {
+        self.resolve(&spec.id)
+            .map(|factory| factory(spec, shared_state))
+    }
+}
+
+/// Process-wide registry used by dispatchers that were not given one
+///
+/// Starts with the built-in bridges; apps register their own at startup
+/// before the first dataflow is started.
+pub fn global() -> &'static RwLock<BridgeRegistry> {
+    static GLOBAL: OnceLock<RwLock<BridgeRegistry>> = OnceLock::new();
+    GLOBAL.get_or_init(|| RwLock::new(BridgeRegistry::with_builtins()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bridge::BridgeState;
+    use crate::data::DoraData;
+
+    struct NamedBridge(String);
+
+    impl DoraBridge for NamedBridge {
+        fn node_id(&self) -> &str {
+            &self.0
+        }
+        fn state(&self) -> BridgeState {
+            BridgeState::Disconnected
+        }
+        fn connect(&mut self) -> BridgeResult<()> {
+            Ok(())
+        }
+        fn disconnect(&mut self) -> BridgeResult<()> {
+            Ok(())
+        }
+        fn send(&self, _output_id: &str, _data: DoraData) -> BridgeResult<()> {
+            Ok(())
+        }
+        fn expected_inputs(&self) -> Vec<String> {
+            vec![]
+        }
+        fn expected_outputs(&self) -> Vec<String> {
+            vec![]
+        }
+    }
+
+    fn spec(id: &str) -> MofaNodeSpec {
+        MofaNodeSpec {
+            id: id.to_string(),
+            node_type: MofaNodeType::from_node_id(id),
+            inputs: vec![],
+            outputs: vec![],
+        }
+    }
+
+    fn tagged(
+        tag: &'static str,
+    ) -> impl Fn(&MofaNodeSpec, Arc<SharedDoraState>) -> BridgeResult<Box<dyn DoraBridge>> {
+        move |_, _| Ok(Box::new(NamedBridge(tag.to_string())))
+    }
+
+    fn created_tag(registry: &BridgeRegistry, id: &str) -> Option<String> {
+        registry
+            .create(&spec(id), SharedDoraState::new())
+            .map(|bridge| bridge.unwrap().node_id().to_string())
+    }
+
+    #[test]
+    fn test_exact_beats_prefix_and_longest_prefix_wins() {
+        let mut registry = BridgeRegistry::new();
+        registry
+            .register_prefix("mofa-", tagged("generic"))
+            .register_prefix("mofa-metrics", tagged("metrics"))
+            .register("mofa-metrics-special", tagged("special"));
+
+        assert_eq!(
+            created_tag(&registry, "mofa-metrics-special").as_deref(),
+            Some("special")
+        );
+        assert_eq!(
+            created_tag(&registry, "mofa-metrics-latency").as_deref(),
+            Some("metrics")
+        );
+        assert_eq!(
+            created_tag(&registry, "mofa-other").as_deref(),
+            Some("generic")
+        );
+        assert!(created_tag(&registry, "tts").is_none());
+
+        assert!(registry.unregister("mofa-"));
+        assert!(!registry.contains("mofa-other"));
+    }
+
+    #[test]
+    fn test_builtins_skip_viewer_only_nodes() {
+        let registry = BridgeRegistry::with_builtins();
+        assert!(registry.contains("mofa-audio-player"));
+        assert!(registry.contains("mofa-asr-listener"));
+        assert!(!registry.contains("mofa-chat-viewer"));
+        assert!(!registry.contains("mofa-participant-panel"));
+        assert!(!registry.contains("mofa-tts-request"));
+    }
+}
@@ -56,7 +56,10 @@ use std::collections::VecDeque;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 
-use crate::data::{AudioData, ChatMessage, LogEntry};
+use crate::data::{AudioData, ChatMessage, LogEntry, ModelStatus};
+use crate::controller::NodeStatus;
+use crate::outbox::ReplyTracker;
+use crate::recorder::Recorder;
 
 /// Thread-safe vector with dirty tracking and maximum size enforcement.
 ///
@@ -135,6 +138,15 @@ impl<T: Clone> DirtyVec<T> {
         self.data.read().clone()
     }
 
+    /// Take all data, leaving the collection empty
+    ///
+    /// For consumers that append entries to their own history (e.g. a log
+    /// view) and must not see the same entry twice.
+    pub fn drain(&self) -> Vec<T> {
+        self.dirty.store(false, Ordering::Release);
+        std::mem::take(&mut *self.data.write())
+    }
+
     /// Clear all data
     pub fn clear(&self) {
         self.data.write().clear();
@@ -656,6 +668,18 @@ pub struct SharedDoraState {
 
     /// ASR transcription result (language, text)
     pub asr_transcription: DirtyValue<Option<(String, String)>>,
+
+    /// Model status reported by the TTS node
+    pub model_status: DirtyValue<ModelStatus>,
+
+    /// Per-node health (state, exit code, recent log lines)
+    pub nodes: DirtyValue<Vec<NodeStatus>>,
+
+    /// Submitted requests waiting for their reply (any bridge may resolve them)
+    pub replies: ReplyTracker,
+
+    /// Bridge traffic recorder (see [`crate::recorder`])
+    recorder: RwLock<Option<Arc<Recorder>>>,
 }
 
 impl SharedDoraState {
@@ -668,6 +692,10 @@ impl SharedDoraState {
             status: DirtyValue::default(),
             mic: MicState::new(),
             asr_transcription: DirtyValue::default(),
+            model_status: DirtyValue::default(),
+            nodes: DirtyValue::default(),
+            replies: ReplyTracker::new(),
+            recorder: RwLock::new(Recorder::from_env()),
         })
     }
 
@@ -680,6 +708,10 @@ impl SharedDoraState {
             status: DirtyValue::default(),
             mic: MicState::new(),
             asr_transcription: DirtyValue::default(),
+            model_status: DirtyValue::default(),
+            nodes: DirtyValue::default(),
+            replies: ReplyTracker::new(),
+            recorder: RwLock::new(Recorder::from_env()),
         })
     }
 
@@ -691,6 +723,9 @@ impl SharedDoraState {
         self.status.set(DoraStatus::default());
         self.mic.clear();
         self.asr_transcription.set(None);
+        self.model_status.set(ModelStatus::Unknown);
+        self.nodes.set(Vec::new());
+        self.replies.cancel_all();
     }
 
     /// Add active bridge
@@ -709,6 +744,16 @@ impl SharedDoraState {
         self.status.set(status);
     }
 
+    /// Start or stop recording bridge traffic
+    pub fn set_recorder(&self, recorder: Option<Arc<Recorder>>) {
+        *self.recorder.write() = recorder;
+    }
+
+    /// Active bridge traffic recorder, if any
+    pub fn recorder(&self) -> Option<Arc<Recorder>> {
+        self.recorder.read().clone()
+    }
+
     /// Set error status
     pub fn set_error(&self, error: Option<String>) {
         let mut status = self.status.read();
@@ -726,6 +771,10 @@ impl Default for SharedDoraState {
             status: DirtyValue::default(),
             mic: MicState::new(),
             asr_transcription: DirtyValue::default(),
+            model_status: DirtyValue::default(),
+            nodes: DirtyValue::default(),
+            replies: ReplyTracker::new(),
+            recorder: RwLock::new(Recorder::from_env()),
         }
     }
 }
@@ -0,0 +1,163 @@
+//! Transport between a bridge and the dataflow
+//!
+//! Bridges don't talk to `DoraNode` directly. Their worker thread asks a
+//! [`Connector`] for a [`Transport`] and then only sends [`NodeOutput`]s and
+//! receives [`TransportEvent`]s:
+//!
+//! | Connector | Transport | Use |
+//! |-----------|-----------|-----|
+//! | [`DoraConnector`] | [`DoraTransport`] | Dynamic node of a running dataflow (default) |
+//! | [`Loopback`](crate::loopback::Loopback) | [`LoopbackTransport`](crate::loopback::LoopbackTransport) | In-memory dataflow for tests |
+//!
+//! [`open`] wraps whichever transport it gets so traffic is written to the
+//! [`Recorder`](crate::recorder::Recorder) installed on
+//! [`SharedDoraState`], if any.
+
+use crate::error::{BridgeError, BridgeResult};
+use crate::recorder::NodeOutput;
+use crate::shared_state::SharedDoraState;
+use arrow::array::ArrayRef;
+use dora_node_api::{
+    dora_core::config::{DataId, NodeId},
+    DoraNode, Event, EventStream, MetadataParameters,
+};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Something a bridge receives from the dataflow
+#[derive(Debug, Clone)]
+pub enum TransportEvent {
+    /// Data on one of the node's inputs
+    Input {
+        id: String,
+        metadata: MetadataParameters,
+        data: ArrayRef,
+    },
+    /// An upstream node closed the input
+    InputClosed { id: String },
+    /// The dataflow asked the node to stop
+    Stop,
+    /// The runtime reported an error
+    Error(String),
+}
+
+/// A connected node: sends outputs and receives events
+///
+/// Owned by the bridge's worker thread.
+pub trait Transport: Send {
+    /// Node id this transport is connected as
+    fn node_id(&self) -> &str;
+
+    /// Send data to one of the node's outputs
+    fn send_output(&mut self, output: NodeOutput) -> BridgeResult<()>;
+
+    /// Wait up to `timeout` for the next event
+    fn recv_timeout(&mut self, timeout: Duration) -> Option<TransportEvent>;
+}
+
+/// Creates transports for bridges
+pub trait Connector: Send + Sync {
+    /// Connect as `node_id`
+    fn connect(&self, node_id: &str) -> BridgeResult<Box<dyn Transport>>;
+}
+
+/// Connects bridges to a running dataflow as dora dynamic nodes
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DoraConnector;
+
+impl Connector for DoraConnector {
+    fn connect(&self, node_id: &str) -> BridgeResult<Box<dyn Transport>> {
+        let (node, events) = DoraNode::init_from_node_id(NodeId::from(node_id.to_string()))
+            .map_err(|e| BridgeError::ConnectionFailed(e.to_string()))?;
+        Ok(Box::new(DoraTransport {
+            node_id: node_id.to_string(),
+            node,
+            events,
+        }))
+    }
+}
+
+/// A dora dynamic node and its event stream
+pub struct DoraTransport {
+    node_id: String,
+    node: DoraNode,
+    events: EventStream,
+}
+
+impl Transport for DoraTransport {
+    fn node_id(&self) -> &str {
+        &self.node_id
+    }
+
+    fn send_output(&mut self, output: NodeOutput) -> BridgeResult<()> {
+        let output_id: DataId = output.id.into();
+        self.node
+            .send_output(output_id, output.parameters, output.data)
+            .map_err(|e| BridgeError::SendFailed(e.to_string()))
+    }
+
+    fn recv_timeout(&mut self, timeout: Duration) -> Option<TransportEvent> {
+        match self.events.recv_timeout(timeout)? {
+            Event::Input { id, metadata, data } => Some(TransportEvent::Input {
+                id: id.to_string(),
+                metadata: metadata.parameters,
+                data: data.0,
+            }),
+            Event::InputClosed { id } => Some(TransportEvent::InputClosed { id: id.to_string() }),
+            Event::Stop(_) => Some(TransportEvent::Stop),
+            Event::Error(e) => Some(TransportEvent::Error(e)),
+            _ => None,
+        }
+    }
+}
+
+/// Connect `node_id` through `connector`, recording its traffic whenever
+/// `shared_state` has a recorder installed
+pub fn open(
+    connector: &dyn Connector,
+    node_id: &str,
+    shared_state: Option<&Arc<SharedDoraState>>,
+) -> BridgeResult<Box<dyn Transport>> {
+    let inner = connector.connect(node_id)?;
+    Ok(match shared_state {
+        Some(ss) => Box::new(Recorded {
+            inner,
+            shared_state: Arc::clone(ss),
+        }),
+        None => inner,
+    })
+}
+
+/// Transport that mirrors its traffic to the shared recorder
+struct Recorded {
+    inner: Box<dyn Transport>,
+    shared_state: Arc<SharedDoraState>,
+}
+
+impl Transport for Recorded {
+    fn node_id(&self) -> &str {
+        self.inner.node_id()
+    }
+
+    fn send_output(&mut self, output: NodeOutput) -> BridgeResult<()> {
+        if let Some(rec) = self.shared_state.recorder() {
+            rec.output(
+                self.inner.node_id(),
+                &output.id,
+                &output.parameters,
+                output.data.as_ref(),
+            );
+        }
+        self.inner.send_output(output)
+    }
+
+    fn recv_timeout(&mut self, timeout: Duration) -> Option<TransportEvent> {
+        let event = self.inner.recv_timeout(timeout)?;
+        if let TransportEvent::Input { id, metadata, data } = &event {
+            if let Some(rec) = self.shared_state.recorder() {
+                rec.input(self.inner.node_id(), id, metadata, data.as_ref());
+            }
+        }
+        Some(event)
+    }
+}
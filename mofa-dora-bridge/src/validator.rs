@@ -0,0 +1,759 @@
+//! Dataflow validation
+//!
+//! Checks a dataflow YAML for wiring mistakes before it is started:
+//! - Nodes the parser can't classify (and would otherwise silently drop)
+//! - Duplicate node ids
+//! - Dangling inputs, unknown source nodes and unknown source outputs
+//! - MoFA nodes whose declared ports don't match their bridge's
+//!   `expected_inputs()` / `expected_outputs()`
+//! - Missing env vars
+//!
+//! Diagnostics carry the 1-based line of the offending node, input or env
+//! entry so they can be shown next to the file.
+//!
+//! ```rust,ignore
+//! use mofa_dora_bridge::validator::DataflowValidator;
+//!
+//! let report = DataflowValidator::new().validate_file("dataflow/tts.yml")?;
+//! for diagnostic in &report.diagnostics {
+//!     println!("{}", diagnostic);
+//! }
+//! if report.has_errors() {
+//!     return Err("dataflow is invalid".into());
+//! }
+//! ```
+
+use crate::error::BridgeResult;
+use crate::parser::{DataflowParser, MofaNodeSpec, ParsedDataflow};
+use crate::registry::{self, BridgeRegistry};
+use crate::shared_state::SharedDoraState;
+use crate::MofaNodeType;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// Diagnostic severity
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    /// Worth knowing, the dataflow works
+    Info,
+    /// Likely a mistake, the dataflow may still start
+    Warning,
+    /// The dataflow won't work as written
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Info => write!(f, "info"),
+            Severity::Warning => write!(f, "warning"),
+            Severity::Error => write!(f, "error"),
+        }
+    }
+}
+
+/// What a diagnostic is about
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiagnosticKind {
+    /// Node entry without an id, or without `path`, `operator` or `custom`
+    UnclassifiedNode { node_id: Option<String> },
+    /// Node id used more than once
+    DuplicateNodeId { node_id: String },
+    /// Input source isn't of the form `node/output`
+    DanglingInput {
+        node_id: String,
+        input_id: String,
+        source: String,
+    },
+    /// Input refers to a node that doesn't exist
+    UnknownSourceNode {
+        node_id: String,
+        input_id: String,
+        source_node: String,
+    },
+    /// Input refers to an output its source node doesn't declare
+    UnknownSourceOutput {
+        node_id: String,
+        input_id: String,
+        source_node: String,
+        output: String,
+    },
+    /// MoFA node has no registered bridge
+    NoBridge { node_id: String },
+    /// MoFA node declares an output its bridge never sends
+    UnexpectedBridgeOutput { node_id: String, output: String },
+    /// Bridge can send an output the MoFA node doesn't declare
+    UndeclaredBridgeOutput { node_id: String, output: String },
+    /// MoFA node receives an input its bridge ignores
+    UnexpectedBridgeInput { node_id: String, input_id: String },
+    /// Required env var is not set
+    MissingEnvVar { key: String, used_by: Vec<String> },
+}
+
+impl fmt::Display for DiagnosticKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DiagnosticKind::UnclassifiedNode { node_id: Some(id) } => write!(
+                f,
+                "node '{}' has no path, operator or custom section and is ignored",
+                id
+            ),
+            DiagnosticKind::UnclassifiedNode { node_id: None } => {
+                write!(f, "node without an id is ignored")
+            }
+            DiagnosticKind::DuplicateNodeId { node_id } => {
+                write!(f, "node id '{}' is used more than once", node_id)
+            }
+            DiagnosticKind::DanglingInput {
+                node_id,
+                input_id,
+                source,
+            } => write!(
+                f,
+                "input '{}/{}' has source '{}', expected 'node/output'",
+                node_id, input_id, source
+            ),
+            DiagnosticKind::UnknownSourceNode {
+                node_id,
+                input_id,
+                source_node,
+            } => write!(
+                f,
+                "input '{}/{}' refers to unknown node '{}'",
+                node_id, input_id, source_node
+            ),
+            DiagnosticKind::UnknownSourceOutput {
+                node_id,
+                input_id,
+                source_node,
+                output,
+            } => write!(
+                f,
+                "input '{}/{}' refers to '{}/{}', but '{}' has no output '{}'",
+                node_id, input_id, source_node, output, source_node, output
+            ),
+            DiagnosticKind::NoBridge { node_id } => {
+                write!(f, "no bridge registered for MoFA node '{}'", node_id)
+            }
+            DiagnosticKind::UnexpectedBridgeOutput { node_id, output } => write!(
+                f,
+                "'{}' declares output '{}' which its bridge never sends",
+                node_id, output
+            ),
+            DiagnosticKind::UndeclaredBridgeOutput { node_id, output } => write!(
+                f,
+                "bridge for '{}' can send '{}' but the node doesn't declare it",
+                node_id, output
+            ),
+            DiagnosticKind::UnexpectedBridgeInput { node_id, input_id } => write!(
+                f,
+                "'{}' receives input '{}' which its bridge ignores",
+                node_id, input_id
+            ),
+            DiagnosticKind::MissingEnvVar { key, used_by } => write!(
+                f,
+                "env var '{}' is not set (used by {})",
+                key,
+                used_by.join(", ")
+            ),
+        }
+    }
+}
+
+/// A single validation finding
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub kind: DiagnosticKind,
+    /// 1-based line in the dataflow file, when it could be located
+    pub line: Option<usize>,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "{} (line {}): {}", self.severity, line, self.kind),
+            None => write!(f, "{}: {}", self.severity, self.kind),
+        }
+    }
+}
+
+/// Result of validating a dataflow
+#[derive(Debug, Clone, Default)]
+pub struct ValidationReport {
+    /// Dataflow file path
+    pub path: PathBuf,
+    /// Findings, ordered by line
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl ValidationReport {
+    /// Whether any finding prevents the dataflow from working
+    pub fn has_errors(&self) -> bool {
+        self.diagnostics
+            .iter()
+            .any(|d| d.severity == Severity::Error)
+    }
+
+    /// Findings at or above a severity
+    pub fn at_least(&self, severity: Severity) -> impl Iterator<Item = &Diagnostic> {
+        self.diagnostics
+            .iter()
+            .filter(move |d| d.severity >= severity)
+    }
+
+    /// Whether nothing was found
+    pub fn is_clean(&self) -> bool {
+        self.diagnostics.is_empty()
+    }
+}
+
+impl fmt::Display for ValidationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for diagnostic in &self.diagnostics {
+            writeln!(f, "{}: {}", self.path.display(), diagnostic)?;
+        }
+        Ok(())
+    }
+}
+
+/// Dataflow validator
+///
+/// Uses the process-wide [`BridgeRegistry`] unless one is given, and checks
+/// env vars against the process environment plus any configured values.
+#[derive(Clone)]
+pub struct DataflowValidator {
+    registry: Option<BridgeRegistry>,
+    env: HashMap<String, String>,
+}
+
+impl Default for DataflowValidator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DataflowValidator {
+    /// Create a validator using the global registry
+    pub fn new() -> Self {
+        Self {
+            registry: None,
+            env: HashMap::new(),
+        }
+    }
+
+    /// Check bridges against a specific registry
+    pub fn with_registry(mut self, registry: BridgeRegistry) -> Self {
+        self.registry = Some(registry);
+        self
+    }
+
+    /// Env vars that will be set for the dataflow
+    pub fn with_env(mut self, env: HashMap<String, String>) -> Self {
+        self.env = env;
+        self
+    }
+
+    /// Validate a dataflow file
+    pub fn validate_file(&self, path: impl AsRef<Path>) -> BridgeResult<ValidationReport> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path)?;
+        self.validate_str(&content, path.to_path_buf())
+    }
+
+    /// Validate dataflow YAML
+    pub fn validate_str(&self, yaml: &str, path: PathBuf) -> BridgeResult<ValidationReport> {
+        let parsed = DataflowParser::parse_string(yaml, path)?;
+        Ok(self.validate_parsed(&parsed, yaml))
+    }
+
+    /// Validate an already parsed dataflow; `yaml` is its source, used for line numbers
+    pub fn validate_parsed(&self, parsed: &ParsedDataflow, yaml: &str) -> ValidationReport {
+        let lines = LineIndex::new(yaml);
+        let mut diagnostics = Vec::new();
+
+        self.check_nodes(parsed, &lines, &mut diagnostics);
+        self.check_inputs(parsed, &lines, &mut diagnostics);
+        match &self.registry {
+            Some(registry) => self.check_bridges(parsed, registry, &lines, &mut diagnostics),
+            None => {
+                let registry = registry::global().read();
+                self.check_bridges(parsed, &registry, &lines, &mut diagnostics)
+            }
+        }
+        self.check_env(parsed, &lines, &mut diagnostics);
+
+        diagnostics.sort_by_key(|d| d.line.unwrap_or(usize::MAX));
+        ValidationReport {
+            path: parsed.path.clone(),
+            diagnostics,
+        }
+    }
+
+    /// Unclassified nodes and duplicate ids
+    fn check_nodes(&self, parsed: &ParsedDataflow, lines: &LineIndex, out: &mut Vec<Diagnostic>) {
+        let raw_nodes = parsed
+            .raw_yaml
+            .get("nodes")
+            .and_then(|n| n.as_sequence())
+            .map(|s| s.as_slice())
+            .unwrap_or_default();
+
+        let mut seen = HashSet::new();
+        for (index, raw) in raw_nodes.iter().enumerate() {
+            let id = raw.get("id").and_then(|v| v.as_str()).map(String::from);
+            let line = lines.node_line(index);
+
+            let classified = id
+                .as_ref()
+                .map(|id| parsed.nodes.iter().any(|n| &n.id == id))
+                .unwrap_or(false);
+            if !classified {
+                out.push(Diagnostic {
+                    severity: Severity::Warning,
+                    kind: DiagnosticKind::UnclassifiedNode {
+                        node_id: id.clone(),
+                    },
+                    line,
+                });
+            }
+
+            if let Some(id) = id {
+                if !seen.insert(id.clone()) {
+                    out.push(Diagnostic {
+                        severity: Severity::Error,
+                        kind: DiagnosticKind::DuplicateNodeId { node_id: id },
+                        line,
+                    });
+                }
+            }
+        }
+    }
+
+    /// Input sources must name an existing node and one of its outputs
+    fn check_inputs(&self, parsed: &ParsedDataflow, lines: &LineIndex, out: &mut Vec<Diagnostic>) {
+        for node in &parsed.nodes {
+            for input in &node.inputs {
+                let line = lines.key_line(&node.id, &input.id);
+
+                // Built-in sources such as dora/timer/secs/1
+                if input.source.starts_with("dora/") {
+                    continue;
+                }
+
+                let Some((source_node, output)) = input.source.split_once('/') else {
+                    out.push(Diagnostic {
+                        severity: Severity::Error,
+                        kind: DiagnosticKind::DanglingInput {
+                            node_id: node.id.clone(),
+                            input_id: input.id.clone(),
+                            source: input.source.clone(),
+                        },
+                        line,
+                    });
+                    continue;
+                };
+
+                match parsed.get_node(source_node) {
+                    None => out.push(Diagnostic {
+                        severity: Severity::Error,
+                        kind: DiagnosticKind::UnknownSourceNode {
+                            node_id: node.id.clone(),
+                            input_id: input.id.clone(),
+                            source_node: source_node.to_string(),
+                        },
+                        line,
+                    }),
+                    Some(source) if !source.outputs.iter().any(|o| o == output) => {
+                        out.push(Diagnostic {
+                            severity: Severity::Error,
+                            kind: DiagnosticKind::UnknownSourceOutput {
+                                node_id: node.id.clone(),
+                                input_id: input.id.clone(),
+                                source_node: source_node.to_string(),
+                                output: output.to_string(),
+                            },
+                            line,
+                        })
+                    }
+                    Some(_) => {}
+                }
+            }
+        }
+    }
+
+    /// MoFA node ports must match what their bridge handles
+    fn check_bridges(
+        &self,
+        parsed: &ParsedDataflow,
+        registry: &BridgeRegistry,
+        lines: &LineIndex,
+        out: &mut Vec<Diagnostic>,
+    ) {
+        for spec in &parsed.mofa_nodes {
+            let node_line = lines.node_line_by_id(&spec.id);
+
+            let (inputs, outputs) = match bridge_ports(spec, registry) {
+                Some(ports) => ports,
+                None => {
+                    // Viewer-only built-ins intentionally have no bridge
+                    let viewer_only = matches!(
+                        spec.node_type,
+                        Some(MofaNodeType::ChatViewer | MofaNodeType::ParticipantPanel)
+                    );
+                    if !viewer_only {
+                        out.push(Diagnostic {
+                            severity: Severity::Warning,
+                            kind: DiagnosticKind::NoBridge {
+                                node_id: spec.id.clone(),
+                            },
+                            line: node_line,
+                        });
+                    }
+                    continue;
+                }
+            };
+
+            for output in &spec.outputs {
+                if !outputs.contains(output) {
+                    out.push(Diagnostic {
+                        severity: Severity::Warning,
+                        kind: DiagnosticKind::UnexpectedBridgeOutput {
+                            node_id: spec.id.clone(),
+                            output: output.clone(),
+                        },
+                        line: lines.list_item_line(&spec.id, output).or(node_line),
+                    });
+                }
+            }
+
+            for output in &outputs {
+                if !spec.outputs.contains(output) {
+                    out.push(Diagnostic {
+                        severity: Severity::Info,
+                        kind: DiagnosticKind::UndeclaredBridgeOutput {
+                            node_id: spec.id.clone(),
+                            output: output.clone(),
+                        },
+                        line: node_line,
+                    });
+                }
+            }
+
+            // Bridges with no declared inputs accept whatever they are wired to (e.g. system log)
+            if !inputs.is_empty() {
+                for input in &spec.inputs {
+                    if !inputs.contains(&input.id) {
+                        out.push(Diagnostic {
+                            severity: Severity::Warning,
+                            kind: DiagnosticKind::UnexpectedBridgeInput {
+                                node_id: spec.id.clone(),
+                                input_id: input.id.clone(),
+                            },
+                            line: lines.key_line(&spec.id, &input.id),
+                        });
+                    }
+                }
+            }
+        }
+    }
+
+    /// Required env vars and `$VAR` references in env values must be set
+    fn check_env(&self, parsed: &ParsedDataflow, lines: &LineIndex, out: &mut Vec<Diagnostic>) {
+        let is_set = |key: &str| self.env.contains_key(key) || std::env::var(key).is_ok();
+
+        for req in parsed.env_requirements.iter().filter(|r| r.required) {
+            if !is_set(&req.key) {
+                out.push(Diagnostic {
+                    severity: Severity::Error,
+                    kind: DiagnosticKind::MissingEnvVar {
+                        key: req.key.clone(),
+                        used_by: req.used_by.clone(),
+                    },
+                    line: req
+                        .used_by
+                        .first()
+                        .and_then(|node| lines.key_line(node, &req.key)),
+                });
+            }
+        }
+
+        // Interpolated references, e.g. `$HOME/.dora/models`
+        let mut reported = HashSet::new();
+        for node in &parsed.nodes {
+            let mut keys: Vec<&String> = node.env.keys().collect();
+            keys.sort();
+            for key in keys {
+                for var in referenced_vars(&node.env[key]) {
+                    if var == *key || is_set(&var) || !reported.insert(var.clone()) {
+                        continue;
+                    }
+                    out.push(Diagnostic {
+                        severity: Severity::Warning,
+                        kind: DiagnosticKind::MissingEnvVar {
+                            key: var,
+                            used_by: vec![node.id.clone()],
+                        },
+                        line: lines.key_line(&node.id, key),
+                    });
+                }
+            }
+        }
+    }
+}
+
+/// Inputs and outputs handled by the bridge registered for a MoFA node
+fn bridge_ports(
+    spec: &MofaNodeSpec,
+    registry: &BridgeRegistry,
+) -> Option<(Vec<String>, Vec<String>)> {
+    // Bridges are only constructed, never connected, so this has no side effects
+    let bridge = registry.create(spec, SharedDoraState::new())?.ok()?;
+    Some((bridge.expected_inputs(), bridge.expected_outputs()))
+}
+
+/// Env var names referenced with `$VAR` or `${VAR}` / `${VAR:-default}`
+fn referenced_vars(value: &str) -> Vec<String> {
+    let mut vars = Vec::new();
+    let mut rest = value;
+    while let Some(pos) = rest.find('$') {
+        rest = &rest[pos + 1..];
+        if let Some(inner) = rest.strip_prefix('{') {
+            let Some(end) = inner.find('}') else { break };
+            let body = &inner[..end];
+            // Vars with a default don't need to be set
+            if !body.contains(":-") && !body.is_empty() {
+                vars.push(body.to_string());
+            }
+            rest = &inner[end + 1..];
+        } else {
+            let name: String = rest
+                .chars()
+                .take_while(|c| c.is_ascii_alphanumeric() || *c == '_')
+                .collect();
+            if !name.is_empty() {
+                rest = &rest[name.len()..];
+                vars.push(name);
+            }
+        }
+    }
+    vars
+}
+
+/// Line lookup for nodes and their keys in dataflow YAML
+///
+/// serde_yaml doesn't keep spans, so this scans the text: every `- ` item
+/// directly under `nodes:` starts a node block.
+struct LineIndex<'a> {
+    lines: Vec<&'a str>,
+    /// (start line index, node id) per node block, in file order
+    blocks: Vec<(usize, Option<String>)>,
+}
+
+impl<'a> LineIndex<'a> {
+    fn new(yaml: &'a str) -> Self {
+        let lines: Vec<&str> = yaml.lines().collect();
+        let mut blocks = Vec::new();
+
+        let nodes_start = lines
+            .iter()
+            .position(|l| l.trim_end() == "nodes:")
+            .map(|i| i + 1);
+        if let Some(start) = nodes_start {
+            let mut item_indent = None;
+            for (i, line) in lines.iter().enumerate().skip(start) {
+                let trimmed = line.trim_start();
+                if trimmed.is_empty() || trimmed.starts_with('#') {
+                    continue;
+                }
+                let indent = line.len() - trimmed.len();
+                // A top-level key ends the nodes list
+                if indent == 0 && !trimmed.starts_with("- ") {
+                    break;
+                }
+                if trimmed.starts_with("- ") && *item_indent.get_or_insert(indent) == indent {
+                    blocks.push((i, None));
+                }
+                if let Some((_, id @ None)) = blocks.last_mut() {
+                    let item = trimmed.trim_start_matches("- ");
+                    if let Some(value) = item.strip_prefix("id:") {
+                        *id = Some(strip_value(value).to_string());
+                    }
+                }
+            }
+        }
+
+        Self { lines, blocks }
+    }
+
+    /// Line range of a node block
+    fn block(&self, index: usize) -> (usize, usize) {
+        let start = self.blocks[index].0;
+        let end = self
+            .blocks
+            .get(index + 1)
+            .map(|(s, _)| *s)
+            .unwrap_or(self.lines.len());
+        (start, end)
+    }
+
+    fn block_by_id(&self, node_id: &str) -> Option<(usize, usize)> {
+        self.blocks
+            .iter()
+            .position(|(_, id)| id.as_deref() == Some(node_id))
+            .map(|i| self.block(i))
+    }
+
+    /// Line of the n-th node entry
+    fn node_line(&self, index: usize) -> Option<usize> {
+        self.blocks.get(index).map(|(start, _)| start + 1)
+    }
+
+    /// Line of the first node with this id
+    fn node_line_by_id(&self, node_id: &str) -> Option<usize> {
+        self.block_by_id(node_id).map(|(start, _)| start + 1)
+    }
+
+    /// Line of `key:` inside a node block (inputs, env entries)
+    fn key_line(&self, node_id: &str, key: &str) -> Option<usize> {
+        let (start, end) = self.block_by_id(node_id)?;
+        let prefix = format!("{}:", key);
+        (start..end)
+            .find(|&i| {
+                self.lines[i]
+                    .trim_start()
+                    .trim_start_matches("- ")
+                    .starts_with(&prefix)
+            })
+            .map(|i| i + 1)
+    }
+
+    /// Line of `- item` inside a node block (outputs)
+    fn list_item_line(&self, node_id: &str, item: &str) -> Option<usize> {
+        let (start, end) = self.block_by_id(node_id)?;
+        (start..end)
+            .find(|&i| {
+                self.lines[i]
+                    .trim_start()
+                    .strip_prefix("- ")
+                    .map(|v| strip_value(v) == item)
+                    .unwrap_or(false)
+            })
+            .map(|i| i + 1)
+    }
+}
+
+/// YAML scalar without surrounding whitespace, quotes or trailing comment
+fn strip_value(value: &str) -> &str {
+    let value = value.split(" #").next().unwrap_or(value).trim();
+    value.trim_matches(|c| c == '"' || c == '\'')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const YAML: &str = r#"nodes:
+  - id: mofa-prompt-input
+    path: dynamic
+    outputs:
+      - prompt
+
+  - id: tts
+    path: dora-primespeech
+    inputs:
+      text: mofa-prompt-input/control
+      tick: dora/timer/secs/1
+      voice: missing-node/voice
+    outputs:
+      - audio
+    env:
+      MODEL_DIR: $MOFA_TEST_UNSET_DIR/models
+
+  - id: tts
+    custom:
+      source: other
+
+  - id: broken
+    inputs:
+      x: tts/audio
+"#;
+
+    fn validate() -> ValidationReport {
+        DataflowValidator::new()
+            .with_registry(BridgeRegistry::with_builtins())
+            .validate_str(YAML, PathBuf::from("test.yml"))
+            .unwrap()
+    }
+
+    fn find(report: &ValidationReport, pred: impl Fn(&DiagnosticKind) -> bool) -> &Diagnostic {
+        report
+            .diagnostics
+            .iter()
+            .find(|d| pred(&d.kind))
+            .unwrap_or_else(|| panic!("diagnostic not found in:\n{}", report))
+    }
+
+    #[test]
+    fn test_reports_wiring_errors_with_lines() {
+        let report = validate();
+        assert!(report.has_errors());
+
+        let output = find(
+            &report,
+            |k| matches!(k, DiagnosticKind::UnknownSourceOutput { output, .. } if output == "control"),
+        );
+        assert_eq!(output.line, Some(10));
+
+        let node = find(
+            &report,
+            |k| matches!(k, DiagnosticKind::UnknownSourceNode { source_node, .. } if source_node == "missing-node"),
+        );
+        assert_eq!(node.line, Some(12));
+
+        let duplicate = find(&report, |k| {
+            matches!(k, DiagnosticKind::DuplicateNodeId { .. })
+        });
+        assert_eq!(duplicate.line, Some(18));
+
+        let unclassified = find(
+            &report,
+            |k| matches!(k, DiagnosticKind::UnclassifiedNode { node_id: Some(id) } if id == "broken"),
+        );
+        assert_eq!(unclassified.line, Some(22));
+
+        // The timer input is a built-in source
+        assert!(!report.diagnostics.iter().any(|d| matches!(
+            &d.kind,
+            DiagnosticKind::UnknownSourceNode { input_id, .. } if input_id == "tick"
+        )));
+    }
+
+    #[test]
+    fn test_reports_bridge_mismatch_and_env() {
+        let report = validate();
+
+        // PromptInputBridge sends on "control", not "prompt"
+        let mismatch = find(
+            &report,
+            |k| matches!(k, DiagnosticKind::UnexpectedBridgeOutput { output, .. } if output == "prompt"),
+        );
+        assert_eq!(mismatch.line, Some(5));
+
+        let env = find(
+            &report,
+            |k| matches!(k, DiagnosticKind::MissingEnvVar { key, .. } if key == "MOFA_TEST_UNSET_DIR"),
+        );
+        assert_eq!(env.severity, Severity::Warning);
+        assert_eq!(env.line, Some(16));
+    }
+
+    #[test]
+    fn test_referenced_vars() {
+        assert_eq!(referenced_vars("$HOME/.dora/models"), vec!["HOME"]);
+        assert_eq!(referenced_vars("${API_KEY}"), vec!["API_KEY"]);
+        assert!(referenced_vars("${LEVEL:-INFO}").is_empty());
+        assert!(referenced_vars("plain").is_empty());
+    }
+}
@@ -9,14 +9,14 @@
 //! - Audio segments for ASR
 
 use crate::bridge::{BridgeState, DoraBridge};
+use crate::codec;
 use crate::data::DoraData;
 use crate::error::{BridgeError, BridgeResult};
+use crate::recorder::NodeOutput;
 use crate::shared_state::SharedDoraState;
+use crate::transport::{self, Connector, DoraConnector, Transport, TransportEvent};
 use crossbeam_channel::{bounded, Receiver, Sender};
-use dora_node_api::{
-    dora_core::config::{DataId, NodeId},
-    DoraNode, Event, IntoArrow, Parameter,
-};
+use dora_node_api::{IntoArrow, Parameter};
 use libloading::{Library, Symbol};
 use parking_lot::RwLock;
 use std::collections::BTreeMap;
@@ -27,6 +27,9 @@ use std::thread;
 use std::time::{Duration, Instant};
 use tracing::{debug, error, info, warn};
 
+/// Sample rate of both capture paths (AEC library and CPAL fallback)
+const CAPTURE_SAMPLE_RATE: u32 = 16000;
+
 /// Control commands for AEC input
 #[derive(Debug, Clone)]
 pub enum AecControlCommand {
@@ -255,7 +258,7 @@ impl CpalMicCapture {
             stream: None,
             audio_buffer: Arc::new(parking_lot::Mutex::new(Vec::new())),
             is_recording: false,
-            sample_rate: 16000,
+            sample_rate: CAPTURE_SAMPLE_RATE,
             vad_threshold: 0.01, // Simple energy-based VAD threshold
         })
     }
@@ -363,6 +366,7 @@ pub struct AecInputBridge {
     worker_handle: Option<thread::JoinHandle<()>>,
     is_recording: Arc<AtomicBool>,
     aec_enabled: Arc<AtomicBool>,
+    connector: Arc<dyn Connector>,
 }
 
 impl AecInputBridge {
@@ -383,9 +387,16 @@ impl AecInputBridge {
             worker_handle: None,
             is_recording: Arc::new(AtomicBool::new(false)),
             aec_enabled: Arc::new(AtomicBool::new(false)), // Default to CPAL (safer startup)
+            connector: Arc::new(DoraConnector),
         }
     }
 
+    /// Connect through `connector` instead of dora (e.g. a [`Loopback`](crate::loopback::Loopback))
+    pub fn with_connector(mut self, connector: Arc<dyn Connector>) -> Self {
+        self.connector = connector;
+        self
+    }
+
     /// Send control command (from UI)
     pub fn send_control(&self, cmd: AecControlCommand) -> BridgeResult<()> {
         self.control_sender
@@ -443,6 +454,7 @@ impl AecInputBridge {
         stop_receiver: Receiver<()>,
         is_recording: Arc<AtomicBool>,
         aec_enabled: Arc<AtomicBool>,
+        connector: Arc<dyn Connector>,
     ) {
         eprintln!("[AecInput] Starting event loop for {}", node_id);
 
@@ -486,11 +498,11 @@ impl AecInputBridge {
 
         // Initialize dora node
         eprintln!("[AecInput] Initializing dora node for {}", node_id);
-        let (mut node, mut events) =
-            match DoraNode::init_from_node_id(NodeId::from(node_id.clone())) {
-                Ok(n) => {
+        let mut transport =
+            match transport::open(connector.as_ref(), &node_id, shared_state.as_ref()) {
+                Ok(t) => {
                     eprintln!("[AecInput] Dora node init SUCCESS for {}", node_id);
-                    n
+                    t
                 }
                 Err(e) => {
                     eprintln!("[AecInput] FAILED to init dora node {}: {}", node_id, e);
@@ -515,7 +527,7 @@ impl AecInputBridge {
 
         // Log config on startup (matching Python behavior)
         let _ = Self::send_log(
-            &mut node,
+            transport.as_mut(),
             &node_id,
             "INFO",
             &format!(
@@ -526,7 +538,7 @@ impl AecInputBridge {
         let speech_end_ms = vad_state.speech_end_threshold * 10; // ~10ms per frame
         let total_silence_ms = speech_end_ms as f64 + vad_state.question_end_silence_ms;
         let _ = Self::send_log(
-            &mut node,
+            transport.as_mut(),
             &node_id,
             "INFO",
             &format!(
@@ -543,12 +555,12 @@ impl AecInputBridge {
             if let Some(ref mut aec) = aec_capture {
                 aec.start();
             }
-            let _ = Self::send_log(&mut node, &node_id, "INFO", "🎙️ Recording started with AEC (echo cancellation ON)");
+            let _ = Self::send_log(transport.as_mut(), &node_id, "INFO", "🎙️ Recording started with AEC (echo cancellation ON)");
         } else {
             if let Err(e) = cpal_capture.start() {
                 error!("Failed to start CPAL capture: {}", e);
             }
-            let _ = Self::send_log(&mut node, &node_id, "INFO", "🎙️ Recording started without AEC (regular mic)");
+            let _ = Self::send_log(transport.as_mut(), &node_id, "INFO", "🎙️ Recording started without AEC (regular mic)");
         }
         is_recording.store(true, Ordering::Release);
         recording_active = true;
@@ -560,15 +572,15 @@ impl AecInputBridge {
         }
 
         let _ = Self::send_log(
-            &mut node,
+            transport.as_mut(),
             &node_id,
             "INFO",
             "Node ready - outputting: audio, is_speaking, speech_started, speech_ended, audio_segment, question_ended",
         );
 
         // Send initial status
-        let _ = Self::send_status(&mut node, "recording");
-        let _ = Self::send_log(&mut node, &node_id, "INFO", "🎙️ Mic recording STARTED (auto-start on connect)");
+        let _ = Self::send_status(transport.as_mut(), "recording");
+        let _ = Self::send_log(transport.as_mut(), &node_id, "INFO", "🎙️ Mic recording STARTED (auto-start on connect)");
 
         // Main event loop
         let poll_interval = Duration::from_millis(10);
@@ -592,19 +604,19 @@ impl AecInputBridge {
                                 if let Some(ref mut aec) = aec_capture {
                                     aec.start();
                                 }
-                                let _ = Self::send_log(&mut node, &node_id, "INFO", "🎙️ Recording STARTED with AEC");
+                                let _ = Self::send_log(transport.as_mut(), &node_id, "INFO", "🎙️ Recording STARTED with AEC");
                             } else {
                                 if let Err(e) = cpal_capture.start() {
                                     error!("Failed to start CPAL: {}", e);
                                 }
-                                let _ = Self::send_log(&mut node, &node_id, "INFO", "🎙️ Recording STARTED without AEC");
+                                let _ = Self::send_log(transport.as_mut(), &node_id, "INFO", "🎙️ Recording STARTED without AEC");
                             }
                             recording_active = true;
                             is_recording.store(true, Ordering::Release);
                             if let Some(ref ss) = shared_state {
                                 ss.mic.set_recording(true);
                             }
-                            let _ = Self::send_status(&mut node, "recording");
+                            let _ = Self::send_status(transport.as_mut(), "recording");
                         }
                     }
                     AecControlCommand::StopRecording => {
@@ -619,8 +631,8 @@ impl AecInputBridge {
                             if let Some(ref ss) = shared_state {
                                 ss.mic.set_recording(false);
                             }
-                            let _ = Self::send_status(&mut node, "stopped");
-                            let _ = Self::send_log(&mut node, &node_id, "INFO", "🔇 Mic recording STOPPED");
+                            let _ = Self::send_status(transport.as_mut(), "stopped");
+                            let _ = Self::send_log(transport.as_mut(), &node_id, "INFO", "🔇 Mic recording STOPPED");
                         }
                     }
                     AecControlCommand::SetAecEnabled(enabled) => {
@@ -648,12 +660,12 @@ impl AecInputBridge {
                                     if let Some(ref mut aec) = aec_capture {
                                         aec.start();
                                     }
-                                    let _ = Self::send_log(&mut node, &node_id, "INFO", "🔄 Switched to AEC capture (echo cancellation ON)");
+                                    let _ = Self::send_log(transport.as_mut(), &node_id, "INFO", "🔄 Switched to AEC capture (echo cancellation ON)");
                                 } else {
                                     if let Err(e) = cpal_capture.start() {
                                         error!("Failed to start CPAL: {}", e);
                                     }
-                                    let _ = Self::send_log(&mut node, &node_id, "INFO", "🔄 Switched to regular mic (echo cancellation OFF)");
+                                    let _ = Self::send_log(transport.as_mut(), &node_id, "INFO", "🔄 Switched to regular mic (echo cancellation OFF)");
                                 }
                             }
                         }
@@ -731,19 +743,19 @@ impl AecInputBridge {
                 if question_ended {
                     let old_qid = vad_state.current_question_id;
                     let _ = Self::send_log(
-                        &mut node,
+                        transport.as_mut(),
                         &node_id,
                         "INFO",
                         &format!("📤 SENDING question_ended with OLD question_id={}", old_qid),
                     );
-                    if let Err(e) = Self::send_question_ended(&mut node, old_qid) {
+                    if let Err(e) = Self::send_question_ended(transport.as_mut(), old_qid) {
                         warn!("Failed to send question_ended: {}", e);
                     }
                     // Generate new question_id for next question
                     let new_qid = rand::random::<u32>() % 900000 + 100000;
                     vad_state.current_question_id = new_qid;
                     let _ = Self::send_log(
-                        &mut node,
+                        transport.as_mut(),
                         &node_id,
                         "INFO",
                         &format!("🆕 GENERATED NEW question_id={} for NEXT question", new_qid),
@@ -761,7 +773,7 @@ impl AecInputBridge {
                 }
 
                 // Send continuous audio stream (matching Python behavior)
-                if let Err(e) = Self::send_audio(&mut node, &all_audio) {
+                if let Err(e) = Self::send_audio(transport.as_mut(), &all_audio) {
                     warn!("Failed to send audio: {}", e);
                 }
 
@@ -847,14 +859,14 @@ impl AecInputBridge {
 
                 // Send dora outputs
                 if speech_started {
-                    if let Err(e) = Self::send_speech_started(&mut node) {
+                    if let Err(e) = Self::send_speech_started(transport.as_mut()) {
                         warn!("Failed to send speech_started: {}", e);
                     }
-                    if let Err(e) = Self::send_is_speaking(&mut node, true) {
+                    if let Err(e) = Self::send_is_speaking(transport.as_mut(), true) {
                         warn!("Failed to send is_speaking: {}", e);
                     }
                     let _ = Self::send_log(
-                        &mut node,
+                        transport.as_mut(),
                         &node_id,
                         "INFO",
                         &format!(
@@ -865,14 +877,14 @@ impl AecInputBridge {
                 }
 
                 if speech_ended {
-                    if let Err(e) = Self::send_speech_ended(&mut node) {
+                    if let Err(e) = Self::send_speech_ended(transport.as_mut()) {
                         warn!("Failed to send speech_ended: {}", e);
                     }
-                    if let Err(e) = Self::send_is_speaking(&mut node, false) {
+                    if let Err(e) = Self::send_is_speaking(transport.as_mut(), false) {
                         warn!("Failed to send is_speaking: {}", e);
                     }
                     let _ = Self::send_log(
-                        &mut node,
+                        transport.as_mut(),
                         &node_id,
                         "INFO",
                         &format!(
@@ -885,7 +897,7 @@ impl AecInputBridge {
                 // Send audio segment for ASR
                 if let Some(segment) = audio_segment {
                     if let Err(e) =
-                        Self::send_audio_segment(&mut node, &segment, vad_state.current_question_id)
+                        Self::send_audio_segment(transport.as_mut(), &segment, vad_state.current_question_id)
                     {
                         warn!("Failed to send audio_segment: {}", e);
                     } else {
@@ -895,7 +907,7 @@ impl AecInputBridge {
                             vad_state.current_question_id
                         );
                         let _ = Self::send_log(
-                            &mut node,
+                            transport.as_mut(),
                             &node_id,
                             "INFO",
                             &format!(
@@ -909,12 +921,12 @@ impl AecInputBridge {
             }
 
             // Handle dora events (control inputs)
-            match events.recv_timeout(Duration::from_millis(1)) {
-                Some(Event::Input { id, .. }) => {
-                    debug!("Received input: {}", id.as_str());
+            match transport.recv_timeout(Duration::from_millis(1)) {
+                Some(TransportEvent::Input { id, .. }) => {
+                    debug!("Received input: {}", id);
                     // Handle control inputs if needed
                 }
-                Some(Event::Stop(_)) => {
+                Some(TransportEvent::Stop) => {
                     // Don't break on Stop - other bridges ignore it too
                     // Breaking causes immediate disconnect and retry loops
                     eprintln!("[AecInput] Received Stop event from dora (ignoring)");
@@ -939,43 +951,36 @@ impl AecInputBridge {
         info!("AEC input bridge event loop ended");
     }
 
-    fn send_speech_started(node: &mut DoraNode) -> BridgeResult<()> {
+    fn send_speech_started(transport: &mut dyn Transport) -> BridgeResult<()> {
         let now = std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
             .map(|d| d.as_secs_f64())
             .unwrap_or(0.0);
         let data = vec![now].into_arrow();
-        let output_id: DataId = "speech_started".to_string().into();
-        node.send_output(output_id, BTreeMap::new(), data)
-            .map_err(|e| BridgeError::SendFailed(e.to_string()))
+        transport.send_output(NodeOutput::new("speech_started", BTreeMap::new(), data))
     }
 
-    fn send_speech_ended(node: &mut DoraNode) -> BridgeResult<()> {
+    fn send_speech_ended(transport: &mut dyn Transport) -> BridgeResult<()> {
         let now = std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
             .map(|d| d.as_secs_f64())
             .unwrap_or(0.0);
         let data = vec![now].into_arrow();
-        let output_id: DataId = "speech_ended".to_string().into();
-        node.send_output(output_id, BTreeMap::new(), data)
-            .map_err(|e| BridgeError::SendFailed(e.to_string()))
+        transport.send_output(NodeOutput::new("speech_ended", BTreeMap::new(), data))
     }
 
-    fn send_is_speaking(node: &mut DoraNode, speaking: bool) -> BridgeResult<()> {
+    fn send_is_speaking(transport: &mut dyn Transport, speaking: bool) -> BridgeResult<()> {
         // Convert bool to u8 since Vec<bool> doesn't implement IntoArrow
         let data = vec![speaking as u8].into_arrow();
-        let output_id: DataId = "is_speaking".to_string().into();
-        node.send_output(output_id, BTreeMap::new(), data)
-            .map_err(|e| BridgeError::SendFailed(e.to_string()))
+        transport.send_output(NodeOutput::new("is_speaking", BTreeMap::new(), data))
     }
 
-    fn send_question_ended(node: &mut DoraNode, question_id: u32) -> BridgeResult<()> {
+    fn send_question_ended(transport: &mut dyn Transport, question_id: u32) -> BridgeResult<()> {
         let now = std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
             .map(|d| d.as_secs_f64())
             .unwrap_or(0.0);
         let data = vec![now].into_arrow();
-        let output_id: DataId = "question_ended".to_string().into();
 
         let mut params: BTreeMap<String, Parameter> = BTreeMap::new();
         params.insert(
@@ -983,39 +988,32 @@ impl AecInputBridge {
             Parameter::Integer(question_id as i64),
         );
 
-        node.send_output(output_id, params, data)
-            .map_err(|e| BridgeError::SendFailed(e.to_string()))
+        transport.send_output(NodeOutput::new("question_ended", params, data))
     }
 
     fn send_audio_segment(
-        node: &mut DoraNode,
+        transport: &mut dyn Transport,
         samples: &[f32],
         question_id: u32,
     ) -> BridgeResult<()> {
-        let data = samples.to_vec().into_arrow();
-        let output_id: DataId = "audio_segment".to_string().into();
-
-        let mut params: BTreeMap<String, Parameter> = BTreeMap::new();
-        params.insert(
+        let audio = DoraData::audio(samples.to_vec(), CAPTURE_SAMPLE_RATE, 1);
+        let mut output = codec::encode_output("audio_segment", &audio)?;
+        output.parameters.insert(
             "question_id".to_string(),
             Parameter::Integer(question_id as i64),
         );
-        params.insert("sample_rate".to_string(), Parameter::Integer(16000));
 
-        node.send_output(output_id, params, data)
-            .map_err(|e| BridgeError::SendFailed(e.to_string()))
+        transport.send_output(output)
     }
 
     /// Send continuous audio stream (for recording/monitoring)
-    fn send_audio(node: &mut DoraNode, samples: &[f32]) -> BridgeResult<()> {
-        let data = samples.to_vec().into_arrow();
-        let output_id: DataId = "audio".to_string().into();
-        node.send_output(output_id, BTreeMap::new(), data)
-            .map_err(|e| BridgeError::SendFailed(e.to_string()))
+    fn send_audio(transport: &mut dyn Transport, samples: &[f32]) -> BridgeResult<()> {
+        let audio = DoraData::audio(samples.to_vec(), CAPTURE_SAMPLE_RATE, 1);
+        transport.send_output(codec::encode_output("audio", &audio)?)
     }
 
     /// Send log message to dora log output
-    fn send_log(node: &mut DoraNode, node_id: &str, level: &str, message: &str) -> BridgeResult<()> {
+    fn send_log(transport: &mut dyn Transport, node_id: &str, level: &str, message: &str) -> BridgeResult<()> {
         let log_entry = serde_json::json!({
             "level": level,
             "message": message,
@@ -1027,17 +1025,13 @@ impl AecInputBridge {
         });
         let log_str = log_entry.to_string();
         let data = vec![log_str].into_arrow();
-        let output_id: DataId = "log".to_string().into();
-        node.send_output(output_id, BTreeMap::new(), data)
-            .map_err(|e| BridgeError::SendFailed(e.to_string()))
+        transport.send_output(NodeOutput::new("log", BTreeMap::new(), data))
     }
 
     /// Send status update (recording/stopped)
-    fn send_status(node: &mut DoraNode, status: &str) -> BridgeResult<()> {
+    fn send_status(transport: &mut dyn Transport, status: &str) -> BridgeResult<()> {
         let data = vec![status.to_string()].into_arrow();
-        let output_id: DataId = "status".to_string().into();
-        node.send_output(output_id, BTreeMap::new(), data)
-            .map_err(|e| BridgeError::SendFailed(e.to_string()))
+        transport.send_output(NodeOutput::new("status", BTreeMap::new(), data))
     }
 }
 
@@ -1077,6 +1071,7 @@ impl DoraBridge for AecInputBridge {
         let state = Arc::clone(&self.state);
         let shared_state = self.shared_state.clone();
         let control_receiver = self.control_receiver.clone();
+        let connector = Arc::clone(&self.connector);
         let is_recording = Arc::clone(&self.is_recording);
         let aec_enabled = Arc::clone(&self.aec_enabled);
 
@@ -1089,6 +1084,7 @@ impl DoraBridge for AecInputBridge {
                 stop_rx,
                 is_recording,
                 aec_enabled,
+                connector,
             );
         });
 
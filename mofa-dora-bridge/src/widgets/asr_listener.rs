@@ -1,16 +1,17 @@
 //! ASR Listener Bridge
 //!
-//! Listens to ASR node transcription output and writes it to SharedDoraState
+//! Listens to ASR node transcription output and writes it to SharedDoraState.
+//! Each transcription also completes the audio request it answers (matched
+//! on the `question_id` metadata the ASR node passes through).
 
 use crate::bridge::{BridgeState, DoraBridge};
+use crate::codec;
 use crate::data::DoraData;
 use crate::error::{BridgeError, BridgeResult};
+use crate::outbox::{SendOutcome, CORRELATION_KEY};
 use crate::shared_state::SharedDoraState;
+use crate::transport::{self, Connector, DoraConnector, TransportEvent};
 use crossbeam_channel::{bounded, Receiver, Sender};
-use dora_node_api::{
-    dora_core::config::{DataId, NodeId},
-    DoraNode, Event,
-};
 use parking_lot::RwLock;
 use std::sync::Arc;
 use std::thread;
@@ -28,6 +29,8 @@ pub struct AsrListenerBridge {
     stop_sender: Option<Sender<()>>,
     /// Worker thread handle
     worker_handle: Option<thread::JoinHandle<()>>,
+    /// Opens the node's transport (dora unless replaced for tests)
+    connector: Arc<dyn Connector>,
 }
 
 impl AsrListenerBridge {
@@ -44,22 +47,30 @@ impl AsrListenerBridge {
             shared_state,
             stop_sender: None,
             worker_handle: None,
+            connector: Arc::new(DoraConnector),
         }
     }
 
+    /// Connect through `connector` instead of dora (e.g. a [`Loopback`](crate::loopback::Loopback))
+    pub fn with_connector(mut self, connector: Arc<dyn Connector>) -> Self {
+        self.connector = connector;
+        self
+    }
+
     /// Worker thread that listens to ASR transcription output
     fn run_event_loop(
         node_id: String,
         state: Arc<RwLock<BridgeState>>,
         shared_state: Option<Arc<SharedDoraState>>,
+        connector: Arc<dyn Connector>,
         stop_receiver: Receiver<()>,
     ) {
         info!("[AsrListener] Worker started for node: {}", node_id);
 
         // Connect to dora as dynamic node
-        let (mut node, mut events) =
-            match DoraNode::init_from_node_id(NodeId::from(node_id.clone())) {
-                Ok(n) => n,
+        let mut transport =
+            match transport::open(connector.as_ref(), &node_id, shared_state.as_ref()) {
+                Ok(t) => t,
                 Err(e) => {
                     error!("[AsrListener] Failed to init dora node: {}", e);
                     *state.write() = BridgeState::Error;
@@ -84,67 +95,74 @@ impl AsrListenerBridge {
             }
 
             // Poll for dora events
-            if let Some(event) = events.recv_timeout(std::time::Duration::from_millis(100)) {
+            if let Some(event) = transport.recv_timeout(std::time::Duration::from_millis(100)) {
                 match event {
-                    Event::Input { id, metadata, data } => {
+                    TransportEvent::Input { id, metadata, data } => {
                         // Check if this is a transcription output from ASR node
-                        if id == DataId::from("transcription".to_owned()) {
+                        if id == "transcription" {
                             debug!("[AsrListener] Received transcription event");
 
-                            // Parse the data as text - use try_as_str for ArrowData
-                            use arrow::array::Array;
-                            if let Some(text_str) =
-                                data.as_any().downcast_ref::<arrow::array::StringArray>()
-                            {
-                                if text_str.len() > 0 {
-                                    if let Some(text) = text_str.value(0).to_string().into() {
-                                        // Try to parse as JSON (ASR might send JSON)
-                                        let (language, transcription) = if let Ok(json) =
-                                            serde_json::from_str::<serde_json::Value>(&text)
-                                        {
-                                            let lang = json
-                                                .get("language")
-                                                .and_then(|v| v.as_str())
-                                                .unwrap_or("auto")
-                                                .to_string();
-                                            let txt = json
-                                                .get("text")
-                                                .and_then(|v| v.as_str())
-                                                .unwrap_or("")
-                                                .to_string();
-                                            (lang, txt)
-                                        } else {
-                                            // If not JSON, treat as plain text
-                                            ("auto".to_string(), text)
-                                        };
-
-                                        info!(
-                                            "[AsrListener] Transcription: language={}, text={}",
-                                            language, transcription
-                                        );
-
-                                        // Write to shared state
-                                        if let Some(ref state) = shared_state {
-                                            state
-                                                .asr_transcription
-                                                .set(Some((language, transcription)));
-                                        }
-                                    }
+                            // Parse the data as text
+                            if let Some(text) = codec::decode_string(&data) {
+                                // Try to parse as JSON (ASR might send JSON)
+                                let (language, transcription) = if let Ok(json) =
+                                    serde_json::from_str::<serde_json::Value>(&text)
+                                {
+                                    let lang = json
+                                        .get("language")
+                                        .and_then(|v| v.as_str())
+                                        .unwrap_or("auto")
+                                        .to_string();
+                                    let txt = json
+                                        .get("text")
+                                        .and_then(|v| v.as_str())
+                                        .unwrap_or("")
+                                        .to_string();
+                                    (lang, txt)
+                                } else {
+                                    // If not JSON, treat as plain text
+                                    ("auto".to_string(), text)
+                                };
+
+                                info!(
+                                    "[AsrListener] Transcription: language={}, text={}",
+                                    language, transcription
+                                );
+
+                                // Write to shared state
+                                if let Some(ref state) = shared_state {
+                                    let event_meta = codec::event_metadata(&metadata);
+                                    let outcome = match event_meta.get("error") {
+                                        Some(error) => SendOutcome::Failed(error.to_string()),
+                                        None => SendOutcome::Replied(DoraData::Json(
+                                            serde_json::json!({
+                                                "language": language,
+                                                "text": transcription,
+                                            }),
+                                        )),
+                                    };
+                                    state
+                                        .asr_transcription
+                                        .set(Some((language, transcription)));
+                                    state.replies.resolve(
+                                        "transcription",
+                                        event_meta.get(CORRELATION_KEY),
+                                        outcome,
+                                    );
                                 }
                             }
                         }
                     }
-                    Event::Stop(_) => {
+                    TransportEvent::Stop => {
                         info!("[AsrListener] Received stop event from dora");
                         break;
                     }
-                    Event::InputClosed { id } => {
+                    TransportEvent::InputClosed { id } => {
                         debug!("[AsrListener] Input closed: {:?}", id);
                     }
-                    Event::Error(e) => {
+                    TransportEvent::Error(e) => {
                         error!("[AsrListener] Dora error: {}", e);
                     }
-                    _ => {}
                 }
             }
         }
@@ -181,11 +199,12 @@ impl DoraBridge for AsrListenerBridge {
         let node_id = self.node_id.clone();
         let state = Arc::clone(&self.state);
         let shared_state = self.shared_state.clone();
+        let connector = Arc::clone(&self.connector);
 
         let handle = thread::Builder::new()
             .name(format!("asr-listener-{}", node_id))
             .spawn(move || {
-                Self::run_event_loop(node_id, state, shared_state, stop_rx);
+                Self::run_event_loop(node_id, state, shared_state, connector, stop_rx);
             })
             .map_err(|e| BridgeError::ThreadSpawnFailed(e.to_string()))?;
 
@@ -5,22 +5,30 @@
 //! to the dora dataflow for processing by ASR or other audio nodes.
 //!
 //! This differs from AecInputBridge which captures live microphone audio with VAD.
+//!
+//! Audio is queued in a bounded [`Outbox`] and written by the worker thread,
+//! tagged with its request id as `question_id` metadata so the ASR
+//! transcription can be matched back to it.
 
 use crate::bridge::{BridgeState, DoraBridge};
+use crate::codec;
 use crate::data::DoraData;
 use crate::error::{BridgeError, BridgeResult};
+use crate::outbox::{Outbox, OutboxItem, SendHandle, SendRequest, CORRELATION_KEY};
+use crate::recorder::NodeOutput;
 use crate::shared_state::SharedDoraState;
-use dora_node_api::{
-    dora_core::config::{DataId, NodeId},
-    DoraNode, IntoArrow,
-};
-use parking_lot::RwLock;
-use std::collections::BTreeMap;
+use crate::transport::{self, Connector, DoraConnector, Transport};
+use dora_node_api::Parameter;
+use parking_lot::{Mutex, RwLock};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::thread;
+use std::time::Duration;
 use tracing::{error, info, warn};
 
+/// Recordings that may wait for the worker; each can be several seconds of audio
+const OUTBOX_CAPACITY: usize = 4;
+
 /// Audio Input Bridge
 ///
 /// Simple bridge for sending audio data directly to dora without VAD or segmentation.
@@ -32,12 +40,16 @@ pub struct AudioInputBridge {
     id: String,
     /// Connection state
     state: Arc<RwLock<BridgeState>>,
-    /// Dora node (stored after connection)
-    node: Arc<RwLock<Option<DoraNode>>>,
+    /// Node transport (stored after connection)
+    transport: Arc<Mutex<Option<Box<dyn Transport>>>>,
     /// Shared state for UI communication
     shared_state: Arc<SharedDoraState>,
     /// Running flag
     running: Arc<AtomicBool>,
+    /// Audio waiting for the worker thread
+    outbox: Arc<Outbox>,
+    /// Opens the node's transport (dora unless replaced for tests)
+    connector: Arc<dyn Connector>,
 }
 
 impl AudioInputBridge {
@@ -46,9 +58,11 @@ impl AudioInputBridge {
         Self {
             id: id.to_string(),
             state: Arc::new(RwLock::new(BridgeState::Disconnected)),
-            node: Arc::new(RwLock::new(None)),
+            transport: Arc::new(Mutex::new(None)),
             shared_state: SharedDoraState::new(),
             running: Arc::new(AtomicBool::new(false)),
+            outbox: Arc::new(Outbox::new(id, OUTBOX_CAPACITY)),
+            connector: Arc::new(DoraConnector),
         }
     }
 
@@ -57,11 +71,42 @@ impl AudioInputBridge {
         Self {
             id: id.to_string(),
             state: Arc::new(RwLock::new(BridgeState::Disconnected)),
-            node: Arc::new(RwLock::new(None)),
+            transport: Arc::new(Mutex::new(None)),
             shared_state,
             running: Arc::new(AtomicBool::new(false)),
+            outbox: Arc::new(Outbox::new(id, OUTBOX_CAPACITY)),
+            connector: Arc::new(DoraConnector),
         }
     }
+
+    /// Connect through `connector` instead of dora (e.g. a [`Loopback`](crate::loopback::Loopback))
+    pub fn with_connector(mut self, connector: Arc<dyn Connector>) -> Self {
+        self.connector = connector;
+        self
+    }
+
+    /// Output for a queued recording
+    fn item_output(item: &OutboxItem) -> BridgeResult<NodeOutput> {
+        let DoraData::Audio(audio_data) = &item.request.data else {
+            return Err(BridgeError::InvalidData(
+                "AudioInputBridge only sends audio".to_string(),
+            ));
+        };
+        info!(
+            "[AudioInputBridge] Sending audio {}: {} samples at {}Hz",
+            item.id(),
+            audio_data.samples.len(),
+            audio_data.sample_rate
+        );
+
+        // Samples plus sample_rate/channels metadata, tagged with the request id
+        let mut output = codec::encode_output("audio", &item.request.data)?;
+        output.parameters.insert(
+            CORRELATION_KEY.to_string(),
+            Parameter::String(item.id().to_string()),
+        );
+        Ok(output)
+    }
 }
 
 impl DoraBridge for AudioInputBridge {
@@ -83,9 +128,12 @@ impl DoraBridge for AudioInputBridge {
         }
 
         let state = self.state.clone();
-        let node_arc = self.node.clone();
+        let transport_slot = self.transport.clone();
+        let connector = self.connector.clone();
         let id = self.id.clone();
         let running = self.running.clone();
+        let outbox = self.outbox.clone();
+        let shared_state = self.shared_state.clone();
 
         // Set to connecting
         *state.write() = BridgeState::Connecting;
@@ -96,32 +144,50 @@ impl DoraBridge for AudioInputBridge {
             .spawn(move || {
                 info!("[AudioInputBridge] Initializing for node_id: {}", id);
 
-                // Initialize dora node - returns (DoraNode, EventStream)
-                let (node, _events) = match DoraNode::init_from_node_id(NodeId::from(id.clone())) {
-                    Ok(n) => {
+                // Initialize dora node
+                let transport = match transport::open(connector.as_ref(), &id, Some(&shared_state)) {
+                    Ok(t) => {
                         info!("[AudioInputBridge] Successfully connected to Dora");
-                        n
+                        t
                     }
                     Err(e) => {
                         error!("[AudioInputBridge] Failed to connect: {}", e);
                         *state.write() = BridgeState::Error;
+                        outbox.cancel_all();
                         return;
                     }
                 };
 
                 // Store the node
-                *node_arc.write() = Some(node);
+                *transport_slot.lock() = Some(transport);
                 *state.write() = BridgeState::Connected;
                 running.store(true, Ordering::SeqCst);
 
                 info!("[AudioInputBridge] Worker thread started - staying alive for send operations");
 
-                // No event loop needed - audio sending happens synchronously via send() method
-                // Just keep thread alive until disconnect() is called
+                // No event loop needed - just write queued audio until disconnect() is called
                 while running.load(Ordering::SeqCst) {
-                    thread::sleep(std::time::Duration::from_millis(100));
+                    for item in outbox.drain_timeout(Duration::from_millis(100)) {
+                        let result = match transport_slot.lock().as_mut() {
+                            Some(transport) => Self::item_output(&item)
+                                .and_then(|output| transport.send_output(output)),
+                            None => Err(BridgeError::NotConnected),
+                        };
+                        match result {
+                            Ok(()) => {
+                                info!("[AudioInputBridge] Audio {} sent successfully", item.id())
+                            }
+                            Err(ref e) => error!(
+                                "[AudioInputBridge] Failed to send audio {}: {}",
+                                item.id(),
+                                e
+                            ),
+                        }
+                        item.finish(result, Some(&shared_state.replies));
+                    }
                 }
 
+                outbox.cancel_all();
                 info!("[AudioInputBridge] Worker thread exiting");
                 *state.write() = BridgeState::Disconnected;
             })
@@ -169,53 +235,36 @@ impl DoraBridge for AudioInputBridge {
         // Wait for worker thread to stop
         thread::sleep(std::time::Duration::from_millis(200));
 
-        *self.node.write() = None;
+        *self.transport.lock() = None;
         *self.state.write() = BridgeState::Disconnected;
 
         Ok(())
     }
 
     fn send(&self, output_id: &str, data: DoraData) -> BridgeResult<()> {
-        if !self.is_connected() {
+        self.submit(SendRequest::new(output_id, data)).map(|_| ())
+    }
+
+    fn submit(&self, request: SendRequest) -> BridgeResult<SendHandle> {
+        // Audio queued while connecting is sent once the node is up
+        if !matches!(self.state(), BridgeState::Connected | BridgeState::Connecting) {
             return Err(BridgeError::NotConnected);
         }
 
-        match (output_id, data) {
-            ("audio", DoraData::Audio(audio_data)) => {
-                // Get node and send audio directly
-                let mut node_guard = self.node.write();
-                if let Some(ref mut node) = *node_guard {
-                    info!(
-                        "[AudioInputBridge] Sending audio: {} samples at {}Hz",
-                        audio_data.samples.len(),
-                        audio_data.sample_rate
-                    );
-
-                    // Convert f32 samples to Arrow format
-                    let data = audio_data.samples.into_arrow();
-                    let output_id: DataId = "audio".to_string().into();
-                    node.send_output(output_id, BTreeMap::new(), data)
-                        .map_err(|e| BridgeError::SendFailed(e.to_string()))?;
-
-                    info!("[AudioInputBridge] Audio sent successfully");
-                } else {
-                    return Err(BridgeError::NotConnected);
-                }
-            }
+        match (request.output.as_str(), &request.data) {
+            ("audio", DoraData::Audio(_)) => self.outbox.submit(request),
             (output, data_type) => {
                 warn!(
                     "[AudioInputBridge] Unknown output '{}' with data type {:?}",
                     output,
-                    std::any::type_name_of_val(&data_type)
+                    std::any::type_name_of_val(data_type)
                 );
-                return Err(BridgeError::NotSupported(format!(
+                Err(BridgeError::NotSupported(format!(
                     "Output '{}' not supported by AudioInputBridge",
                     output
-                )));
+                )))
             }
         }
-
-        Ok(())
     }
 
     fn expected_inputs(&self) -> Vec<String> {
@@ -125,16 +125,17 @@
 //! ```
 
 use crate::bridge::{BridgeState, DoraBridge};
-use crate::data::{AudioData, DoraData, EventMetadata};
+use crate::codec;
+use crate::data::{DoraData, EventMetadata, ModelStatus};
 use crate::error::{BridgeError, BridgeResult};
+use crate::recorder::{self, NodeOutput, Record, Recording, ReplayHandler};
 use crate::shared_state::SharedDoraState;
-use arrow::array::Array;
+use crate::transport::{self, Connector, DoraConnector, TransportEvent};
+use arrow::array::ArrayRef;
 use crossbeam_channel::{bounded, Receiver, Sender};
-use dora_node_api::{
-    dora_core::config::{DataId, NodeId},
-    DoraNode, Event, IntoArrow, Parameter,
-};
+use dora_node_api::{IntoArrow, MetadataParameters, Parameter};
 use parking_lot::RwLock;
+use std::collections::HashSet;
 use std::sync::Arc;
 use std::thread;
 use tracing::{debug, error, info, warn};
@@ -162,6 +163,8 @@ pub struct AudioPlayerBridge {
     stop_sender: Option<Sender<()>>,
     /// Worker thread handle
     worker_handle: Option<thread::JoinHandle<()>>,
+    /// Opens the node's transport (dora unless replaced for tests)
+    connector: Arc<dyn Connector>,
 }
 
 impl AudioPlayerBridge {
@@ -182,9 +185,16 @@ impl AudioPlayerBridge {
             buffer_status_receiver: buffer_rx,
             stop_sender: None,
             worker_handle: None,
+            connector: Arc::new(DoraConnector),
         }
     }
 
+    /// Connect through `connector` instead of dora (e.g. a [`Loopback`](crate::loopback::Loopback))
+    pub fn with_connector(mut self, connector: Arc<dyn Connector>) -> Self {
+        self.connector = connector;
+        self
+    }
+
     /// Send buffer status back to dora (widget calls this)
     pub fn send_buffer_status(&self, fill_percentage: f64) -> BridgeResult<()> {
         self.buffer_status_sender
@@ -192,20 +202,34 @@ impl AudioPlayerBridge {
             .map_err(|_| BridgeError::ChannelSendError)
     }
 
+    /// Replay the inputs a recording captured for `node_id` without a dora daemon
+    ///
+    /// Audio and status updates land in `shared_state` as they would live.
+    /// Returns the outputs the bridge would have sent, comparable with the
+    /// recorded ones.
+    pub fn replay(
+        recording: &Recording,
+        node_id: &str,
+        shared_state: Option<Arc<SharedDoraState>>,
+    ) -> Vec<Record> {
+        recorder::replay(recording, &mut PlayerSession::new(node_id, shared_state))
+    }
+
     /// Run the dora event loop in background thread
     fn run_event_loop(
         node_id: String,
         state: Arc<RwLock<BridgeState>>,
         shared_state: Option<Arc<SharedDoraState>>,
+        connector: Arc<dyn Connector>,
         buffer_status_receiver: Receiver<f64>,
         stop_receiver: Receiver<()>,
     ) {
         info!("Starting audio player bridge event loop for {}", node_id);
 
         // Initialize dora node
-        let (mut node, mut events) =
-            match DoraNode::init_from_node_id(NodeId::from(node_id.clone())) {
-                Ok(n) => n,
+        let mut transport =
+            match transport::open(connector.as_ref(), &node_id, shared_state.as_ref()) {
+                Ok(t) => t,
                 Err(e) => {
                     error!("Failed to init dora node {}: {}", node_id, e);
                     *state.write() = BridgeState::Error;
@@ -221,21 +245,7 @@ impl AudioPlayerBridge {
             ss.add_bridge(node_id.clone());
         }
 
-        // Session tracking - track which question_ids we've sent session_start for
-        // to avoid flooding the controller with duplicate signals
-        let mut session_start_sent_for: std::collections::HashSet<String> =
-            std::collections::HashSet::new();
-
-        // Active participant tracking for LED visualization
-        let mut active_participant: Option<String> = None;
-        let mut active_switch_for: std::collections::HashSet<String> =
-            std::collections::HashSet::new();
-
-        // Smart reset state (matches Python audio_player.py)
-        // When reset arrives with question_id, we enter filtering_mode
-        // and reject audio chunks until we receive one with matching question_id
-        let mut filtering_mode = false;
-        let mut reset_question_id: Option<String> = None;
+        let mut session = PlayerSession::new(&node_id, shared_state.clone());
 
         // Event loop
         loop {
@@ -249,7 +259,12 @@ impl AudioPlayerBridge {
             // The actual buffer fill percentage comes from CircularAudioBuffer::fill_percentage()
             // in the UI layer, sent here via channel every 50ms
             while let Ok(status) = buffer_status_receiver.try_recv() {
-                if let Err(e) = Self::send_buffer_status_to_dora(&mut node, status) {
+                let output = NodeOutput::new(
+                    "buffer_status",
+                    Default::default(),
+                    vec![status].into_arrow(),
+                );
+                if let Err(e) = transport.send_output(output) {
                     warn!("Failed to send buffer status: {}", e);
                 } else {
                     debug!("Buffer status: {:.1}%", status);
@@ -257,21 +272,20 @@ impl AudioPlayerBridge {
             }
 
             // Receive dora events with timeout
-            match events.recv_timeout(std::time::Duration::from_millis(100)) {
-                Some(event) => {
-                    Self::handle_dora_event(
-                        event,
-                        &mut node,
-                        shared_state.as_ref(),
-                        &mut session_start_sent_for,
-                        &mut active_participant,
-                        &mut active_switch_for,
-                        &mut filtering_mode,
-                        &mut reset_question_id,
-                    );
+            match transport.recv_timeout(std::time::Duration::from_millis(100)) {
+                Some(TransportEvent::Input { id, data, metadata }) => {
+                    for output in session.handle_input(&id, &metadata, &data) {
+                        let output_id = output.id.clone();
+                        if let Err(e) = transport.send_output(output) {
+                            warn!("Failed to send {}: {}", output_id, e);
+                        }
+                    }
                 }
-                None => {
-                    // Timeout or no event, continue
+                Some(TransportEvent::Stop) => {
+                    info!("Received stop event from dora");
+                }
+                _ => {
+                    // Timeout or other event, continue
                 }
             }
         }
@@ -282,257 +296,264 @@ impl AudioPlayerBridge {
         }
         info!("Audio player bridge event loop ended");
     }
+}
 
-    /// Handle a dora event
-    fn handle_dora_event(
-        event: Event,
-        node: &mut DoraNode,
-        shared_state: Option<&Arc<SharedDoraState>>,
-        session_start_sent_for: &mut std::collections::HashSet<String>,
-        active_participant: &mut Option<String>,
-        active_switch_for: &mut std::collections::HashSet<String>,
-        filtering_mode: &mut bool,
-        reset_question_id: &mut Option<String>,
-    ) {
-        match event {
-            Event::Input { id, data, metadata } => {
-                let input_id = id.as_str();
-
-                // Extract metadata (handle all parameter types like conference-dashboard)
-                let mut event_meta = EventMetadata::default();
-                for (key, value) in metadata.parameters.iter() {
-                    let string_value = match value {
-                        Parameter::String(s) => s.clone(),
-                        Parameter::Integer(i) => i.to_string(),
-                        Parameter::Float(f) => f.to_string(),
-                        Parameter::Bool(b) => b.to_string(),
-                        Parameter::ListInt(l) => format!("{:?}", l),
-                        Parameter::ListFloat(l) => format!("{:?}", l),
-                        Parameter::ListString(l) => format!("{:?}", l),
-                    };
-                    event_meta.values.insert(key.clone(), string_value);
-                }
+/// Audio player state machine
+///
+/// Everything the bridge does with an input, independent of the dora node:
+/// smart reset filtering, session_start dedupe and audio_complete
+/// acknowledgements. The event loop sends the outputs it returns; replaying
+/// a [`Recording`] drives it the same way.
+pub(crate) struct PlayerSession {
+    node_id: String,
+    shared_state: Option<Arc<SharedDoraState>>,
+    /// question_ids we've sent session_start for, to avoid flooding the
+    /// controller with duplicate signals
+    session_start_sent_for: HashSet<String>,
+    /// Active participant tracking for LED visualization
+    active_participant: Option<String>,
+    active_switch_for: HashSet<String>,
+    /// Smart reset state (matches Python audio_player.py)
+    /// When reset arrives with question_id, we enter filtering_mode
+    /// and reject audio chunks until we receive one with matching question_id
+    filtering_mode: bool,
+    reset_question_id: Option<String>,
+}
 
-                // Handle reset input - immediately clear audio buffer (human speaking interrupt)
-                // Smart reset: if question_id is provided, filter incoming audio until matching question_id arrives
-                if input_id == "reset" {
-                    // Extract command from data or metadata
-                    let command = if let Some(cmd) = event_meta.get("command") {
-                        cmd.to_string()
-                    } else {
-                        // Try to read from data (StringArray)
-                        use arrow::array::AsArray;
-                        data.as_string::<i32>()
-                            .iter()
-                            .filter_map(|s| s)
-                            .next()
-                            .map(|s| s.to_string())
-                            .unwrap_or_default()
-                    };
-
-                    if command == "cancel" || command == "reset" {
-                        // Extract question_id for smart reset
-                        let new_question_id = event_meta.get("question_id").map(|s| s.to_string());
-
-                        if let Some(ref qid) = new_question_id {
-                            // Smart reset - clear buffer and enter filtering mode
-                            info!("🔇 Audio player SMART RESET: clearing buffer, filtering for question_id={}", qid);
-
-                            // Signal UI to clear its circular buffer (with force_mute)
-                            if let Some(ss) = shared_state {
-                                ss.audio.signal_clear();
-                            }
-
-                            // Enable filtering mode - reject audio until matching question_id arrives
-                            *filtering_mode = true;
-                            *reset_question_id = Some(qid.clone());
-
-                            // Clear session tracking
-                            session_start_sent_for.clear();
-                            active_switch_for.clear();
-                            *active_participant = None;
-                        } else {
-                            // Full reset - clear everything without filtering
-                            info!("🔇 Audio player FULL RESET: clearing buffer (no question_id)");
-
-                            // Signal UI to clear its circular buffer
-                            if let Some(ss) = shared_state {
-                                ss.audio.signal_clear();
-                            }
-
-                            // Disable filtering mode
-                            *filtering_mode = false;
-                            *reset_question_id = None;
-
-                            // Clear session tracking
-                            session_start_sent_for.clear();
-                            active_switch_for.clear();
-                            *active_participant = None;
-                        }
+impl PlayerSession {
+    pub(crate) fn new(node_id: &str, shared_state: Option<Arc<SharedDoraState>>) -> Self {
+        Self {
+            node_id: node_id.to_string(),
+            shared_state,
+            session_start_sent_for: HashSet::new(),
+            active_participant: None,
+            active_switch_for: HashSet::new(),
+            filtering_mode: false,
+            reset_question_id: None,
+        }
+    }
+
+    /// Handle one dora input, returning the outputs to send
+    pub(crate) fn handle_input(
+        &mut self,
+        input_id: &str,
+        parameters: &MetadataParameters,
+        data: &ArrayRef,
+    ) -> Vec<NodeOutput> {
+        let mut outputs = Vec::new();
+        let shared_state = self.shared_state.as_ref();
+
+        let event_meta = codec::event_metadata(parameters);
+
+        // Handle reset input - immediately clear audio buffer (human speaking interrupt)
+        // Smart reset: if question_id is provided, filter incoming audio until matching question_id arrives
+        if input_id == "reset" {
+            // Extract command from data or metadata
+            let command = if let Some(cmd) = event_meta.get("command") {
+                cmd.to_string()
+            } else {
+                // Try to read from data (StringArray)
+                codec::decode_string(data).unwrap_or_default()
+            };
+
+            if command == "cancel" || command == "reset" {
+                // Extract question_id for smart reset
+                let new_question_id = event_meta.get("question_id").map(|s| s.to_string());
+
+                if let Some(ref qid) = new_question_id {
+                    // Smart reset - clear buffer and enter filtering mode
+                    info!("🔇 Audio player SMART RESET: clearing buffer, filtering for question_id={}", qid);
+
+                    // Signal UI to clear its circular buffer (with force_mute)
+                    if let Some(ss) = shared_state {
+                        ss.audio.signal_clear();
                     }
-                    return; // Don't process reset as audio
+
+                    // Enable filtering mode - reject audio until matching question_id arrives
+                    self.filtering_mode = true;
+                    self.reset_question_id = Some(qid.clone());
+                } else {
+                    // Full reset - clear everything without filtering
+                    info!("🔇 Audio player FULL RESET: clearing buffer (no question_id)");
+
+                    // Signal UI to clear its circular buffer
+                    if let Some(ss) = shared_state {
+                        ss.audio.signal_clear();
+                    }
+
+                    // Disable filtering mode
+                    self.filtering_mode = false;
+                    self.reset_question_id = None;
                 }
 
-                // Handle audio inputs
-                if input_id.contains("audio") {
-                    if let Some(audio_data) = Self::extract_audio(&data, &event_meta) {
-                        let sample_count = audio_data.samples.len();
-
-                        // Extract participant ID from input_id (e.g., "audio_student1" -> "student1")
-                        let participant_id = input_id
-                            .strip_prefix("audio_")
-                            .unwrap_or("unknown")
-                            .to_string();
-
-                        // Get question_id from metadata
-                        let question_id = event_meta.get("question_id");
-
-                        // Smart reset filtering: reject stale audio until matching question_id arrives
-                        if *filtering_mode {
-                            let incoming_qid = question_id.map(|s| s.to_string());
-                            let expected_qid = reset_question_id.as_ref();
-
-                            match (&incoming_qid, expected_qid) {
-                                (Some(incoming), Some(expected)) if incoming == expected => {
-                                    // First chunk with matching question_id - exit filtering mode
-                                    *filtering_mode = false;
-                                    info!(
-                                        "✅ Exiting filtering mode: received matching question_id={} from {}",
-                                        incoming, participant_id
-                                    );
-                                }
-                                (Some(incoming), Some(expected)) => {
-                                    // Reject stale audio - question_id doesn't match
-                                    debug!(
-                                        "🚫 Filtering out stale audio from {} (question_id={}, expected={})",
-                                        participant_id, incoming, expected
-                                    );
-                                    return; // Skip this audio chunk
-                                }
-                                _ => {
-                                    // No question_id in audio or reset - assume new content, exit filtering
-                                    *filtering_mode = false;
-                                    debug!("Exiting filtering mode: no question_id available");
-                                }
-                            }
-                        }
+                // Clear session tracking
+                self.session_start_sent_for.clear();
+                self.active_switch_for.clear();
+                self.active_participant = None;
+            }
+            return outputs; // Don't process reset as audio
+        }
 
-                        debug!(
-                            "Received audio: {} samples, {}Hz from {}",
-                            sample_count, audio_data.sample_rate, input_id
-                        );
-
-                        // Send session_start ONCE per question_id on FIRST audio chunk
-                        // (matching conference-dashboard behavior: send on first audio OR when session_status="started")
-                        // This marks when audio playback begins for a new LLM/TTS response
-                        // The controller waits for this signal to advance to the next speaker
-                        if let Some(qid) = question_id {
-                            // Only send if we haven't sent for this question_id yet
-                            if !session_start_sent_for.contains(qid) {
-                                if let Err(e) =
-                                    Self::send_session_start(node, input_id, &event_meta)
-                                {
-                                    warn!("Failed to send session_start: {}", e);
-                                } else {
-                                    info!(
-                                        "Session started for question_id={} (first audio chunk)",
-                                        qid
-                                    );
-                                    session_start_sent_for.insert(qid.to_string());
-
-                                    // Keep the set size bounded (only track last 100 question_ids)
-                                    if session_start_sent_for.len() > 100 {
-                                        // Remove oldest entries (approximation)
-                                        let to_remove: Vec<_> = session_start_sent_for
-                                            .iter()
-                                            .take(50)
-                                            .cloned()
-                                            .collect();
-                                        for key in to_remove {
-                                            session_start_sent_for.remove(&key);
-                                        }
-                                    }
-                                }
-                            }
-
-                            // Switch active speaker ONCE per question_id (for logging)
-                            if !active_switch_for.contains(qid) {
-                                if active_participant.as_ref() != Some(&participant_id) {
-                                    *active_participant = Some(participant_id.clone());
-                                    debug!("Active speaker changed to: {}", participant_id);
-                                }
-                                active_switch_for.insert(qid.to_string());
-
-                                // Keep the set size bounded
-                                if active_switch_for.len() > 100 {
-                                    let to_remove: Vec<_> =
-                                        active_switch_for.iter().take(50).cloned().collect();
-                                    for key in to_remove {
-                                        active_switch_for.remove(&key);
-                                    }
-                                }
-                            }
-                        }
+        // Handle TTS model status - drives readiness detection in the app
+        if input_id == "tts_status" {
+            let status = codec::decode_string(data).unwrap_or_default();
+            let model_status = ModelStatus::from_status(&status, &event_meta);
+            debug!("TTS model status: {:?}", model_status);
 
-                        // NOTE: LED visualization is calculated in screen.rs from output waveform
-                        // (more accurate since it reflects what's actually being played)
-                        // The bridge only tracks active speaker for session management
-
-                        // IMPORTANT: Override participant_id from input_id (more reliable than metadata)
-                        // input_id is "audio_student1" -> participant_id is "student1"
-                        // Also ensure question_id is set for smart reset support
-                        let mut audio_data_with_participant = audio_data.clone();
-                        audio_data_with_participant.participant_id = Some(participant_id.clone());
-                        if let Some(qid) = question_id {
-                            audio_data_with_participant.question_id = Some(qid.to_string());
-                        }
+            // Nodes re-send their status periodically; only publish changes
+            if let Some(ss) = shared_state {
+                if ss.model_status.read() != model_status {
+                    ss.model_status.set(model_status);
+                }
+            }
+            return outputs;
+        }
 
-                        // Push audio to SharedDoraState for UI consumption
-                        // AudioState.push() uses a ring buffer internally
-                        if let Some(ss) = shared_state {
-                            ss.audio.push(audio_data_with_participant.clone());
-                        }
+        // Handle audio inputs
+        if !input_id.contains("audio") {
+            return outputs;
+        }
+        let Some(audio_data) = codec::decode_audio(data, &event_meta) else {
+            return outputs;
+        };
+        let sample_count = audio_data.samples.len();
+
+        // Extract participant ID from input_id (e.g., "audio_student1" -> "student1")
+        let participant_id = input_id
+            .strip_prefix("audio_")
+            .unwrap_or("unknown")
+            .to_string();
+
+        // Get question_id from metadata
+        let question_id = event_meta.get("question_id");
+
+        // Smart reset filtering: reject stale audio until matching question_id arrives
+        if self.filtering_mode {
+            let incoming_qid = question_id.map(|s| s.to_string());
+            let expected_qid = self.reset_question_id.as_ref();
+
+            match (&incoming_qid, expected_qid) {
+                (Some(incoming), Some(expected)) if incoming == expected => {
+                    // First chunk with matching question_id - exit filtering mode
+                    self.filtering_mode = false;
+                    info!(
+                        "✅ Exiting filtering mode: received matching question_id={} from {}",
+                        incoming, participant_id
+                    );
+                }
+                (Some(incoming), Some(expected)) => {
+                    // Reject stale audio - question_id doesn't match
+                    debug!(
+                        "🚫 Filtering out stale audio from {} (question_id={}, expected={})",
+                        participant_id, incoming, expected
+                    );
+                    return outputs; // Skip this audio chunk
+                }
+                _ => {
+                    // No question_id in audio or reset - assume new content, exit filtering
+                    self.filtering_mode = false;
+                    debug!("Exiting filtering mode: no question_id available");
+                }
+            }
+        }
 
-                        // Send audio_complete signal back to text-segmenter
-                        // This allows the next segment to be released
-                        // CRITICAL: This must be sent for every audio chunk to keep the pipeline flowing
-                        if let Err(e) = Self::send_audio_complete(node, input_id, &event_meta) {
-                            warn!("Failed to send audio_complete: {}", e);
-                        } else {
-                            debug!(
-                                "Sent audio_complete for {} (qid={:?})",
-                                input_id,
-                                event_meta.get("question_id")
-                            );
-                        }
+        debug!(
+            "Received audio: {} samples, {}Hz from {}",
+            sample_count, audio_data.sample_rate, input_id
+        );
+
+        // Send session_start ONCE per question_id on FIRST audio chunk
+        // (matching conference-dashboard behavior: send on first audio OR when session_status="started")
+        // This marks when audio playback begins for a new LLM/TTS response
+        // The controller waits for this signal to advance to the next speaker
+        if let Some(qid) = question_id {
+            // Only send if we haven't sent for this question_id yet
+            if !self.session_start_sent_for.contains(qid) {
+                outputs.push(Self::session_start(input_id, &event_meta));
+                info!(
+                    "Session started for question_id={} (first audio chunk)",
+                    qid
+                );
+                self.session_start_sent_for.insert(qid.to_string());
+
+                // Keep the set size bounded (only track last 100 question_ids)
+                if self.session_start_sent_for.len() > 100 {
+                    // Remove oldest entries (approximation)
+                    let to_remove: Vec<_> = self
+                        .session_start_sent_for
+                        .iter()
+                        .take(50)
+                        .cloned()
+                        .collect();
+                    for key in to_remove {
+                        self.session_start_sent_for.remove(&key);
                     }
                 }
             }
-            Event::Stop(_) => {
-                info!("Received stop event from dora");
+
+            // Switch active speaker ONCE per question_id (for logging)
+            if !self.active_switch_for.contains(qid) {
+                if self.active_participant.as_ref() != Some(&participant_id) {
+                    self.active_participant = Some(participant_id.clone());
+                    debug!("Active speaker changed to: {}", participant_id);
+                }
+                self.active_switch_for.insert(qid.to_string());
+
+                // Keep the set size bounded
+                if self.active_switch_for.len() > 100 {
+                    let to_remove: Vec<_> =
+                        self.active_switch_for.iter().take(50).cloned().collect();
+                    for key in to_remove {
+                        self.active_switch_for.remove(&key);
+                    }
+                }
             }
-            _ => {}
         }
+
+        // NOTE: LED visualization is calculated in screen.rs from output waveform
+        // (more accurate since it reflects what's actually being played)
+        // The bridge only tracks active speaker for session management
+
+        // IMPORTANT: Override participant_id from input_id (more reliable than metadata)
+        // input_id is "audio_student1" -> participant_id is "student1"
+        // Also ensure question_id is set for smart reset support
+        let mut audio_data_with_participant = audio_data;
+        audio_data_with_participant.participant_id = Some(participant_id);
+        if let Some(qid) = question_id {
+            audio_data_with_participant.question_id = Some(qid.to_string());
+        }
+
+        // Push audio to SharedDoraState for UI consumption
+        // AudioState.push() uses a ring buffer internally
+        if let Some(ss) = shared_state {
+            ss.audio.push(audio_data_with_participant);
+        }
+
+        // Send audio_complete signal back to text-segmenter
+        // This allows the next segment to be released
+        // CRITICAL: This must be sent for every audio chunk to keep the pipeline flowing
+        outputs.push(Self::audio_complete(input_id, &event_meta));
+        debug!(
+            "Sending audio_complete for {} (qid={:?})",
+            input_id,
+            event_meta.get("question_id")
+        );
+
+        outputs
     }
 
     // NOTE: Audio level and band calculation removed - now done in screen.rs from output waveform
     // This is more accurate since it reflects what's actually being played,
     // not what's being received (which may be buffered ahead of playback)
 
-    /// Send audio_complete signal to notify text-segmenter that audio was received
+    /// audio_complete signal notifying text-segmenter that audio was received
     /// Matches conference-dashboard's implementation for compatibility
-    fn send_audio_complete(
-        node: &mut DoraNode,
-        input_id: &str,
-        metadata: &EventMetadata,
-    ) -> BridgeResult<()> {
-        use std::collections::BTreeMap;
-
+    fn audio_complete(input_id: &str, metadata: &EventMetadata) -> NodeOutput {
         // Extract participant from input_id (e.g., "audio_student1" -> "student1")
         let participant = input_id.strip_prefix("audio_").unwrap_or(input_id);
 
         // Build metadata with participant info (matching conference-dashboard format)
-        let mut params: BTreeMap<String, Parameter> = BTreeMap::new();
+        let mut params = MetadataParameters::new();
         params.insert(
             "participant".to_string(),
             Parameter::String(participant.to_string()),
@@ -554,35 +575,29 @@ impl AudioPlayerBridge {
             );
         }
 
-        // Use vec!["received"] format to match conference-dashboard
-        let data = vec!["received".to_string()].into_arrow();
-        let output_id: DataId = "audio_complete".to_string().into();
-
         debug!(
-            "Sending audio_complete for participant: {} (question_id={:?}, session_status={:?})",
+            "audio_complete for participant: {} (question_id={:?}, session_status={:?})",
             participant,
             metadata.get("question_id"),
             metadata.get("session_status")
         );
 
-        node.send_output(output_id, params, data)
-            .map_err(|e| BridgeError::SendFailed(e.to_string()))
+        // Use vec!["received"] format to match conference-dashboard
+        NodeOutput::new(
+            "audio_complete",
+            params,
+            vec!["received".to_string()].into_arrow(),
+        )
     }
 
-    /// Send session_start signal to notify conference-controller that audio playback has begun
+    /// session_start signal notifying conference-controller that audio playback has begun
     /// This is critical for the controller to advance to the next speaker
-    fn send_session_start(
-        node: &mut DoraNode,
-        input_id: &str,
-        metadata: &EventMetadata,
-    ) -> BridgeResult<()> {
-        use std::collections::BTreeMap;
-
+    fn session_start(input_id: &str, metadata: &EventMetadata) -> NodeOutput {
         // Extract participant from input_id (e.g., "audio_student1" -> "student1")
         let participant = input_id.strip_prefix("audio_").unwrap_or(input_id);
 
         // Build metadata (matching conference-dashboard format)
-        let mut params: BTreeMap<String, Parameter> = BTreeMap::new();
+        let mut params = MetadataParameters::new();
 
         // Include question_id - REQUIRED by conference-controller
         if let Some(qid) = metadata.get("question_id") {
@@ -610,142 +625,33 @@ impl AudioPlayerBridge {
             );
         }
 
-        // Use vec!["audio_started"] format to match conference-dashboard
-        let data = vec!["audio_started".to_string()].into_arrow();
-        let output_id: DataId = "session_start".to_string().into();
-
         info!(
-            "Sending session_start for participant: {} (question_id={:?})",
+            "session_start for participant: {} (question_id={:?})",
             participant,
             metadata.get("question_id")
         );
 
-        node.send_output(output_id, params, data)
-            .map_err(|e| BridgeError::SendFailed(e.to_string()))
+        // Use vec!["audio_started"] format to match conference-dashboard
+        NodeOutput::new(
+            "session_start",
+            params,
+            vec!["audio_started".to_string()].into_arrow(),
+        )
     }
+}
 
-    /// Extract audio data from dora arrow data
-    /// Handles multiple formats: Float32, Float64, Int16, ListArray, LargeListArray
-    fn extract_audio(
-        data: &dora_node_api::ArrowData,
-        metadata: &EventMetadata,
-    ) -> Option<AudioData> {
-        use arrow::array::{Float32Array, Float64Array, Int16Array, LargeListArray, ListArray};
-        use arrow::datatypes::DataType;
-
-        let array = &data.0;
-        if array.is_empty() {
-            return None;
-        }
-
-        // Try to extract f32 array
-        let samples: Vec<f32> = match array.data_type() {
-            DataType::Float32 => array
-                .as_any()
-                .downcast_ref::<Float32Array>()
-                .map(|arr| arr.values().to_vec())?,
-            DataType::Float64 => array
-                .as_any()
-                .downcast_ref::<Float64Array>()
-                .map(|arr| arr.values().iter().map(|&x| x as f32).collect())?,
-            DataType::Int16 => array
-                .as_any()
-                .downcast_ref::<Int16Array>()
-                .map(|arr| arr.values().iter().map(|&x| x as f32 / 32768.0).collect())?,
-            // Handle ListArray<Float32> - primespeech sends pa.array([audio_array])
-            DataType::List(_) | DataType::LargeList(_) => {
-                debug!("Audio data is ListArray, extracting inner array");
-
-                // Try ListArray first
-                if let Some(list_arr) = array.as_any().downcast_ref::<ListArray>() {
-                    if list_arr.len() > 0 {
-                        let first_value = list_arr.value(0);
-                        if let Some(float_arr) = first_value.as_any().downcast_ref::<Float32Array>()
-                        {
-                            debug!("Extracted {} f32 samples from ListArray", float_arr.len());
-                            float_arr.values().to_vec()
-                        } else if let Some(float_arr) =
-                            first_value.as_any().downcast_ref::<Float64Array>()
-                        {
-                            debug!("Extracted {} f64 samples from ListArray", float_arr.len());
-                            float_arr.values().iter().map(|&v| v as f32).collect()
-                        } else {
-                            warn!(
-                                "ListArray inner type not Float32/Float64: {:?}",
-                                first_value.data_type()
-                            );
-                            return None;
-                        }
-                    } else {
-                        return None;
-                    }
-                } else if let Some(list_arr) = array.as_any().downcast_ref::<LargeListArray>() {
-                    if list_arr.len() > 0 {
-                        let first_value = list_arr.value(0);
-                        if let Some(float_arr) = first_value.as_any().downcast_ref::<Float32Array>()
-                        {
-                            debug!(
-                                "Extracted {} f32 samples from LargeListArray",
-                                float_arr.len()
-                            );
-                            float_arr.values().to_vec()
-                        } else if let Some(float_arr) =
-                            first_value.as_any().downcast_ref::<Float64Array>()
-                        {
-                            debug!(
-                                "Extracted {} f64 samples from LargeListArray",
-                                float_arr.len()
-                            );
-                            float_arr.values().iter().map(|&v| v as f32).collect()
-                        } else {
-                            warn!(
-                                "LargeListArray inner type not Float32/Float64: {:?}",
-                                first_value.data_type()
-                            );
-                            return None;
-                        }
-                    } else {
-                        return None;
-                    }
-                } else {
-                    warn!("Failed to extract audio from ListArray");
-                    return None;
-                }
-            }
-            dt => {
-                warn!("Unsupported audio data type: {:?}", dt);
-                return None;
-            }
-        };
-
-        if samples.is_empty() {
-            return None;
-        }
-
-        // Get sample rate from metadata or use default
-        let sample_rate = metadata
-            .get("sample_rate")
-            .and_then(|s| s.parse().ok())
-            .unwrap_or(32000);
-
-        let participant_id = metadata.participant_id().map(|s| s.to_string());
-        let question_id = metadata.get("question_id").map(|s| s.to_string());
-
-        Some(AudioData {
-            samples,
-            sample_rate,
-            channels: 1,
-            participant_id,
-            question_id,
-        })
+impl ReplayHandler for PlayerSession {
+    fn node_id(&self) -> &str {
+        &self.node_id
     }
 
-    /// Send buffer status to dora
-    fn send_buffer_status_to_dora(node: &mut DoraNode, status: f64) -> BridgeResult<()> {
-        let data = vec![status].into_arrow();
-        let output_id: DataId = "buffer_status".to_string().into();
-        node.send_output(output_id, Default::default(), data)
-            .map_err(|e| BridgeError::SendFailed(e.to_string()))
+    fn handle_input(
+        &mut self,
+        input_id: &str,
+        parameters: &MetadataParameters,
+        data: &ArrayRef,
+    ) -> Vec<NodeOutput> {
+        PlayerSession::handle_input(self, input_id, parameters, data)
     }
 }
 
@@ -771,10 +677,18 @@ impl DoraBridge for AudioPlayerBridge {
         let node_id = self.node_id.clone();
         let state = Arc::clone(&self.state);
         let shared_state = self.shared_state.clone();
+        let connector = Arc::clone(&self.connector);
         let buffer_receiver = self.buffer_status_receiver.clone();
 
         let handle = thread::spawn(move || {
-            Self::run_event_loop(node_id, state, shared_state, buffer_receiver, stop_rx);
+            Self::run_event_loop(
+                node_id,
+                state,
+                shared_state,
+                connector,
+                buffer_receiver,
+                stop_rx,
+            );
         });
 
         self.worker_handle = Some(handle);
@@ -827,6 +741,7 @@ impl DoraBridge for AudioPlayerBridge {
             "audio_student1".to_string(),
             "audio_student2".to_string(),
             "audio_tutor".to_string(),
+            "tts_status".to_string(),
         ]
     }
 
@@ -846,3 +761,130 @@ impl Drop for AudioPlayerBridge {
         let _ = self.disconnect();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::recorder::{Direction, Payload};
+    use arrow::array::{Array, Float32Array, StringArray};
+
+    const NODE: &str = "mofa-audio-player";
+
+    fn input(port: &str, question_id: Option<&str>, data: &dyn Array) -> Record {
+        let mut params = MetadataParameters::new();
+        if let Some(qid) = question_id {
+            params.insert("question_id".into(), Parameter::String(qid.into()));
+        }
+        Record::new(NODE, Direction::Input, port, &params, data)
+    }
+
+    fn audio(question_id: &str) -> Record {
+        input(
+            "audio",
+            Some(question_id),
+            &Float32Array::from(vec![0.1f32; 4]),
+        )
+    }
+
+    fn reset(question_id: Option<&str>) -> Record {
+        input("reset", question_id, &StringArray::from(vec!["reset"]))
+    }
+
+    /// Output ports with their question_id, in order
+    fn summary(outputs: &[Record]) -> Vec<(String, Option<String>)> {
+        outputs
+            .iter()
+            .map(|r| {
+                let qid = match r.parameters().get("question_id") {
+                    Some(Parameter::String(q)) => Some(q.clone()),
+                    _ => None,
+                };
+                (r.port.clone(), qid)
+            })
+            .collect()
+    }
+
+    fn out(port: &str, qid: &str) -> (String, Option<String>) {
+        (port.to_string(), Some(qid.to_string()))
+    }
+
+    #[test]
+    fn test_session_start_sent_once_per_question() {
+        let state = SharedDoraState::new();
+        let recording = Recording::new(vec![audio("1"), audio("1"), audio("2")]);
+        let outputs = AudioPlayerBridge::replay(&recording, NODE, Some(state.clone()));
+
+        assert_eq!(
+            summary(&outputs),
+            vec![
+                out("session_start", "1"),
+                out("audio_complete", "1"),
+                out("audio_complete", "1"),
+                out("session_start", "2"),
+                out("audio_complete", "2"),
+            ]
+        );
+        assert_eq!(state.audio.len(), 3);
+        assert_eq!(
+            outputs[1].payload,
+            Payload::Utf8(vec![Some("received".into())])
+        );
+    }
+
+    #[test]
+    fn test_smart_reset_filters_stale_audio() {
+        let state = SharedDoraState::new();
+        let recording = Recording::new(vec![
+            audio("5"),
+            reset(Some("6")),
+            // Still in flight from the interrupted answer
+            audio("5"),
+            audio("6"),
+            // Filtering ended with the first matching chunk
+            audio("5"),
+        ]);
+        let outputs = AudioPlayerBridge::replay(&recording, NODE, Some(state.clone()));
+
+        assert_eq!(
+            summary(&outputs),
+            vec![
+                out("session_start", "5"),
+                out("audio_complete", "5"),
+                out("session_start", "6"),
+                out("audio_complete", "6"),
+                // Session tracking was cleared by the reset
+                out("session_start", "5"),
+                out("audio_complete", "5"),
+            ]
+        );
+        assert!(state.audio.take_clear_signal());
+        let qids: Vec<_> = state
+            .audio
+            .drain()
+            .into_iter()
+            .map(|chunk| chunk.question_id.unwrap())
+            .collect();
+        assert_eq!(qids, vec!["6", "5"]);
+    }
+
+    #[test]
+    fn test_full_reset_does_not_filter() {
+        let recording = Recording::new(vec![
+            audio("1"),
+            reset(None),
+            audio("1"),
+            input("tts_status", None, &StringArray::from(vec!["ready"])),
+        ]);
+        let outputs = AudioPlayerBridge::replay(&recording, NODE, None);
+
+        assert_eq!(
+            summary(&outputs),
+            vec![
+                out("session_start", "1"),
+                out("audio_complete", "1"),
+                out("session_start", "1"),
+                out("audio_complete", "1"),
+            ]
+        );
+    }
+}
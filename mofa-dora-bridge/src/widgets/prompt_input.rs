@@ -4,22 +4,32 @@
 //! Sends user prompts to LLM nodes and receives:
 //! - Text responses (streaming)
 //! - Status updates
+//! - `segment_complete` from TTS, which completes the originating request
+//!
+//! Prompts are queued in a bounded [`Outbox`] and tagged with their request
+//! id as `question_id` metadata.
 
 use crate::bridge::{BridgeState, DoraBridge};
-use crate::data::{ChatMessage, ControlCommand, DoraData, EventMetadata, MessageRole};
+use crate::codec;
+use crate::data::{ChatMessage, ControlCommand, DoraData, MessageRole};
 use crate::error::{BridgeError, BridgeResult};
+use crate::outbox::{
+    Outbox, OutboxItem, RequestId, SendHandle, SendOutcome, SendRequest, CORRELATION_KEY,
+    DEFAULT_OUTBOX_CAPACITY,
+};
+use crate::recorder::NodeOutput;
 use crate::shared_state::SharedDoraState;
-use arrow::array::Array;
+use crate::transport::{self, Connector, DoraConnector, TransportEvent};
 use crossbeam_channel::{bounded, Receiver, Sender};
-use dora_node_api::{
-    dora_core::config::{DataId, NodeId},
-    DoraNode, Event, IntoArrow, Parameter,
-};
+use dora_node_api::Parameter;
 use parking_lot::RwLock;
 use std::sync::Arc;
 use std::thread;
 use tracing::{error, info, warn};
 
+/// Input on which TTS reports that a prompt was synthesized
+pub const SEGMENT_COMPLETE_INPUT: &str = "segment_complete";
+
 /// Prompt input bridge - sends prompts to dora, receives responses
 ///
 /// Status updates (connected/disconnected/error) are communicated via SharedDoraState.
@@ -31,10 +41,8 @@ pub struct PromptInputBridge {
     state: Arc<RwLock<BridgeState>>,
     /// Shared state for direct UI communication
     shared_state: Option<Arc<SharedDoraState>>,
-    /// Prompt sender from widget
-    prompt_sender: Sender<String>,
-    /// Prompt receiver for dora
-    prompt_receiver: Receiver<String>,
+    /// Prompts waiting for the event loop
+    outbox: Arc<Outbox>,
     /// Control command sender from widget
     control_sender: Sender<ControlCommand>,
     /// Control command receiver for dora
@@ -43,6 +51,8 @@ pub struct PromptInputBridge {
     stop_sender: Option<Sender<()>>,
     /// Worker thread handle
     worker_handle: Option<thread::JoinHandle<()>>,
+    /// Opens the node's transport (dora unless replaced for tests)
+    connector: Arc<dyn Connector>,
 }
 
 impl PromptInputBridge {
@@ -53,27 +63,31 @@ impl PromptInputBridge {
 
     /// Create a new prompt input bridge with shared state
     pub fn with_shared_state(node_id: &str, shared_state: Option<Arc<SharedDoraState>>) -> Self {
-        let (prompt_tx, prompt_rx) = bounded(10);
         let (control_tx, control_rx) = bounded(10);
 
         Self {
             node_id: node_id.to_string(),
             state: Arc::new(RwLock::new(BridgeState::Disconnected)),
             shared_state,
-            prompt_sender: prompt_tx,
-            prompt_receiver: prompt_rx,
+            outbox: Arc::new(Outbox::new(node_id, DEFAULT_OUTBOX_CAPACITY)),
             control_sender: control_tx,
             control_receiver: control_rx,
             stop_sender: None,
             worker_handle: None,
+            connector: Arc::new(DoraConnector),
         }
     }
 
+    /// Connect through `connector` instead of dora (e.g. a [`Loopback`](crate::loopback::Loopback))
+    pub fn with_connector(mut self, connector: Arc<dyn Connector>) -> Self {
+        self.connector = connector;
+        self
+    }
+
     /// Send a prompt to dora (widget calls this)
     pub fn send_prompt(&self, prompt: impl Into<String>) -> BridgeResult<()> {
-        self.prompt_sender
-            .send(prompt.into())
-            .map_err(|_| BridgeError::ChannelSendError)
+        self.submit(SendRequest::new("control", DoraData::Text(prompt.into())))
+            .map(|_| ())
     }
 
     /// Send a control command to dora (widget calls this)
@@ -88,22 +102,24 @@ impl PromptInputBridge {
         node_id: String,
         state: Arc<RwLock<BridgeState>>,
         shared_state: Option<Arc<SharedDoraState>>,
-        prompt_receiver: Receiver<String>,
+        connector: Arc<dyn Connector>,
+        outbox: Arc<Outbox>,
         control_receiver: Receiver<ControlCommand>,
         stop_receiver: Receiver<()>,
     ) {
         info!("Starting prompt input bridge event loop for {}", node_id);
 
         // Initialize dora node
-        let (mut node, mut events) =
-            match DoraNode::init_from_node_id(NodeId::from(node_id.clone())) {
-                Ok(n) => n,
+        let mut transport =
+            match transport::open(connector.as_ref(), &node_id, shared_state.as_ref()) {
+                Ok(t) => t,
                 Err(e) => {
                     error!("Failed to init dora node {}: {}", node_id, e);
                     *state.write() = BridgeState::Error;
                     if let Some(ref ss) = shared_state {
                         ss.set_error(Some(format!("Init failed: {}", e)));
                     }
+                    outbox.cancel_all();
                     return;
                 }
             };
@@ -121,22 +137,27 @@ impl PromptInputBridge {
                 break;
             }
 
-            // Check for prompts to send
-            while let Ok(prompt) = prompt_receiver.try_recv() {
-                if let Err(e) = Self::send_prompt_to_dora(&mut node, &prompt) {
-                    warn!("Failed to send prompt: {}", e);
+            // Send queued requests
+            for item in outbox.drain() {
+                let result =
+                    Self::item_output(&item).and_then(|output| transport.send_output(output));
+                if let Err(ref e) = result {
+                    warn!("Failed to send request {}: {}", item.id(), e);
                 }
+                item.finish(result, shared_state.as_deref().map(|ss| &ss.replies));
             }
 
             // Check for control commands to send
             while let Ok(cmd) = control_receiver.try_recv() {
-                if let Err(e) = Self::send_control_to_dora(&mut node, &cmd) {
+                let result =
+                    Self::control_output(&cmd).and_then(|output| transport.send_output(output));
+                if let Err(e) = result {
                     warn!("Failed to send control: {}", e);
                 }
             }
 
             // Receive dora events with timeout
-            match events.recv_timeout(std::time::Duration::from_millis(100)) {
+            match transport.recv_timeout(std::time::Duration::from_millis(100)) {
                 Some(event) => {
                     Self::handle_dora_event(event, shared_state.as_ref());
                 }
@@ -147,6 +168,7 @@ impl PromptInputBridge {
         }
 
         *state.write() = BridgeState::Disconnected;
+        outbox.cancel_all();
         if let Some(ref ss) = shared_state {
             ss.remove_bridge(&node_id);
         }
@@ -154,29 +176,33 @@ impl PromptInputBridge {
     }
 
     /// Handle a dora event
-    fn handle_dora_event(event: Event, shared_state: Option<&Arc<SharedDoraState>>) {
+    fn handle_dora_event(event: TransportEvent, shared_state: Option<&Arc<SharedDoraState>>) {
         match event {
-            Event::Input { id, data, metadata } => {
+            TransportEvent::Input { id, data, metadata } => {
                 let input_id = id.as_str();
 
-                // Extract metadata (handle all parameter types like conference-dashboard)
-                let mut event_meta = EventMetadata::default();
-                for (key, value) in metadata.parameters.iter() {
-                    let string_value = match value {
-                        Parameter::String(s) => s.clone(),
-                        Parameter::Integer(i) => i.to_string(),
-                        Parameter::Float(f) => f.to_string(),
-                        Parameter::Bool(b) => b.to_string(),
-                        Parameter::ListInt(l) => format!("{:?}", l),
-                        Parameter::ListFloat(l) => format!("{:?}", l),
-                        Parameter::ListString(l) => format!("{:?}", l),
-                    };
-                    event_meta.values.insert(key.clone(), string_value);
+                let event_meta = codec::event_metadata(&metadata);
+
+                // TTS finished (or failed) a prompt we sent
+                if input_id == SEGMENT_COMPLETE_INPUT {
+                    if let Some(ss) = shared_state {
+                        let status = codec::decode_string(&data).unwrap_or_default();
+                        let outcome = match event_meta.get("error") {
+                            Some(error) => SendOutcome::Failed(error.to_string()),
+                            None if status == "error" => {
+                                SendOutcome::Failed("TTS synthesis failed".to_string())
+                            }
+                            None => SendOutcome::Replied(DoraData::Text(status)),
+                        };
+                        ss.replies
+                            .resolve(input_id, event_meta.get(CORRELATION_KEY), outcome);
+                    }
+                    return;
                 }
 
                 // Handle text inputs (responses from LLM)
                 if input_id.contains("text") || input_id.contains("response") {
-                    if let Some(text) = Self::extract_string(&data) {
+                    if let Some(text) = codec::decode_string(&data) {
                         let sender = Self::extract_sender(input_id);
                         let session_id = event_meta
                             .get("question_id")
@@ -207,7 +233,7 @@ impl PromptInputBridge {
                     }
                 }
             }
-            Event::Stop(_) => {
+            TransportEvent::Stop => {
                 info!("Received stop event from dora");
             }
             _ => {}
@@ -227,63 +253,39 @@ impl PromptInputBridge {
         }
     }
 
-    /// Extract string from arrow data
-    fn extract_string(data: &dora_node_api::ArrowData) -> Option<String> {
-        match data.0.data_type() {
-            arrow::datatypes::DataType::Utf8 => {
-                let array = data
-                    .0
-                    .as_any()
-                    .downcast_ref::<arrow::array::StringArray>()?;
-                if array.len() > 0 {
-                    return Some(array.value(0).to_string());
-                }
-            }
-            arrow::datatypes::DataType::LargeUtf8 => {
-                let array = data
-                    .0
-                    .as_any()
-                    .downcast_ref::<arrow::array::LargeStringArray>()?;
-                if array.len() > 0 {
-                    return Some(array.value(0).to_string());
-                }
-            }
-            arrow::datatypes::DataType::UInt8 => {
-                let array = data.0.as_any().downcast_ref::<arrow::array::UInt8Array>()?;
-                let bytes: Vec<u8> = array.values().to_vec();
-                return String::from_utf8(bytes).ok();
-            }
-            _ => {
-                warn!("Unsupported text data type: {:?}", data.0.data_type());
-            }
+    /// Output for a queued request
+    fn item_output(item: &OutboxItem) -> BridgeResult<NodeOutput> {
+        match &item.request.data {
+            DoraData::Text(prompt) => Self::prompt_output(prompt, item.id()),
+            DoraData::Control(cmd) => Self::control_output(cmd),
+            _ => Err(BridgeError::NotSupported(format!(
+                "Output '{}' not supported by PromptInputBridge",
+                item.request.output
+            ))),
         }
-        None
     }
 
-    /// Send prompt to dora via control output
+    /// Prompt for dora's control output
     /// The conference-controller expects JSON with "prompt" field
-    fn send_prompt_to_dora(node: &mut DoraNode, prompt: &str) -> BridgeResult<()> {
+    fn prompt_output(prompt: &str, request_id: RequestId) -> BridgeResult<NodeOutput> {
         // Create JSON payload that conference-controller expects
         let payload = serde_json::json!({
             "prompt": prompt
         });
 
-        info!("Sending prompt to dora: {}", prompt);
-        let data = payload.to_string().into_arrow();
-        let output_id: DataId = "control".to_string().into(); // Use control output
-        node.send_output(output_id, Default::default(), data)
-            .map_err(|e| BridgeError::SendFailed(e.to_string()))
+        info!("Sending prompt {} to dora: {}", request_id, prompt);
+        // Use control output
+        let mut output = codec::encode_output("control", &DoraData::Json(payload))?;
+        output.parameters.insert(
+            CORRELATION_KEY.to_string(),
+            Parameter::String(request_id.to_string()),
+        );
+        Ok(output)
     }
 
-    /// Send control command to dora
-    fn send_control_to_dora(node: &mut DoraNode, cmd: &ControlCommand) -> BridgeResult<()> {
-        let payload =
-            serde_json::to_string(cmd).map_err(|e| BridgeError::SendFailed(e.to_string()))?;
-
-        let data = payload.into_arrow();
-        let output_id: DataId = "control".to_string().into();
-        node.send_output(output_id, Default::default(), data)
-            .map_err(|e| BridgeError::SendFailed(e.to_string()))
+    /// Control command for dora's control output
+    fn control_output(cmd: &ControlCommand) -> BridgeResult<NodeOutput> {
+        codec::encode_output("control", &DoraData::Control(cmd.clone()))
     }
 }
 
@@ -309,7 +311,8 @@ impl DoraBridge for PromptInputBridge {
         let node_id = self.node_id.clone();
         let state = Arc::clone(&self.state);
         let shared_state = self.shared_state.clone();
-        let prompt_receiver = self.prompt_receiver.clone();
+        let connector = Arc::clone(&self.connector);
+        let outbox = Arc::clone(&self.outbox);
         let control_receiver = self.control_receiver.clone();
 
         let handle = thread::spawn(move || {
@@ -317,7 +320,8 @@ impl DoraBridge for PromptInputBridge {
                 node_id,
                 state,
                 shared_state,
-                prompt_receiver,
+                connector,
+                outbox,
                 control_receiver,
                 stop_rx,
             );
@@ -345,23 +349,21 @@ impl DoraBridge for PromptInputBridge {
     }
 
     fn send(&self, output_id: &str, data: DoraData) -> BridgeResult<()> {
-        if !self.is_connected() {
+        self.submit(SendRequest::new(output_id, data)).map(|_| ())
+    }
+
+    fn submit(&self, request: SendRequest) -> BridgeResult<SendHandle> {
+        // Requests queued while connecting are sent once the node is up
+        if !matches!(self.state(), BridgeState::Connected | BridgeState::Connecting) {
             return Err(BridgeError::NotConnected);
         }
 
-        match (output_id, data) {
-            // Prompts are sent via the prompt channel, which sends to "control" output as JSON
+        match (request.output.as_str(), &request.data) {
+            // Prompts go out on the "control" output as JSON
             ("prompt", DoraData::Text(text)) | ("control", DoraData::Text(text)) => {
                 info!("Queuing prompt for sending: {}", text);
-                self.prompt_sender
-                    .send(text)
-                    .map_err(|_| BridgeError::ChannelSendError)?;
-            }
-            ("control", DoraData::Control(cmd)) => {
-                self.control_sender
-                    .send(cmd)
-                    .map_err(|_| BridgeError::ChannelSendError)?;
             }
+            ("control", DoraData::Control(_)) => {}
             // Audio data needs direct sending via node (not via channel)
             // For now, we reject it with a clear error
             ("audio", DoraData::Audio(_)) => {
@@ -371,7 +373,7 @@ impl DoraBridge for PromptInputBridge {
                 ));
             }
             (output, data_type) => {
-                let data_type_name = match &data_type {
+                let data_type_name = match data_type {
                     DoraData::Text(_) => "Text",
                     DoraData::Audio(_) => "Audio",
                     DoraData::Control(_) => "Control",
@@ -391,12 +393,13 @@ impl DoraBridge for PromptInputBridge {
             }
         }
 
-        Ok(())
+        self.outbox.submit(request)
     }
 
     fn expected_inputs(&self) -> Vec<String> {
         vec![
             "text".to_string(),
+            SEGMENT_COMPLETE_INPUT.to_string(),
             "student1_text".to_string(),
             "student2_text".to_string(),
             "tutor_text".to_string(),
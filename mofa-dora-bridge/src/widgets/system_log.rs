@@ -6,17 +6,18 @@
 //! - Per-source filtering capability
 
 use crate::bridge::{BridgeState, DoraBridge};
+use crate::codec;
 use crate::data::{current_timestamp, DoraData, EventMetadata, LogEntry, LogLevel};
 use crate::error::{BridgeError, BridgeResult};
 use crate::shared_state::SharedDoraState;
-use arrow::array::Array;
+use crate::transport::{self, Connector, DoraConnector, TransportEvent};
+use arrow::array::ArrayRef;
 use crossbeam_channel::{bounded, Receiver, Sender};
-use dora_node_api::{dora_core::config::NodeId, DoraNode, Event, Parameter};
 use parking_lot::RwLock;
 use std::collections::HashSet;
 use std::sync::Arc;
 use std::thread;
-use tracing::{debug, error, info, warn};
+use tracing::{debug, error, info};
 
 /// System log bridge - receives logs from multiple dora nodes
 ///
@@ -37,6 +38,8 @@ pub struct SystemLogBridge {
     stop_sender: Option<Sender<()>>,
     /// Worker thread handle
     worker_handle: Option<thread::JoinHandle<()>>,
+    /// Opens the node's transport (dora unless replaced for tests)
+    connector: Arc<dyn Connector>,
 }
 
 impl SystemLogBridge {
@@ -55,9 +58,16 @@ impl SystemLogBridge {
             min_level: Arc::new(RwLock::new(LogLevel::Info)),
             stop_sender: None,
             worker_handle: None,
+            connector: Arc::new(DoraConnector),
         }
     }
 
+    /// Connect through `connector` instead of dora (e.g. a [`Loopback`](crate::loopback::Loopback))
+    pub fn with_connector(mut self, connector: Arc<dyn Connector>) -> Self {
+        self.connector = connector;
+        self
+    }
+
     /// Set minimum log level filter
     pub fn set_min_level(&self, level: LogLevel) {
         *self.min_level.write() = level;
@@ -73,6 +83,7 @@ impl SystemLogBridge {
         node_id: String,
         state: Arc<RwLock<BridgeState>>,
         shared_state: Option<Arc<SharedDoraState>>,
+        connector: Arc<dyn Connector>,
         log_sources: Arc<RwLock<HashSet<String>>>,
         min_level: Arc<RwLock<LogLevel>>,
         stop_receiver: Receiver<()>,
@@ -80,17 +91,18 @@ impl SystemLogBridge {
         info!("Starting system log bridge event loop for {}", node_id);
 
         // Initialize dora node
-        let (_node, mut events) = match DoraNode::init_from_node_id(NodeId::from(node_id.clone())) {
-            Ok(n) => n,
-            Err(e) => {
-                error!("Failed to init dora node {}: {}", node_id, e);
-                *state.write() = BridgeState::Error;
-                if let Some(ref ss) = shared_state {
-                    ss.set_error(Some(format!("Init failed: {}", e)));
+        let mut transport =
+            match transport::open(connector.as_ref(), &node_id, shared_state.as_ref()) {
+                Ok(t) => t,
+                Err(e) => {
+                    error!("Failed to init dora node {}: {}", node_id, e);
+                    *state.write() = BridgeState::Error;
+                    if let Some(ref ss) = shared_state {
+                        ss.set_error(Some(format!("Init failed: {}", e)));
+                    }
+                    return;
                 }
-                return;
-            }
-        };
+            };
 
         *state.write() = BridgeState::Connected;
         if let Some(ref ss) = shared_state {
@@ -106,7 +118,7 @@ impl SystemLogBridge {
             }
 
             // Receive dora events with timeout
-            match events.recv_timeout(std::time::Duration::from_millis(100)) {
+            match transport.recv_timeout(std::time::Duration::from_millis(100)) {
                 Some(event) => {
                     Self::handle_dora_event(event, shared_state.as_ref(), &log_sources, &min_level);
                 }
@@ -125,13 +137,13 @@ impl SystemLogBridge {
 
     /// Handle a dora event
     fn handle_dora_event(
-        event: Event,
+        event: TransportEvent,
         shared_state: Option<&Arc<SharedDoraState>>,
         log_sources: &Arc<RwLock<HashSet<String>>>,
         min_level: &Arc<RwLock<LogLevel>>,
     ) {
         match event {
-            Event::Input { id, data, metadata } => {
+            TransportEvent::Input { id, data, metadata } => {
                 let input_id = id.as_str();
 
                 // Extract source node from input ID (e.g., "tts_log" -> "tts")
@@ -143,20 +155,7 @@ impl SystemLogBridge {
                 // Track log source
                 log_sources.write().insert(source_node.to_string());
 
-                // Extract metadata (handle all parameter types like conference-dashboard)
-                let mut event_meta = EventMetadata::default();
-                for (key, value) in metadata.parameters.iter() {
-                    let string_value = match value {
-                        Parameter::String(s) => s.clone(),
-                        Parameter::Integer(i) => i.to_string(),
-                        Parameter::Float(f) => f.to_string(),
-                        Parameter::Bool(b) => b.to_string(),
-                        Parameter::ListInt(l) => format!("{:?}", l),
-                        Parameter::ListFloat(l) => format!("{:?}", l),
-                        Parameter::ListString(l) => format!("{:?}", l),
-                    };
-                    event_meta.values.insert(key.clone(), string_value);
-                }
+                let event_meta = codec::event_metadata(&metadata);
 
                 // Try to parse log entry
                 if let Some(log_entry) = Self::extract_log_entry(&data, source_node, &event_meta) {
@@ -175,7 +174,7 @@ impl SystemLogBridge {
                     }
                 }
             }
-            Event::Stop(_) => {
+            TransportEvent::Stop => {
                 info!("Received stop event from dora");
             }
             _ => {}
@@ -184,12 +183,12 @@ impl SystemLogBridge {
 
     /// Extract log entry from dora data
     fn extract_log_entry(
-        data: &dora_node_api::ArrowData,
+        data: &ArrayRef,
         source_node: &str,
         _metadata: &EventMetadata,
     ) -> Option<LogEntry> {
         // Try to extract string data
-        let text = Self::extract_string(data)?;
+        let text = codec::decode_string(data)?;
 
         // Try to parse as JSON log
         if let Ok(json) = serde_json::from_str::<serde_json::Value>(&text) {
@@ -228,39 +227,6 @@ impl SystemLogBridge {
         // Plain text log
         Some(LogEntry::new(LogLevel::Info, text, source_node))
     }
-
-    /// Extract string from arrow data
-    fn extract_string(data: &dora_node_api::ArrowData) -> Option<String> {
-        match data.0.data_type() {
-            arrow::datatypes::DataType::Utf8 => {
-                let array = data
-                    .0
-                    .as_any()
-                    .downcast_ref::<arrow::array::StringArray>()?;
-                if array.len() > 0 {
-                    return Some(array.value(0).to_string());
-                }
-            }
-            arrow::datatypes::DataType::LargeUtf8 => {
-                let array = data
-                    .0
-                    .as_any()
-                    .downcast_ref::<arrow::array::LargeStringArray>()?;
-                if array.len() > 0 {
-                    return Some(array.value(0).to_string());
-                }
-            }
-            arrow::datatypes::DataType::UInt8 => {
-                let array = data.0.as_any().downcast_ref::<arrow::array::UInt8Array>()?;
-                let bytes: Vec<u8> = array.values().to_vec();
-                return String::from_utf8(bytes).ok();
-            }
-            _ => {
-                warn!("Unsupported log data type: {:?}", data.0.data_type());
-            }
-        }
-        None
-    }
 }
 
 impl DoraBridge for SystemLogBridge {
@@ -285,6 +251,7 @@ impl DoraBridge for SystemLogBridge {
         let node_id = self.node_id.clone();
         let state = Arc::clone(&self.state);
         let shared_state = self.shared_state.clone();
+        let connector = Arc::clone(&self.connector);
         let log_sources = Arc::clone(&self.log_sources);
         let min_level = Arc::clone(&self.min_level);
 
@@ -293,6 +260,7 @@ impl DoraBridge for SystemLogBridge {
                 node_id,
                 state,
                 shared_state,
+                connector,
                 log_sources,
                 min_level,
                 stop_rx,
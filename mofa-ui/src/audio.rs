@@ -217,6 +217,11 @@ impl AudioManager {
         // Note: Output device selection would be used when playing audio
     }
 
+    /// Follow the system default output device
+    pub fn use_default_output_device(&mut self) {
+        self.current_output_device = None;
+    }
+
     /// Get current input device name
     pub fn current_input_device(&self) -> Option<&str> {
         self.current_input_device.as_deref()
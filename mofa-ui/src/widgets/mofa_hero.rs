@@ -512,6 +512,10 @@ pub enum ConnectionStatus {
     Stopping,
     Stopped,
     Failed,
+    /// Dataflow died and is being restarted (1-based attempt number)
+    Recovering { attempt: u32 },
+    /// Dataflow is up, model still loading (progress 0.0–1.0 if known)
+    Loading { progress: Option<f32> },
 }
 
 impl Widget for MofaHero {
@@ -774,6 +778,7 @@ impl MofaHero {
     pub fn set_connection_status(&mut self, cx: &mut Cx, status: ConnectionStatus) {
         self.connection_status = status.clone();
 
+        let dynamic_text;
         let (status_val, text, dot_color) = match status {
             ConnectionStatus::Ready => (0.0, "Ready", (0.13, 0.77, 0.37)), // Green
             ConnectionStatus::Connecting => (0.5, "Connecting", (0.8, 0.8, 0.0)), // Yellow
@@ -781,6 +786,17 @@ impl MofaHero {
             ConnectionStatus::Stopping => (0.5, "Stopping", (0.8, 0.6, 0.0)), // Orange
             ConnectionStatus::Stopped => (0.0, "Stopped", (0.5, 0.5, 0.5)), // Gray
             ConnectionStatus::Failed => (2.0, "Failed", (0.95, 0.25, 0.25)), // Red
+            ConnectionStatus::Recovering { attempt } => {
+                dynamic_text = format!("Recovering ({})", attempt);
+                (0.5, dynamic_text.as_str(), (0.8, 0.6, 0.0)) // Orange
+            }
+            ConnectionStatus::Loading { progress } => {
+                dynamic_text = match progress {
+                    Some(p) => format!("Loading {:.0}%", p * 100.0),
+                    None => "Loading".to_string(),
+                };
+                (0.5, dynamic_text.as_str(), (0.8, 0.8, 0.0)) // Yellow
+            }
         };
 
         self.view
@@ -1,452 +1,1142 @@
-//! Audio Player Module - Circular buffer audio playback using cpal
+//! Audio Player Module - Shared playback engine
 //!
-//! Features:
-//! - Thread-safe circular buffer for audio samples
-//! - Configurable sample rate (default 32kHz for PrimeSpeech, 24kHz for Kokoro)
+//! Every player in MoFA apps runs on this engine:
+//! - Streams audio as it arrives (`write_audio`) or plays a loaded clip
+//!   (`load`) with sample-accurate `seek` and position reporting
+//! - Loops a range of the clip (`set_loop`)
+//! - Clips keep their own sample rate and channel count and are converted to
+//!   the device's with a streaming sinc resampler
+//! - Optional question/participant tagging of streamed audio
 //! - Buffer status reporting for backpressure control
-//! - Uses channels for thread-safe communication
+//! - Lock-free waveform and spectrum tap of the audio actually sent to the
+//!   output ([`AudioTap`])
+//! - Per-player finished signal for the UI thread
+//! - Plays through an [`audio_sink`](crate::audio_sink), follows output device
+//!   switches and unplugs, and also runs on machines without a sound card
+//!
+//! The player owns the clip; the sink callback advances its cursor, so the
+//! reported position is what has actually been handed to the output.
 
-use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
-use crossbeam_channel::{unbounded, Receiver, Sender};
+use crate::audio_sink::{open_sink, output_device_available, RenderFn, SinkConfig, SinkStream};
+use crate::audio_tap::AudioTap;
+use crossbeam_channel::{bounded, select, unbounded, Receiver, Sender};
+use makepad_widgets::SignalToUI;
 use parking_lot::Mutex;
+use rubato::{
+    Resampler, SincFixedIn, SincInterpolationParameters, SincInterpolationType, WindowFunction,
+};
 use std::collections::VecDeque;
-use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
 
-/// Segment tracking for knowing which participant owns audio in the buffer
-#[derive(Clone, Debug)]
-struct AudioSegment {
-    participant_idx: Option<usize>,
-    samples_remaining: usize,
-}
+/// Seconds of streamed audio the player is sized for: fill percentage is
+/// reported against it, and played audio beyond it is dropped
+const STREAM_BUFFER_SECONDS: f64 = 60.0;
+
+/// Unplayed audio needed before streamed audio starts playing by itself
+const AUTO_START_SECONDS: f64 = 0.1;
+
+/// Source frames handed to the resampler per process call
+const RESAMPLER_CHUNK_FRAMES: usize = 512;
+
+/// Frames per chunk of streamed clip audio
+const CLIP_CHUNK_FRAMES: usize = 16384;
+
+/// How often a degraded output (no device, or the default standing in for an
+/// unplugged selected device) tries to get back to the preferred device
+const DEVICE_RETRY_INTERVAL: Duration = Duration::from_secs(2);
 
 /// Commands sent to the audio thread
 enum AudioCommand {
-    Write(Vec<f32>, Option<u32>, Option<usize>), // samples, question_id, participant_idx
-    Reset,
+    /// Append samples, tagged with an optional question and participant
+    Write {
+        samples: Vec<f32>,
+        format: ClipFormat,
+        question_id: Option<u32>,
+        participant_idx: Option<usize>,
+    },
+    Load(Vec<f32>, ClipFormat),  // Replace the clip, paused at its start
+    Seek(f64),                   // Move the cursor to a time in seconds
+    SetLoop(Option<(f64, f64)>), // Loop a range in seconds, or play through
+    Reset,                       // Clear the clip
+    EndStream,                   // No more audio follows the buffered stream
     Pause,
     Resume,
-    Stop,
+    SetOutputDevice(Option<String>), // Rebuild the stream on another device
+}
+
+/// Sample rate and channel layout of a clip
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct ClipFormat {
+    sample_rate: u32,
+    channels: usize,
 }
 
-/// Circular audio buffer for thread-safe audio streaming with segment tracking
-struct CircularAudioBuffer {
-    buffer: Vec<f32>,
-    write_pos: usize,
-    read_pos: usize,
-    available_samples: usize,
-    buffer_size: usize,
-    /// Track which participant owns each segment of audio
-    segments: VecDeque<AudioSegment>,
-    /// Current participant being played
-    current_playing_participant: Option<usize>,
+impl ClipFormat {
+    fn new(sample_rate: u32, channels: u16) -> Self {
+        Self {
+            sample_rate,
+            channels: channels.max(1) as usize,
+        }
+    }
+}
+
+/// Interleaved clip samples held in chunks
+///
+/// Appending fills the last chunk and adds new ones, so audio already held
+/// never moves, and played audio is dropped by skipping into the first chunk
+/// and releasing chunks once they are skipped entirely. Chunks are allocated
+/// and freed by the caller, outside the clip lock.
+#[derive(Default)]
+struct ClipSamples {
+    chunks: VecDeque<Vec<f32>>,
+    /// Samples skipped at the start of the first chunk
+    head: usize,
+    /// Samples held after `head`
+    len: usize,
 }
 
-impl CircularAudioBuffer {
-    fn new(size_seconds: f32, sample_rate: u32) -> Self {
-        let buffer_size = (size_seconds * sample_rate as f32) as usize;
+impl From<Vec<f32>> for ClipSamples {
+    fn from(samples: Vec<f32>) -> Self {
+        let len = samples.len();
         Self {
-            buffer: vec![0.0; buffer_size],
-            write_pos: 0,
-            read_pos: 0,
-            available_samples: 0,
-            buffer_size,
-            segments: VecDeque::new(),
-            current_playing_participant: None,
+            chunks: VecDeque::from([samples]),
+            head: 0,
+            len,
         }
     }
+}
+
+impl ClipSamples {
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Room left in the last chunk
+    fn spare(&self) -> usize {
+        self.chunks
+            .back()
+            .map(|chunk| chunk.capacity() - chunk.len())
+            .unwrap_or(0)
+    }
+
+    /// Append `samples`, spilling what the last chunk has no room for into
+    /// `chunk`
+    fn push(&mut self, samples: &[f32], chunk: Option<Vec<f32>>) {
+        let fits = samples.len().min(self.spare());
+        if let Some(last) = self.chunks.back_mut() {
+            last.extend_from_slice(&samples[..fits]);
+        }
+        if fits < samples.len() {
+            let mut chunk = chunk.unwrap_or_default();
+            chunk.extend_from_slice(&samples[fits..]);
+            self.chunks.push_back(chunk);
+        }
+        self.len += samples.len();
+    }
 
-    fn write_with_participant(&mut self, samples: &[f32], participant_idx: Option<usize>) -> usize {
+    /// Drop `count` samples from the front
+    fn skip(&mut self, count: usize) {
+        let count = count.min(self.len);
+        self.head += count;
+        self.len -= count;
+    }
+
+    /// Take out the first chunk once it has been skipped entirely, to be
+    /// freed once the clip is unlocked
+    fn release_skipped(&mut self) -> Option<Vec<f32>> {
+        let first = self.chunks.front()?.len();
+        if self.head < first || (self.len == 0 && self.chunks.len() == 1) {
+            return None;
+        }
+        self.head -= first;
+        self.chunks.pop_front()
+    }
+
+    /// Copy `output.len()` samples starting `start` samples into the held audio
+    fn copy_to(&self, start: usize, output: &mut [f32]) {
+        let mut skip = self.head + start;
         let mut written = 0;
-        for &sample in samples {
-            if self.available_samples < self.buffer_size {
-                self.buffer[self.write_pos] = sample;
-                self.write_pos = (self.write_pos + 1) % self.buffer_size;
-                self.available_samples += 1;
-                written += 1;
-            } else {
-                // Buffer full - overwrite oldest data and update segment tracking
-                self.buffer[self.write_pos] = sample;
-                self.write_pos = (self.write_pos + 1) % self.buffer_size;
-                self.read_pos = (self.read_pos + 1) % self.buffer_size;
-                // Remove one sample from oldest segment
-                if let Some(front) = self.segments.front_mut() {
-                    if front.samples_remaining > 0 {
-                        front.samples_remaining -= 1;
-                    }
-                    if front.samples_remaining == 0 {
-                        self.segments.pop_front();
-                    }
-                }
-                written += 1;
+        for chunk in &self.chunks {
+            if written == output.len() {
+                break;
+            }
+            if skip >= chunk.len() {
+                skip -= chunk.len();
+                continue;
             }
+            let count = (chunk.len() - skip).min(output.len() - written);
+            output[written..written + count].copy_from_slice(&chunk[skip..skip + count]);
+            written += count;
+            skip = 0;
         }
+    }
+}
 
-        // Add segment tracking for this write
-        if written > 0 {
-            // Try to merge with last segment if same participant
-            if let Some(last) = self.segments.back_mut() {
-                if last.participant_idx == participant_idx {
-                    last.samples_remaining += written;
-                } else {
-                    self.segments.push_back(AudioSegment {
-                        participant_idx,
-                        samples_remaining: written,
-                    });
-                }
-            } else {
-                self.segments.push_back(AudioSegment {
-                    participant_idx,
-                    samples_remaining: written,
-                });
-            }
+/// Clip loaded into the player with its play cursor
+///
+/// Shared by the audio callback, which reads from the cursor, and the audio
+/// thread, which writes, loads and seeks it. Samples are interleaved;
+/// positions are in source frames from the start of the clip, including
+/// played audio a long stream has already dropped.
+struct PlaybackClip {
+    samples: ClipSamples,
+    format: ClipFormat,
+    /// Frames dropped from the front (first frame held in `samples`)
+    base: usize,
+    /// Next source frame to hand to the output converter
+    cursor: usize,
+    /// Source frame currently reaching the device (behind `cursor` by the
+    /// converter's buffering)
+    played: usize,
+    /// Bumped on every discontinuity (load, seek, clear) so the converter
+    /// drops its buffered state
+    generation: u64,
+    /// Participant owning each run of streamed audio, by first frame
+    segments: VecDeque<(usize, Option<usize>)>,
+    /// Frames `start..end` played repeatedly
+    loop_range: Option<(usize, usize)>,
+    /// Streamed audio may still be appended (until the stream is ended or
+    /// the clip replaced)
+    open: bool,
+}
+
+impl PlaybackClip {
+    fn new(format: ClipFormat) -> Self {
+        Self {
+            samples: ClipSamples::default(),
+            format,
+            base: 0,
+            cursor: 0,
+            played: 0,
+            generation: 0,
+            segments: VecDeque::new(),
+            loop_range: None,
+            open: false,
         }
+    }
 
-        written
+    /// Capacity of a new chunk for appending `samples` values in `format`,
+    /// when the last chunk lacks room or a new clip starts
+    fn growth(&self, samples: usize, format: ClipFormat) -> Option<usize> {
+        let chunk = CLIP_CHUNK_FRAMES * format.channels;
+        if format != self.format {
+            return Some(samples.max(chunk));
+        }
+        let spare = self.samples.spare();
+        (samples > spare).then(|| (samples - spare).max(chunk))
     }
 
-    fn read(&mut self, output: &mut [f32]) -> usize {
-        let mut read_count = 0;
-        for sample in output.iter_mut() {
-            if self.available_samples > 0 {
-                *sample = self.buffer[self.read_pos];
-                self.read_pos = (self.read_pos + 1) % self.buffer_size;
-                self.available_samples -= 1;
-                read_count += 1;
-
-                // Update segment tracking - consume from front segment
-                if let Some(front) = self.segments.front_mut() {
-                    // Update current playing participant
-                    self.current_playing_participant = front.participant_idx;
-
-                    if front.samples_remaining > 0 {
-                        front.samples_remaining -= 1;
-                    }
-                    if front.samples_remaining == 0 {
-                        self.segments.pop_front();
-                    }
-                }
-            } else {
-                *sample = 0.0; // Underrun - output silence
+    /// Append samples owned by `participant_idx` to the end of the clip
+    /// (streaming). Audio in a different format starts a new clip.
+    ///
+    /// `chunk` is an empty buffer of the size [`Self::growth`] asked for,
+    /// so no audio is allocated or moved here; a clip it replaces is
+    /// returned to be freed once the clip is unlocked.
+    fn append(
+        &mut self,
+        samples: &[f32],
+        format: ClipFormat,
+        participant_idx: Option<usize>,
+        chunk: Option<Vec<f32>>,
+    ) -> Option<ClipSamples> {
+        let (replaced, chunk) = if format != self.format {
+            let clip = ClipSamples::from(chunk.unwrap_or_default());
+            (Some(self.load(clip, format)), None)
+        } else {
+            (None, chunk)
+        };
+        let start = self.frames();
+        self.samples.push(samples, chunk);
+        self.open = true;
+        if self.segments.back().map(|&(_, owner)| owner) != Some(participant_idx) {
+            self.segments.push_back((start, participant_idx));
+        }
+        replaced
+    }
+
+    /// Replace the clip and rewind to its start. Returns the old samples,
+    /// to be freed once the clip is unlocked.
+    fn load(&mut self, samples: impl Into<ClipSamples>, format: ClipFormat) -> ClipSamples {
+        let old = std::mem::replace(&mut self.samples, samples.into());
+        self.format = format;
+        self.base = 0;
+        self.segments.clear();
+        self.loop_range = None;
+        self.open = false;
+        self.seek(0);
+        old
+    }
+
+    /// No more streamed audio follows what is held
+    fn end_stream(&mut self) {
+        self.open = false;
+    }
+
+    fn clear(&mut self) -> ClipSamples {
+        self.load(ClipSamples::default(), self.format)
+    }
+
+    /// Drop played audio from the front once the clip holds more than twice
+    /// `keep` frames, so an endless stream stays bounded. Chunks dropped
+    /// entirely are handed out by [`Self::release_played`].
+    fn trim(&mut self, keep: usize) {
+        let held = self.frames() - self.base;
+        if held <= keep * 2 || self.loop_range.is_some() {
+            return;
+        }
+        let drop = (held - keep).min(self.played - self.base);
+        self.samples.skip(drop * self.format.channels);
+        self.base += drop;
+        while self.segments.len() > 1 && self.segments[1].0 <= self.base {
+            self.segments.pop_front();
+        }
+    }
+
+    /// Take out a chunk of audio dropped by [`Self::trim`], to be freed once
+    /// the clip is unlocked
+    fn release_played(&mut self) -> Option<Vec<f32>> {
+        self.samples.release_skipped()
+    }
+
+    /// Move the cursor to a source frame, clamped to the audio still held
+    fn seek(&mut self, frame: usize) {
+        self.cursor = frame.clamp(self.base, self.frames());
+        self.played = self.cursor;
+        self.generation += 1;
+    }
+
+    /// Play `start..end` repeatedly once the cursor gets there (`None` plays
+    /// through). The range is clamped to the audio held; an empty one clears
+    /// the loop.
+    fn set_loop(&mut self, range: Option<(usize, usize)>) {
+        let (base, frames) = (self.base, self.frames());
+        self.loop_range = range
+            .map(|(start, end)| (start.clamp(base, frames), end.clamp(base, frames)))
+            .filter(|&(start, end)| start < end);
+    }
+
+    /// Jump back to the loop start if the cursor reached the loop end
+    fn wrap_loop(&mut self) -> bool {
+        match self.loop_range {
+            Some((start, end)) if self.cursor == end => {
+                self.seek(start);
+                true
             }
+            _ => false,
+        }
+    }
+
+    fn position(&self) -> usize {
+        self.played
+    }
+
+    fn frames(&self) -> usize {
+        self.base + self.samples.len() / self.format.channels.max(1)
+    }
+
+    fn remaining(&self) -> usize {
+        self.frames() - self.cursor
+    }
+
+    /// Frames not yet reached by the device, including converter buffering
+    fn unplayed(&self) -> usize {
+        self.frames() - self.played
+    }
+
+    /// Frames readable before the cursor hits the clip or loop end
+    fn readable(&self) -> usize {
+        match self.loop_range {
+            Some((_, end)) if self.cursor <= end => end - self.cursor,
+            _ => self.remaining(),
         }
-        read_count
     }
 
-    /// Get the participant whose audio is currently being played
+    fn is_finished(&self) -> bool {
+        self.cursor >= self.frames()
+    }
+
+    /// Participant owning the last frame that reached the device
     fn current_participant(&self) -> Option<usize> {
-        self.current_playing_participant
+        self.segments
+            .iter()
+            .rev()
+            .find(|&&(start, _)| start < self.played)
+            .and_then(|&(_, owner)| owner)
     }
 
-    fn fill_percentage(&self) -> f64 {
-        (self.available_samples as f64 / self.buffer_size as f64) * 100.0
+    fn seconds(&self, frames: usize) -> f64 {
+        frames as f64 / self.format.sample_rate as f64
     }
 
-    fn available_seconds(&self, sample_rate: u32) -> f64 {
-        self.available_samples as f64 / sample_rate as f64
+    /// Copy interleaved frames from the cursor into `output` and advance it.
+    /// Returns the number of frames read.
+    fn read(&mut self, output: &mut [f32]) -> usize {
+        let channels = self.format.channels.max(1);
+        let frames = (output.len() / channels).min(self.readable());
+        let start = (self.cursor - self.base) * channels;
+        self.samples
+            .copy_to(start, &mut output[..frames * channels]);
+        self.cursor += frames;
+        frames
     }
+}
 
-    fn reset(&mut self) {
-        self.write_pos = 0;
-        self.read_pos = 0;
-        self.available_samples = 0;
-        self.segments.clear();
-        self.current_playing_participant = None;
+/// Converts the clip to the device's sample rate and channel layout
+///
+/// Owned by the sink callback. Source audio goes through a fixed-input sinc
+/// resampler in chunks of [`RESAMPLER_CHUNK_FRAMES`]; resampled frames that do
+/// not fit the current callback stay buffered for the next one, so chunk and
+/// callback boundaries are seamless. The resampler's delay is trimmed from the
+/// head and its zero-padded tail is cut when the clip runs dry, which keeps
+/// device frames aligned with source time for position reporting.
+struct OutputConverter {
+    device_rate: u32,
+    device_channels: usize,
+    /// Source format and clip generation the buffered state belongs to
+    format: ClipFormat,
+    generation: u64,
+    /// `None` when the clip already matches the device rate
+    resampler: Option<SincFixedIn<f32>>,
+    /// Interleaved frames read from the clip
+    read_buf: Vec<f32>,
+    /// Planar resampler input and output
+    input: Vec<Vec<f32>>,
+    output: Vec<Vec<f32>>,
+    /// Valid frames in `output` and the next one to hand to the device
+    output_len: usize,
+    output_pos: usize,
+    /// Leading output frames still to drop (resampler delay)
+    skip: usize,
+    /// Source frame at the last reset
+    origin: usize,
+    /// Source frames fed since the last reset
+    fed: usize,
+    /// Device frames emitted since the last reset
+    emitted: usize,
+    /// A zero-padded chunk was fed since the last reset
+    padded: bool,
+}
+
+impl OutputConverter {
+    fn new(device_rate: u32, device_channels: usize) -> Self {
+        Self {
+            device_rate,
+            device_channels: device_channels.max(1),
+            format: ClipFormat {
+                sample_rate: 0,
+                channels: 0,
+            },
+            generation: 0,
+            resampler: None,
+            read_buf: Vec::new(),
+            input: Vec::new(),
+            output: Vec::new(),
+            output_len: 0,
+            output_pos: 0,
+            skip: 0,
+            origin: 0,
+            fed: 0,
+            emitted: 0,
+            padded: false,
+        }
+    }
+
+    /// Device frames per source frame
+    fn ratio(&self) -> f64 {
+        self.device_rate as f64 / self.format.sample_rate as f64
+    }
+
+    /// Rebuild for a new source format, or drop buffered audio after a
+    /// discontinuity in the clip. Rebuilding allocates, which only happens
+    /// when a clip in a different format is loaded.
+    fn sync(&mut self, clip: &PlaybackClip) {
+        if clip.format != self.format {
+            self.format = clip.format;
+            let channels = clip.format.channels.max(1);
+            self.resampler = if clip.format.sample_rate == self.device_rate {
+                None
+            } else {
+                let params = SincInterpolationParameters {
+                    sinc_len: 256,
+                    f_cutoff: 0.95,
+                    oversampling_factor: 256,
+                    interpolation: SincInterpolationType::Linear,
+                    window: WindowFunction::BlackmanHarris2,
+                };
+                match SincFixedIn::<f32>::new(
+                    self.ratio(),
+                    1.0,
+                    params,
+                    RESAMPLER_CHUNK_FRAMES,
+                    channels,
+                ) {
+                    Ok(resampler) => Some(resampler),
+                    Err(e) => {
+                        log::warn!("Resampler error: {}, playing without rate conversion", e);
+                        None
+                    }
+                }
+            };
+            let output_frames = self
+                .resampler
+                .as_ref()
+                .map(|r| r.output_frames_max())
+                .unwrap_or(RESAMPLER_CHUNK_FRAMES);
+            self.read_buf = vec![0.0; RESAMPLER_CHUNK_FRAMES * channels];
+            self.input = vec![vec![0.0; RESAMPLER_CHUNK_FRAMES]; channels];
+            self.output = vec![vec![0.0; output_frames]; channels];
+            self.restart(clip.cursor);
+        } else if clip.generation != self.generation {
+            self.restart(clip.cursor);
+        }
+        self.generation = clip.generation;
     }
 
-    fn available(&self) -> usize {
-        self.available_samples
+    /// Drop buffered audio and resampler history, continuing from `origin`
+    fn restart(&mut self, origin: usize) {
+        if let Some(resampler) = self.resampler.as_mut() {
+            resampler.reset();
+        }
+        self.skip = self
+            .resampler
+            .as_ref()
+            .map(|r| r.output_delay())
+            .unwrap_or(0);
+        self.output_len = 0;
+        self.output_pos = 0;
+        self.origin = origin;
+        self.fed = 0;
+        self.emitted = 0;
+        self.padded = false;
+    }
+
+    /// Fill `data` (interleaved device frames) from the clip and update the
+    /// clip's played position. Returns the number of frames that carried
+    /// clip audio; the rest is silence.
+    fn render(&mut self, clip: &mut PlaybackClip, data: &mut [f32]) -> usize {
+        self.sync(clip);
+        let frames = data.len() / self.device_channels;
+        let mut rendered = 0;
+        let mut wrapped_at = None;
+
+        while rendered < frames {
+            if self.output_pos == self.output_len {
+                if self.refill(clip) {
+                    continue;
+                }
+                // A looped range ran dry: go on from its start, unless the
+                // last jump back produced nothing
+                if wrapped_at != Some(rendered) && clip.wrap_loop() {
+                    wrapped_at = Some(rendered);
+                    self.sync(clip);
+                    continue;
+                }
+                break;
+            }
+            let take = (frames - rendered).min(self.output_len - self.output_pos);
+            for i in 0..take {
+                let offset = (rendered + i) * self.device_channels;
+                self.map_channels(
+                    self.output_pos + i,
+                    &mut data[offset..offset + self.device_channels],
+                );
+            }
+            self.output_pos += take;
+            self.emitted += take;
+            rendered += take;
+        }
+
+        for sample in &mut data[rendered * self.device_channels..] {
+            *sample = 0.0;
+        }
+
+        let played = self.origin + (self.emitted as f64 / self.ratio()).round() as usize;
+        clip.played = played.min(clip.cursor);
+        rendered
     }
 
-    fn get_waveform(&self, num_samples: usize) -> Vec<f32> {
-        let mut samples = Vec::with_capacity(num_samples);
-        if self.available_samples == 0 {
-            return vec![0.0; num_samples];
+    /// Convert the next chunk of the clip. Returns false once everything fed
+    /// so far has been emitted and the clip has nothing more.
+    ///
+    /// Zero padding stays in the resampler history, so audio must never
+    /// follow it: while the stream is open a short tail waits for a full
+    /// chunk, and audio appended after a padded chunk is only read once the
+    /// padding has been flushed and the converter restarted.
+    fn refill(&mut self, clip: &mut PlaybackClip) -> bool {
+        let channels = self.input.len();
+        if !self.padded
+            && self.resampler.is_some()
+            && clip.open
+            && clip.readable() < RESAMPLER_CHUNK_FRAMES
+        {
+            return false;
         }
 
-        let start = if self.available_samples >= num_samples {
-            (self.read_pos + self.buffer_size - num_samples) % self.buffer_size
+        let read = if self.padded {
+            0
         } else {
-            self.read_pos
+            clip.read(&mut self.read_buf)
         };
+        if read == 0 {
+            let expected = (self.fed as f64 * self.ratio()).round() as usize;
+            if self.fed == 0 || self.emitted >= expected {
+                // Drained: start the next stretch of audio from a clean
+                // state, including any that arrived during the flush
+                let flushed = self.padded;
+                self.restart(clip.cursor);
+                return flushed && self.refill(clip);
+            }
+        }
 
-        for i in 0..num_samples.min(self.available_samples) {
-            let idx = (start + i) % self.buffer_size;
-            samples.push(self.buffer[idx]);
+        for (ch, input) in self.input.iter_mut().enumerate() {
+            for (frame, sample) in input.iter_mut().enumerate() {
+                *sample = if frame < read {
+                    self.read_buf[frame * channels + ch]
+                } else {
+                    0.0
+                };
+            }
         }
+        self.fed += read;
+        self.padded = self.resampler.is_some() && read < RESAMPLER_CHUNK_FRAMES;
+
+        let produced = match self.resampler.as_mut() {
+            Some(resampler) => {
+                match resampler.process_into_buffer(&self.input, &mut self.output, None) {
+                    Ok((_, produced)) => produced,
+                    Err(e) => {
+                        log::error!("Resampler error: {}", e);
+                        self.restart(clip.cursor);
+                        return false;
+                    }
+                }
+            }
+            None => {
+                for (output, input) in self.output.iter_mut().zip(&self.input) {
+                    output[..read].copy_from_slice(&input[..read]);
+                }
+                read
+            }
+        };
+
+        // Trim the resampler delay from the head of the stream
+        let skipped = self.skip.min(produced);
+        self.skip -= skipped;
+        self.output_pos = skipped;
 
-        samples.resize(num_samples, 0.0);
-        samples
+        // Cut zero padding so only frames backed by real input are emitted
+        let expected = (self.fed as f64 * self.ratio()).round() as usize;
+        let real = expected.saturating_sub(self.emitted);
+        self.output_len = produced.min(skipped + real);
+        true
+    }
+
+    /// Write one device frame from resampled frame `index`: mono is copied to
+    /// every channel, multichannel is averaged down to a mono device, and
+    /// otherwise channels map one to one with extra device channels silent.
+    fn map_channels(&self, index: usize, frame: &mut [f32]) {
+        let source_channels = self.output.len();
+        if source_channels == 1 {
+            frame.fill(self.output[0][index]);
+        } else if frame.len() == 1 {
+            let sum: f32 = self.output.iter().map(|ch| ch[index]).sum();
+            frame[0] = sum / source_channels as f32;
+        } else {
+            for (ch, sample) in frame.iter_mut().enumerate() {
+                *sample = self.output.get(ch).map(|c| c[index]).unwrap_or(0.0);
+            }
+        }
     }
 }
 
 /// Shared state between audio thread and main thread
 struct SharedAudioState {
-    buffer_fill: f64,
-    buffer_seconds: f64,
-    is_playing: bool,
-    waveform: Vec<f32>,
-    output_waveform: Vec<f32>, // Samples currently being played
     current_question_id: u32,
-    current_participant_idx: Option<usize>, // 0=student1, 1=student2, 2=tutor
+    /// Output the stream is currently playing on (`None` while none is open)
+    output_device: Option<String>,
 }
 
-/// Audio player handle - can be cloned and shared across threads
+/// Audio player handle - can be cloned and shared across threads. The audio
+/// thread stops once the last handle is dropped.
 #[derive(Clone)]
 pub struct AudioPlayer {
     command_tx: Sender<AudioCommand>,
     state: Arc<Mutex<SharedAudioState>>,
+    clip: Arc<Mutex<PlaybackClip>>,
+    is_playing: Arc<AtomicBool>,
+    finished: Arc<SignalToUI>,
+    tap: AudioTap,
     sample_rate: u32,
 }
 
 impl AudioPlayer {
-    /// Create a new audio player with specified sample rate
+    /// Create a new audio player on the default output device. `sample_rate`
+    /// is the rate of audio passed to [`write_audio`](Self::write_audio).
     pub fn new(sample_rate: u32) -> Result<Self, String> {
+        Self::with_output_device(sample_rate, None)
+    }
+
+    /// Create a new audio player on the named output device (`None` for the
+    /// system default). Falls back to the default if the device is missing.
+    /// [`SINK_ENV_VAR`](crate::audio_sink::SINK_ENV_VAR) overrides the device.
+    pub fn with_output_device(sample_rate: u32, device: Option<String>) -> Result<Self, String> {
+        Self::spawn(sample_rate, device, SinkConfig::from_env())
+    }
+
+    /// Create a new audio player on `sink`, regardless of the environment.
+    /// A null or WAV sink is kept for the player's lifetime.
+    pub fn with_sink(sample_rate: u32, sink: SinkConfig) -> Result<Self, String> {
+        match sink {
+            SinkConfig::Cpal { device } => Self::spawn(sample_rate, device, None),
+            sink => Self::spawn(sample_rate, None, Some(sink)),
+        }
+    }
+
+    /// Start the audio thread on `device`, or on `sink_override` when set
+    fn spawn(
+        sample_rate: u32,
+        device: Option<String>,
+        sink_override: Option<SinkConfig>,
+    ) -> Result<Self, String> {
+        if sample_rate == 0 {
+            return Err("Sample rate must be positive".to_string());
+        }
         let (command_tx, command_rx) = unbounded::<AudioCommand>();
 
         let state = Arc::new(Mutex::new(SharedAudioState {
-            buffer_fill: 0.0,
-            buffer_seconds: 0.0,
-            is_playing: false,
-            waveform: vec![0.0; 512],
-            output_waveform: vec![0.0; 512],
             current_question_id: 0,
-            current_participant_idx: None,
+            output_device: None,
         }));
+        let clip = Arc::new(Mutex::new(PlaybackClip::new(ClipFormat::new(
+            sample_rate,
+            1,
+        ))));
+        let is_playing = Arc::new(AtomicBool::new(false));
+        let finished = Arc::new(SignalToUI::new());
+        let tap = AudioTap::new(sample_rate);
 
-        let state_clone = Arc::clone(&state);
+        let playback = Playback {
+            state: Arc::clone(&state),
+            clip: Arc::clone(&clip),
+            is_playing: Arc::clone(&is_playing),
+            finished: Arc::clone(&finished),
+            tap: tap.clone(),
+        };
 
-        // Spawn audio thread
-        std::thread::spawn(move || {
-            if let Err(e) = run_audio_thread(sample_rate, command_rx, state_clone) {
-                log::error!("Audio thread error: {}", e);
-            }
-        });
+        std::thread::spawn(move || run_audio_thread(device, sink_override, command_rx, playback));
 
         Ok(Self {
             command_tx,
             state,
+            clip,
+            is_playing,
+            finished,
+            tap,
             sample_rate,
         })
     }
 
-    /// Add audio samples to the buffer
+    /// Check if playback has finished (call this in handle_event to detect
+    /// completion). Fires when the clip plays to its end or a stream runs dry.
+    pub fn check_playback_finished(&self) -> bool {
+        self.finished.check_and_clear()
+    }
+
+    /// Add mono samples at the player's sample rate for streaming playback,
+    /// optionally tagged with the question and participant they belong to
     pub fn write_audio(
         &self,
         samples: &[f32],
         question_id: Option<u32>,
         participant_idx: Option<usize>,
     ) {
-        let _ = self.command_tx.send(AudioCommand::Write(
-            samples.to_vec(),
+        let _ = self.command_tx.send(AudioCommand::Write {
+            samples: samples.to_vec(),
+            format: ClipFormat::new(self.sample_rate, 1),
             question_id,
             participant_idx,
-        ));
+        });
+    }
+
+    /// Add interleaved samples in the given format for streaming playback. A
+    /// format change starts a new clip; a missing rate means the player's.
+    pub fn write_audio_with_format(&self, samples: &[f32], sample_rate: u32, channels: u16) {
+        let _ = self.command_tx.send(AudioCommand::Write {
+            samples: samples.to_vec(),
+            format: self.clip_format(sample_rate, channels),
+            question_id: None,
+            participant_idx: None,
+        });
+    }
+
+    /// Mark the end of streamed audio: what is buffered plays even if it is
+    /// shorter than the auto-start threshold (or, when resampling, than one
+    /// resampler chunk), and the finished signal fires
+    /// once it has played (at once if it already has). Until then a stream
+    /// that runs dry also signals finished, so callers streaming audio should
+    /// ignore the signal before calling this.
+    pub fn end_stream(&self) {
+        let _ = self.command_tx.send(AudioCommand::EndStream);
+    }
+
+    /// Replace the clip with interleaved `samples`, paused at its start (call
+    /// `resume` to play)
+    pub fn load(&self, samples: &[f32], sample_rate: u32, channels: u16) {
+        let format = self.clip_format(sample_rate, channels);
+        let _ = self
+            .command_tx
+            .send(AudioCommand::Load(samples.to_vec(), format));
     }
 
-    /// Get buffer fill percentage
+    fn clip_format(&self, sample_rate: u32, channels: u16) -> ClipFormat {
+        let sample_rate = if sample_rate > 0 {
+            sample_rate
+        } else {
+            self.sample_rate
+        };
+        ClipFormat::new(sample_rate, channels)
+    }
+
+    /// Get buffer fill percentage (unplayed audio against the stream buffer)
     pub fn buffer_fill_percentage(&self) -> f64 {
-        self.state.lock().buffer_fill
+        let clip = self.clip.lock();
+        (clip.seconds(clip.unplayed()) / STREAM_BUFFER_SECONDS * 100.0).min(100.0)
     }
 
-    /// Get available seconds in buffer
+    /// Get unplayed seconds in buffer
     pub fn buffer_seconds(&self) -> f64 {
-        self.state.lock().buffer_seconds
+        let clip = self.clip.lock();
+        clip.seconds(clip.unplayed())
     }
 
     /// Check if currently playing
     pub fn is_playing(&self) -> bool {
-        self.state.lock().is_playing
+        self.is_playing.load(Ordering::Relaxed)
     }
 
-    /// Pause playback
+    /// Pause playback. Streamed audio does not restart it until `resume`.
     pub fn pause(&self) {
         let _ = self.command_tx.send(AudioCommand::Pause);
     }
 
-    /// Resume playback
+    /// Resume from the current position, or from the start if the clip
+    /// played to the end
     pub fn resume(&self) {
         let _ = self.command_tx.send(AudioCommand::Resume);
     }
 
-    /// Reset the buffer (for new question)
+    /// Reset playback and clear the buffer (for a new question or clip)
     pub fn reset(&self) {
         let _ = self.command_tx.send(AudioCommand::Reset);
     }
 
+    /// Move the play position to `seconds` into the clip (clamped to its length)
+    pub fn seek(&self, seconds: f64) {
+        let _ = self.command_tx.send(AudioCommand::Seek(seconds));
+    }
+
+    /// Play the range `start..end` (seconds) repeatedly, or play through with
+    /// `None`. Loading or resetting the clip clears the loop.
+    pub fn set_loop(&self, range: Option<(f64, f64)>) {
+        let _ = self.command_tx.send(AudioCommand::SetLoop(range));
+    }
+
+    /// Current play position in seconds, as consumed by the output
+    pub fn position(&self) -> f64 {
+        let clip = self.clip.lock();
+        clip.seconds(clip.position())
+    }
+
+    /// Length of the clip (or everything streamed since the last reset) in seconds
+    pub fn duration(&self) -> f64 {
+        let clip = self.clip.lock();
+        clip.seconds(clip.frames())
+    }
+
     /// Get current question_id
     pub fn current_question_id(&self) -> u32 {
         self.state.lock().current_question_id
     }
 
-    /// Get current participant index (0=student1, 1=student2, 2=tutor)
+    /// Get the participant whose audio is currently playing (0=student1,
+    /// 1=student2, 2=tutor)
     pub fn current_participant_idx(&self) -> Option<usize> {
-        self.state.lock().current_participant_idx
+        self.clip.lock().current_participant()
     }
 
-    /// Get sample rate
+    /// Get sample rate of audio passed to `write_audio`
     pub fn sample_rate(&self) -> u32 {
         self.sample_rate
     }
 
-    /// Get waveform data for visualization (from current audio output)
-    pub fn get_waveform_data(&self, _num_samples: usize) -> Vec<f32> {
-        self.state.lock().output_waveform.clone()
+    /// Switch playback to the named output device (`None` for the system
+    /// default). The stream is rebuilt and continues from the current position.
+    pub fn set_output_device(&self, device: Option<String>) {
+        let _ = self.command_tx.send(AudioCommand::SetOutputDevice(device));
+    }
+
+    /// Output the player is currently playing on
+    pub fn output_device(&self) -> Option<String> {
+        self.state.lock().output_device.clone()
+    }
+
+    /// Get the `num_samples` most recent samples sent to the output, mixed
+    /// to mono, for visualization
+    pub fn get_waveform_data(&self, num_samples: usize) -> Vec<f32> {
+        self.tap.waveform(num_samples)
+    }
+
+    /// Get log-spaced spectrum levels (0.0-1.0) of the audio being output,
+    /// e.g. 8 bands for a [`WaveformView`](crate::waveform_view::WaveformView)
+    pub fn get_spectrum_bands(&self, num_bands: usize) -> Vec<f32> {
+        self.tap.bands(num_bands)
     }
-}
 
-impl Drop for AudioPlayer {
-    fn drop(&mut self) {
-        let _ = self.command_tx.send(AudioCommand::Stop);
+    /// Lock-free tap of the output, for visualizers that keep their own handle
+    pub fn audio_tap(&self) -> AudioTap {
+        self.tap.clone()
     }
 }
 
-/// Run the audio thread with cpal stream
-fn run_audio_thread(
-    sample_rate: u32,
-    command_rx: Receiver<AudioCommand>,
+/// Handles shared between a player and the stream rendering it
+#[derive(Clone)]
+struct Playback {
     state: Arc<Mutex<SharedAudioState>>,
-) -> Result<(), String> {
-    let buffer_seconds = 60.0;
-    let buffer = Arc::new(Mutex::new(CircularAudioBuffer::new(
-        buffer_seconds,
-        sample_rate,
-    )));
-    let is_playing = Arc::new(AtomicBool::new(false));
-    let current_question_id = Arc::new(AtomicU32::new(0));
-
-    // Initialize cpal audio output
-    let host = cpal::default_host();
-    let device = host
-        .default_output_device()
-        .ok_or_else(|| "No audio output device found".to_string())?;
+    clip: Arc<Mutex<PlaybackClip>>,
+    is_playing: Arc<AtomicBool>,
+    finished: Arc<SignalToUI>,
+    tap: AudioTap,
+}
+
+/// Open a playing stream on the configured sink; a preferred cpal device
+/// that is not connected falls back to the default one
+fn open_output(
+    config: &SinkConfig,
+    playback: &Playback,
+    lost_tx: &Sender<()>,
+) -> Result<SinkStream, String> {
+    let sink = open_sink(config, None)?;
+    let format = sink.format();
 
     log::info!(
-        "Audio thread started - device: {}",
-        device.name().unwrap_or_default()
+        "Audio player started - output: {} ({} channels, {} Hz)",
+        sink.name(),
+        format.channels,
+        format.sample_rate
     );
 
-    let config = cpal::StreamConfig {
-        channels: 1,
-        sample_rate: cpal::SampleRate(sample_rate),
-        buffer_size: cpal::BufferSize::Default,
-    };
+    // Continue from what the previous stream actually played; audio it had
+    // buffered but not output yet is rendered again on this one
+    {
+        let mut clip = playback.clip.lock();
+        let played = clip.position();
+        clip.seek(played);
+    }
+    let channels = format.channels.max(1) as usize;
+    let mut converter = OutputConverter::new(format.sample_rate, channels);
 
-    let buffer_clone = Arc::clone(&buffer);
-    let is_playing_clone = Arc::clone(&is_playing);
-    let state_for_callback = Arc::clone(&state);
-
-    let stream = device
-        .build_output_stream(
-            &config,
-            move |data: &mut [f32], _: &cpal::OutputCallbackInfo| {
-                if is_playing_clone.load(Ordering::Relaxed) {
-                    let mut buf = buffer_clone.lock();
-                    buf.read(data);
-                    let current_participant = buf.current_participant();
-                    drop(buf); // Release buffer lock before acquiring state lock
-
-                    // Update state with current playing participant and waveform
-                    if let Some(mut s) = state_for_callback.try_lock() {
-                        // Update current participant immediately from audio callback
-                        s.current_participant_idx = current_participant;
-
-                        // Store the most recent output samples, stretching if needed
-                        let samples: Vec<f32> = data.iter().copied().collect();
-                        if samples.len() >= 512 {
-                            s.output_waveform = samples[..512].to_vec();
-                        } else if !samples.is_empty() {
-                            // Stretch samples to fill 512 by repeating/interpolating
-                            s.output_waveform.clear();
-                            s.output_waveform.reserve(512);
-                            let ratio = samples.len() as f32 / 512.0;
-                            for i in 0..512 {
-                                let src_idx = ((i as f32 * ratio) as usize).min(samples.len() - 1);
-                                s.output_waveform.push(samples[src_idx]);
-                            }
-                        } else {
-                            s.output_waveform = vec![0.0; 512];
-                        }
-                    }
-                } else {
-                    for sample in data.iter_mut() {
-                        *sample = 0.0;
-                    }
+    let mut tap = playback.tap.writer(format.sample_rate);
+    let Playback {
+        clip,
+        is_playing,
+        finished,
+        ..
+    } = playback.clone();
+    let render: RenderFn = Box::new(move |data: &mut [f32]| {
+        let clip = if is_playing.load(Ordering::Relaxed) {
+            clip.try_lock()
+        } else {
+            None
+        };
+        match clip {
+            Some(mut clip) => {
+                let rendered = converter.render(&mut clip, data);
+
+                if rendered == 0 {
+                    // Clip played to the end - playback finished
+                    is_playing.store(false, Ordering::Relaxed);
+                    finished.set();
                 }
-            },
-            move |err| {
-                log::error!("Audio stream error: {}", err);
-            },
-            None,
-        )
-        .map_err(|e| format!("Failed to build audio stream: {}", e))?;
+            }
+            // Paused, or the audio thread is updating the clip: the device
+            // callback never waits for it
+            None => data.fill(0.0),
+        }
 
-    stream
-        .play()
-        .map_err(|e| format!("Failed to start audio stream: {}", e))?;
+        tap.push(data, channels);
+    });
 
-    // Process commands
-    loop {
-        // Non-blocking check for commands
-        match command_rx.try_recv() {
-            Ok(AudioCommand::Write(samples, question_id, participant_idx)) => {
-                if let Some(qid) = question_id {
-                    current_question_id.store(qid, Ordering::Relaxed);
-                }
+    let lost_tx = lost_tx.clone();
+    sink.start(
+        render,
+        Box::new(move || {
+            let _ = lost_tx.try_send(());
+        }),
+    )
+}
+
+/// Run the audio thread: owns the output stream and rebuilds it when the
+/// output device is switched or disappears. Exits when the last player
+/// handle is dropped.
+fn run_audio_thread(
+    mut preferred: Option<String>,
+    sink_override: Option<SinkConfig>,
+    command_rx: Receiver<AudioCommand>,
+    playback: Playback,
+) {
+    let Playback {
+        state,
+        clip,
+        is_playing,
+        finished,
+        ..
+    } = &playback;
+    let (lost_tx, lost_rx) = bounded::<()>(1);
+    // A null or WAV sink overrides the device selection (e.g. in CI)
+    let sink_config = |preferred: Option<&str>| {
+        sink_override.clone().unwrap_or(SinkConfig::Cpal {
+            device: preferred.map(str::to_string),
+        })
+    };
+    // Paused by the user: streamed audio must not restart playback
+    let mut held = false;
 
-                let mut buf = buffer.lock();
+    // (Re)open the stream, dropping the old one first so the device is free
+    let reopen = |output: &mut Option<SinkStream>, preferred: Option<&str>| {
+        *output = None;
+        *output = match open_output(&sink_config(preferred), &playback, &lost_tx) {
+            Ok(stream) => Some(stream),
+            Err(e) => {
+                log::error!("Audio output unavailable: {}", e);
+                None
+            }
+        };
+        // Losses reported by the stream just replaced are stale
+        while lost_rx.try_recv().is_ok() {}
+        state.lock().output_device = output.as_ref().map(|o| o.name().to_string());
+    };
 
-                // Write audio with participant tracking - the buffer will track
-                // which participant owns each segment of audio
-                buf.write_with_participant(&samples, participant_idx);
+    let mut output = None;
+    reopen(&mut output, preferred.as_deref());
 
-                // Start playing if we have enough audio
-                if buf.available() > sample_rate as usize / 10 {
+    loop {
+        select! {
+            recv(command_rx) -> command => match command {
+                Ok(AudioCommand::Write {
+                    samples,
+                    format,
+                    question_id,
+                    participant_idx,
+                }) => {
+                    if let Some(qid) = question_id {
+                        state.lock().current_question_id = qid;
+                    }
+                    // Only this thread changes the samples, so the room
+                    // checked here is still missing when the clip is locked
+                    // again; the device callback never waits on allocation,
+                    // and appending never moves the audio already held
+                    let growth = clip.lock().growth(samples.len(), format);
+                    let chunk = growth.map(Vec::with_capacity);
+                    let mut locked = clip.lock();
+                    let replaced = locked.append(&samples, format, participant_idx, chunk);
+                    locked.trim((STREAM_BUFFER_SECONDS * format.sample_rate as f64) as usize);
+                    // Start playing once enough audio is buffered
+                    let start_frames = (AUTO_START_SECONDS * format.sample_rate as f64) as usize;
+                    if !held && locked.remaining() > start_frames {
+                        is_playing.store(true, Ordering::Relaxed);
+                    }
+                    let mut played = locked.release_played();
+                    drop(locked);
+                    drop(replaced);
+                    // Played chunks are freed one at a time between locks
+                    while played.is_some() {
+                        drop(played);
+                        played = clip.lock().release_played();
+                    }
+                }
+                Ok(AudioCommand::Load(samples, format)) => {
+                    is_playing.store(false, Ordering::Relaxed);
+                    held = true;
+                    // The chunk list is built and the old samples freed
+                    // outside the lock
+                    let samples = ClipSamples::from(samples);
+                    let old = clip.lock().load(samples, format);
+                    drop(old);
+                }
+                Ok(AudioCommand::Seek(seconds)) => {
+                    let mut clip = clip.lock();
+                    let frame =
+                        (seconds.max(0.0) * clip.format.sample_rate as f64).round() as usize;
+                    clip.seek(frame);
+                }
+                Ok(AudioCommand::SetLoop(range)) => {
+                    let mut clip = clip.lock();
+                    let rate = clip.format.sample_rate as f64;
+                    let frame = |seconds: f64| (seconds.max(0.0) * rate).round() as usize;
+                    clip.set_loop(range.map(|(start, end)| (frame(start), frame(end))));
+                }
+                Ok(AudioCommand::Reset) => {
+                    is_playing.store(false, Ordering::Relaxed);
+                    held = false;
+                    let old = clip.lock().clear();
+                    drop(old);
+                    log::info!("Audio buffer reset");
+                }
+                Ok(AudioCommand::EndStream) => {
+                    let mut clip = clip.lock();
+                    clip.end_stream();
+                    if !held {
+                        if clip.remaining() > 0 {
+                            is_playing.store(true, Ordering::Relaxed);
+                        } else if !is_playing.load(Ordering::Relaxed) {
+                            finished.set();
+                        }
+                    }
+                }
+                Ok(AudioCommand::Pause) => {
+                    is_playing.store(false, Ordering::Relaxed);
+                    held = true;
+                }
+                Ok(AudioCommand::Resume) => {
+                    let mut clip = clip.lock();
+                    if clip.is_finished() {
+                        clip.seek(0);
+                    }
+                    held = false;
                     is_playing.store(true, Ordering::Relaxed);
                 }
+                Ok(AudioCommand::SetOutputDevice(device)) => {
+                    preferred = device;
+                    reopen(&mut output, preferred.as_deref());
+                }
+                Err(_) => {
+                    log::info!("Audio thread stopping");
+                    break;
+                }
+            },
+            recv(lost_rx) -> _ => {
+                log::warn!("Audio output device disconnected, reopening");
+                reopen(&mut output, preferred.as_deref());
             }
-            Ok(AudioCommand::Reset) => {
-                is_playing.store(false, Ordering::Relaxed);
-                buffer.lock().reset();
-                log::info!("Audio buffer reset");
-            }
-            Ok(AudioCommand::Pause) => {
-                is_playing.store(false, Ordering::Relaxed);
-            }
-            Ok(AudioCommand::Resume) => {
-                is_playing.store(true, Ordering::Relaxed);
-            }
-            Ok(AudioCommand::Stop) => {
-                log::info!("Audio thread stopping");
-                break;
-            }
-            Err(crossbeam_channel::TryRecvError::Empty) => {
-                // No command, update state
-            }
-            Err(crossbeam_channel::TryRecvError::Disconnected) => {
-                log::info!("Audio command channel disconnected");
-                break;
+            default(DEVICE_RETRY_INTERVAL) => {
+                // Degraded: no output at all, the null fallback, or playing on
+                // the default while the selected device is unplugged. A sink
+                // override (null or WAV) is never retried.
+                let degraded = match (&output, preferred.as_deref()) {
+                    _ if sink_override.is_some() => false,
+                    (None, _) => true,
+                    (Some(current), _) if current.is_fallback() => true,
+                    (Some(current), Some(name)) => current.name() != name,
+                    (Some(_), None) => false,
+                };
+                if degraded && output_device_available(preferred.as_deref()) {
+                    reopen(&mut output, preferred.as_deref());
+                }
             }
         }
-
-        // Update shared state
-        {
-            let buf = buffer.lock();
-            let mut s = state.lock();
-            s.buffer_fill = buf.fill_percentage();
-            s.buffer_seconds = buf.available_seconds(sample_rate);
-            s.is_playing = is_playing.load(Ordering::Relaxed);
-            s.waveform = buf.get_waveform(512);
-            s.current_question_id = current_question_id.load(Ordering::Relaxed);
-            // Get current participant from buffer segment tracking - reflects what's actually playing
-            s.current_participant_idx = buf.current_participant();
-        }
-
-        // Small sleep to avoid busy loop (5ms for responsive state updates)
-        std::thread::sleep(std::time::Duration::from_millis(5));
     }
-
-    Ok(())
 }
 
 /// Audio player reference type for sharing across threads
@@ -456,3 +1146,238 @@ pub type AudioPlayerRef = Arc<AudioPlayer>;
 pub fn create_audio_player(sample_rate: u32) -> Result<AudioPlayerRef, String> {
     AudioPlayer::new(sample_rate).map(Arc::new)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ramp(frames: usize) -> Vec<f32> {
+        (0..frames).map(|i| i as f32 / frames as f32).collect()
+    }
+
+    /// Render the whole clip in device callbacks of `callback_frames`
+    fn render_all(
+        converter: &mut OutputConverter,
+        clip: &mut PlaybackClip,
+        callback_frames: usize,
+    ) -> Vec<f32> {
+        let mut out = Vec::new();
+        let mut data = vec![0.0; callback_frames * converter.device_channels];
+        loop {
+            let rendered = converter.render(clip, &mut data);
+            if rendered == 0 {
+                return out;
+            }
+            out.extend_from_slice(&data[..rendered * converter.device_channels]);
+        }
+    }
+
+    #[test]
+    fn test_resampled_clip_keeps_its_duration_and_position() {
+        let format = ClipFormat::new(32000, 1);
+        let mut clip = PlaybackClip::new(format);
+        clip.load(ramp(32000), format);
+        let mut converter = OutputConverter::new(48000, 2);
+
+        // Odd callback size so chunk and callback boundaries never line up
+        let out = render_all(&mut converter, &mut clip, 441);
+        assert_eq!(out.len(), 48000 * 2);
+        assert_eq!(clip.position(), 32000);
+        assert!(clip.is_finished());
+
+        // Mono is copied to both channels, and the ramp stays continuous
+        // across boundaries once the filter has settled
+        let left: Vec<f32> = out.chunks(2).map(|f| f[0]).collect();
+        assert!(out.chunks(2).all(|f| f[0] == f[1]));
+        assert!(left[1000..47000]
+            .windows(2)
+            .all(|w| (w[1] - w[0]).abs() < 1e-3));
+    }
+
+    #[test]
+    fn test_seek_restarts_conversion_at_the_target_frame() {
+        let format = ClipFormat::new(24000, 2);
+        let mut clip = PlaybackClip::new(format);
+        clip.load(vec![0.25; 24000 * 2], format);
+        let mut converter = OutputConverter::new(48000, 1);
+
+        let mut data = vec![0.0; 4800];
+        assert_eq!(converter.render(&mut clip, &mut data), 4800);
+        assert_eq!(clip.position(), 2400);
+
+        clip.seek(18000);
+        assert_eq!(clip.position(), 18000);
+        let out = render_all(&mut converter, &mut clip, 1000);
+        assert_eq!(out.len(), 12000);
+        assert_eq!(clip.position(), 24000);
+        // Stereo is averaged down for a mono device
+        assert!((out[6000] - 0.25).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_matching_rate_passes_samples_through() {
+        let format = ClipFormat::new(48000, 1);
+        let mut clip = PlaybackClip::new(format);
+        clip.load(ramp(1000), format);
+        let mut converter = OutputConverter::new(48000, 1);
+        assert_eq!(render_all(&mut converter, &mut clip, 300), ramp(1000));
+    }
+
+    #[test]
+    fn test_loop_repeats_its_range_until_cleared() {
+        let format = ClipFormat::new(48000, 1);
+        let mut clip = PlaybackClip::new(format);
+        let source = ramp(1000);
+        clip.load(source.clone(), format);
+        clip.set_loop(Some((200, 500)));
+        clip.seek(100);
+        let mut converter = OutputConverter::new(48000, 1);
+
+        // Plays into the loop, then wraps back to its start mid-callback
+        let mut data = vec![0.0; 1000];
+        assert_eq!(converter.render(&mut clip, &mut data), 1000);
+        let expected: Vec<f32> = [&source[100..500], &source[200..500], &source[200..500]].concat();
+        assert_eq!(&data[..], &expected[..1000]);
+        assert_eq!(clip.position(), 500);
+        assert!(!clip.is_finished());
+
+        // Cleared, the clip plays through to its end
+        clip.set_loop(None);
+        assert_eq!(render_all(&mut converter, &mut clip, 300), source[500..]);
+        assert!(clip.is_finished());
+
+        // Empty or out of range loops are dropped or clamped
+        clip.set_loop(Some((300, 300)));
+        assert_eq!(clip.loop_range, None);
+        clip.set_loop(Some((900, 5000)));
+        assert_eq!(clip.loop_range, Some((900, 1000)));
+    }
+
+    #[test]
+    fn test_stream_tags_follow_playback_and_trim_played_audio() {
+        let format = ClipFormat::new(1000, 1);
+        let mut clip = PlaybackClip::new(format);
+        clip.append(&[0.1; 600], format, Some(0), None);
+        clip.append(&[0.2; 600], format, Some(2), None);
+        clip.append(&[0.3; 600], format, Some(2), None);
+        assert_eq!(clip.segments.len(), 2);
+        assert_eq!(clip.current_participant(), None);
+
+        let mut converter = OutputConverter::new(1000, 1);
+        let mut data = vec![0.0; 700];
+        converter.render(&mut clip, &mut data);
+        assert_eq!(clip.current_participant(), Some(2));
+
+        // Only played audio is dropped, and positions stay absolute
+        clip.trim(500);
+        assert_eq!(clip.base, 700);
+        assert_eq!(clip.frames(), 1800);
+        assert_eq!(clip.segments.len(), 1);
+        clip.seek(0);
+        assert_eq!(clip.position(), 700);
+        assert_eq!(converter.render(&mut clip, &mut data), 700);
+        assert_eq!(data[0], 0.2);
+        assert_eq!(data[699], 0.3);
+    }
+
+    #[test]
+    fn test_stream_appended_after_a_partial_read_stays_seamless() {
+        let format = ClipFormat::new(16000, 1);
+        let sine: Vec<f32> = (0..16000)
+            .map(|i| (i as f32 * 440.0 * std::f32::consts::TAU / 16000.0).sin() * 0.5)
+            .collect();
+        let mut reference = PlaybackClip::new(format);
+        reference.load(sine.clone(), format);
+        let expected = render_all(&mut OutputConverter::new(48000, 1), &mut reference, 480);
+
+        // The first write ends mid-chunk and runs dry before the next arrives
+        let mut clip = PlaybackClip::new(format);
+        let mut converter = OutputConverter::new(48000, 1);
+        let split = RESAMPLER_CHUNK_FRAMES * 3 + 100;
+        clip.append(&sine[..split], format, None, None);
+        let mut out = render_all(&mut converter, &mut clip, 480);
+        assert!(clip.position() < split);
+
+        clip.append(&sine[split..], format, None, None);
+        clip.end_stream();
+        out.extend(render_all(&mut converter, &mut clip, 480));
+
+        assert_eq!(out.len(), expected.len());
+        assert_eq!(clip.position(), 16000);
+        let worst = out
+            .iter()
+            .zip(&expected)
+            .map(|(a, b)| (a - b).abs())
+            .fold(0.0f32, f32::max);
+        assert!(worst < 1e-4, "stream diverged from the clip by {}", worst);
+    }
+
+    #[test]
+    fn test_append_fills_chunks_without_moving_held_audio() {
+        let format = ClipFormat::new(1000, 1);
+        let chunk = CLIP_CHUNK_FRAMES;
+        let mut clip = PlaybackClip::new(format);
+        assert_eq!(clip.growth(100, format), Some(chunk));
+        assert!(clip
+            .append(&[0.1; 100], format, None, Some(Vec::with_capacity(chunk)))
+            .is_none());
+        let first = clip.samples.chunks[0].as_ptr();
+
+        // Fits the chunk, then spills into a new one; held audio stays put
+        assert_eq!(clip.growth(chunk - 100, format), None);
+        clip.append(&vec![0.2; chunk - 100], format, None, None);
+        assert_eq!(clip.growth(10, format), Some(chunk));
+        clip.append(&[0.3; 10], format, None, Some(Vec::with_capacity(chunk)));
+        assert_eq!(clip.samples.chunks.len(), 2);
+        assert_eq!(clip.samples.chunks[0].as_ptr(), first);
+        assert_eq!(clip.frames(), chunk + 10);
+
+        // Reads run across the chunk boundary
+        clip.seek(chunk - 2);
+        let mut out = [0.0; 4];
+        assert_eq!(clip.read(&mut out), 4);
+        assert_eq!(out, [0.2, 0.2, 0.3, 0.3]);
+
+        // Played audio is released a chunk at a time once skipped entirely
+        clip.samples.skip(chunk - 1);
+        assert!(clip.release_played().is_none());
+        clip.samples.skip(1);
+        assert_eq!(clip.release_played().map(|c| c.as_ptr()), Some(first));
+        assert!(clip.release_played().is_none());
+
+        // A new format starts a new clip in the given chunk
+        let stereo = ClipFormat::new(1000, 2);
+        assert_eq!(clip.growth(40, stereo), Some(2 * chunk));
+        let replaced = clip.append(&[0.3; 40], stereo, None, Some(Vec::with_capacity(40)));
+        assert_eq!(replaced.map(|old| old.len()), Some(10));
+        assert_eq!(clip.frames(), 20);
+    }
+
+    /// Poll `check` until it holds or `timeout` passes
+    fn wait_until(timeout: Duration, mut check: impl FnMut() -> bool) -> bool {
+        let deadline = std::time::Instant::now() + timeout;
+        while !check() {
+            if std::time::Instant::now() >= deadline {
+                return false;
+            }
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        true
+    }
+
+    #[test]
+    fn test_null_sink_plays_a_loaded_clip_to_the_end() {
+        let player = AudioPlayer::with_sink(16000, SinkConfig::Null).unwrap();
+        player.load(&ramp(3200), 16000, 1);
+        assert!(wait_until(Duration::from_secs(2), || {
+            (player.buffer_seconds() - 0.2).abs() < 1e-9
+        }));
+        assert!(!player.is_playing());
+
+        player.resume();
+        assert!(wait_until(Duration::from_secs(5), || player.check_playback_finished()));
+        assert!(!player.is_playing());
+        assert_eq!(player.buffer_seconds(), 0.0);
+        assert!((player.position() - 0.2).abs() < 1e-9);
+    }
+}
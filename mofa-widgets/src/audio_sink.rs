@@ -0,0 +1,481 @@
+//! Audio output sinks
+//!
+//! Players render audio in a pull callback; a sink decides where it goes:
+//! - [`SinkConfig::Cpal`] plays on a sound card through cpal
+//! - [`SinkConfig::Null`] discards audio at real-time pace
+//! - [`SinkConfig::Wav`] records audio to a WAV file at real-time pace
+//!
+//! The null and WAV sinks drive the callback from their own clock thread, so
+//! playback state (finished signals, buffer levels) advances just as it does
+//! with a device, on headless servers and CI runners too. A sink is chosen
+//! by config ([`SinkConfig::from_env`]), and [`open_sink`] falls back from
+//! cpal to the null sink when the machine has no output device.
+
+use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+/// Environment variable selecting the sink: `cpal`, `cpal:<device>`, `null`
+/// or `wav:<path>`
+pub const SINK_ENV_VAR: &str = "MOFA_AUDIO_SINK";
+
+/// Format of clocked sinks when the player has no preference
+pub const DEFAULT_SINK_FORMAT: SinkFormat = SinkFormat {
+    sample_rate: 48000,
+    channels: 2,
+};
+
+/// Callbacks per second on clocked sinks
+const CLOCK_TICKS_PER_SECOND: u32 = 100;
+
+/// Conversion buffer of cpal streams in a non-f32 format, when the device
+/// doesn't fix its callback size (larger callbacks render in pieces)
+const CPAL_SCRATCH_SECONDS: f64 = 0.1;
+
+/// Which sink to play through
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SinkConfig {
+    /// Sound card output; `None` uses the system default device
+    Cpal { device: Option<String> },
+    /// Discard audio (headless machines, CI)
+    Null,
+    /// Record audio to a 32-bit float WAV file
+    Wav { path: PathBuf },
+}
+
+impl Default for SinkConfig {
+    fn default() -> Self {
+        SinkConfig::Cpal { device: None }
+    }
+}
+
+impl SinkConfig {
+    /// Parse a sink spec: `cpal`, `cpal:<device>`, `null` or `wav:<path>`
+    pub fn parse(spec: &str) -> Option<Self> {
+        let (kind, arg) = match spec.split_once(':') {
+            Some((kind, arg)) => (kind, Some(arg)),
+            None => (spec, None),
+        };
+        match (kind.trim(), arg) {
+            ("cpal", None) => Some(SinkConfig::Cpal { device: None }),
+            ("cpal", Some(device)) => Some(SinkConfig::Cpal {
+                device: Some(device.to_string()),
+            }),
+            ("null", None) => Some(SinkConfig::Null),
+            ("wav", Some(path)) if !path.is_empty() => Some(SinkConfig::Wav {
+                path: PathBuf::from(path),
+            }),
+            _ => None,
+        }
+    }
+
+    /// Sink chosen through [`SINK_ENV_VAR`], if set
+    pub fn from_env() -> Option<Self> {
+        let spec = std::env::var(SINK_ENV_VAR).ok()?;
+        let config = Self::parse(&spec);
+        if config.is_none() {
+            log::warn!("Ignoring invalid {}={:?}", SINK_ENV_VAR, spec);
+        }
+        config
+    }
+}
+
+/// Sample rate and channel count a sink runs at
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SinkFormat {
+    pub sample_rate: u32,
+    pub channels: u16,
+}
+
+/// Fills interleaved f32 frames in the sink's format
+pub type RenderFn = Box<dyn FnMut(&mut [f32]) + Send + 'static>;
+
+/// Called when the sink's device disappears (e.g. headphones unplugged)
+pub type LostFn = Box<dyn FnMut() + Send + 'static>;
+
+/// Where an opened sink sends audio
+enum SinkTarget {
+    Cpal {
+        device: cpal::Device,
+        config: cpal::StreamConfig,
+        sample_format: cpal::SampleFormat,
+    },
+    Null,
+    Wav {
+        path: PathBuf,
+    },
+}
+
+/// A sink with its device and format resolved, ready to start
+pub struct Sink {
+    target: SinkTarget,
+    name: String,
+    format: SinkFormat,
+    fallback: bool,
+}
+
+/// Resolve a sink and its format. `preferred` asks for a specific format
+/// (cpal then plays f32 in it); otherwise the device's default is used.
+pub fn open_sink(config: &SinkConfig, preferred: Option<SinkFormat>) -> Result<Sink, String> {
+    let clocked = |target: SinkTarget, name: String, fallback: bool| Sink {
+        target,
+        name,
+        format: preferred.unwrap_or(DEFAULT_SINK_FORMAT),
+        fallback,
+    };
+
+    match config {
+        SinkConfig::Cpal { device } => {
+            let host = cpal::default_host();
+            let device = match device {
+                Some(name) => find_output_device(&host, name).or_else(|| {
+                    log::warn!("Output device '{}' not found, using default", name);
+                    host.default_output_device()
+                }),
+                None => host.default_output_device(),
+            };
+            let Some(device) = device else {
+                log::warn!("No audio output device found, rendering to the null sink");
+                return Ok(clocked(SinkTarget::Null, "null".to_string(), true));
+            };
+
+            let name = device.name().unwrap_or_default();
+            let (config, sample_format) = match preferred {
+                Some(format) => (
+                    cpal::StreamConfig {
+                        channels: format.channels,
+                        sample_rate: cpal::SampleRate(format.sample_rate),
+                        buffer_size: cpal::BufferSize::Default,
+                    },
+                    cpal::SampleFormat::F32,
+                ),
+                None => {
+                    let default_config =
+                        device.default_output_config().map_err(|e| e.to_string())?;
+                    let sample_format = default_config.sample_format();
+                    (default_config.into(), sample_format)
+                }
+            };
+            let format = SinkFormat {
+                sample_rate: config.sample_rate.0,
+                channels: config.channels,
+            };
+            Ok(Sink {
+                target: SinkTarget::Cpal {
+                    device,
+                    config,
+                    sample_format,
+                },
+                name,
+                format,
+                fallback: false,
+            })
+        }
+        SinkConfig::Null => Ok(clocked(SinkTarget::Null, "null".to_string(), false)),
+        SinkConfig::Wav { path } => Ok(clocked(
+            SinkTarget::Wav { path: path.clone() },
+            format!("wav:{}", path.display()),
+            false,
+        )),
+    }
+}
+
+/// Whether the named output device (or any default device) is connected
+pub fn output_device_available(name: Option<&str>) -> bool {
+    let host = cpal::default_host();
+    match name {
+        Some(name) => find_output_device(&host, name).is_some(),
+        None => host.default_output_device().is_some(),
+    }
+}
+
+/// Find an output device by name
+fn find_output_device(host: &cpal::Host, name: &str) -> Option<cpal::Device> {
+    host.output_devices()
+        .ok()?
+        .find(|device| device.name().map(|n| n == name).unwrap_or(false))
+}
+
+impl Sink {
+    /// Device or sink name, for logs and the UI
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn format(&self) -> SinkFormat {
+        self.format
+    }
+
+    /// Whether this is the null sink standing in for a missing output device
+    pub fn is_fallback(&self) -> bool {
+        self.fallback
+    }
+
+    /// Start pulling audio from `render`. Output runs until the returned
+    /// stream is dropped.
+    pub fn start(self, render: RenderFn, on_lost: LostFn) -> Result<SinkStream, String> {
+        let output = match self.target {
+            SinkTarget::Cpal {
+                device,
+                config,
+                sample_format,
+            } => {
+                let stream = match sample_format {
+                    cpal::SampleFormat::I16 => {
+                        build_cpal_stream::<i16>(&device, &config, render, on_lost)
+                    }
+                    cpal::SampleFormat::U16 => {
+                        build_cpal_stream::<u16>(&device, &config, render, on_lost)
+                    }
+                    _ => build_cpal_stream::<f32>(&device, &config, render, on_lost),
+                }
+                .map_err(|e| format!("Failed to build audio stream: {}", e))?;
+                stream
+                    .play()
+                    .map_err(|e| format!("Failed to start audio stream: {}", e))?;
+                SinkOutput::Cpal(stream)
+            }
+            SinkTarget::Null => SinkOutput::Clocked(ClockedSink::start(self.format, render, None)?),
+            SinkTarget::Wav { path } => {
+                let spec = hound::WavSpec {
+                    channels: self.format.channels,
+                    sample_rate: self.format.sample_rate,
+                    bits_per_sample: 32,
+                    sample_format: hound::SampleFormat::Float,
+                };
+                let writer = hound::WavWriter::create(&path, spec)
+                    .map_err(|e| format!("Failed to create {}: {}", path.display(), e))?;
+                SinkOutput::Clocked(ClockedSink::start(self.format, render, Some(writer))?)
+            }
+        };
+
+        Ok(SinkStream {
+            name: self.name,
+            format: self.format,
+            fallback: self.fallback,
+            _output: output,
+        })
+    }
+}
+
+/// Build a cpal stream in the device's sample format around an f32 render callback
+fn build_cpal_stream<T>(
+    device: &cpal::Device,
+    config: &cpal::StreamConfig,
+    mut render: RenderFn,
+    mut on_lost: LostFn,
+) -> Result<cpal::Stream, cpal::BuildStreamError>
+where
+    T: cpal::Sample + cpal::FromSample<f32> + cpal::SizedSample,
+{
+    // Sized here, as the callback must not allocate
+    let frames = match config.buffer_size {
+        cpal::BufferSize::Fixed(frames) => frames as usize,
+        cpal::BufferSize::Default => (config.sample_rate.0 as f64 * CPAL_SCRATCH_SECONDS) as usize,
+    };
+    let mut scratch = vec![0.0f32; frames.max(1) * config.channels.max(1) as usize];
+    device.build_output_stream(
+        config,
+        move |data: &mut [T], _: &cpal::OutputCallbackInfo| {
+            // Whole frames per piece, since the scratch length is a multiple
+            // of the channel count
+            for piece in data.chunks_mut(scratch.len()) {
+                let buffer = &mut scratch[..piece.len()];
+                render(buffer);
+                for (sample, &val) in piece.iter_mut().zip(buffer.iter()) {
+                    *sample = T::from_sample(val);
+                }
+            }
+        },
+        move |err| {
+            log::error!("Audio stream error: {}", err);
+            if matches!(err, cpal::StreamError::DeviceNotAvailable) {
+                on_lost();
+            }
+        },
+        None,
+    )
+}
+
+/// A running sink; output stops when it is dropped
+pub struct SinkStream {
+    name: String,
+    format: SinkFormat,
+    fallback: bool,
+    _output: SinkOutput,
+}
+
+impl SinkStream {
+    /// Device or sink name, for logs and the UI
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn format(&self) -> SinkFormat {
+        self.format
+    }
+
+    /// Whether this is the null sink standing in for a missing output device
+    pub fn is_fallback(&self) -> bool {
+        self.fallback
+    }
+}
+
+enum SinkOutput {
+    Cpal(#[allow(dead_code)] cpal::Stream),
+    Clocked(#[allow(dead_code)] ClockedSink),
+}
+
+/// Clock thread pulling audio at real-time pace, optionally recording it
+struct ClockedSink {
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl ClockedSink {
+    fn start(
+        format: SinkFormat,
+        mut render: RenderFn,
+        mut writer: Option<hound::WavWriter<BufWriter<File>>>,
+    ) -> Result<Self, String> {
+        let stop = Arc::new(AtomicBool::new(false));
+        let stop_flag = Arc::clone(&stop);
+
+        // Whole frames per tick, with the period derived from them so the
+        // clock does not drift for rates that don't divide evenly
+        let frames = (format.sample_rate / CLOCK_TICKS_PER_SECOND).max(1) as usize;
+        let period = Duration::from_secs_f64(frames as f64 / format.sample_rate.max(1) as f64);
+
+        let thread = std::thread::Builder::new()
+            .name("audio-sink-clock".to_string())
+            .spawn(move || {
+                let mut buffer = vec![0.0; frames * format.channels.max(1) as usize];
+                let mut next_tick = Instant::now();
+                while !stop_flag.load(Ordering::Relaxed) {
+                    render(&mut buffer);
+                    if let Some(wav) = writer.as_mut() {
+                        let written: Result<(), hound::Error> =
+                            buffer.iter().try_for_each(|&s| wav.write_sample(s));
+                        if let Err(e) = written {
+                            log::error!("WAV sink write failed: {}", e);
+                            writer = None;
+                        }
+                    }
+
+                    next_tick += period;
+                    let now = Instant::now();
+                    if next_tick > now {
+                        std::thread::sleep(next_tick - now);
+                    } else {
+                        // Fell behind (stalled thread); don't burst to catch up
+                        next_tick = now;
+                    }
+                }
+                if let Some(wav) = writer {
+                    if let Err(e) = wav.finalize() {
+                        log::error!("WAV sink finalize failed: {}", e);
+                    }
+                }
+            })
+            .map_err(|e| format!("Failed to start sink clock: {}", e))?;
+
+        Ok(Self {
+            stop,
+            thread: Some(thread),
+        })
+    }
+}
+
+impl Drop for ClockedSink {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicUsize;
+
+    #[test]
+    fn test_parse_sink_spec() {
+        assert_eq!(
+            SinkConfig::parse("cpal"),
+            Some(SinkConfig::Cpal { device: None })
+        );
+        assert_eq!(
+            SinkConfig::parse("cpal:USB Headset"),
+            Some(SinkConfig::Cpal {
+                device: Some("USB Headset".to_string())
+            })
+        );
+        assert_eq!(SinkConfig::parse("null"), Some(SinkConfig::Null));
+        assert_eq!(
+            SinkConfig::parse("wav:/tmp/out.wav"),
+            Some(SinkConfig::Wav {
+                path: PathBuf::from("/tmp/out.wav")
+            })
+        );
+        assert_eq!(SinkConfig::parse("wav:"), None);
+        assert_eq!(SinkConfig::parse("pulse"), None);
+    }
+
+    #[test]
+    fn test_null_sink_pulls_at_real_time_pace() {
+        let format = SinkFormat {
+            sample_rate: 16000,
+            channels: 1,
+        };
+        let sink = open_sink(&SinkConfig::Null, Some(format)).unwrap();
+        assert_eq!(sink.format(), format);
+
+        let pulled = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&pulled);
+        let stream = sink
+            .start(
+                Box::new(move |data| {
+                    counter.fetch_add(data.len(), Ordering::Relaxed);
+                }),
+                Box::new(|| {}),
+            )
+            .unwrap();
+        std::thread::sleep(Duration::from_millis(200));
+        drop(stream);
+
+        // ~3200 frames in 200 ms; allow for scheduling jitter on busy runners
+        let frames = pulled.load(Ordering::Relaxed);
+        assert!((1600..=4800).contains(&frames), "pulled {} frames", frames);
+    }
+
+    #[test]
+    fn test_wav_sink_records_rendered_audio() {
+        let path = std::env::temp_dir().join(format!("mofa-sink-{}.wav", std::process::id()));
+        let format = SinkFormat {
+            sample_rate: 8000,
+            channels: 2,
+        };
+        let sink = open_sink(&SinkConfig::Wav { path: path.clone() }, Some(format)).unwrap();
+        let stream = sink
+            .start(Box::new(|data| data.fill(0.5)), Box::new(|| {}))
+            .unwrap();
+        std::thread::sleep(Duration::from_millis(50));
+        drop(stream);
+
+        let reader = hound::WavReader::open(&path).unwrap();
+        assert_eq!(reader.spec().channels, 2);
+        assert_eq!(reader.spec().sample_rate, 8000);
+        let samples: Vec<f32> = reader.into_samples().map(|s| s.unwrap()).collect();
+        let _ = std::fs::remove_file(&path);
+        assert!(!samples.is_empty());
+        assert_eq!(samples.len() % 2, 0);
+        assert!(samples.iter().all(|&s| s == 0.5));
+    }
+}
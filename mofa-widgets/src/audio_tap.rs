@@ -0,0 +1,284 @@
+//! Audio Tap Module - Lock-free view of what a player is outputting
+//!
+//! The audio callback pushes every frame it hands to the output, mixed to
+//! mono, into a ring of atomics and publishes an FFT magnitude spectrum each
+//! time a window fills. The UI reads both from an [`AudioTap`] without ever
+//! blocking the callback:
+//! - [`AudioTap::waveform`] - the most recent output samples
+//! - [`AudioTap::spectrum`] - magnitudes of the last full window
+//! - [`AudioTap::bands`] - log-spaced levels for bar visualizers such as
+//!   [`WaveformView`](crate::waveform_view::WaveformView)
+//!
+//! The writer never waits: a reader racing it may see a sample from the next
+//! lap of the ring, and retries a spectrum read that overlapped a publish.
+
+use realfft::num_complex::Complex;
+use realfft::{RealFftPlanner, RealToComplex};
+use std::sync::atomic::{fence, AtomicU32, AtomicUsize, Ordering};
+use std::sync::Arc;
+
+/// Mono samples kept in the ring
+pub const TAP_RING_SIZE: usize = 8192;
+
+/// Samples per FFT window
+pub const FFT_SIZE: usize = 1024;
+
+/// Magnitude bins in a published spectrum (DC up to Nyquist)
+pub const SPECTRUM_BINS: usize = FFT_SIZE / 2 + 1;
+
+/// Frequency range covered by [`AudioTap::bands`]
+const BAND_MIN_HZ: f32 = 60.0;
+const BAND_MAX_HZ: f32 = 12_000.0;
+
+/// Level mapped to an empty band; 0 dBFS fills it
+const BAND_FLOOR_DB: f32 = -60.0;
+
+/// Spectrum reads retried while the writer keeps publishing
+const SPECTRUM_READ_ATTEMPTS: usize = 4;
+
+/// State shared by the writer and all readers
+struct TapShared {
+    /// Mono samples as `f32` bits, indexed by `written % TAP_RING_SIZE`
+    ring: Box<[AtomicU32]>,
+    /// Samples ever written; slots before it are complete
+    written: AtomicUsize,
+    /// Last published magnitudes as `f32` bits
+    spectrum: Box<[AtomicU32]>,
+    /// Odd while a spectrum is being published (seqlock)
+    spectrum_seq: AtomicUsize,
+    /// Rate of the tapped output, for mapping bins to frequencies
+    sample_rate: AtomicU32,
+}
+
+fn atomic_zeros(len: usize) -> Box<[AtomicU32]> {
+    (0..len).map(|_| AtomicU32::new(0)).collect()
+}
+
+/// Read side of a tap - cheap to clone and share with the UI
+#[derive(Clone)]
+pub struct AudioTap {
+    shared: Arc<TapShared>,
+}
+
+/// Write side of a tap, owned by the audio callback
+pub(crate) struct TapWriter {
+    shared: Arc<TapShared>,
+    fft: Arc<dyn RealToComplex<f32>>,
+    window: Vec<f32>,
+    /// Scale turning a bin magnitude into sine amplitude
+    norm: f32,
+    /// Samples collected for the next window
+    pending: Vec<f32>,
+    input: Vec<f32>,
+    output: Vec<Complex<f32>>,
+    scratch: Vec<Complex<f32>>,
+}
+
+impl TapWriter {
+    /// Push interleaved output frames. Never blocks or allocates.
+    pub(crate) fn push(&mut self, data: &[f32], channels: usize) {
+        let channels = channels.max(1);
+        let mut written = self.shared.written.load(Ordering::Relaxed);
+        for frame in data.chunks_exact(channels) {
+            let sample = frame.iter().sum::<f32>() / channels as f32;
+            self.shared.ring[written % TAP_RING_SIZE].store(sample.to_bits(), Ordering::Relaxed);
+            written = written.wrapping_add(1);
+
+            self.pending.push(sample);
+            if self.pending.len() == FFT_SIZE {
+                self.publish_spectrum();
+                self.pending.clear();
+            }
+        }
+        self.shared.written.store(written, Ordering::Release);
+    }
+
+    fn publish_spectrum(&mut self) {
+        for ((input, sample), weight) in self.input.iter_mut().zip(&self.pending).zip(&self.window)
+        {
+            *input = sample * weight;
+        }
+        if self
+            .fft
+            .process_with_scratch(&mut self.input, &mut self.output, &mut self.scratch)
+            .is_err()
+        {
+            return;
+        }
+
+        let shared = &self.shared;
+        let seq = shared.spectrum_seq.load(Ordering::Relaxed);
+        shared
+            .spectrum_seq
+            .store(seq.wrapping_add(1), Ordering::Relaxed);
+        fence(Ordering::Release);
+        for (slot, bin) in shared.spectrum.iter().zip(&self.output) {
+            slot.store((bin.norm() * self.norm).to_bits(), Ordering::Relaxed);
+        }
+        shared
+            .spectrum_seq
+            .store(seq.wrapping_add(2), Ordering::Release);
+    }
+}
+
+impl AudioTap {
+    /// Create an empty tap for output at `sample_rate`
+    pub fn new(sample_rate: u32) -> Self {
+        Self {
+            shared: Arc::new(TapShared {
+                ring: atomic_zeros(TAP_RING_SIZE),
+                written: AtomicUsize::new(0),
+                spectrum: atomic_zeros(SPECTRUM_BINS),
+                spectrum_seq: AtomicUsize::new(0),
+                sample_rate: AtomicU32::new(sample_rate),
+            }),
+        }
+    }
+
+    /// Writer for a (re)built output stream at `sample_rate`. Only one
+    /// writer may push at a time; plans the FFT, so call it off the callback.
+    pub(crate) fn writer(&self, sample_rate: u32) -> TapWriter {
+        self.shared
+            .sample_rate
+            .store(sample_rate, Ordering::Relaxed);
+        let fft = RealFftPlanner::<f32>::new().plan_fft_forward(FFT_SIZE);
+        // Hann window; a full-scale sine then peaks at 1.0
+        let window: Vec<f32> = (0..FFT_SIZE)
+            .map(|i| 0.5 - 0.5 * (2.0 * std::f32::consts::PI * i as f32 / FFT_SIZE as f32).cos())
+            .collect();
+        TapWriter {
+            shared: Arc::clone(&self.shared),
+            input: fft.make_input_vec(),
+            output: fft.make_output_vec(),
+            scratch: fft.make_scratch_vec(),
+            fft,
+            norm: 2.0 / window.iter().sum::<f32>(),
+            window,
+            pending: Vec::with_capacity(FFT_SIZE),
+        }
+    }
+
+    /// Rate of the tapped output
+    pub fn sample_rate(&self) -> u32 {
+        self.shared.sample_rate.load(Ordering::Relaxed)
+    }
+
+    /// The `num_samples` most recent output samples, mixed to mono, oldest
+    /// first (at most [`TAP_RING_SIZE`]; zeros before any audio arrived)
+    pub fn waveform(&self, num_samples: usize) -> Vec<f32> {
+        let shared = &self.shared;
+        let num_samples = num_samples.min(TAP_RING_SIZE);
+        let written = shared.written.load(Ordering::Acquire);
+        (0..num_samples)
+            .map(|i| {
+                let index = written.wrapping_sub(num_samples - i);
+                f32::from_bits(shared.ring[index % TAP_RING_SIZE].load(Ordering::Relaxed))
+            })
+            .collect()
+    }
+
+    /// Magnitude spectrum of the last full window, [`SPECTRUM_BINS`] bins
+    /// from DC to Nyquist. A full-scale sine peaks at about 1.0.
+    pub fn spectrum(&self) -> Vec<f32> {
+        let shared = &self.shared;
+        let mut bins = vec![0.0; SPECTRUM_BINS];
+        for _ in 0..SPECTRUM_READ_ATTEMPTS {
+            let before = shared.spectrum_seq.load(Ordering::Acquire);
+            if before & 1 == 1 {
+                std::hint::spin_loop();
+                continue;
+            }
+            for (bin, slot) in bins.iter_mut().zip(shared.spectrum.iter()) {
+                *bin = f32::from_bits(slot.load(Ordering::Relaxed));
+            }
+            fence(Ordering::Acquire);
+            if shared.spectrum_seq.load(Ordering::Relaxed) == before {
+                break;
+            }
+        }
+        bins
+    }
+
+    /// Spectrum folded into `num_bands` log-spaced bands between 60 Hz and
+    /// 12 kHz, each the loudest bin on a 60 dB scale (0.0-1.0)
+    pub fn bands(&self, num_bands: usize) -> Vec<f32> {
+        spectrum_bands(&self.spectrum(), self.sample_rate(), num_bands)
+    }
+}
+
+fn spectrum_bands(spectrum: &[f32], sample_rate: u32, num_bands: usize) -> Vec<f32> {
+    let bin_hz = sample_rate as f32 / FFT_SIZE as f32;
+    let max_hz = BAND_MAX_HZ.min(sample_rate as f32 / 2.0);
+    let last_bin = spectrum.len().saturating_sub(1);
+    let edge_bin = |band: usize| {
+        let hz = BAND_MIN_HZ * (max_hz / BAND_MIN_HZ).powf(band as f32 / num_bands as f32);
+        ((hz / bin_hz).round() as usize).min(last_bin)
+    };
+
+    (0..num_bands)
+        .map(|band| {
+            let start = edge_bin(band);
+            let end = edge_bin(band + 1).max(start + 1).min(spectrum.len());
+            let peak = spectrum[start.min(end)..end]
+                .iter()
+                .fold(0.0f32, |peak, &m| peak.max(m));
+            let db = 20.0 * peak.max(1e-6).log10();
+            ((db - BAND_FLOOR_DB) / -BAND_FLOOR_DB).clamp(0.0, 1.0)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sine(hz: f32, sample_rate: u32, frames: usize) -> Vec<f32> {
+        (0..frames)
+            .map(|i| (2.0 * std::f32::consts::PI * hz * i as f32 / sample_rate as f32).sin())
+            .collect()
+    }
+
+    #[test]
+    fn test_waveform_returns_latest_mono_samples_across_the_ring_wrap() {
+        let tap = AudioTap::new(48000);
+        let mut writer = tap.writer(48000);
+        assert_eq!(tap.waveform(4), vec![0.0; 4]);
+
+        let stereo: Vec<f32> = (0..TAP_RING_SIZE + 10)
+            .flat_map(|i| [i as f32, i as f32 + 2.0])
+            .collect();
+        writer.push(&stereo, 2);
+
+        let latest = tap.waveform(3);
+        let last = (TAP_RING_SIZE + 9) as f32 + 1.0;
+        assert_eq!(latest, vec![last - 2.0, last - 1.0, last]);
+        assert_eq!(tap.waveform(usize::MAX).len(), TAP_RING_SIZE);
+    }
+
+    #[test]
+    fn test_spectrum_peaks_at_the_played_tone() {
+        let tap = AudioTap::new(48000);
+        let mut writer = tap.writer(48000);
+        assert!(tap.spectrum().iter().all(|&m| m == 0.0));
+
+        // Exactly on bin 20 (937.5 Hz), fed in callback-sized pieces
+        let tone = sine(20.0 * 48000.0 / FFT_SIZE as f32, 48000, FFT_SIZE);
+        for chunk in tone.chunks(300) {
+            writer.push(chunk, 1);
+        }
+
+        let spectrum = tap.spectrum();
+        let peak = (0..SPECTRUM_BINS)
+            .max_by(|&a, &b| spectrum[a].total_cmp(&spectrum[b]))
+            .unwrap();
+        assert_eq!(peak, 20);
+        assert!((spectrum[20] - 1.0).abs() < 0.01, "peak {}", spectrum[20]);
+
+        let bands = tap.bands(8);
+        let loudest = (0..8)
+            .max_by(|&a, &b| bands[a].total_cmp(&bands[b]))
+            .unwrap();
+        assert_eq!(loudest, 4); // 60 Hz..12 kHz in 8 log bands: ~0.85-1.6 kHz
+        assert!(bands[0] < 0.2 && bands[7] < 0.2);
+    }
+}
@@ -26,9 +26,12 @@
 //! - [`app_trait`] - Plugin app interface (`MofaApp`, `AppRegistry`)
 //! - [`participant_panel`] - User avatar with audio waveform
 //! - [`waveform_view`] - Real-time audio waveform visualization
+//! - [`waveform_overview`] - Full-clip waveform with seek and loop selection
 //! - [`log_panel`] - Scrollable Markdown log display
 //! - [`led_gauge`] - LED-style bar gauge for levels
-//! - [`audio_player`] - Audio playback engine
+//! - [`audio_player`] - Audio playback engine shared by all players
+//! - [`audio_sink`] - Output sinks (cpal, null, WAV file) for players
+//! - [`audio_tap`] - Lock-free waveform and spectrum of a player's output
 //!
 //! ## Theme System
 //!
@@ -76,11 +79,14 @@
 
 pub mod app_trait;
 pub mod audio_player;
+pub mod audio_sink;
+pub mod audio_tap;
 pub mod card;
 pub mod led_gauge;
 pub mod log_panel;
 pub mod participant_panel;
 pub mod theme;
+pub mod waveform_overview;
 pub mod waveform_view;
 
 // Re-export app trait types for convenience
@@ -108,7 +114,7 @@ use makepad_widgets::Cx;
 /// # Registration Order
 ///
 /// 1. `theme` - Fonts and base styles (required by all widgets)
-/// 2. `waveform_view` - Audio visualization
+/// 2. `waveform_view`, `waveform_overview` - Audio visualization
 /// 3. `participant_panel` - User panels with waveforms
 /// 4. `log_panel` - Log display
 /// 5. `led_gauge` - Level indicators
@@ -119,6 +125,7 @@ pub fn live_design(cx: &mut Cx) {
     // Register widgets in dependency order
     card::live_design(cx);
     waveform_view::live_design(cx);
+    waveform_overview::live_design(cx);
     participant_panel::live_design(cx);
     log_panel::live_design(cx);
     led_gauge::live_design(cx);
@@ -126,4 +133,5 @@ pub fn live_design(cx: &mut Cx) {
 
 // Re-export commonly used types
 pub use audio_player::*;
+pub use audio_tap::AudioTap;
 pub use participant_panel::ParticipantPanel;
@@ -0,0 +1,406 @@
+//! # Waveform Overview Widget
+//!
+//! Full-clip waveform for a player bar: one min/max column per few pixels,
+//! with the played part highlighted. Doubles as the scrubber.
+//!
+//! ## Features
+//!
+//! - **Peak Summary**: The clip is summarized once ([`PeakSummary`]) and
+//!   folded into as many columns as fit the current width
+//! - **Played Region**: Columns up to the play position use the played color
+//! - **Seek**: Click or drag to seek
+//! - **Loop**: Shift-drag selects a range to loop; shift-click clears it
+//!
+//! ## Usage
+//!
+//! ```rust,ignore
+//! live_design! {
+//!     use mofa_widgets::waveform_overview::WaveformOverview;
+//!
+//!     overview = <WaveformOverview> {
+//!         width: Fill, height: 28
+//!     }
+//! }
+//! ```
+//!
+//! ## Feeding and Handling Actions
+//!
+//! ```rust,ignore
+//! let overview = self.view.waveform_overview(ids!(overview));
+//! // Once per clip
+//! overview.set_clip(cx, &samples, channels);
+//! // On every progress update (0.0-1.0)
+//! overview.set_progress(cx, position / duration);
+//!
+//! // Positions in actions are fractions of the clip
+//! if let Some(at) = overview.seek_requested(&actions) {
+//!     player.seek(at * duration);
+//! }
+//! if let Some(range) = overview.loop_requested(&actions) {
+//!     player.set_loop(range.map(|(start, end)| (start * duration, end * duration)));
+//! }
+//! ```
+
+use makepad_widgets::*;
+
+live_design! {
+    use link::theme::*;
+    use link::shaders::*;
+    use link::widgets::*;
+
+    use crate::theme::*;
+
+    pub WaveformOverview = {{WaveformOverview}} {
+        width: Fill, height: 28
+
+        column_width: 2.0
+        column_gap: 1.0
+
+        wave_color: (GRAY_300)
+        wave_color_dark: (GRAY_600)
+        played_color: (PRIMARY_500)
+        played_color_dark: (PRIMARY_400)
+        loop_color: #3b82f626
+        loop_color_dark: #60a5fa33
+
+        // Hit area only
+        draw_bg: {
+            fn pixel(self) -> vec4 {
+                return vec4(0.0, 0.0, 0.0, 0.0);
+            }
+        }
+    }
+}
+
+/// Buckets a clip is summarized into by [`WaveformOverviewRef::set_clip`]
+const SUMMARY_BUCKETS: usize = 2048;
+
+/// Columns of a silent stretch stay visible as a track this high
+const MIN_COLUMN_HEIGHT: f64 = 2.0;
+
+/// Shift-drags shorter than this (in pixels) clear the loop instead
+const MIN_SELECTION_PX: f64 = 4.0;
+
+/// Min/max of a clip, mixed to mono, at a fixed number of buckets
+///
+/// Computed once per clip; drawing folds the buckets into however many
+/// columns the view has room for.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PeakSummary {
+    peaks: Vec<(f32, f32)>,
+}
+
+impl PeakSummary {
+    /// Summarize interleaved `samples` into at most `buckets` min/max pairs
+    pub fn from_samples(samples: &[f32], channels: usize, buckets: usize) -> Self {
+        let channels = channels.max(1);
+        let frames = samples.len() / channels;
+        let buckets = buckets.min(frames);
+        let peaks = (0..buckets)
+            .map(|bucket| {
+                let start = bucket * frames / buckets;
+                let end = (bucket + 1) * frames / buckets;
+                samples[start * channels..end * channels]
+                    .chunks_exact(channels)
+                    .map(|frame| frame.iter().sum::<f32>() / channels as f32)
+                    .fold((f32::MAX, f32::MIN), |(min, max), s| (min.min(s), max.max(s)))
+            })
+            .collect();
+        Self { peaks }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.peaks.is_empty()
+    }
+
+    /// Fold the summary into `count` min/max columns; an empty summary gives
+    /// flat columns
+    pub fn columns(&self, count: usize) -> Vec<(f32, f32)> {
+        let len = self.peaks.len();
+        if len == 0 {
+            return vec![(0.0, 0.0); count];
+        }
+        (0..count)
+            .map(|column| {
+                let start = column * len / count;
+                let end = ((column + 1) * len / count).max(start + 1);
+                self.peaks[start..end]
+                    .iter()
+                    .fold((f32::MAX, f32::MIN), |(min, max), &(lo, hi)| {
+                        (min.min(lo), max.max(hi))
+                    })
+            })
+            .collect()
+    }
+}
+
+/// Actions emitted by WaveformOverview; positions are fractions of the clip
+#[derive(Clone, Debug, DefaultNone)]
+pub enum WaveformOverviewAction {
+    None,
+    /// Clicked or dragged to a point
+    Seek(f64),
+    /// Range selected with shift-drag, or `None` to stop looping
+    Loop(Option<(f64, f64)>),
+}
+
+#[derive(Live, LiveHook, Widget)]
+pub struct WaveformOverview {
+    #[walk]
+    walk: Walk,
+
+    #[layout]
+    layout: Layout,
+
+    #[redraw]
+    #[live]
+    draw_bg: DrawQuad,
+
+    #[live]
+    draw_column: DrawColor,
+
+    #[live]
+    draw_loop: DrawColor,
+
+    #[live]
+    column_width: f64,
+
+    #[live]
+    column_gap: f64,
+
+    #[live]
+    wave_color: Vec4,
+
+    #[live]
+    wave_color_dark: Vec4,
+
+    #[live]
+    played_color: Vec4,
+
+    #[live]
+    played_color_dark: Vec4,
+
+    #[live]
+    loop_color: Vec4,
+
+    #[live]
+    loop_color_dark: Vec4,
+
+    #[rust]
+    peaks: PeakSummary,
+
+    /// Play position as a fraction of the clip
+    #[rust]
+    progress: f64,
+
+    #[rust]
+    loop_range: Option<(f64, f64)>,
+
+    /// Shift-drag in progress: anchor and current point
+    #[rust]
+    selecting: Option<(f64, f64)>,
+
+    #[rust]
+    dark_mode: f64,
+}
+
+impl Widget for WaveformOverview {
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
+        let uid = self.widget_uid();
+        match event.hits(cx, self.draw_bg.area()) {
+            Hit::FingerHoverIn(_) => {
+                cx.set_cursor(MouseCursor::Hand);
+            }
+            Hit::FingerDown(fe) => {
+                let at = self.fraction_at(cx, fe.abs.x);
+                if fe.modifiers.shift {
+                    self.selecting = Some((at, at));
+                    self.draw_bg.redraw(cx);
+                } else {
+                    cx.widget_action(uid, &scope.path, WaveformOverviewAction::Seek(at));
+                }
+            }
+            Hit::FingerMove(fe) => {
+                let at = self.fraction_at(cx, fe.abs.x);
+                if let Some((anchor, _)) = self.selecting {
+                    self.selecting = Some((anchor, at));
+                    self.draw_bg.redraw(cx);
+                } else {
+                    cx.widget_action(uid, &scope.path, WaveformOverviewAction::Seek(at));
+                }
+            }
+            Hit::FingerUp(_) => {
+                if let Some((anchor, at)) = self.selecting.take() {
+                    let width = self.draw_bg.area().rect(cx).size.x;
+                    let range = ((at - anchor).abs() * width >= MIN_SELECTION_PX)
+                        .then(|| (anchor.min(at), anchor.max(at)));
+                    self.loop_range = range;
+                    self.draw_bg.redraw(cx);
+                    cx.widget_action(uid, &scope.path, WaveformOverviewAction::Loop(range));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn draw_walk(&mut self, cx: &mut Cx2d, _scope: &mut Scope, walk: Walk) -> DrawStep {
+        self.draw_bg.begin(cx, walk, self.layout);
+        let rect = cx.turtle().rect();
+        let step = self.column_width + self.column_gap;
+        let count = ((rect.size.x + self.column_gap) / step).max(0.0) as usize;
+
+        // Loop range, or the selection being dragged, behind the waveform
+        let shown_range = self
+            .selecting
+            .map(|(anchor, at)| (anchor.min(at), anchor.max(at)))
+            .or(self.loop_range);
+        if let Some((start, end)) = shown_range {
+            self.draw_loop.color = mix_color(self.loop_color, self.loop_color_dark, self.dark_mode);
+            self.draw_loop.draw_abs(
+                cx,
+                Rect {
+                    pos: dvec2(rect.pos.x + start * rect.size.x, rect.pos.y),
+                    size: dvec2((end - start) * rect.size.x, rect.size.y),
+                },
+            );
+        }
+
+        let wave = mix_color(self.wave_color, self.wave_color_dark, self.dark_mode);
+        let played = mix_color(self.played_color, self.played_color_dark, self.dark_mode);
+        let mid = rect.pos.y + rect.size.y / 2.0;
+        let half = rect.size.y / 2.0;
+        for (i, (min, max)) in self.peaks.columns(count).into_iter().enumerate() {
+            let top = mid - max.clamp(-1.0, 1.0) as f64 * half;
+            let bottom = mid - min.clamp(-1.0, 1.0) as f64 * half;
+            let height = (bottom - top).max(MIN_COLUMN_HEIGHT);
+            let is_played = (i as f64 + 0.5) / count as f64 <= self.progress;
+            self.draw_column.color = if is_played { played } else { wave };
+            self.draw_column.draw_abs(
+                cx,
+                Rect {
+                    pos: dvec2(
+                        rect.pos.x + i as f64 * step,
+                        (top + bottom - height) / 2.0,
+                    ),
+                    size: dvec2(self.column_width, height),
+                },
+            );
+        }
+
+        self.draw_bg.end(cx);
+        DrawStep::done()
+    }
+}
+
+fn mix_color(light: Vec4, dark: Vec4, t: f64) -> Vec4 {
+    let t = t as f32;
+    vec4(
+        light.x + (dark.x - light.x) * t,
+        light.y + (dark.y - light.y) * t,
+        light.z + (dark.z - light.z) * t,
+        light.w + (dark.w - light.w) * t,
+    )
+}
+
+impl WaveformOverview {
+    /// Fraction of the clip under absolute x position `abs_x`
+    fn fraction_at(&self, cx: &Cx, abs_x: f64) -> f64 {
+        let rect = self.draw_bg.area().rect(cx);
+        if rect.size.x <= 0.0 {
+            return 0.0;
+        }
+        ((abs_x - rect.pos.x) / rect.size.x).clamp(0.0, 1.0)
+    }
+}
+
+impl WaveformOverviewRef {
+    /// Show a new clip (interleaved samples), rewound and without a loop
+    pub fn set_clip(&self, cx: &mut Cx, samples: &[f32], channels: usize) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.peaks = PeakSummary::from_samples(samples, channels, SUMMARY_BUCKETS);
+            inner.progress = 0.0;
+            inner.loop_range = None;
+            inner.selecting = None;
+            inner.draw_bg.redraw(cx);
+        }
+    }
+
+    /// Drop the clip, leaving a flat track
+    pub fn clear(&self, cx: &mut Cx) {
+        self.set_clip(cx, &[], 1);
+    }
+
+    /// Set the play position as a fraction of the clip (0.0-1.0)
+    pub fn set_progress(&self, cx: &mut Cx, progress: f64) {
+        if let Some(mut inner) = self.borrow_mut() {
+            let progress = progress.clamp(0.0, 1.0);
+            if inner.progress != progress {
+                inner.progress = progress;
+                inner.draw_bg.redraw(cx);
+            }
+        }
+    }
+
+    /// Show a loop range (fractions of the clip), or none
+    pub fn set_loop(&self, cx: &mut Cx, range: Option<(f64, f64)>) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.loop_range = range;
+            inner.draw_bg.redraw(cx);
+        }
+    }
+
+    /// Update dark mode for this widget
+    pub fn update_dark_mode(&self, cx: &mut Cx, dark_mode: f64) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.dark_mode = dark_mode;
+            inner.draw_bg.redraw(cx);
+        }
+    }
+
+    /// Point clicked or dragged to, if any
+    pub fn seek_requested(&self, actions: &Actions) -> Option<f64> {
+        match actions.find_widget_action(self.widget_uid()).cast() {
+            WaveformOverviewAction::Seek(at) => Some(at),
+            _ => None,
+        }
+    }
+
+    /// Loop range selected (`Some(None)` when the loop was cleared), if any
+    pub fn loop_requested(&self, actions: &Actions) -> Option<Option<(f64, f64)>> {
+        match actions.find_widget_action(self.widget_uid()).cast() {
+            WaveformOverviewAction::Loop(range) => Some(range),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_peak_summary_mixes_to_mono_and_keeps_extremes() {
+        // Stereo: left ramps up, right is silent
+        let samples: Vec<f32> = (0..8).flat_map(|i| [i as f32 / 4.0, 0.0]).collect();
+        let summary = PeakSummary::from_samples(&samples, 2, 4);
+        assert_eq!(
+            summary.columns(4),
+            vec![(0.0, 0.125), (0.25, 0.375), (0.5, 0.625), (0.75, 0.875)]
+        );
+        // Folding keeps the extremes of the merged buckets
+        assert_eq!(summary.columns(2), vec![(0.0, 0.375), (0.5, 0.875)]);
+    }
+
+    #[test]
+    fn test_peak_summary_stretches_short_clips_and_flattens_empty_ones() {
+        let summary = PeakSummary::from_samples(&[-0.5, 0.5], 1, 2048);
+        assert_eq!(
+            summary.columns(4),
+            vec![(-0.5, -0.5), (-0.5, -0.5), (0.5, 0.5), (0.5, 0.5)]
+        );
+
+        let empty = PeakSummary::from_samples(&[], 1, 2048);
+        assert!(empty.is_empty());
+        assert_eq!(empty.columns(3), vec![(0.0, 0.0); 3]);
+    }
+}
@@ -39,7 +39,15 @@
 //!
 //! ## Updating Band Levels
 //!
-//! The widget uses target levels for smooth interpolation. Update via `apply_over`:
+//! The widget uses target levels for smooth interpolation. Feed it live levels,
+//! e.g. from an audio player's output tap, with `set_levels`:
+//!
+//! ```rust,ignore
+//! let bands = player.get_spectrum_bands(8);
+//! self.view.waveform_view(ids!(my_waveform)).set_levels(cx, &bands);
+//! ```
+//!
+//! Or update the shader directly via `apply_over`:
 //!
 //! ```rust,ignore
 //! // Direct shader update (immediate)
@@ -210,13 +218,19 @@ impl Widget for WaveformView {
         if let Event::NextFrame(nf) = event {
             self.animator_time = nf.time;
 
+            let mut moving = false;
             for i in 0..8 {
                 let diff = self.target_levels[i] - self.band_levels[i];
                 if diff.abs() > 0.01 {
                     let speed = if diff > 0.0 { 0.3 } else { 0.1 };
                     self.band_levels[i] += diff * speed;
+                    moving = true;
                 }
             }
+            // Keep animating until the bars settle on their targets
+            if moving {
+                cx.new_next_frame();
+            }
 
             self.view.apply_over(
                 cx,
@@ -242,3 +256,16 @@ impl Widget for WaveformView {
         self.view.draw_walk(cx, scope, walk)
     }
 }
+
+impl WaveformViewRef {
+    /// Set the target level (0.0-1.0) of each band, lowest first; the bars
+    /// animate towards them. Missing bands drop to zero.
+    pub fn set_levels(&self, cx: &mut Cx, levels: &[f32]) {
+        if let Some(mut inner) = self.borrow_mut() {
+            for (i, target) in inner.target_levels.iter_mut().enumerate() {
+                *target = levels.get(i).copied().unwrap_or(0.0).clamp(0.0, 1.0);
+            }
+            cx.new_next_frame();
+        }
+    }
+}
@@ -34,6 +34,14 @@ def send_log(node, level, message, config_level="INFO"):
     common_send_log(node, level, message, "primespeech-tts", config_level)
 
 
+def send_status(node, status, message="", progress=None):
+    """Report model lifecycle ("loading", "ready", "error") on the status output."""
+    metadata = {"message": message}
+    if progress is not None:
+        metadata["progress"] = float(progress)
+    node.send_output("status", pa.array([status]), metadata=metadata)
+
+
 def validate_language_config(lang_code, param_name, node, log_level):
     """Validate language configuration and provide helpful error messages"""
     # Valid language codes for MoYoYo TTS v2
@@ -219,14 +227,19 @@ def main():
     # Initialize TTS engine
     tts_engine: Optional[MoYoYoTTSWrapper] = None
     model_loaded = False
+    # Last reported status, re-sent on every tick so late subscribers catch up
+    current_status = ("loading", "Validating models", 0.0)
 
     # Pre-initialize TTS engine to avoid first-call delay
     try:
         send_log(node, "INFO", "Pre-initializing TTS engine...", config.LOG_LEVEL)
+        send_status(node, *current_status)
         start_time = time.time()
 
         # Validate models directory early
         _validate_models_path(lambda lvl, msg: send_log(node, lvl, msg, config.LOG_LEVEL))
+        current_status = ("loading", f"Loading voice {voice_name}", 0.3)
+        send_status(node, *current_status)
 
         # Initialize TTS wrapper
         moyoyo_voice = voice_name.lower().replace(" ", "")
@@ -264,13 +277,18 @@ def main():
         init_time = time.time() - start_time
         send_log(node, "INFO", f"TTS engine pre-initialized in {init_time:.2f}s", config.LOG_LEVEL)
         send_log(node, "INFO", f"Ready to synthesize speech with voice: {voice_name}", config.LOG_LEVEL)
+        current_status = ("ready", f"Voice {voice_name} loaded in {init_time:.1f}s", 1.0)
 
     except Exception as init_err:
-        send_log(node, "WARNING", f"Failed to pre-initialize TTS engine: {init_err}", config.LOG_LEVEL)
-        send_log(node, "WARNING", "TTS engine will be initialized on first use", config.LOG_LEVEL)
+        send_log(node, "ERROR", f"Failed to pre-initialize TTS engine: {init_err}", config.LOG_LEVEL)
         send_log(node, "DEBUG", f"Traceback: {traceback.format_exc()}", config.LOG_LEVEL)
         model_loaded = False
         tts_engine = None
+        # The model did not load, so the UI must not offer generation; a request
+        # that still arrives retries the engine lazily
+        current_status = ("error", str(init_err), 0.0)
+
+    send_status(node, *current_status)
 
     # Statistics
     total_syntheses = 0
@@ -550,6 +568,8 @@ def main():
                         # Skip tts_engine.tts access check - can deadlock on macOS
                         send_log(node, "DEBUG", "TTS engine initialized successfully", config.LOG_LEVEL)
                         model_loaded = True
+                        current_status = ("ready", f"Voice {current_voice_name} loaded", 1.0)
+                        send_status(node, *current_status)
                         send_log(node, "DEBUG", "TTS engine ready", config.LOG_LEVEL)
                     except Exception as init_err:
                         send_log(node, "ERROR", f"TTS init error: {init_err}", config.LOG_LEVEL)
@@ -739,6 +759,10 @@ def main():
                     # The text segmenter will handle error cases appropriately based on session_status metadata
                     send_log(node, "ERROR", f"TTS synthesis error for question_id {metadata.get('question_id', 'default')}: {e}", config.LOG_LEVEL)
 
+            elif input_id == "tick":
+                # Periodic status heartbeat for readiness detection in the UI
+                send_status(node, *current_status)
+
             elif input_id == "control":
                 # Handle control commands
                 command = event["value"][0].as_py()
@@ -0,0 +1,249 @@
+"""
+Trained Voice Evaluation Service
+
+Exercises freshly trained GPT-SoVITS weights on a fixed set of test sentences:
+1. Synthesize each sentence with the trained weights
+2. Transcribe the results with the same ASR the training pipeline uses
+3. Score speaker similarity against the reference audio (cosine similarity of
+   the SoVITS reference encoder's speaker embeddings)
+
+The character error rate of each transcript is computed by the caller.
+
+Communication: JSON-RPC over stdin/stdout (same as training_service.py)
+- Reads the evaluation request from stdin as JSON
+- Emits progress events to stdout as JSON lines:
+  SAMPLE   {"text", "transcript", "audio_path"} per sentence
+  COMPLETE {"speaker_similarity"}
+"""
+
+import json
+import logging
+import math
+import os
+import sys
+import time
+import traceback
+import wave
+from typing import Dict, List, Optional, Sequence
+
+# Setup logging to stderr (stdout is reserved for JSON events)
+logging.basicConfig(
+    level=logging.INFO,
+    format='%(asctime)s - %(levelname)s - %(message)s',
+    stream=sys.stderr
+)
+logger = logging.getLogger(__name__)
+
+
+def emit_progress(event_type: str, message: str, data: Optional[Dict] = None):
+    """Emit JSON event to stdout for Rust to parse"""
+    event = {
+        "type": event_type,
+        "message": message,
+        "timestamp": time.time()
+    }
+    if data:
+        event["data"] = data
+
+    print(json.dumps(event, ensure_ascii=False), flush=True)
+    logger.info(f"[{event_type}] {message}")
+
+
+def validate_request(request: Dict) -> List[str]:
+    """Return the request's test sentences, raising if a field is missing"""
+    for key in ("gpt_weights", "sovits_weights", "reference_audio", "language", "output_dir"):
+        if not request.get(key):
+            raise ValueError(f"Evaluation request is missing '{key}'")
+    for key in ("gpt_weights", "sovits_weights", "reference_audio"):
+        if not os.path.exists(request[key]):
+            raise FileNotFoundError(f"{key} not found: {request[key]}")
+    sentences = [s for s in request.get("sentences", []) if s.strip()]
+    if not sentences:
+        raise ValueError("Evaluation request has no test sentences")
+    return sentences
+
+
+def sample_path(output_dir: str, index: int) -> str:
+    """Where the synthesized audio of sentence `index` is written"""
+    return os.path.join(output_dir, f"sample_{index:02d}.wav")
+
+
+def write_wav(path: str, sample_rate: int, samples: Sequence[float]):
+    """Write mono float samples (-1.0..1.0) as 16-bit PCM"""
+    frames = bytearray()
+    for sample in samples:
+        value = int(max(-1.0, min(1.0, float(sample))) * 32767)
+        frames += value.to_bytes(2, "little", signed=True)
+    with wave.open(path, "wb") as f:
+        f.setnchannels(1)
+        f.setsampwidth(2)
+        f.setframerate(sample_rate)
+        f.writeframes(bytes(frames))
+
+
+def parse_asr_list(list_path: str) -> Dict[str, str]:
+    """Transcripts by audio file name from an ASR `.list` file
+
+    Lines are `path|folder|LANG|text`; the text may itself contain `|`.
+    """
+    transcripts = {}
+    with open(list_path, "r", encoding="utf-8") as f:
+        for line in f:
+            parts = line.rstrip("\n").split("|", 3)
+            if len(parts) == 4:
+                transcripts[os.path.basename(parts[0])] = parts[3].strip()
+    return transcripts
+
+
+def cosine_similarity(a: Sequence[float], b: Sequence[float]) -> float:
+    """Cosine similarity of two vectors, 0.0 if either is all zeros"""
+    dot = sum(x * y for x, y in zip(a, b))
+    norm = math.sqrt(sum(x * x for x in a)) * math.sqrt(sum(y * y for y in b))
+    return dot / norm if norm > 0 else 0.0
+
+
+def transcribe(samples_dir: str, output_dir: str, language: str) -> Dict[str, str]:
+    """Run the training pipeline's ASR over every WAV in `samples_dir`"""
+    if language == "zh":
+        from moyoyo_tts.tools.asr.funasr_asr import execute_asr
+        list_path = execute_asr(
+            input_folder=samples_dir,
+            output_folder=output_dir,
+            model_size="large",
+            language="zh"
+        )
+    else:
+        from moyoyo_tts.tools.asr.fasterwhisper_asr import execute_asr
+        list_path = execute_asr(
+            input_folder=samples_dir,
+            output_folder=output_dir,
+            model_size="large",
+            language="en",
+            precision="float16" if _cuda_available() else "float32"
+        )
+    if not list_path or not os.path.exists(list_path):
+        raise RuntimeError("ASR produced no transcripts")
+    return parse_asr_list(list_path)
+
+
+def _cuda_available() -> bool:
+    import torch
+    return torch.cuda.is_available()
+
+
+def speaker_embedding(tts, audio_path: str) -> List[float]:
+    """Speaker embedding of `audio_path` from the SoVITS reference encoder"""
+    import torch
+
+    spec = tts._get_ref_spec(audio_path)
+    vits = tts.vits_model
+    if getattr(vits, "version", "v2") != "v1":
+        spec = spec[:, :704]
+    mask = torch.ones((1, 1, spec.size(2)), dtype=spec.dtype, device=spec.device)
+    with torch.no_grad():
+        embedding = vits.ref_enc(spec * mask, mask)
+    return embedding.flatten().float().cpu().tolist()
+
+
+def run_evaluation(request: Dict):
+    """
+    Synthesize, transcribe and score the test sentences.
+
+    request = {
+        "gpt_weights": str,
+        "sovits_weights": str,
+        "reference_audio": str,
+        "reference_text": str,
+        "language": str,
+        "sentences": [str],
+        "output_dir": str,
+    }
+    """
+    sentences = validate_request(request)
+    language = request["language"]
+    output_dir = request["output_dir"]
+    samples_dir = os.path.join(output_dir, "samples")
+    os.makedirs(samples_dir, exist_ok=True)
+    for name in os.listdir(samples_dir):
+        if name.endswith(".wav"):
+            os.remove(os.path.join(samples_dir, name))
+
+    os.environ["version"] = "v2"
+    from dora_primespeech.moyoyo_tts_wrapper_streaming_fix import (
+        MOYOYO_AVAILABLE,
+        StreamingMoYoYoTTSWrapper,
+    )
+    if not MOYOYO_AVAILABLE:
+        raise RuntimeError("MoYoYo TTS is not available in this environment")
+
+    emit_progress("STAGE", "Loading trained voice", {"current": 1, "total": 3})
+    device = "cuda" if _cuda_available() else "cpu"
+    engine = StreamingMoYoYoTTSWrapper(
+        voice="evaluation",
+        device=device,
+        enable_streaming=False,
+        voice_config={
+            "gpt_weights": request["gpt_weights"],
+            "sovits_weights": request["sovits_weights"],
+            "reference_audio": request["reference_audio"],
+            "prompt_text": request.get("reference_text", ""),
+        },
+        logger_func=lambda level, msg: logger.info(f"[TTS-{level}] {msg}")
+    )
+    if engine.tts is None:
+        raise RuntimeError("Failed to load the trained weights")
+
+    emit_progress("STAGE", "Synthesizing test sentences", {"current": 2, "total": 3})
+    paths = []
+    for index, text in enumerate(sentences):
+        sample_rate, audio = engine.synthesize(text, language=language, speed=1.0)
+        if sample_rate is None or audio is None or len(audio) == 0:
+            emit_progress("WARNING", f"No audio for sentence {index + 1}: {text}")
+            paths.append(None)
+            continue
+        path = sample_path(samples_dir, index)
+        write_wav(path, int(sample_rate), audio)
+        paths.append(path)
+        emit_progress("INFO", f"Synthesized {index + 1}/{len(sentences)}")
+    if not any(paths):
+        raise RuntimeError("No test sentence could be synthesized")
+
+    emit_progress("STAGE", "Transcribing and scoring", {"current": 3, "total": 3})
+    transcripts = transcribe(samples_dir, output_dir, language)
+    for text, path in zip(sentences, paths):
+        if path is None:
+            continue
+        emit_progress("SAMPLE", text, {
+            "text": text,
+            "transcript": transcripts.get(os.path.basename(path), ""),
+            "audio_path": path,
+        })
+
+    reference = speaker_embedding(engine.tts, request["reference_audio"])
+    similarities = [
+        cosine_similarity(reference, speaker_embedding(engine.tts, path))
+        for path in paths if path is not None
+    ]
+    similarity = sum(similarities) / len(similarities)
+
+    emit_progress("COMPLETE", "Evaluation completed", {
+        "speaker_similarity": similarity,
+    })
+
+
+def main():
+    """Entry point - read request from stdin, execute evaluation"""
+    try:
+        request = json.loads(sys.stdin.read())
+        run_evaluation(request)
+    except json.JSONDecodeError as e:
+        emit_progress("ERROR", f"Invalid JSON request: {e}")
+        sys.exit(1)
+    except Exception as e:
+        logger.error(traceback.format_exc())
+        emit_progress("ERROR", str(e), {"traceback": traceback.format_exc()})
+        sys.exit(1)
+
+
+if __name__ == "__main__":
+    main()
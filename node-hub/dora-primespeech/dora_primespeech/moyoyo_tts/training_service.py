@@ -9,6 +9,9 @@ Orchestrates the complete GPT-SoVITS training pipeline:
 5. Train GPT model (semantic)
 6. Train SoVITS model (acoustic)
 
+When the request carries a `dataset_manifest` (clips already sliced and
+transcribed in the app), steps 1-3 are skipped and its transcripts are used.
+
 Communication: JSON-RPC over stdin/stdout
 - Reads training request from stdin as JSON
 - Emits progress events to stdout as JSON lines
@@ -176,6 +179,70 @@ def validate_audio_file(audio_file: str, min_duration: float = 10.0, max_duratio
     return duration
 
 
+def load_dataset_manifest(manifest_path: str, min_duration: float = 10.0) -> Dict:
+    """Load a dataset manifest and check its clips are usable for training"""
+    if not os.path.exists(manifest_path):
+        raise FileNotFoundError(f"Dataset manifest not found: {manifest_path}")
+
+    with open(manifest_path, 'r', encoding='utf-8') as f:
+        manifest = json.load(f)
+
+    clips = [c for c in manifest.get("clips", []) if c.get("included", True)]
+    if not clips:
+        raise ValueError("Dataset manifest has no included clips")
+
+    for clip in clips:
+        if not os.path.exists(clip["audio_path"]):
+            raise FileNotFoundError(f"Clip audio not found: {clip['audio_path']}")
+        if not clip.get("transcript", "").strip():
+            raise ValueError(f"Clip {clip['id']} has no transcript")
+
+    duration = sum(c["end_secs"] - c["start_secs"] for c in clips)
+    if duration < min_duration:
+        raise ValueError(
+            f"Dataset too short: {duration:.1f}s (minimum: {min_duration:.1f}s). "
+            "Please include at least 10 seconds of audio."
+        )
+
+    manifest["clips"] = clips
+    return manifest
+
+
+def write_manifest_list(manifest: Dict, list_path: str, language: str) -> str:
+    """
+    Write the manifest's clips as an ASR `.list` file, in place of slicing,
+    denoising and ASR.
+
+    Lines are `path|dataset|LANG|text`, the same format execute_asr produces.
+    """
+    lines = []
+    for clip in manifest["clips"]:
+        # Transcripts are single-line; `|` would break the list format
+        text = " ".join(clip["transcript"].replace("|", " ").split())
+        lines.append(f"{clip['audio_path']}|dataset|{language.upper()}|{text}")
+
+    with open(list_path, 'w', encoding='utf-8') as f:
+        f.write("\n".join(lines))
+    return list_path
+
+
+def manifest_reference(manifest: Dict, reference_audio: str) -> Tuple[str, str]:
+    """
+    Reference audio and text for the trained voice: the clip at
+    `reference_audio`, or the longest clip if it is not in the manifest.
+
+    Returns: (audio_path, text)
+    """
+    clips = manifest["clips"]
+    target = os.path.abspath(reference_audio) if reference_audio else None
+    for clip in clips:
+        if target and os.path.abspath(clip["audio_path"]) == target:
+            return clip["audio_path"], clip["transcript"].strip()
+
+    best = max(clips, key=lambda c: c["end_secs"] - c["start_secs"])
+    return best["audio_path"], best["transcript"].strip()
+
+
 def create_directory_structure(workspace_dir: str):
     """Create workspace directory structure"""
     dirs = [
@@ -668,6 +735,7 @@ def run_training_pipeline(request: Dict):
         "audio_file": str,
         "language": str,
         "workspace_dir": str,
+        "dataset_manifest": str,  # optional: pre-sliced, transcribed clips
         "training_params": {
             "gpt_epochs": 15,
             "sovits_epochs": 20,
@@ -678,6 +746,7 @@ def run_training_pipeline(request: Dict):
     voice_id = request["voice_id"]
     voice_name = request["voice_name"]
     audio_file = request["audio_file"]
+    dataset_manifest = request.get("dataset_manifest")
     language = request["language"]
     workspace_dir = request["workspace_dir"]
     params = request.get("training_params", {})
@@ -695,96 +764,111 @@ def run_training_pipeline(request: Dict):
         # Stage 1: Prepare workspace
         create_directory_structure(workspace_dir)
 
-        # Stage 2: Validate and copy audio
-        emit_progress("STAGE", "Validating audio", {"current": 2, "total": 7})
-        duration = validate_audio_file(audio_file)
-
-        # Copy to training data directory
-        training_audio_path = os.path.join(workspace_dir, "training_data/recording.wav")
-        shutil.copy2(audio_file, training_audio_path)
-        emit_progress("INFO", f"Audio validated: {duration:.1f}s")
-
-        # Stage 3: Slice audio into segments
-        emit_progress("STAGE", "Slicing audio into segments", {"current": 3, "total": 7})
-
-        # Add tools directory to path for slicer2 import
-        tools_dir = os.path.join(os.path.dirname(__file__), "tools")
-        if tools_dir not in sys.path:
-            sys.path.insert(0, tools_dir)
-
-        from moyoyo_tts.tools.slice_audio import slice
-
-        sliced_dir = os.path.join(workspace_dir, "processed/sliced")
-        result = slice(
-            inp=training_audio_path,
-            opt_root=sliced_dir,
-            threshold=-34,      # dB threshold for silence detection
-            min_length=4000,    # 4 seconds minimum segment length (ms)
-            min_interval=300,   # 300ms minimum cut interval
-            hop_size=10,        # Precision of silence detection
-            max_sil_kept=500,   # Keep max 500ms silence
-            _max=0.9,           # Normalization ceiling
-            alpha=0.25,         # Mix ratio
-            i_part=0,           # Parallel processing part index
-            all_part=1          # Total parallel processing parts
-        )
+        if dataset_manifest:
+            # Stages 2-5: the dataset's clips are already sliced and transcribed
+            emit_progress("STAGE", "Loading dataset", {"current": 2, "total": 7})
+            manifest = load_dataset_manifest(dataset_manifest)
+            shutil.copy2(dataset_manifest, os.path.join(workspace_dir, "training_data/manifest.json"))
+            emit_progress("INFO", f"Dataset loaded: {len(manifest['clips'])} clips")
+
+            asr_output_path = write_manifest_list(
+                manifest,
+                os.path.join(workspace_dir, "processed/asr_labels.list"),
+                language
+            )
+            ref_audio_path, ref_text = manifest_reference(manifest, audio_file)
+            emit_progress("INFO", f"Reference: {os.path.basename(ref_audio_path)}")
+        else:
+            # Stage 2: Validate and copy audio
+            emit_progress("STAGE", "Validating audio", {"current": 2, "total": 7})
+            duration = validate_audio_file(audio_file)
+
+            # Copy to training data directory
+            training_audio_path = os.path.join(workspace_dir, "training_data/recording.wav")
+            shutil.copy2(audio_file, training_audio_path)
+            emit_progress("INFO", f"Audio validated: {duration:.1f}s")
+
+            # Stage 3: Slice audio into segments
+            emit_progress("STAGE", "Slicing audio into segments", {"current": 3, "total": 7})
+
+            # Add tools directory to path for slicer2 import
+            tools_dir = os.path.join(os.path.dirname(__file__), "tools")
+            if tools_dir not in sys.path:
+                sys.path.insert(0, tools_dir)
+
+            from moyoyo_tts.tools.slice_audio import slice
+
+            sliced_dir = os.path.join(workspace_dir, "processed/sliced")
+            result = slice(
+                inp=training_audio_path,
+                opt_root=sliced_dir,
+                threshold=-34,      # dB threshold for silence detection
+                min_length=4000,    # 4 seconds minimum segment length (ms)
+                min_interval=300,   # 300ms minimum cut interval
+                hop_size=10,        # Precision of silence detection
+                max_sil_kept=500,   # Keep max 500ms silence
+                _max=0.9,           # Normalization ceiling
+                alpha=0.25,         # Mix ratio
+                i_part=0,           # Parallel processing part index
+                all_part=1          # Total parallel processing parts
+            )
 
-        logger.info(f"Slice result: {result}")
+            logger.info(f"Slice result: {result}")
 
-        num_slices = len([f for f in os.listdir(sliced_dir) if f.endswith('.wav')])
-        emit_progress("INFO", f"Audio sliced into {num_slices} segments")
+            num_slices = len([f for f in os.listdir(sliced_dir) if f.endswith('.wav')])
+            emit_progress("INFO", f"Audio sliced into {num_slices} segments")
 
-        if num_slices == 0:
-            raise ValueError("Audio slicing produced no segments. Check audio quality and volume.")
+            if num_slices == 0:
+                raise ValueError("Audio slicing produced no segments. Check audio quality and volume.")
 
-        # Stage 4: Denoise audio segments
-        emit_progress("STAGE", "Denoising audio segments", {"current": 4, "total": 7})
+            # Stage 4: Denoise audio segments
+            emit_progress("STAGE", "Denoising audio segments", {"current": 4, "total": 7})
 
-        # Import from cmd-denoise.py (has hyphen in filename)
-        import importlib.util
-        denoise_path = os.path.join(
-            os.path.dirname(__file__),
-            "tools/cmd-denoise.py"
-        )
-        spec = importlib.util.spec_from_file_location("cmd_denoise", denoise_path)
-        cmd_denoise = importlib.util.module_from_spec(spec)
-        spec.loader.exec_module(cmd_denoise)
-
-        denoised_dir = os.path.join(workspace_dir, "processed/denoised")
-        cmd_denoise.execute_denoise(
-            input_folder=sliced_dir,
-            output_folder=denoised_dir
-        )
+            # Import from cmd-denoise.py (has hyphen in filename)
+            import importlib.util
+            denoise_path = os.path.join(
+                os.path.dirname(__file__),
+                "tools/cmd-denoise.py"
+            )
+            spec = importlib.util.spec_from_file_location("cmd_denoise", denoise_path)
+            cmd_denoise = importlib.util.module_from_spec(spec)
+            spec.loader.exec_module(cmd_denoise)
+
+            denoised_dir = os.path.join(workspace_dir, "processed/denoised")
+            cmd_denoise.execute_denoise(
+                input_folder=sliced_dir,
+                output_folder=denoised_dir
+            )
 
-        num_denoised = len([f for f in os.listdir(denoised_dir) if f.endswith('.wav')])
-        emit_progress("INFO", f"Denoised {num_denoised} audio segments")
+            num_denoised = len([f for f in os.listdir(denoised_dir) if f.endswith('.wav')])
+            emit_progress("INFO", f"Denoised {num_denoised} audio segments")
 
-        # Stage 5: ASR transcription
-        emit_progress("STAGE", "Transcribing audio (ASR)", {"current": 5, "total": 7})
+            # Stage 5: ASR transcription
+            emit_progress("STAGE", "Transcribing audio (ASR)", {"current": 5, "total": 7})
 
-        asr_output_dir = os.path.join(workspace_dir, "processed")
+            asr_output_dir = os.path.join(workspace_dir, "processed")
 
-        if language == "zh":
-            from moyoyo_tts.tools.asr.funasr_asr import execute_asr
-            asr_output_path = execute_asr(
-                input_folder=denoised_dir,
-                output_folder=asr_output_dir,
-                model_size="large",
-                language="zh"
-            )
-        else:  # English or other
-            from moyoyo_tts.tools.asr.fasterwhisper_asr import execute_asr
-            asr_output_path = execute_asr(
-                input_folder=denoised_dir,
-                output_folder=asr_output_dir,
-                model_size="large",
-                language="en"
-            )
+            if language == "zh":
+                from moyoyo_tts.tools.asr.funasr_asr import execute_asr
+                asr_output_path = execute_asr(
+                    input_folder=denoised_dir,
+                    output_folder=asr_output_dir,
+                    model_size="large",
+                    language="zh"
+                )
+            else:  # English or other
+                from moyoyo_tts.tools.asr.fasterwhisper_asr import execute_asr
+                asr_output_path = execute_asr(
+                    input_folder=denoised_dir,
+                    output_folder=asr_output_dir,
+                    model_size="large",
+                    language="en"
+                )
 
-        emit_progress("INFO", f"ASR transcription completed: {asr_output_path}")
+            emit_progress("INFO", f"ASR transcription completed: {asr_output_path}")
 
-        # Auto-select reference audio
-        ref_audio_path, ref_text = select_best_reference_audio(asr_output_path)
+            # Auto-select reference audio
+            ref_audio_path, ref_text = select_best_reference_audio(asr_output_path)
 
         # Stage 5b: Extract features for GPT training
         emit_progress("STAGE", "Extracting features for GPT", {"current": 5, "total": 7, "substage": "gpt_features"})
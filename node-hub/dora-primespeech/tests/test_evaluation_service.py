@@ -0,0 +1,73 @@
+"""Tests for the trained voice evaluation service helpers.
+
+The service module is loaded by path so the package's TTS and dora imports
+are not needed.
+"""
+
+import importlib.util
+import io
+import json
+import os
+import tempfile
+import wave
+from contextlib import redirect_stdout
+from pathlib import Path
+
+import pytest
+
+SERVICE_PATH = (
+    Path(__file__).resolve().parents[1]
+    / "dora_primespeech" / "moyoyo_tts" / "evaluation_service.py"
+)
+spec = importlib.util.spec_from_file_location("evaluation_service", SERVICE_PATH)
+evaluation_service = importlib.util.module_from_spec(spec)
+spec.loader.exec_module(evaluation_service)
+
+
+def test_parse_asr_list_keys_transcripts_by_file_name():
+    with tempfile.TemporaryDirectory() as tmp:
+        list_path = os.path.join(tmp, "samples.list")
+        with open(list_path, "w", encoding="utf-8") as f:
+            f.write("/x/samples/sample_00.wav|samples|ZH|今天天气很好\n")
+            f.write("/x/samples/sample_01.wav|samples|EN|a | b\n")
+            f.write("malformed line\n")
+        transcripts = evaluation_service.parse_asr_list(list_path)
+    assert transcripts == {"sample_00.wav": "今天天气很好", "sample_01.wav": "a | b"}
+
+
+def test_cosine_similarity():
+    assert evaluation_service.cosine_similarity([1.0, 0.0], [2.0, 0.0]) == pytest.approx(1.0)
+    assert evaluation_service.cosine_similarity([1.0, 0.0], [0.0, 3.0]) == pytest.approx(0.0)
+    assert evaluation_service.cosine_similarity([0.0, 0.0], [1.0, 1.0]) == 0.0
+
+
+def test_validate_request_requires_weights_and_sentences():
+    with tempfile.TemporaryDirectory() as tmp:
+        files = {}
+        for key in ("gpt_weights", "sovits_weights", "reference_audio"):
+            files[key] = os.path.join(tmp, key)
+            Path(files[key]).touch()
+        request = dict(files, language="zh", output_dir=tmp, sentences=["你好", " "])
+        assert evaluation_service.validate_request(request) == ["你好"]
+
+        with pytest.raises(ValueError):
+            evaluation_service.validate_request(dict(request, sentences=[]))
+        with pytest.raises(FileNotFoundError):
+            evaluation_service.validate_request(dict(request, gpt_weights=os.path.join(tmp, "none")))
+
+
+def test_write_wav_and_sample_events():
+    with tempfile.TemporaryDirectory() as tmp:
+        path = evaluation_service.sample_path(tmp, 3)
+        evaluation_service.write_wav(path, 32000, [0.0, 0.5, -2.0])
+        with wave.open(path, "rb") as f:
+            assert (f.getnchannels(), f.getframerate(), f.getnframes()) == (1, 32000, 3)
+            frames = f.readframes(3)
+        assert int.from_bytes(frames[4:6], "little", signed=True) == -32767
+
+    out = io.StringIO()
+    with redirect_stdout(out):
+        evaluation_service.emit_progress("SAMPLE", "t", {"text": "t", "transcript": "t", "audio_path": path})
+    event = json.loads(out.getvalue())
+    assert event["type"] == "SAMPLE"
+    assert event["data"]["audio_path"].endswith("sample_03.wav")
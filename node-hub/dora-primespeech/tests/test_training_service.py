@@ -0,0 +1,90 @@
+"""Tests for the training service's dataset manifest handling.
+
+The service module is loaded by path so the package's TTS and dora imports
+are not needed.
+"""
+
+import importlib.util
+import json
+import os
+import tempfile
+from pathlib import Path
+
+import pytest
+
+pytest.importorskip("numpy")
+pytest.importorskip("scipy")
+pytest.importorskip("torch")
+
+SERVICE_PATH = (
+    Path(__file__).resolve().parents[1]
+    / "dora_primespeech" / "moyoyo_tts" / "training_service.py"
+)
+spec = importlib.util.spec_from_file_location("training_service", SERVICE_PATH)
+training_service = importlib.util.module_from_spec(spec)
+spec.loader.exec_module(training_service)
+
+
+def write_manifest(tmp, clips):
+    """Write a manifest with one (empty) WAV per clip, as the app saves it"""
+    entries = []
+    for index, (secs, transcript) in enumerate(clips):
+        audio_path = os.path.join(tmp, f"clip_{index:03d}.wav")
+        Path(audio_path).touch()
+        entries.append({
+            "id": f"clip_{index:03d}",
+            "source": "take_1.wav",
+            "start_secs": 0.0,
+            "end_secs": secs,
+            "audio_path": audio_path,
+            "transcript": transcript,
+            "transcribed": True,
+            "included": True,
+        })
+    manifest_path = os.path.join(tmp, "manifest.json")
+    with open(manifest_path, "w", encoding="utf-8") as f:
+        json.dump({"version": "1.0", "language": "en", "sample_rate": 32000, "clips": entries}, f)
+    return manifest_path
+
+
+def test_manifest_clips_become_asr_list():
+    with tempfile.TemporaryDirectory() as tmp:
+        manifest_path = write_manifest(tmp, [
+            (6.0, "Hello there."),
+            (5.0, "A line\nwith | breaks"),
+        ])
+        manifest = training_service.load_dataset_manifest(manifest_path)
+        list_path = training_service.write_manifest_list(
+            manifest, os.path.join(tmp, "asr_labels.list"), "en"
+        )
+
+        with open(list_path, encoding="utf-8") as f:
+            lines = f.read().split("\n")
+        assert lines == [
+            f"{os.path.join(tmp, 'clip_000.wav')}|dataset|EN|Hello there.",
+            f"{os.path.join(tmp, 'clip_001.wav')}|dataset|EN|A line with breaks",
+        ]
+
+
+def test_manifest_reference_uses_requested_clip():
+    with tempfile.TemporaryDirectory() as tmp:
+        manifest = training_service.load_dataset_manifest(
+            write_manifest(tmp, [(4.0, "Short one."), (8.0, "Longest clip.")])
+        )
+        first = os.path.join(tmp, "clip_000.wav")
+        assert training_service.manifest_reference(manifest, first) == (first, "Short one.")
+
+        longest = os.path.join(tmp, "clip_001.wav")
+        assert training_service.manifest_reference(manifest, "") == (longest, "Longest clip.")
+
+
+def test_manifest_rejects_unusable_datasets():
+    with tempfile.TemporaryDirectory() as tmp:
+        with pytest.raises(ValueError, match="too short"):
+            training_service.load_dataset_manifest(write_manifest(tmp, [(3.0, "Too short.")]))
+
+    with tempfile.TemporaryDirectory() as tmp:
+        with pytest.raises(ValueError, match="no transcript"):
+            training_service.load_dataset_manifest(
+                write_manifest(tmp, [(6.0, "Fine."), (6.0, "  ")])
+            )